@@ -0,0 +1,9 @@
+-- Password hashing Argon2id (PHC string) butuh kolom lebih panjang
+-- dan penanda format agar hash AES lama bisa dibedakan & di-upgrade saat login.
+ALTER TABLE [dbo].[AuthUser] ALTER COLUMN [Password] NVARCHAR(255) NULL;
+GO
+
+-- 0 = AES lama (crypto::encrypt_text), 1 = Argon2id
+ALTER TABLE [dbo].[AuthUser] ADD [PasswordVersion] INT NOT NULL
+    CONSTRAINT [DF_AuthUser_PasswordVersion] DEFAULT (0);
+GO
@@ -0,0 +1,18 @@
+-- Refresh token opaque untuk rotasi sesi, hanya hash SHA-256 yang disimpan.
+-- Satu FamilyID = satu rantai rotasi dari satu kali login.
+CREATE TABLE [dbo].[WEB_RefreshToken] (
+    [RefreshTokenNID] INT IDENTITY(1,1) NOT NULL PRIMARY KEY,
+    [AuthUserNID]     INT NOT NULL,
+    [TokenHash]       NVARCHAR(64) NOT NULL,
+    [FamilyID]        NVARCHAR(32) NOT NULL,
+    [UsedTime]        DATETIME NULL,
+    [IsRevoked]       BIT NOT NULL CONSTRAINT [DF_WEB_RefreshToken_IsRevoked] DEFAULT (0),
+    [ExpiredTime]     DATETIME NOT NULL,
+    [IPAddress]       NVARCHAR(100) NULL,
+    [CreatedTime]     DATETIME NOT NULL CONSTRAINT [DF_WEB_RefreshToken_CreatedTime] DEFAULT (GETDATE())
+);
+GO
+
+CREATE UNIQUE INDEX [UX_WEB_RefreshToken_TokenHash] ON [dbo].[WEB_RefreshToken] ([TokenHash]);
+CREATE INDEX [IX_WEB_RefreshToken_FamilyID] ON [dbo].[WEB_RefreshToken] ([FamilyID]);
+GO
@@ -0,0 +1,51 @@
+-- Role-based access control untuk AuthUser
+CREATE TABLE [dbo].[Roles] (
+    [RoleNID]     INT IDENTITY(1,1) NOT NULL PRIMARY KEY,
+    [RoleName]    NVARCHAR(50) NOT NULL UNIQUE,
+    [Description] NVARCHAR(255) NULL
+);
+
+CREATE TABLE [dbo].[Permissions] (
+    [PermissionNID]  INT IDENTITY(1,1) NOT NULL PRIMARY KEY,
+    [PermissionCode] NVARCHAR(100) NOT NULL UNIQUE,
+    [Description]    NVARCHAR(255) NULL
+);
+
+CREATE TABLE [dbo].[RolePermission] (
+    [RoleNID]       INT NOT NULL REFERENCES [dbo].[Roles]([RoleNID]),
+    [PermissionNID] INT NOT NULL REFERENCES [dbo].[Permissions]([PermissionNID]),
+    PRIMARY KEY ([RoleNID], [PermissionNID])
+);
+
+CREATE TABLE [dbo].[AuthUserRole] (
+    [AuthUserNID] INT NOT NULL,
+    [RoleNID]     INT NOT NULL REFERENCES [dbo].[Roles]([RoleNID]),
+    PRIMARY KEY ([AuthUserNID], [RoleNID])
+);
+GO
+
+INSERT INTO [dbo].[Roles] ([RoleName], [Description]) VALUES
+    ('admin', 'Administrator, akses penuh'),
+    ('client', 'Client terdaftar');
+
+INSERT INTO [dbo].[Permissions] ([PermissionCode], [Description]) VALUES
+    ('data:read', 'Baca data generic'),
+    ('data:clear', 'Kosongkan tabel TempImport'),
+    ('import:write', 'Import file ke TempImport'),
+    ('export:read', 'Export data TempImport'),
+    ('admin:roles', 'Kelola role user');
+
+-- Admin dapat semua permission
+INSERT INTO [dbo].[RolePermission] ([RoleNID], [PermissionNID])
+SELECT r.RoleNID, p.PermissionNID FROM [dbo].[Roles] r CROSS JOIN [dbo].[Permissions] p
+WHERE r.RoleName = 'admin';
+
+INSERT INTO [dbo].[RolePermission] ([RoleNID], [PermissionNID])
+SELECT r.RoleNID, p.PermissionNID FROM [dbo].[Roles] r CROSS JOIN [dbo].[Permissions] p
+WHERE r.RoleName = 'client' AND p.PermissionCode IN ('data:read', 'import:write', 'export:read');
+
+-- User lama otomatis jadi client
+INSERT INTO [dbo].[AuthUserRole] ([AuthUserNID], [RoleNID])
+SELECT u.AuthUserNID, r.RoleNID FROM [dbo].[AuthUser] u CROSS JOIN [dbo].[Roles] r
+WHERE r.RoleName = 'client';
+GO
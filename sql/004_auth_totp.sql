@@ -0,0 +1,20 @@
+-- Two-factor authentication (TOTP, RFC 6238) untuk AuthUser
+ALTER TABLE [dbo].[AuthUser] ADD
+    [TotpSecret]   NVARCHAR(64) NULL,
+    [TotpEnabled]  BIT NOT NULL CONSTRAINT [DF_AuthUser_TotpEnabled] DEFAULT (0),
+    -- Step terakhir yang diterima, kode dengan step <= ini ditolak (anti replay)
+    [TotpLastStep] BIGINT NULL;
+GO
+
+-- Recovery code sekali pakai, hanya hash SHA-256 yang disimpan
+CREATE TABLE [dbo].[AuthUserRecoveryCode] (
+    [RecoveryCodeNID] INT IDENTITY(1,1) NOT NULL PRIMARY KEY,
+    [AuthUserNID]     INT NOT NULL,
+    [CodeHash]        NVARCHAR(64) NOT NULL,
+    [UsedTime]        DATETIME NULL,
+    [CreatedTime]     DATETIME NOT NULL CONSTRAINT [DF_AuthUserRecoveryCode_CreatedTime] DEFAULT (GETDATE())
+);
+GO
+
+CREATE INDEX [IX_AuthUserRecoveryCode_AuthUserNID] ON [dbo].[AuthUserRecoveryCode] ([AuthUserNID]);
+GO
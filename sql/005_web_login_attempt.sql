@@ -0,0 +1,10 @@
+-- Counter gagal login / reset password per key, format key <scope>:<jenis>:<sha256 nilai>, contoh:
+--   login:email:<hash user@mail.com>, login:ip:<hash 10.0.0.1>, reset:email:<hash>, mfa:user:<hash 12>
+-- Key dari input user, jadi email yang tidak terdaftar diperlakukan sama.
+CREATE TABLE [dbo].[WEB_LoginAttempt] (
+    [AttemptKey]     NVARCHAR(320) NOT NULL PRIMARY KEY,
+    [FailedCount]    INT NOT NULL CONSTRAINT [DF_WEB_LoginAttempt_FailedCount] DEFAULT (0),
+    [LastFailedTime] DATETIME NOT NULL CONSTRAINT [DF_WEB_LoginAttempt_LastFailedTime] DEFAULT (GETDATE()),
+    [LockedUntil]    DATETIME NULL
+);
+GO
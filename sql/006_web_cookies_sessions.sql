@@ -0,0 +1,17 @@
+-- Satu baris WEB_Cookies per device (sesi), bukan lagi satu per user.
+-- SessionID = FamilyID refresh token dari login yang membuat sesi tersebut.
+ALTER TABLE [dbo].[WEB_Cookies] ADD
+    [SessionID]   NVARCHAR(32) NULL,
+    [DeviceName]  NVARCHAR(100) NULL,
+    [AppName]     NVARCHAR(100) NULL,
+    [UserAgent]   NVARCHAR(512) NULL,
+    [CreatedTime] DATETIME NULL,
+    [LastSeen]    DATETIME NULL;
+GO
+
+-- Sesi lama tanpa SessionID tidak bisa dipetakan ke device, paksa login ulang
+DELETE FROM [dbo].[WEB_Cookies] WHERE [SessionID] IS NULL;
+GO
+
+CREATE UNIQUE INDEX [UX_WEB_Cookies_User_Session] ON [dbo].[WEB_Cookies] ([AuthUserNID], [SessionID]);
+GO
@@ -0,0 +1,15 @@
+-- Akun OpenID Connect (Google, dll) yang tertaut ke AuthUser.
+-- Kolom AuthUser.Sub hanya muat satu provider, jadi tautan disimpan per (Provider, Subject).
+CREATE TABLE [dbo].[AuthUserExternalLogin] (
+    [ExternalLoginNID] INT IDENTITY(1,1) NOT NULL PRIMARY KEY,
+    [AuthUserNID]      INT NOT NULL,
+    [Provider]         NVARCHAR(50) NOT NULL,
+    [Subject]          NVARCHAR(255) NOT NULL,
+    [Email]            NVARCHAR(255) NULL,
+    [CreatedTime]      DATETIME NOT NULL CONSTRAINT [DF_AuthUserExternalLogin_CreatedTime] DEFAULT (GETDATE())
+);
+GO
+
+CREATE UNIQUE INDEX [UX_AuthUserExternalLogin_Provider_Subject] ON [dbo].[AuthUserExternalLogin] ([Provider], [Subject]);
+CREATE INDEX [IX_AuthUserExternalLogin_AuthUserNID] ON [dbo].[AuthUserExternalLogin] ([AuthUserNID]);
+GO
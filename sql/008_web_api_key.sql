@@ -0,0 +1,21 @@
+-- Personal API key, hanya hash SHA-256 key yang disimpan.
+-- KeyPrefix (ssk_xxxxxxxx) untuk menampilkan key di daftar tanpa membuka isinya.
+-- Scopes = daftar PermissionCode dipisah koma, dibatasi permission pemilik saat dipakai.
+CREATE TABLE [dbo].[WEB_ApiKey] (
+    [ApiKeyNID]    INT IDENTITY(1,1) NOT NULL PRIMARY KEY,
+    [AuthUserNID]  INT NOT NULL,
+    [Name]         NVARCHAR(100) NOT NULL,
+    [KeyPrefix]    NVARCHAR(20) NOT NULL,
+    [KeyHash]      NVARCHAR(64) NOT NULL,
+    [Scopes]       NVARCHAR(1000) NOT NULL,
+    [ExpiredTime]  DATETIME NULL,
+    [LastUsedTime] DATETIME NULL,
+    [LastUsedIP]   NVARCHAR(100) NULL,
+    [IsRevoked]    BIT NOT NULL CONSTRAINT [DF_WEB_ApiKey_IsRevoked] DEFAULT (0),
+    [CreatedTime]  DATETIME NOT NULL CONSTRAINT [DF_WEB_ApiKey_CreatedTime] DEFAULT (GETDATE())
+);
+GO
+
+CREATE UNIQUE INDEX [UX_WEB_ApiKey_KeyHash] ON [dbo].[WEB_ApiKey] ([KeyHash]);
+CREATE INDEX [IX_WEB_ApiKey_AuthUserNID] ON [dbo].[WEB_ApiKey] ([AuthUserNID]);
+GO
@@ -0,0 +1,11 @@
+-- Token aktivasi & reset password sekarang disimpan sebagai hash SHA-256 (base64, 44 karakter)
+-- dan di-NULL-kan setelah dipakai.
+ALTER TABLE [dbo].[AuthUser] ALTER COLUMN [OTPGeneratedLink] NVARCHAR(100) NULL;
+ALTER TABLE [dbo].[AuthUser] ALTER COLUMN [ResetPasswordKey] NVARCHAR(100) NULL;
+GO
+
+-- Token plaintext lama tidak akan pernah cocok dengan hash, buang saja.
+-- User yang belum aktivasi bisa minta link baru lewat /auth/activation/resend.
+UPDATE [dbo].[AuthUser] SET [OTPGeneratedLink] = NULL WHERE [ActivateTime] IS NOT NULL;
+UPDATE [dbo].[AuthUser] SET [ResetPasswordKey] = NULL, [ResetPasswordFlag] = 0;
+GO
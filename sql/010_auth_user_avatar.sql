@@ -0,0 +1,21 @@
+-- Thumbnail avatar hasil resize (WebP + PNG per ukuran), satu set per user.
+-- AvatarKey acak baru setiap upload, AuthUser.Picture menunjuk ke /api/v1/auth/avatar/{AvatarKey}/256.webp
+CREATE TABLE [dbo].[AuthUserAvatar] (
+    [AvatarNID]    INT IDENTITY(1,1) NOT NULL PRIMARY KEY,
+    [AuthUserNID]  INT NOT NULL,
+    [AvatarKey]    NVARCHAR(64) NOT NULL,
+    [Size]         INT NOT NULL,
+    [Format]       NVARCHAR(10) NOT NULL,
+    [ContentType]  NVARCHAR(50) NOT NULL,
+    [Data]         VARBINARY(MAX) NOT NULL,
+    [CreatedTime]  DATETIME NOT NULL CONSTRAINT [DF_AuthUserAvatar_CreatedTime] DEFAULT (GETDATE())
+);
+GO
+
+CREATE UNIQUE INDEX [UX_AuthUserAvatar_Key] ON [dbo].[AuthUserAvatar] ([AvatarKey], [Size], [Format]);
+CREATE INDEX [IX_AuthUserAvatar_AuthUserNID] ON [dbo].[AuthUserAvatar] ([AuthUserNID]);
+GO
+
+-- URL avatar lebih panjang dari path lama
+ALTER TABLE [dbo].[AuthUser] ALTER COLUMN [Picture] NVARCHAR(500) NULL;
+GO
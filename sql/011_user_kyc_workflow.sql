@@ -0,0 +1,38 @@
+-- Workflow onboarding KYC di atas UserKyc.
+-- UserKyc.Stage: 1 personal_data, 2 occupation, 3 bank_account, 4 confirmation, 5 review, 6 approved
+-- Status tambahan tetap memakai IsRejected / IsFinished / IsRevised.
+
+-- Isi tiap tahap (JSON tervalidasi), satu baris per tahap
+CREATE TABLE [dbo].[UserKycData] (
+    [KycNID]      INT NOT NULL,
+    [Stage]       INT NOT NULL,
+    [Data]        NVARCHAR(MAX) NOT NULL,
+    [UpdatedTime] DATETIME NOT NULL CONSTRAINT [DF_UserKycData_UpdatedTime] DEFAULT (GETDATE()),
+    PRIMARY KEY ([KycNID], [Stage])
+);
+
+-- Setiap perpindahan state, termasuk siapa & alasannya
+CREATE TABLE [dbo].[UserKycHistory] (
+    [HistoryNID]  INT IDENTITY(1,1) NOT NULL PRIMARY KEY,
+    [KycNID]      INT NOT NULL,
+    [Action]      NVARCHAR(30) NOT NULL,
+    [FromStage]   INT NOT NULL,
+    [ToStage]     INT NOT NULL,
+    [Status]      NVARCHAR(30) NOT NULL,
+    [Reason]      NVARCHAR(1000) NULL,
+    [ActorNID]    INT NOT NULL,
+    [CreatedTime] DATETIME NOT NULL CONSTRAINT [DF_UserKycHistory_CreatedTime] DEFAULT (GETDATE())
+);
+GO
+
+CREATE INDEX [IX_UserKycHistory_KycNID] ON [dbo].[UserKycHistory] ([KycNID], [HistoryNID]);
+CREATE INDEX [IX_UserKyc_Stage] ON [dbo].[UserKyc] ([Stage], [IsRejected], [IsFinished]) INCLUDE ([LastUpdate]);
+GO
+
+INSERT INTO [dbo].[Permissions] ([PermissionCode], [Description]) VALUES
+    ('kyc:review', 'Review, approve & reject KYC client');
+
+INSERT INTO [dbo].[RolePermission] ([RoleNID], [PermissionNID])
+SELECT r.RoleNID, p.PermissionNID FROM [dbo].[Roles] r CROSS JOIN [dbo].[Permissions] p
+WHERE r.RoleName = 'admin' AND p.PermissionCode = 'kyc:review';
+GO
@@ -0,0 +1,32 @@
+-- Dokumen KYC (foto KTP, selfie, buku tabungan).
+-- Isi file ada di backend DOCUMENT_STORAGE, tabel ini hanya metadata.
+-- Dokumen yang diganti di-soft delete (file-nya dihapus) supaya jejak upload tetap ada.
+CREATE TABLE [dbo].[KycDocument] (
+    [DocumentNID]    INT IDENTITY(1,1) NOT NULL PRIMARY KEY,
+    [KycNID]         INT NOT NULL,
+    [DocumentType]   NVARCHAR(30) NOT NULL,
+    [StorageBackend] NVARCHAR(30) NOT NULL,
+    [StorageKey]     NVARCHAR(200) NOT NULL,
+    [ThumbnailKey]   NVARCHAR(200) NOT NULL,
+    [ContentType]    NVARCHAR(100) NOT NULL,
+    [FileSize]       INT NOT NULL,
+    [Width]          INT NOT NULL,
+    [Height]         INT NOT NULL,
+    [Sha256]         CHAR(64) NOT NULL,
+    [UploadedBy]     INT NOT NULL,
+    [UploadedTime]   DATETIME NOT NULL CONSTRAINT [DF_KycDocument_UploadedTime] DEFAULT (GETDATE()),
+    [IsDeleted]      BIT NOT NULL CONSTRAINT [DF_KycDocument_IsDeleted] DEFAULT (0),
+    [DeletedTime]    DATETIME NULL
+);
+GO
+
+CREATE INDEX [IX_KycDocument_KycNID] ON [dbo].[KycDocument] ([KycNID], [DocumentType]) WHERE [IsDeleted] = 0;
+GO
+
+-- Backend DOCUMENT_STORAGE=database (default)
+CREATE TABLE [dbo].[WEB_StoredFile] (
+    [StorageKey]  NVARCHAR(200) NOT NULL PRIMARY KEY,
+    [Data]        VARBINARY(MAX) NOT NULL,
+    [CreatedTime] DATETIME NOT NULL CONSTRAINT [DF_WEB_StoredFile_CreatedTime] DEFAULT (GETDATE())
+);
+GO
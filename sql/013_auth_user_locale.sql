@@ -0,0 +1,3 @@
+-- Bahasa pesan API pilihan user ('id' / 'en'), NULL = mengikuti header Accept-Language
+ALTER TABLE [dbo].[AuthUser] ADD [PreferredLocale] NVARCHAR(5) NULL;
+GO
@@ -0,0 +1,37 @@
+-- Audit log event keamanan (login, password, role, clear/import/export data).
+-- Append-only: UPDATE & DELETE ditolak trigger, aplikasi hanya melakukan INSERT.
+-- Details berisi JSON bebas per jenis event.
+CREATE TABLE [dbo].[AuditLog] (
+    [AuditLogNID] INT IDENTITY(1,1) NOT NULL PRIMARY KEY,
+    [EventType]   NVARCHAR(50) NOT NULL,
+    [Success]     BIT NOT NULL,
+    [ActorNID]    INT NULL,
+    [ActorEmail]  NVARCHAR(255) NULL,
+    [IPAddress]   NVARCHAR(100) NOT NULL,
+    [UserAgent]   NVARCHAR(512) NOT NULL,
+    [Target]      NVARCHAR(320) NULL,
+    [Details]     NVARCHAR(MAX) NULL CONSTRAINT [CK_AuditLog_Details] CHECK ([Details] IS NULL OR ISJSON([Details]) = 1),
+    [CreatedTime] DATETIME NOT NULL CONSTRAINT [DF_AuditLog_CreatedTime] DEFAULT (GETDATE())
+);
+GO
+
+CREATE INDEX [IX_AuditLog_CreatedTime] ON [dbo].[AuditLog] ([CreatedTime]);
+CREATE INDEX [IX_AuditLog_ActorNID] ON [dbo].[AuditLog] ([ActorNID], [CreatedTime]);
+CREATE INDEX [IX_AuditLog_EventType] ON [dbo].[AuditLog] ([EventType], [CreatedTime]);
+GO
+
+CREATE TRIGGER [dbo].[TR_AuditLog_AppendOnly] ON [dbo].[AuditLog]
+INSTEAD OF UPDATE, DELETE
+AS
+BEGIN
+    THROW 51000, 'AuditLog is append-only', 1;
+END;
+GO
+
+INSERT INTO [dbo].[Permissions] ([PermissionCode], [Description]) VALUES
+    ('audit:read', 'Lihat & export audit log');
+
+INSERT INTO [dbo].[RolePermission] ([RoleNID], [PermissionNID])
+SELECT r.RoleNID, p.PermissionNID FROM [dbo].[Roles] r CROSS JOIN [dbo].[Permissions] p
+WHERE r.RoleName = 'admin' AND p.PermissionCode = 'audit:read';
+GO
@@ -0,0 +1,54 @@
+-- Permintaan hapus akun (hak untuk dilupakan).
+-- Akun dihapus worker setelah ScheduledTime (masa tenggang), selama belum dibatalkan user.
+-- Admin dengan permission account:delete bisa menghapus langsung tanpa masa tenggang.
+CREATE TABLE [dbo].[AuthUserDeletion] (
+    [DeletionNID]   INT IDENTITY(1,1) NOT NULL PRIMARY KEY,
+    [AuthUserNID]   INT NOT NULL,
+    -- User sendiri, atau admin untuk penghapusan langsung
+    [RequestedBy]   INT NOT NULL,
+    -- Alasan dari user, di-NULL-kan saat akun dihapus
+    [Reason]        NVARCHAR(500) NULL,
+    [RequestedTime] DATETIME NOT NULL CONSTRAINT [DF_AuthUserDeletion_RequestedTime] DEFAULT (GETDATE()),
+    [ScheduledTime] DATETIME NOT NULL,
+    [CancelledTime] DATETIME NULL,
+    [CompletedTime] DATETIME NULL
+);
+GO
+
+-- Maksimal satu permintaan aktif per user
+CREATE UNIQUE INDEX [UX_AuthUserDeletion_Pending] ON [dbo].[AuthUserDeletion] ([AuthUserNID])
+    WHERE [CancelledTime] IS NULL AND [CompletedTime] IS NULL;
+CREATE INDEX [IX_AuthUserDeletion_ScheduledTime] ON [dbo].[AuthUserDeletion] ([ScheduledTime])
+    WHERE [CancelledTime] IS NULL AND [CompletedTime] IS NULL;
+GO
+
+-- AuditLog tetap append-only, kecuali anonimisasi data pribadi saat akun dihapus.
+-- Aplikasi menyalakan SESSION_CONTEXT 'audit_anonymize' hanya selama UPDATE tersebut,
+-- dan hanya kolom ActorEmail, IPAddress, UserAgent & Target yang ikut berubah.
+ALTER TRIGGER [dbo].[TR_AuditLog_AppendOnly] ON [dbo].[AuditLog]
+INSTEAD OF UPDATE, DELETE
+AS
+BEGIN
+    IF EXISTS (SELECT 1 FROM inserted) AND CAST(SESSION_CONTEXT(N'audit_anonymize') AS INT) = 1
+    BEGIN
+        UPDATE a SET
+            a.ActorEmail = i.ActorEmail,
+            a.IPAddress = i.IPAddress,
+            a.UserAgent = i.UserAgent,
+            a.Target = i.Target
+        FROM [dbo].[AuditLog] a
+        JOIN inserted i ON i.AuditLogNID = a.AuditLogNID;
+        RETURN;
+    END;
+
+    THROW 51000, 'AuditLog is append-only', 1;
+END;
+GO
+
+INSERT INTO [dbo].[Permissions] ([PermissionCode], [Description]) VALUES
+    ('account:delete', 'Hapus akun user langsung tanpa masa tenggang');
+
+INSERT INTO [dbo].[RolePermission] ([RoleNID], [PermissionNID])
+SELECT r.RoleNID, p.PermissionNID FROM [dbo].[Roles] r CROSS JOIN [dbo].[Permissions] p
+WHERE r.RoleName = 'admin' AND p.PermissionCode = 'account:delete';
+GO
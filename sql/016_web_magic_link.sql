@@ -0,0 +1,17 @@
+-- Link login tanpa password (magic link), hanya hash SHA-256 nonce yang disimpan.
+-- Token di email adalah JWT bertanda tangan berisi nonce, baris ini menandai link sudah dipakai.
+-- Permintaan link baru menghapus link lama yang belum dipakai.
+CREATE TABLE [dbo].[WEB_MagicLink] (
+    [MagicLinkNID] INT IDENTITY(1,1) NOT NULL PRIMARY KEY,
+    [AuthUserNID]  INT NOT NULL,
+    [NonceHash]    NVARCHAR(100) NOT NULL,
+    [ExpiredTime]  DATETIME NOT NULL,
+    [UsedTime]     DATETIME NULL,
+    [IPAddress]    NVARCHAR(100) NULL,
+    [CreatedTime]  DATETIME NOT NULL CONSTRAINT [DF_WEB_MagicLink_CreatedTime] DEFAULT (GETDATE())
+);
+GO
+
+CREATE UNIQUE INDEX [UX_WEB_MagicLink_NonceHash] ON [dbo].[WEB_MagicLink] ([NonceHash]);
+CREATE INDEX [IX_WEB_MagicLink_AuthUserNID] ON [dbo].[WEB_MagicLink] ([AuthUserNID]);
+GO
@@ -0,0 +1,32 @@
+-- Registry tabel / view yang boleh dibuka lewat /data/header & /data/get-table.
+-- Tabel yang tidak terdaftar (atau IsActive = 0) ditolak, seperti tabel yang tidak ada.
+-- AllowedColumns: daftar kolom dipisah koma, kolom lain tidak ikut di SELECT, filter maupun sort.
+-- PrimaryKey: urutan cadangan supaya paging stabil, DefaultSort / DefaultOrder dipakai jika request tanpa sort.
+-- OwnerColumn: jika diisi hanya baris dengan kolom ini = AuthUserNID user login yang tampil,
+-- kecuali user punya BypassPermission.
+CREATE TABLE [dbo].[WEB_DataView] (
+    [ViewName]         NVARCHAR(128) NOT NULL PRIMARY KEY,
+    [AllowedColumns]   NVARCHAR(MAX) NOT NULL,
+    [PermissionCode]   NVARCHAR(100) NOT NULL CONSTRAINT [DF_WEB_DataView_PermissionCode] DEFAULT ('data:read'),
+    [PrimaryKey]       NVARCHAR(128) NOT NULL,
+    [DefaultSort]      NVARCHAR(128) NULL,
+    [DefaultOrder]     VARCHAR(4) NOT NULL CONSTRAINT [DF_WEB_DataView_DefaultOrder] DEFAULT ('DESC'),
+    [OwnerColumn]      NVARCHAR(128) NULL,
+    [BypassPermission] NVARCHAR(100) NULL,
+    [IsActive]         BIT NOT NULL CONSTRAINT [DF_WEB_DataView_IsActive] DEFAULT (1),
+    [Description]      NVARCHAR(255) NULL,
+    CONSTRAINT [CK_WEB_DataView_DefaultOrder] CHECK ([DefaultOrder] IN ('ASC', 'DESC'))
+);
+GO
+
+INSERT INTO [dbo].[WEB_DataView] ([ViewName], [AllowedColumns], [PermissionCode], [PrimaryKey], [DefaultSort], [DefaultOrder], [OwnerColumn], [BypassPermission], [Description]) VALUES
+    ('TempImport',
+     'AutoNID,Email,FullName,Age,Sex,Contact,ProductName,ProductCount,Price,IPAddress,LastUpdate',
+     'data:read', 'AutoNID', 'AutoNID', 'DESC', NULL, NULL,
+     'Hasil import file'),
+    -- Aktivitas keamanan milik user sendiri, auditor (audit:read) melihat semua baris
+    ('AuditLog',
+     'AuditLogNID,EventType,Success,IPAddress,UserAgent,CreatedTime',
+     'data:read', 'AuditLogNID', 'CreatedTime', 'DESC', 'ActorNID', 'audit:read',
+     'Riwayat aktivitas keamanan akun');
+GO
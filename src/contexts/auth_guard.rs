@@ -0,0 +1,237 @@
+use std::rc::Rc;
+use actix_web::{
+    body::MessageBody, dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform}, error, http::header, middleware::Next,
+    web, Error, FromRequest, HttpMessage, HttpRequest, HttpResponse
+};
+use bb8::Pool;
+use bb8_tiberius::ConnectionManager;
+use futures::future::{ready, FutureExt, LocalBoxFuture, Ready};
+
+use crate::services::{api_key_service::ApiKeyService, auth_service::AuthService};
+
+use super::{i18n::t, jwt_session::{validate_jwt, Claims, ACCESS_COOKIE}, model::ActionResult};
+
+/// User yang sudah lolos autentikasi, dipakai sebagai parameter handler
+/// (`user: AuthenticatedUser`) untuk route yang wajib login
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser {
+    pub claims: Claims,
+    /// `true` jika request memakai `Authorization: ApiKey ...`, bukan sesi login
+    pub api_key: bool,
+}
+
+fn unauthorized(error: String) -> Error {
+    let result = ActionResult::<(), _> {
+        result: false,
+        message: t("common.unauthorized"),
+        data: None,
+        error: Some(error),
+    };
+
+    error::InternalError::from_response("Unauthorized", HttpResponse::Unauthorized().json(result)).into()
+}
+
+fn forbidden(permission: &str) -> Error {
+    let result = ActionResult::<(), _> {
+        result: false,
+        message: t("common.forbidden"),
+        data: None,
+        error: Some(format!("Missing permission '{}'", permission)),
+    };
+
+    error::InternalError::from_response("Forbidden", HttpResponse::Forbidden().json(result)).into()
+}
+
+fn session_only() -> Error {
+    let result = ActionResult::<(), _> {
+        result: false,
+        message: t("auth.session_only"),
+        data: None,
+        error: Some("API keys are not accepted on this route".to_string()),
+    };
+
+    error::InternalError::from_response("Forbidden", HttpResponse::Forbidden().json(result)).into()
+}
+
+/// Ambil token dari cookie `snakesystem` atau header `Authorization: Bearer ...`
+pub fn extract_token(req: &HttpRequest) -> Option<String> {
+    if let Some(cookie) = req.cookie(ACCESS_COOKIE) {
+        return Some(cookie.value().to_string());
+    }
+
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// API key dari header `Authorization: ApiKey ssk_...`
+pub fn extract_api_key(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("ApiKey "))
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty())
+}
+
+async fn authenticate_api_key(req: &HttpRequest, connection: web::Data<Pool<ConnectionManager>>, key: String) -> Result<AuthenticatedUser, Error> {
+    match ApiKeyService::authenticate(connection, &key, req).await {
+        response if response.error.is_some() => {
+            Err(error::InternalError::from_response(
+                "Internal Server Error",
+                HttpResponse::InternalServerError().json(response),
+            ).into())
+        },
+        ActionResult { result: true, data: Some(claims), .. } => {
+            Ok(AuthenticatedUser { claims, api_key: true })
+        },
+        response => Err(unauthorized(response.message)),
+    }
+}
+
+/// Validasi API key, atau JWT lalu pastikan sesinya masih ada di `WEB_Cookies`
+pub async fn authenticate(req: &HttpRequest) -> Result<AuthenticatedUser, Error> {
+    let connection = req.app_data::<web::Data<Pool<ConnectionManager>>>()
+        .cloned()
+        .ok_or_else(|| error::ErrorInternalServerError("Database pool not configured"))?;
+
+    if let Some(key) = extract_api_key(req) {
+        return authenticate_api_key(req, connection, key).await;
+    }
+
+    let token = extract_token(req)
+        .ok_or_else(|| unauthorized("Token not found".to_string()))?;
+
+    let claims = validate_jwt(&token)
+        .map_err(|err| unauthorized(err.to_string()))?;
+
+    let session = AuthService::check_session(connection, claims.clone(), token.clone(), "".to_string(), false, false, true).await;
+
+    match session {
+        response if response.error.is_some() => {
+            Err(error::InternalError::from_response(
+                "Internal Server Error",
+                HttpResponse::InternalServerError().json(response),
+            ).into())
+        },
+        response if response.result => Ok(AuthenticatedUser { claims, api_key: false }),
+        response => Err(unauthorized(response.message)),
+    }
+}
+
+/// Middleware untuk scope yang seluruh route-nya wajib login:
+/// `data_scope().wrap(from_fn(require_auth))`
+pub async fn require_auth(req: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let user = authenticate(req.request()).await?;
+    req.extensions_mut().insert(user);
+
+    next.call(req).await
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        // Sudah divalidasi middleware, tidak perlu query ulang
+        if let Some(user) = req.extensions().get::<AuthenticatedUser>() {
+            return ready(Ok(user.clone())).boxed_local();
+        }
+
+        let req = req.clone();
+        async move { authenticate(&req).await }.boxed_local()
+    }
+}
+
+/// User dari sesi login saja, request dengan API key ditolak 403. Dipakai route yang
+/// mengubah sesi, 2FA, profil, key atau data pribadi: `SessionUser(user): SessionUser`
+#[derive(Debug, Clone)]
+pub struct SessionUser(pub AuthenticatedUser);
+
+impl FromRequest for SessionUser {
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let user = AuthenticatedUser::from_request(req, payload);
+
+        async move {
+            match user.await? {
+                user if user.api_key => Err(session_only()),
+                user => Ok(SessionUser(user)),
+            }
+        }
+        .boxed_local()
+    }
+}
+
+/// Guard per route berdasarkan permission di claims, otomatis autentikasi kalau belum:
+/// `#[post("/clear", wrap = "require_permission(\"data:clear\")")]`
+pub fn require_permission(permission: &'static str) -> RequirePermission {
+    RequirePermission { permission }
+}
+
+pub struct RequirePermission {
+    permission: &'static str,
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequirePermission
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequirePermissionMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequirePermissionMiddleware {
+            service: Rc::new(service),
+            permission: self.permission,
+        }))
+    }
+}
+
+pub struct RequirePermissionMiddleware<S> {
+    service: Rc<S>,
+    permission: &'static str,
+}
+
+impl<S, B> Service<ServiceRequest> for RequirePermissionMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let permission = self.permission;
+
+        async move {
+            let existing = req.extensions().get::<AuthenticatedUser>().cloned();
+            let user = match existing {
+                Some(user) => user,
+                None => authenticate(req.request()).await?,
+            };
+
+            if !user.claims.has_permission(permission) {
+                return Err(forbidden(permission));
+            }
+
+            req.extensions_mut().insert(user);
+            service.call(req).await
+        }
+        .boxed_local()
+    }
+}
+
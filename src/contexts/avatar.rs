@@ -0,0 +1,69 @@
+use std::io::Cursor;
+use base64::{engine::general_purpose, Engine as _};
+use image::{imageops::FilterType, DynamicImage, ImageFormat};
+
+use super::{document::decode_image, i18n::t};
+
+/// 🖼️ Ukuran thumbnail avatar (persegi, px), yang pertama dipakai sebagai `AuthUser.Picture`
+pub const AVATAR_SIZES: [u32; 2] = [256, 64];
+
+pub struct AvatarImage {
+    pub size: u32,
+    /// Ekstensi file: `webp` atau `png`
+    pub format: &'static str,
+    pub content_type: &'static str,
+    pub data: Vec<u8>,
+}
+
+pub fn content_type(format: &str) -> Option<&'static str> {
+    match format {
+        "webp" => Some("image/webp"),
+        "png" => Some("image/png"),
+        _ => None,
+    }
+}
+
+/// Decode base64 (sudah lolos `validate_base64_image`), crop tengah jadi persegi,
+/// lalu encode ulang ke WebP + PNG untuk setiap ukuran. Metadata asli (EXIF, GPS) ikut terbuang.
+pub fn render_thumbnails(base64_image: &str) -> Result<Vec<AvatarImage>, String> {
+    let cleaned = base64_image.split(',').next_back().unwrap_or("").trim();
+    let bytes = general_purpose::STANDARD.decode(cleaned).map_err(|_| t("validation.base64"))?;
+
+    // WebP encoder hanya menerima RGB8/RGBA8
+    let source = DynamicImage::ImageRgba8(
+        decode_image(&bytes, &[ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::WebP])?.to_rgba8()
+    );
+
+    let mut thumbnails = Vec::new();
+    for size in AVATAR_SIZES {
+        let resized = source.resize_to_fill(size, size, FilterType::Lanczos3);
+
+        for (format, image_format) in [("webp", ImageFormat::WebP), ("png", ImageFormat::Png)] {
+            let mut data = Cursor::new(Vec::new());
+            resized.write_to(&mut data, image_format).map_err(|_| t("common.internal_error"))?;
+
+            thumbnails.push(AvatarImage {
+                size,
+                format,
+                content_type: content_type(format).unwrap_or("application/octet-stream"),
+                data: data.into_inner(),
+            });
+        }
+    }
+
+    Ok(thumbnails)
+}
+
+/// Nama file dari path `/avatar/{key}/{file}`, contoh `256.webp` -> (256, "webp")
+pub fn parse_file_name(file: &str) -> Option<(u32, &'static str)> {
+    let (size, ext) = file.split_once('.')?;
+    let size: u32 = size.parse().ok()?;
+    if !AVATAR_SIZES.contains(&size) {
+        return None;
+    }
+    match ext {
+        "webp" => Some((size, "webp")),
+        "png" => Some((size, "png")),
+        _ => None,
+    }
+}
@@ -47,4 +47,9 @@ pub fn decrypt_text(encrypted_text: &str, original_text: &str) -> String {
     cipher.apply_keystream(&mut decrypted_data);
 
     String::from_utf8(decrypted_data).expect("Invalid UTF-8")
-}
\ No newline at end of file
+}
+/// #️⃣ Hash token opaque (refresh token, dll) sebelum disimpan ke database
+pub fn hash_token(token: &str) -> String {
+    let hash = Sha256::digest(token.as_bytes());
+    URL_SAFE.encode(hash)
+}
@@ -0,0 +1,205 @@
+use actix_web::{
+    body::MessageBody, cookie::{time, Cookie, SameSite}, dev::{ServiceRequest, ServiceResponse}, error, http::{header, Method}, middleware::Next,
+    Error, HttpRequest, HttpResponse
+};
+use sha2::{Digest, Sha256};
+
+use crate::services::generic_service::GenericService;
+
+use super::{i18n::t, jwt_session::{ACCESS_COOKIE, REFRESH_COOKIE, REFRESH_TOKEN_DAYS}, model::ActionResult};
+
+/// 🛡️ Double-submit cookie: token di cookie `snakesystem_csrf` harus dikirim ulang lewat header `X-CSRF-Token`.
+/// Token diambil dari respon `GET /auth/csrf`, situs lain tidak bisa membacanya karena origin CORS dibatasi
+pub const CSRF_COOKIE: &str = "snakesystem_csrf";
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+const CSRF_TOKEN_LENGTH: usize = 32;
+
+pub fn csrf_cookie(token: String) -> Cookie<'static> {
+    Cookie::build(CSRF_COOKIE, token)
+        .path("/")
+        .http_only(true) // Frontend mengambil token dari body `/auth/csrf`, bukan dari cookie
+        .same_site(SameSite::None)
+        .secure(true)
+        .max_age(time::Duration::days(REFRESH_TOKEN_DAYS))
+        .finish()
+}
+
+/// Token dari cookie jika masih ada, supaya tab lain yang sudah memegang token tidak ikut invalid
+pub fn current_or_new_token(req: &HttpRequest) -> String {
+    req.cookie(CSRF_COOKIE)
+        .map(|cookie| cookie.value().to_string())
+        .filter(|token| token.len() == CSRF_TOKEN_LENGTH)
+        .unwrap_or_else(|| GenericService::random_string(CSRF_TOKEN_LENGTH))
+}
+
+/// Request dengan `Authorization: ApiKey` / `Bearer` tidak memakai cookie sesi, browser tidak mengirimnya otomatis
+fn is_exempt(req: &HttpRequest) -> bool {
+    let authorization = req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+
+    if authorization.starts_with("ApiKey ") {
+        return true;
+    }
+
+    // `extract_token` mendahulukan cookie, jadi Bearer hanya dikecualikan jika cookie sesi tidak ada
+    authorization.starts_with("Bearer ") && req.cookie(ACCESS_COOKIE).is_none() && req.cookie(REFRESH_COOKIE).is_none()
+}
+
+fn tokens_match(expected: &str, actual: &str) -> bool {
+    // Bandingkan hash supaya waktu perbandingan tidak bergantung pada isi token
+    Sha256::digest(expected.as_bytes()) == Sha256::digest(actual.as_bytes())
+}
+
+fn csrf_failed(error: &str) -> Error {
+    let result = ActionResult::<(), _> {
+        result: false,
+        message: t("csrf.invalid"),
+        data: None,
+        error: Some(error.to_string()),
+    };
+
+    error::InternalError::from_response("Forbidden", HttpResponse::Forbidden().json(result)).into()
+}
+
+/// Origin frontend dari secret `CORS_ALLOWED_ORIGINS`, dipakai juga route yang tidak dilindungi CORS seperti handshake WebSocket
+#[derive(Debug, Clone, Default)]
+pub struct AllowedOrigins(pub Vec<String>);
+
+impl AllowedOrigins {
+    /// Browser selalu mengirim `Origin` saat handshake WebSocket, request tanpa `Origin` bukan dari halaman web
+    /// sehingga tidak bisa menumpang cookie sesi korban
+    pub fn allows(&self, req: &HttpRequest) -> bool {
+        match req.headers().get(header::ORIGIN) {
+            None => true,
+            Some(origin) => origin.to_str()
+                .map(|origin| origin.trim_end_matches('/'))
+                .is_ok_and(|origin| self.0.iter().any(|allowed| allowed == origin)),
+        }
+    }
+}
+
+/// Middleware di scope `/api/v1`: semua method selain GET/HEAD/OPTIONS wajib membawa token CSRF yang cocok
+pub async fn require_csrf(req: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let safe_method = matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+
+    if !safe_method && !is_exempt(req.request()) {
+        let cookie = req.cookie(CSRF_COOKIE).map(|cookie| cookie.value().to_string());
+        let header = req.headers()
+            .get(CSRF_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_string());
+
+        match (cookie, header) {
+            (None, _) => return Err(csrf_failed("CSRF cookie not found")),
+            (_, None) => return Err(csrf_failed("CSRF header not found")),
+            (Some(cookie), Some(header)) if !tokens_match(&cookie, &header) => return Err(csrf_failed("CSRF token mismatch")),
+            _ => {}
+        }
+    }
+
+    next.call(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{body, cookie::Cookie, http::StatusCode, middleware::from_fn, test, web, App};
+    use serde_json::Value;
+
+    use super::*;
+
+    const TOKEN: &str = "abcdefghijklmnopqrstuvwxyz012345";
+
+    /// Kirim request lewat middleware, hasilnya status dan kolom `error` (jika ditolak)
+    async fn send(req: test::TestRequest) -> (StatusCode, Option<String>) {
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(require_csrf))
+                .default_service(web::to(HttpResponse::Ok))
+        ).await;
+
+        match test::try_call_service(&app, req.to_request()).await {
+            Ok(res) => (res.status(), None),
+            Err(err) => {
+                let res = err.error_response();
+                let status = res.status();
+                let bytes = body::to_bytes(res.into_body()).await.unwrap();
+                let json: Value = serde_json::from_slice(&bytes).unwrap();
+                (status, json["error"].as_str().map(str::to_string))
+            }
+        }
+    }
+
+    fn post() -> test::TestRequest {
+        test::TestRequest::post().uri("/api/v1/user/profile")
+    }
+
+    #[actix_web::test]
+    async fn origin_must_be_allowed() {
+        let origins = AllowedOrigins(vec!["https://snakesystem.github.io".to_string()]);
+        let with_origin = |origin: &str| test::TestRequest::get().insert_header((header::ORIGIN, origin)).to_http_request();
+
+        assert!(origins.allows(&with_origin("https://snakesystem.github.io")));
+        assert!(origins.allows(&with_origin("https://snakesystem.github.io/")));
+        assert!(!origins.allows(&with_origin("https://evil.example")));
+        assert!(!origins.allows(&with_origin("null")));
+        // Client non-browser tidak mengirim Origin
+        assert!(origins.allows(&test::TestRequest::get().to_http_request()));
+    }
+
+    #[actix_web::test]
+    async fn safe_method_needs_no_token() {
+        let (status, _) = send(test::TestRequest::get().uri("/api/v1/user/profile")).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn matching_header_and_cookie_pass() {
+        let req = post().cookie(Cookie::new(CSRF_COOKIE, TOKEN)).insert_header((CSRF_HEADER, TOKEN));
+        assert_eq!(send(req).await.0, StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn missing_header_is_rejected() {
+        let req = post().cookie(Cookie::new(CSRF_COOKIE, TOKEN));
+        assert_eq!(send(req).await, (StatusCode::FORBIDDEN, Some("CSRF header not found".to_string())));
+    }
+
+    #[actix_web::test]
+    async fn missing_cookie_is_rejected() {
+        let req = post().insert_header((CSRF_HEADER, TOKEN));
+        assert_eq!(send(req).await, (StatusCode::FORBIDDEN, Some("CSRF cookie not found".to_string())));
+    }
+
+    #[actix_web::test]
+    async fn mismatched_header_is_rejected() {
+        let req = post().cookie(Cookie::new(CSRF_COOKIE, TOKEN)).insert_header((CSRF_HEADER, "x".repeat(CSRF_TOKEN_LENGTH)));
+        assert_eq!(send(req).await, (StatusCode::FORBIDDEN, Some("CSRF token mismatch".to_string())));
+    }
+
+    #[actix_web::test]
+    async fn api_key_is_exempt() {
+        let req = post().insert_header((header::AUTHORIZATION, "ApiKey sk_test"));
+        assert_eq!(send(req).await.0, StatusCode::OK);
+
+        // Cookie sesi tidak mengubah pengecualian ApiKey
+        let req = post().insert_header((header::AUTHORIZATION, "ApiKey sk_test")).cookie(Cookie::new(ACCESS_COOKIE, "jwt"));
+        assert_eq!(send(req).await.0, StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn bearer_without_session_cookie_is_exempt() {
+        let req = post().insert_header((header::AUTHORIZATION, "Bearer jwt"));
+        assert_eq!(send(req).await.0, StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn bearer_with_session_cookie_is_not_exempt() {
+        let req = post().insert_header((header::AUTHORIZATION, "Bearer jwt")).cookie(Cookie::new(ACCESS_COOKIE, "jwt"));
+        assert_eq!(send(req).await.0, StatusCode::FORBIDDEN);
+
+        let req = post().insert_header((header::AUTHORIZATION, "Bearer jwt")).cookie(Cookie::new(REFRESH_COOKIE, "refresh"));
+        assert_eq!(send(req).await.0, StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,105 @@
+use std::io::Cursor;
+use image::{codecs::jpeg::JpegEncoder, DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits};
+use serde::Serialize;
+
+use super::i18n::{t, t_args};
+
+/// Batas dimensi gambar asli, mencegah decompression bomb (file kecil, resolusi raksasa)
+const MAX_SOURCE_DIMENSION: u32 = 8000;
+const MAX_DECODE_ALLOC: u64 = 256 * 1024 * 1024;
+
+/// Ukuran maksimal satu file dokumen KYC
+pub const MAX_DOCUMENT_BYTES: usize = 8 * 1024 * 1024;
+
+const DOCUMENT_JPEG_QUALITY: u8 = 90;
+const THUMBNAIL_SIZE: u32 = 400;
+const THUMBNAIL_JPEG_QUALITY: u8 = 75;
+
+/// 📄 Jenis dokumen onboarding, wajib lengkap sebelum KYC dikirim untuk review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KycDocumentType {
+    IdCard,
+    Selfie,
+    BankBook,
+}
+
+pub const REQUIRED_DOCUMENTS: [KycDocumentType; 3] = [KycDocumentType::IdCard, KycDocumentType::Selfie, KycDocumentType::BankBook];
+
+impl KycDocumentType {
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        match slug {
+            "id_card" => Some(Self::IdCard),
+            "selfie" => Some(Self::Selfie),
+            "bank_book" => Some(Self::BankBook),
+            _ => None,
+        }
+    }
+
+    pub fn slug(self) -> &'static str {
+        match self {
+            Self::IdCard => "id_card",
+            Self::Selfie => "selfie",
+            Self::BankBook => "bank_book",
+        }
+    }
+}
+
+/// Decode dengan batas dimensi/alokasi, format dideteksi dari magic bytes (bukan ekstensi / Content-Type).
+/// Orientasi EXIF diterapkan ke pixel dulu karena metadata-nya akan dibuang saat encode ulang.
+pub fn decode_image(bytes: &[u8], allowed: &[ImageFormat]) -> Result<DynamicImage, String> {
+    let format = image::guess_format(bytes).map_err(|_| t("validation.image_invalid"))?;
+    if !allowed.contains(&format) {
+        return Err(t("validation.image_format"));
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC);
+
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits);
+
+    let mut decoder = reader.into_decoder().map_err(|_| t("validation.image_invalid"))?;
+    let orientation = decoder.orientation().map_err(|_| t("validation.image_invalid"))?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|_| t("validation.image_invalid"))?;
+    image.apply_orientation(orientation);
+
+    Ok(image)
+}
+
+fn encode_jpeg(image: &DynamicImage, quality: u8) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    // JPEG tidak punya alpha channel
+    let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+    rgb.write_with_encoder(JpegEncoder::new_with_quality(&mut data, quality))
+        .map_err(|_| t("common.internal_error"))?;
+    Ok(data)
+}
+
+pub struct ProcessedDocument {
+    pub data: Vec<u8>,
+    pub thumbnail: Vec<u8>,
+    pub content_type: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Foto dokumen di-encode ulang ke JPEG: EXIF (lokasi GPS, model HP, dsb) dan data tersembunyi
+/// setelah marker akhir file ikut terbuang. Thumbnail kecil untuk daftar reviewer.
+pub fn process_document(bytes: &[u8]) -> Result<ProcessedDocument, String> {
+    if bytes.len() > MAX_DOCUMENT_BYTES {
+        return Err(t_args("document.too_large", &[("max", &(MAX_DOCUMENT_BYTES / 1024 / 1024))]));
+    }
+
+    let image = decode_image(bytes, &[ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::WebP])?;
+
+    Ok(ProcessedDocument {
+        data: encode_jpeg(&image, DOCUMENT_JPEG_QUALITY)?,
+        thumbnail: encode_jpeg(&image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE), THUMBNAIL_JPEG_QUALITY)?,
+        content_type: "image/jpeg",
+        width: image.width(),
+        height: image.height(),
+    })
+}
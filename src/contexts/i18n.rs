@@ -0,0 +1,354 @@
+use std::{collections::HashMap, fmt::Display, future::Future};
+use actix_web::{
+    body::MessageBody, dev::{ServiceRequest, ServiceResponse}, http::header::{self, HeaderValue}, middleware::Next, Error, HttpMessage
+};
+use once_cell::sync::Lazy;
+
+use super::{auth_guard::extract_token, jwt_session::validate_jwt};
+
+/// 🌐 Bahasa response API. Pilihan user (`AuthUser.PreferredLocale`, ikut di claims JWT)
+/// didahulukan, lalu header `Accept-Language`, terakhir `DEFAULT_LOCALE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    Id,
+    En,
+}
+
+pub const DEFAULT_LOCALE: Locale = Locale::En;
+
+impl Locale {
+    /// `id`, `id-ID`, `in` (kode lama Indonesia), `en`, `en-US`, dst
+    pub fn from_code(code: &str) -> Option<Self> {
+        let primary = code.trim().split(['-', '_']).next().unwrap_or("").to_ascii_lowercase();
+        match primary.as_str() {
+            "id" | "in" => Some(Self::Id),
+            "en" => Some(Self::En),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::En => "en",
+        }
+    }
+
+    /// Bahasa yang didukung dengan nilai `q` tertinggi, contoh `id-ID,id;q=0.9,en;q=0.8`
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        let mut best: Option<(Self, f32)> = None;
+        for part in header.split(',') {
+            let mut pieces = part.split(';');
+            let Some(locale) = pieces.next().and_then(Self::from_code) else {
+                continue;
+            };
+            let quality = pieces
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+                best = Some((locale, quality));
+            }
+        }
+        best.map(|(locale, _)| locale)
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_LOCALE: Locale;
+}
+
+/// Locale request yang sedang diproses, `DEFAULT_LOCALE` di luar request (background task, startup)
+pub fn current_locale() -> Locale {
+    CURRENT_LOCALE.try_with(|locale| *locale).unwrap_or(DEFAULT_LOCALE)
+}
+
+/// Jalankan future dengan locale tertentu, dipakai untuk `tokio::spawn` yang masih mengirim pesan ke user
+pub async fn with_locale<F: Future>(locale: Locale, future: F) -> F::Output {
+    CURRENT_LOCALE.scope(locale, future).await
+}
+
+/// Versi sinkron untuk closure `web::block`
+pub fn in_locale<R>(locale: Locale, f: impl FnOnce() -> R) -> R {
+    CURRENT_LOCALE.sync_scope(locale, f)
+}
+
+/// Middleware di scope `/api/v1`, harus paling luar supaya error auth/validasi ikut diterjemahkan
+pub async fn resolve_locale(req: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    // Token cukup dicek signature-nya, hanya untuk membaca preferensi bahasa
+    let preferred = extract_token(req.request())
+        .and_then(|token| validate_jwt(&token).ok())
+        .and_then(|claims| claims.locale)
+        .and_then(|code| Locale::from_code(&code));
+
+    let locale = preferred
+        .or_else(|| req.headers().get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Locale::from_accept_language))
+        .unwrap_or(DEFAULT_LOCALE);
+
+    req.extensions_mut().insert(locale);
+
+    let mut res = with_locale(locale, next.call(req)).await?;
+    res.headers_mut().insert(header::CONTENT_LANGUAGE, HeaderValue::from_static(locale.code()));
+    res.headers_mut().append(header::VARY, HeaderValue::from_static("Accept-Language"));
+    Ok(res)
+}
+
+/// Katalog pesan: (message ID, Indonesia, English). Placeholder `{nama}` diisi lewat `t_args`.
+const CATALOG: &[(&str, &str, &str)] = &[
+    // Umum
+    ("common.internal_error", "Terjadi kesalahan pada server", "Internal server error"),
+    ("common.connection_failed", "Gagal terhubung ke database", "Connection failed"),
+    ("common.invalid_request", "Request tidak valid", "Invalid request"),
+    ("common.not_found", "Tidak ditemukan", "Not found"),
+    ("common.data_retrieved", "Data berhasil diambil", "Data retrieved successfully"),
+    ("common.data_not_found", "Data tidak ditemukan", "Data not found"),
+    ("common.nothing_to_update", "Tidak ada data yang diubah", "Nothing to update"),
+    ("common.unauthorized", "Silakan login terlebih dahulu", "Unauthorized"),
+    ("common.forbidden", "Anda tidak memiliki akses", "Forbidden"),
+    ("auth.session_only", "Hanya bisa diakses dari sesi login, bukan API key", "Only available from a login session, not with an API key"),
+    ("csrf.invalid", "Token CSRF tidak valid, muat ulang halaman lalu coba lagi", "Invalid CSRF token, reload the page and try again"),
+    ("csrf.issued", "Token CSRF dibuat", "CSRF token issued"),
+    ("common.query_failed", "Gagal mengambil data", "Failed to query"),
+    ("common.too_many_attempts", "Terlalu banyak percobaan gagal, coba lagi dalam {minutes} menit", "Too many failed attempts, please try again in {minutes} minute(s)"),
+
+    // Validasi
+    ("validation.failed", "Data tidak valid", "Validation failed"),
+    ("validation.valid", "Valid", "Valid"),
+    ("validation.invalid", "{field} tidak valid", "{field} is invalid"),
+    ("validation.required", "{field} wajib diisi", "{field} is required"),
+    ("validation.email", "Format email tidak valid", "Invalid email format"),
+    ("validation.length_max", "{field} maksimal {max} karakter", "{field} max {max} characters"),
+    ("validation.length_min", "{field} minimal {min} karakter", "{field} min {min} characters"),
+    ("validation.name", "{field} hanya boleh berisi huruf dan spasi", "{field} may only contain letters and spaces"),
+    ("validation.password", "Password minimal 8 karakter, berisi huruf dan angka", "Password must be at least 8 characters with letters and numbers"),
+    ("validation.digits", "{field} hanya boleh berisi angka", "{field} must contain digits only"),
+    ("validation.phone", "Nomor telepon Indonesia tidak valid", "Invalid Indonesian phone number"),
+    ("validation.base64", "Base64 tidak valid", "Invalid base64"),
+    ("validation.image_too_large", "Ukuran file maksimal {max}MB", "File size max {max}MB"),
+    ("validation.image_invalid", "File bukan gambar yang valid", "File is not a valid image"),
+    ("validation.image_format", "Format gambar harus JPEG, PNG, atau WebP", "Image format must be JPEG, PNG or WebP"),
+    ("validation.nik_length", "NIK harus 16 digit", "NIK must be 16 digits"),
+    ("validation.nik_region", "Kode wilayah NIK tidak valid", "NIK region code is not valid"),
+    ("validation.nik_sequence", "Nomor urut NIK tidak valid", "NIK sequence number is not valid"),
+    ("validation.nik_birth_date", "Tanggal lahir pada NIK tidak valid", "NIK birth date is not valid"),
+    ("validation.nik_birth_date_mismatch", "Tanggal lahir tidak sesuai dengan NIK", "Birth date does not match NIK"),
+    ("validation.nik_gender_mismatch", "Jenis kelamin tidak sesuai dengan NIK", "Gender does not match NIK"),
+    ("validation.gender", "Jenis kelamin harus male atau female", "Gender must be male or female"),
+    ("validation.npwp_digits", "NPWP hanya boleh berisi angka", "NPWP must contain digits only"),
+    ("validation.npwp_length", "NPWP harus 15 atau 16 digit", "NPWP must be 15 or 16 digits"),
+    ("validation.npwp_nik", "NPWP 16 digit harus berupa NIK yang valid", "NPWP is not a valid NIK"),
+    ("validation.npwp_check_digit", "Check digit NPWP tidak valid", "NPWP check digit is not valid"),
+    ("validation.bank_code", "Kode bank tidak didukung", "Bank code is not supported"),
+    ("validation.bank_account", "Nomor rekening {bank} harus {lengths} digit", "{bank} account number must be {lengths} digits"),
+    ("validation.locale", "Bahasa harus id atau en", "Language must be id or en"),
+    ("validation.unknown_field", "Field tidak dikenal, gunakan nik, npwp, phone atau bank_account", "Unknown field, use nik, npwp, phone or bank_account"),
+    ("validation.banks", "Daftar bank yang didukung", "Supported banks"),
+
+    // Auth
+    ("auth.welcome", "Selamat datang {email}", "Welcome {email}"),
+    ("auth.invalid_credentials", "Email atau password salah", "Invalid email or password"),
+    ("auth.user_not_found", "User tidak ditemukan", "User not found"),
+    ("auth.logout", "Logout berhasil, cookie dihapus", "Logout successful, cookie deleted"),
+    ("auth.mfa_required", "Verifikasi dua langkah diperlukan", "Two-factor authentication required"),
+    ("auth.magic_link_sent", "Jika email terdaftar, link login telah dikirim", "If the email is registered, a login link has been sent"),
+    ("auth.magic_link_invalid", "Link login tidak valid, kedaluwarsa atau sudah dipakai", "Invalid, expired or already used login link"),
+    ("auth.mfa_expired", "Sesi verifikasi dua langkah kedaluwarsa, silakan login ulang", "Two-factor session expired, please login again"),
+    ("auth.provider_unknown", "Provider login tidak dikenal", "Unknown login provider"),
+    ("auth.provider_cancelled", "Login dibatalkan atau ditolak oleh provider", "Login cancelled or rejected by provider"),
+    ("auth.provider_failed", "Gagal memverifikasi login dengan provider", "Failed to verify login with provider"),
+    ("auth.provider_state_invalid", "Sesi login tidak valid atau kedaluwarsa, silakan coba lagi", "Login session is invalid or has expired, please try again"),
+    ("auth.provider_email_unverified", "Email dari provider belum terverifikasi", "Email from provider is not verified"),
+    ("auth.provider_no_account", "Belum ada akun untuk {email}, silakan daftar terlebih dahulu", "No account registered for {email}, please register first"),
+    ("auth.registered", "Registrasi berhasil, silakan cek email untuk aktivasi akun", "User registered successfully, please check your email to activate your account"),
+    ("auth.registered_mail_failed", "Registrasi berhasil, tetapi email aktivasi gagal dikirim. Silakan minta link aktivasi baru", "User registered successfully, but the activation email could not be sent. Please request a new activation link"),
+    ("auth.activated", "Aktivasi akun berhasil", "Activation successfully"),
+    ("auth.activation_expired", "Link aktivasi kedaluwarsa, silakan minta yang baru", "Activation link has expired, please request a new one"),
+    ("auth.activation_invalid", "Link aktivasi tidak valid atau sudah dipakai", "Invalid or already used activation link"),
+    ("auth.activation_resent", "Jika akun menunggu aktivasi, link aktivasi baru telah dikirim", "If the account is waiting for activation, a new activation link has been sent"),
+    ("auth.reset_sent", "Jika email terdaftar, link reset password telah dikirim", "If the email is registered, a reset password link has been sent"),
+    ("auth.reset_expired", "Link reset password kedaluwarsa, silakan minta yang baru", "Reset password link has expired, please request a new one"),
+    ("auth.reset_invalid", "Link reset password tidak valid atau sudah dipakai", "Invalid or already used reset password link"),
+    ("auth.password_reset", "Password berhasil diubah", "Change password successfully"),
+    ("auth.active_login", "User sedang login", "User active login"),
+    ("auth.session_expired", "Sesi telah kedaluwarsa", "Session has expired."),
+    ("auth.session_revoked", "Sesi telah dicabut", "Session has been revoked."),
+    ("auth.session_not_found", "Sesi tidak ditemukan", "Session not found."),
+    ("auth.refresh_created", "Refresh token dibuat", "Refresh token created"),
+    ("auth.refresh_invalid", "Refresh token tidak valid", "Invalid refresh token"),
+    ("auth.refresh_expired", "Refresh token kedaluwarsa", "Refresh token has expired"),
+    ("auth.refresh_reuse", "Refresh token dipakai ulang, semua sesi dalam keluarga ini telah dicabut", "Refresh token reuse detected, all sessions in this family have been revoked"),
+    ("auth.refresh_revoked", "Refresh token dicabut", "Refresh token revoked"),
+    ("auth.session_refreshed", "Sesi diperbarui", "Session refreshed"),
+
+    // Sesi per device
+    ("session.created", "Sesi dibuat", "Session created"),
+    ("session.list", "Sesi aktif", "Active sessions"),
+    ("session.renamed", "Nama sesi diubah", "Session renamed"),
+    ("session.not_found", "Sesi tidak ditemukan", "Session not found"),
+    ("session.revoked", "Sesi dicabut", "Session revoked"),
+    ("session.others_revoked", "{count} sesi lain dicabut", "{count} other session(s) revoked"),
+
+    // MFA
+    ("mfa.already_enabled", "Verifikasi dua langkah sudah aktif", "Two-factor authentication is already enabled"),
+    ("mfa.enroll_started", "Scan QR code, lalu konfirmasi dengan kode pertama", "Scan the QR code, then confirm with the first code"),
+    ("mfa.enroll_not_started", "Pendaftaran verifikasi dua langkah belum dimulai", "Two-factor enrollment not started"),
+    ("mfa.invalid_code", "Kode verifikasi salah", "Invalid verification code"),
+    ("mfa.enabled", "Verifikasi dua langkah aktif, simpan recovery code di tempat aman", "Two-factor authentication enabled, store the recovery codes safely"),
+    ("mfa.not_enabled", "Verifikasi dua langkah belum aktif", "Two-factor authentication is not enabled"),
+    ("mfa.code_verified", "Kode terverifikasi", "Code verified"),
+    ("mfa.recovery_accepted", "Recovery code diterima", "Recovery code accepted"),
+    ("mfa.disabled", "Verifikasi dua langkah dinonaktifkan", "Two-factor authentication disabled"),
+
+    // API key
+    ("api_key.scope_required", "Minimal satu scope wajib diisi", "At least one scope is required"),
+    ("api_key.scope_forbidden", "Anda tidak memiliki permission '{scope}'", "You don't have permission '{scope}'"),
+    ("api_key.expiry_range", "Masa berlaku harus antara 1 dan {max} hari", "Expiry must be between 1 and {max} days"),
+    ("api_key.created", "API key dibuat, salin sekarang karena tidak akan ditampilkan lagi", "API key created, copy it now because it will not be shown again"),
+    ("api_key.list", "Daftar API key", "API keys"),
+    ("api_key.revoked", "API key dicabut", "API key revoked"),
+    ("api_key.not_found", "API key tidak ditemukan", "API key not found"),
+    ("api_key.invalid", "API key tidak valid", "Invalid API key"),
+    ("api_key.invalid_or_expired", "API key tidak valid atau kedaluwarsa", "Invalid or expired API key"),
+
+    // Profil
+    ("profile.updated", "Profil berhasil diubah", "Profile updated successfully"),
+    ("profile.password_incorrect", "Password saat ini salah", "Current password is incorrect"),
+    ("profile.password_same", "Password baru harus berbeda dari password saat ini", "New password must be different from the current password"),
+    ("profile.password_changed", "Password berhasil diubah, sesi lain telah dikeluarkan", "Password changed successfully, other sessions have been signed out"),
+    ("profile.avatar_updated", "Foto profil berhasil diubah", "Avatar updated successfully"),
+    ("profile.avatar_not_found", "Foto profil tidak ditemukan", "Avatar not found"),
+
+    // Account deletion & export
+    ("account.deletion_requested", "Akun akan dihapus permanen pada {date}, batalkan sebelum waktu tersebut jika berubah pikiran", "Your account will be permanently deleted on {date}, cancel before then if you change your mind"),
+    ("account.deletion_pending", "Akun dijadwalkan dihapus pada {date}", "Account deletion is scheduled for {date}"),
+    ("account.deletion_none", "Tidak ada permintaan hapus akun", "No pending account deletion"),
+    ("account.deletion_cancelled", "Permintaan hapus akun dibatalkan", "Account deletion cancelled"),
+    ("account.deleted", "Akun dan seluruh data pribadi telah dihapus", "Account and all personal data have been deleted"),
+    ("account.delete_self", "Gunakan /auth/me/deletion untuk menghapus akun sendiri", "Use /auth/me/deletion to delete your own account"),
+
+    // KYC
+    ("kyc.not_found", "Data KYC tidak ditemukan", "KYC data not found"),
+    ("kyc.unknown_stage", "Tahap KYC tidak dikenal", "Unknown KYC stage"),
+    ("kyc.stage_saved", "Tahap '{stage}' disimpan", "Stage '{stage}' saved"),
+    ("kyc.submitted", "KYC dikirim untuk direview", "KYC submitted for review"),
+    ("kyc.approved", "KYC disetujui", "KYC approved"),
+    ("kyc.rejected", "KYC ditolak", "KYC rejected"),
+    ("kyc.revision_requested", "Revisi diminta untuk tahap '{stage}'", "Revision requested for stage '{stage}'"),
+    ("kyc.stage_not_submittable", "Tahap '{stage}' tidak bisa dikirim", "Stage '{stage}' cannot be submitted"),
+    ("kyc.locked", "Data KYC sudah tidak bisa diubah", "KYC data can no longer be changed"),
+    ("kyc.stage_incomplete", "Lengkapi tahap '{stage}' terlebih dahulu", "Please complete stage '{stage}' first"),
+    ("kyc.data_incomplete", "Data KYC belum lengkap", "KYC data is not complete yet"),
+    ("kyc.not_in_review", "KYC tidak sedang menunggu review", "KYC is not waiting for review"),
+    ("kyc.stage_not_revisable", "Tahap '{stage}' tidak bisa direvisi", "Stage '{stage}' cannot be revised"),
+    ("kyc.own_review", "Anda tidak bisa mereview KYC milik sendiri", "You cannot review your own KYC"),
+    ("kyc.documents_missing", "Silakan upload: {documents}", "Please upload: {documents}"),
+
+    // Dokumen KYC
+    ("document.unknown_type", "Jenis dokumen tidak dikenal", "Unknown document type"),
+    ("document.uploaded", "Dokumen '{document}' berhasil diupload", "Document '{document}' uploaded"),
+    ("document.locked", "Dokumen KYC sudah tidak bisa diubah", "KYC documents can no longer be changed"),
+    ("document.link_invalid", "Link download tidak valid atau kedaluwarsa", "Download link is invalid or has expired"),
+    ("document.not_found", "Dokumen tidak ditemukan", "Document not found"),
+    ("document.too_large", "File terlalu besar, maksimal {max}MB", "File too large, maximum {max}MB"),
+    ("document.no_file", "Tidak ada file yang diupload", "No file uploaded"),
+    ("document.read_failed", "Gagal membaca file", "Failed to read file"),
+    ("document.read_field_failed", "Gagal membaca field", "Failed to read field"),
+
+    // Role
+    ("role.user_not_found", "User {user} tidak ditemukan", "User {user} not found"),
+    ("role.not_found", "Role '{role}' tidak ditemukan", "Role '{role}' not found"),
+    ("role.assigned", "Role '{role}' diberikan ke user {user}", "Role '{role}' assigned to user {user}"),
+    ("role.revoked", "Role '{role}' dicabut dari user {user}", "Role '{role}' revoked from user {user}"),
+    ("role.not_assigned", "User {user} tidak memiliki role '{role}'", "User {user} does not have role '{role}'"),
+    ("role.last_admin", "Admin terakhir tidak bisa dicabut", "Cannot revoke the last admin"),
+
+    // Import
+    ("import.uploaded", "File {format} berhasil diupload, sedang diproses", "{format} file uploaded, processing in background"),
+    ("import.file_not_found", "File tidak ditemukan", "File not found"),
+    ("import.no_file", "Tidak ada file {format} yang diupload", "No {format} file uploaded"),
+    ("import.file_open_error", "Gagal membuka file", "File open error"),
+    ("import.parse_error", "Format {format} tidak valid", "{format} parse error"),
+    ("import.delimiter_unknown", "Delimiter tidak dikenali", "Unknown delimiter"),
+    ("import.column_count", "Baris {line} harus punya {count} kolom", "Line {line} must have {count} columns"),
+    ("import.not_number", "Baris {line}: {column} bukan angka", "Line {line}: {column} is not a number"),
+    ("import.row_failed", "Baris ke-{line} gagal insert", "Failed to insert line {line}"),
+    ("import.worksheet_not_found", "Worksheet tidak ditemukan atau error", "Worksheet not found or unreadable"),
+    ("import.insert_error", "Gagal menyimpan data", "Insert error"),
+    ("import.commit_failed", "Gagal menyimpan transaksi", "Failed to commit"),
+    ("import.inserted", "Berhasil insert {count} baris", "{count} row(s) inserted"),
+    ("import.nothing_inserted", "Tidak ada data yang di-insert", "No data inserted"),
+    ("import.done", "Import selesai", "Import finished"),
+    ("import.upload_failed", "Gagal menyimpan file upload", "Failed to save uploaded file"),
+
+    // Export
+    ("export.success", "Export {format} berhasil", "Export {format} successful"),
+    ("export.row_failed", "Gagal membaca data", "Error fetching row"),
+    ("export.dir_failed", "Gagal membuat folder", "Failed to create directory"),
+    ("export.write_failed", "Gagal menulis file", "Failed to write file"),
+    ("export.pdf_failed", "Gagal membuat PDF", "Failed to generate PDF"),
+    ("export.read_failed", "Gagal membaca file {format}", "Failed to read {format} file"),
+
+    // Email
+    ("mail.sent", "Email berhasil dikirim", "Email sent successfully"),
+    ("mail.send_failed", "Email gagal dikirim", "Failed to send email"),
+    ("mail.limit_exceeded", "Batas pengiriman email sudah tercapai", "Has exceeded the limit"),
+    ("mail.lockout_subject", "Akun kamu dikunci sementara", "Your account has been temporarily locked"),
+    ("mail.lockout_message", "Kami mendeteksi beberapa percobaan login gagal ke akun kamu (terakhir dari IP {ip}). Login dikunci selama {minutes} menit. Jika ini bukan kamu, segera reset password.", "We detected several failed login attempts to your account (last from IP {ip}). Login is locked for {minutes} minute(s). If this wasn't you, please reset your password."),
+    ("mail.activation_subject", "Aktivasi akun Snakesystem", "Activate your Snakesystem account"),
+    ("mail.activation_message", "Terima kasih sudah mendaftar. Klik tombol di bawah untuk mengaktifkan akun kamu.", "Thanks for signing up. Click the button below to activate your account."),
+    ("mail.activation_button", "Aktivasi Akun", "Activate Account"),
+    ("mail.reset_subject", "Reset password Snakesystem", "Reset your Snakesystem password"),
+    ("mail.reset_message", "Kami menerima permintaan untuk mengganti password akun kamu. Klik tombol di bawah untuk membuat password baru.", "We received a request to change your account password. Click the button below to create a new password."),
+    ("mail.reset_button", "Reset Password", "Reset Password"),
+    ("mail.expires_minutes", "{minutes} menit", "{minutes} minute(s)"),
+    ("mail.expires_hours", "{hours} jam", "{hours} hour(s)"),
+    ("mail.magic_link_subject", "Login ke Snakesystem", "Sign in to Snakesystem"),
+    ("mail.magic_link_message", "Klik tombol di bawah untuk login tanpa password. Link hanya bisa dipakai sekali. Permintaan dari IP {ip}, abaikan email ini jika bukan kamu.", "Click the button below to sign in without a password. The link can only be used once. Requested from IP {ip}, ignore this email if it wasn't you."),
+    ("mail.magic_link_button", "Login", "Sign in"),
+
+    // Library & data
+    ("library.note_created", "Catatan berhasil dibuat", "Notes created successfully"),
+    ("library.category_empty", "Kategori wajib diisi", "Category is empty"),
+    ("library.slug_empty", "Slug wajib diisi", "Slug is empty"),
+    ("data.parse_failed", "Gagal membaca data JSON", "Failed to parse JSON"),
+    ("data.cleared", "Data berhasil dibersihkan", "Data cleared successfully"),
+    ("data.view_unavailable", "Tabel tidak tersedia", "Table is not available"),
+    ("generic.company", "Nama perusahaan", "Company name"),
+    ("generic.company_not_found", "Perusahaan tidak ditemukan", "No company found"),
+];
+
+static MESSAGES: Lazy<HashMap<&'static str, (&'static str, &'static str)>> = Lazy::new(|| {
+    CATALOG.iter().map(|(key, id, en)| (*key, (*id, *en))).collect()
+});
+
+pub fn is_message_id(key: &str) -> bool {
+    MESSAGES.contains_key(key)
+}
+
+/// Terjemahan dalam locale tertentu, ID yang tidak ada di katalog dikembalikan apa adanya
+pub fn translate(locale: Locale, key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let Some((id, en)) = MESSAGES.get(key) else {
+        return key.to_string();
+    };
+
+    let mut message = match locale {
+        Locale::Id => id.to_string(),
+        Locale::En => en.to_string(),
+    };
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    message
+}
+
+/// Pesan dalam locale request saat ini: `result.message = t("auth.invalid_credentials")`
+pub fn t(key: &str) -> String {
+    translate(current_locale(), key, &[])
+}
+
+/// `t_args("import.inserted", &[("count", &rows)])`
+pub fn t_args(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    translate(current_locale(), key, args)
+}
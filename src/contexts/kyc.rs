@@ -0,0 +1,321 @@
+use serde::Serialize;
+
+use super::i18n::{t, t_args};
+
+/// 🪪 Tahapan onboarding KYC, nilainya sama dengan kolom `UserKyc.Stage`
+/// (`register` membuat baris baru di `PersonalData` = 1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KycStage {
+    PersonalData = 1,
+    Occupation = 2,
+    BankAccount = 3,
+    /// Semua data terisi, menunggu client mengirim untuk direview
+    Confirmation = 4,
+    Review = 5,
+    Approved = 6,
+}
+
+/// Tahap yang diisi client, urut
+pub const DATA_STAGES: [KycStage; 3] = [KycStage::PersonalData, KycStage::Occupation, KycStage::BankAccount];
+
+impl KycStage {
+    pub fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            1 => Some(Self::PersonalData),
+            2 => Some(Self::Occupation),
+            3 => Some(Self::BankAccount),
+            4 => Some(Self::Confirmation),
+            5 => Some(Self::Review),
+            6 => Some(Self::Approved),
+            _ => None,
+        }
+    }
+
+    /// Nama tahap di URL, contoh `/kyc/stages/personal_data`
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        match slug {
+            "personal_data" => Some(Self::PersonalData),
+            "occupation" => Some(Self::Occupation),
+            "bank_account" => Some(Self::BankAccount),
+            _ => None,
+        }
+    }
+
+    pub fn slug(self) -> &'static str {
+        match self {
+            Self::PersonalData => "personal_data",
+            Self::Occupation => "occupation",
+            Self::BankAccount => "bank_account",
+            Self::Confirmation => "confirmation",
+            Self::Review => "review",
+            Self::Approved => "approved",
+        }
+    }
+
+    pub fn is_data_stage(self) -> bool {
+        DATA_STAGES.contains(&self)
+    }
+}
+
+/// Status gabungan dari `Stage`, `IsRejected`, `IsFinished`, `IsRevised`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KycStatus {
+    Draft,
+    RevisionRequested,
+    InReview,
+    Approved,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KycState {
+    pub stage: KycStage,
+    pub is_rejected: bool,
+    pub is_finished: bool,
+    pub is_revised: bool,
+}
+
+/// Aksi yang menggerakkan state, dicatat di `UserKycHistory.Action`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KycAction {
+    /// Client mengisi / memperbaiki data satu tahap
+    Submit(KycStage),
+    /// Client mengirim semua data untuk direview
+    SubmitForReview,
+    Approve,
+    Reject,
+    /// Reviewer mengembalikan ke tahap tertentu
+    RequestRevision(KycStage),
+}
+
+impl KycAction {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Submit(_) => "submit",
+            Self::SubmitForReview => "submit_for_review",
+            Self::Approve => "approve",
+            Self::Reject => "reject",
+            Self::RequestRevision(_) => "request_revision",
+        }
+    }
+}
+
+impl KycState {
+    pub fn status(&self) -> KycStatus {
+        if self.is_rejected {
+            KycStatus::Rejected
+        } else if self.is_finished || self.stage == KycStage::Approved {
+            KycStatus::Approved
+        } else if self.stage == KycStage::Review {
+            KycStatus::InReview
+        } else if self.is_revised {
+            KycStatus::RevisionRequested
+        } else {
+            KycStatus::Draft
+        }
+    }
+
+    /// Client masih boleh mengubah data
+    pub fn is_editable(&self) -> bool {
+        matches!(self.status(), KycStatus::Draft | KycStatus::RevisionRequested)
+    }
+
+    /// Terapkan aksi, `completed` = tahap data yang sudah pernah diisi (termasuk yang baru disubmit).
+    /// Tahap berikutnya adalah tahap data pertama yang belum terisi, atau `Confirmation` jika semua lengkap.
+    pub fn apply(&self, action: KycAction, completed: &[KycStage]) -> Result<KycState, String> {
+        let mut next = *self;
+
+        match action {
+            KycAction::Submit(stage) => {
+                if !stage.is_data_stage() {
+                    return Err(t_args("kyc.stage_not_submittable", &[("stage", &stage.slug())]));
+                }
+                if !self.is_editable() {
+                    return Err(t("kyc.locked"));
+                }
+                // Tidak boleh loncat tahap, tahap sebelumnya boleh diperbaiki
+                if stage > self.stage {
+                    return Err(t_args("kyc.stage_incomplete", &[("stage", &self.stage.slug())]));
+                }
+                next.stage = DATA_STAGES
+                    .iter()
+                    .copied()
+                    .find(|s| *s != stage && !completed.contains(s))
+                    .unwrap_or(KycStage::Confirmation);
+            }
+            KycAction::SubmitForReview => {
+                if !self.is_editable() || self.stage != KycStage::Confirmation {
+                    return Err(t("kyc.data_incomplete"));
+                }
+                next.stage = KycStage::Review;
+                next.is_revised = false;
+            }
+            KycAction::Approve => {
+                if self.status() != KycStatus::InReview {
+                    return Err(t("kyc.not_in_review"));
+                }
+                next.stage = KycStage::Approved;
+                next.is_finished = true;
+            }
+            KycAction::Reject => {
+                if self.status() != KycStatus::InReview {
+                    return Err(t("kyc.not_in_review"));
+                }
+                next.is_rejected = true;
+            }
+            KycAction::RequestRevision(stage) => {
+                if self.status() != KycStatus::InReview {
+                    return Err(t("kyc.not_in_review"));
+                }
+                if !stage.is_data_stage() {
+                    return Err(t_args("kyc.stage_not_revisable", &[("stage", &stage.slug())]));
+                }
+                next.stage = stage;
+                next.is_revised = true;
+            }
+        }
+
+        Ok(next)
+    }
+
+    /// Aksi client yang valid dari state ini, untuk ditampilkan di frontend
+    pub fn client_actions(&self) -> Vec<String> {
+        if !self.is_editable() {
+            return vec![];
+        }
+
+        let mut actions: Vec<String> = DATA_STAGES
+            .iter()
+            .filter(|stage| **stage <= self.stage)
+            .map(|stage| format!("submit:{}", stage.slug()))
+            .collect();
+        if self.stage == KycStage::Confirmation {
+            actions.push("submit_for_review".to_string());
+        }
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(stage: KycStage) -> KycState {
+        KycState { stage, is_rejected: false, is_finished: false, is_revised: false }
+    }
+
+    fn in_review() -> KycState {
+        state(KycStage::Review)
+    }
+
+    #[test]
+    fn submit_moves_to_first_missing_stage() {
+        let next = state(KycStage::PersonalData).apply(KycAction::Submit(KycStage::PersonalData), &[KycStage::PersonalData]).unwrap();
+        assert_eq!(next.stage, KycStage::Occupation);
+
+        let all = [KycStage::PersonalData, KycStage::Occupation, KycStage::BankAccount];
+        let next = state(KycStage::BankAccount).apply(KycAction::Submit(KycStage::BankAccount), &all).unwrap();
+        assert_eq!(next.stage, KycStage::Confirmation);
+    }
+
+    #[test]
+    fn submit_cannot_skip_stage() {
+        let err = state(KycStage::PersonalData).apply(KycAction::Submit(KycStage::BankAccount), &[]).unwrap_err();
+        assert_eq!(err, t_args("kyc.stage_incomplete", &[("stage", &"personal_data")]));
+    }
+
+    #[test]
+    fn resubmit_earlier_stage_keeps_progress() {
+        let all = [KycStage::PersonalData, KycStage::Occupation, KycStage::BankAccount];
+        let next = state(KycStage::Confirmation).apply(KycAction::Submit(KycStage::PersonalData), &all).unwrap();
+        assert_eq!(next.stage, KycStage::Confirmation);
+
+        let done = [KycStage::PersonalData, KycStage::Occupation];
+        let next = state(KycStage::BankAccount).apply(KycAction::Submit(KycStage::Occupation), &done).unwrap();
+        assert_eq!(next.stage, KycStage::BankAccount);
+    }
+
+    #[test]
+    fn submit_rejects_non_data_stage() {
+        let err = state(KycStage::Confirmation).apply(KycAction::Submit(KycStage::Review), &[]).unwrap_err();
+        assert_eq!(err, t_args("kyc.stage_not_submittable", &[("stage", &"review")]));
+    }
+
+    #[test]
+    fn submit_for_review_only_from_confirmation() {
+        let next = state(KycStage::Confirmation).apply(KycAction::SubmitForReview, &[]).unwrap();
+        assert_eq!(next.stage, KycStage::Review);
+        assert_eq!(next.status(), KycStatus::InReview);
+
+        let err = state(KycStage::BankAccount).apply(KycAction::SubmitForReview, &[]).unwrap_err();
+        assert_eq!(err, t("kyc.data_incomplete"));
+        let err = in_review().apply(KycAction::SubmitForReview, &[]).unwrap_err();
+        assert_eq!(err, t("kyc.data_incomplete"));
+    }
+
+    #[test]
+    fn submit_for_review_clears_revision_flag() {
+        let revised = KycState { is_revised: true, ..state(KycStage::Confirmation) };
+        assert_eq!(revised.status(), KycStatus::RevisionRequested);
+
+        let next = revised.apply(KycAction::SubmitForReview, &[]).unwrap();
+        assert!(!next.is_revised);
+    }
+
+    #[test]
+    fn review_actions_only_while_in_review() {
+        for action in [KycAction::Approve, KycAction::Reject, KycAction::RequestRevision(KycStage::Occupation)] {
+            let err = state(KycStage::Confirmation).apply(action, &[]).unwrap_err();
+            assert_eq!(err, t("kyc.not_in_review"), "{}", action.name());
+        }
+
+        let approved = in_review().apply(KycAction::Approve, &[]).unwrap();
+        assert_eq!(approved.stage, KycStage::Approved);
+        assert_eq!(approved.status(), KycStatus::Approved);
+
+        let rejected = in_review().apply(KycAction::Reject, &[]).unwrap();
+        assert_eq!(rejected.status(), KycStatus::Rejected);
+
+        let revised = in_review().apply(KycAction::RequestRevision(KycStage::Occupation), &[]).unwrap();
+        assert_eq!(revised.stage, KycStage::Occupation);
+        assert_eq!(revised.status(), KycStatus::RevisionRequested);
+    }
+
+    #[test]
+    fn revision_only_to_data_stage() {
+        let err = in_review().apply(KycAction::RequestRevision(KycStage::Confirmation), &[]).unwrap_err();
+        assert_eq!(err, t_args("kyc.stage_not_revisable", &[("stage", &"confirmation")]));
+    }
+
+    #[test]
+    fn locked_states_reject_client_actions() {
+        let approved = in_review().apply(KycAction::Approve, &[]).unwrap();
+        let rejected = in_review().apply(KycAction::Reject, &[]).unwrap();
+
+        for locked in [in_review(), approved, rejected] {
+            let err = locked.apply(KycAction::Submit(KycStage::PersonalData), &[]).unwrap_err();
+            assert_eq!(err, t("kyc.locked"));
+            assert!(locked.client_actions().is_empty());
+        }
+
+        let err = approved.apply(KycAction::Approve, &[]).unwrap_err();
+        assert_eq!(err, t("kyc.not_in_review"));
+        let err = rejected.apply(KycAction::RequestRevision(KycStage::PersonalData), &[]).unwrap_err();
+        assert_eq!(err, t("kyc.not_in_review"));
+    }
+
+    #[test]
+    fn client_actions_follow_stage() {
+        assert_eq!(state(KycStage::PersonalData).client_actions(), vec!["submit:personal_data"]);
+        assert_eq!(state(KycStage::Occupation).client_actions(), vec!["submit:personal_data", "submit:occupation"]);
+        assert_eq!(
+            state(KycStage::Confirmation).client_actions(),
+            vec!["submit:personal_data", "submit:occupation", "submit:bank_account", "submit_for_review"]
+        );
+
+        let revised = in_review().apply(KycAction::RequestRevision(KycStage::Occupation), &[]).unwrap();
+        assert_eq!(revised.client_actions(), vec!["submit:personal_data", "submit:occupation"]);
+    }
+}
@@ -3,7 +3,8 @@ use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 use crate::utils::validation::validator::{
-    required, valid_phone_number, valid_name, required_int, valid_password
+    required, valid_phone_number, valid_name, required_int, valid_password, validate_base64_image, valid_number_card,
+    valid_nik, valid_npwp, valid_gender, valid_personal_data, valid_bank_code, valid_bank_account, valid_locale
 }; 
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -44,7 +45,7 @@ pub struct Company {
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct LoginRequest {
-    #[validate(required, email(message = "Invalid email format"))]
+    #[validate(required, email(message = "validation.email"))]
     pub email: Option<String>,
 
     #[validate(custom(function = "required"), custom(function = "valid_password"))]
@@ -53,7 +54,7 @@ pub struct LoginRequest {
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct RegisterRequest {
-    #[validate(required, email(message = "Invalid email format"))]
+    #[validate(required, email(message = "validation.email"))]
     pub email: Option<String>,
 
     #[validate(custom(function = "required"), custom(function = "valid_password"))]
@@ -80,13 +81,13 @@ pub struct RegisterRequest {
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct ResetPasswordRequest {
-    #[validate(required, email(message = "Invalid email format"))]
+    #[validate(required, email(message = "validation.email"))]
     pub email: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct ChangePasswordRequest {
-    #[validate(required, email(message = "Invalid email format"))]
+    #[validate(required, email(message = "validation.email"))]
     pub email: Option<String>,
 
     #[validate(custom(function = "required"), custom(function = "valid_password"))]
@@ -95,6 +96,43 @@ pub struct ChangePasswordRequest {
     pub reset_password_key: String
 }
 
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct TotpCodeRequest {
+    #[validate(custom(function = "required"))]
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct MfaLoginRequest {
+    #[validate(custom(function = "required"))]
+    pub mfa_token: String,
+
+    /// Kode 6 digit dari aplikasi authenticator atau recovery code
+    #[validate(custom(function = "required"))]
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct MagicLinkRequest {
+    #[validate(required, email(message = "validation.email"))]
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct MagicLinkLoginRequest {
+    /// Query `token` dari link di email
+    #[validate(custom(function = "required"))]
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TotpEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+    /// QR code PNG dalam bentuk data URI base64
+    pub qr_code: String,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct WebUser {
     pub auth_usernid: i32,
@@ -126,6 +164,344 @@ pub struct EmailRequest {
     pub message: String,
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    /// Diisi provider jika user menolak / terjadi error
+    pub error: Option<String>,
+}
+
+// Region Session
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserSession {
+    pub session_id: String,
+    pub device_name: Option<String>,
+    pub comp_name: Option<String>,
+    pub ip_address: Option<String>,
+    pub app_name: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_time: Option<DateTime<Utc>>,
+    pub last_seen: Option<DateTime<Utc>>,
+    /// Sesi yang sedang dipakai request ini
+    pub current: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct RenameSessionRequest {
+    #[validate(custom(function = "required"), length(max = 100))]
+    pub device_name: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CsrfToken {
+    /// Kirim ulang di header `header` untuk setiap POST/PUT/PATCH/DELETE
+    pub csrf_token: String,
+    pub header: String,
+}
+
+// Region API Key
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct CreateApiKeyRequest {
+    #[validate(custom(function = "required"), length(max = 100))]
+    pub name: String,
+
+    /// Permission yang boleh dipakai key ini, contoh: `["import:write", "export:read"]`
+    #[serde(default)]
+    pub scopes: Vec<String>,
+
+    /// Default 90 hari, maksimal 365
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiKeyInfo {
+    pub api_key_nid: i32,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub expired_time: Option<DateTime<Utc>>,
+    pub last_used_time: Option<DateTime<Utc>>,
+    pub last_used_ip: Option<String>,
+    pub created_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedApiKey {
+    pub api_key_nid: i32,
+    pub name: String,
+    /// Hanya ditampilkan sekali
+    pub key: String,
+    pub scopes: Vec<String>,
+    pub expired_time: DateTime<Utc>,
+}
+
+/// Data template `mail_link.mustache`
+#[derive(Debug, Serialize, Clone)]
+pub struct LinkEmail {
+    pub recipient: String,
+    pub name: String,
+    pub subject: String,
+    pub message: String,
+    pub link: Option<String>,
+    pub link_label: String,
+    /// Contoh: "24 jam", ditampilkan di bawah tombol
+    pub expires_in: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ResendActivationRequest {
+    #[validate(required, email(message = "validation.email"))]
+    pub email: Option<String>,
+}
+
+// Region Profile
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserProfile {
+    pub auth_usernid: i32,
+    pub email: String,
+    pub full_name: Option<String>,
+    pub mobile_phone: Option<String>,
+    pub picture: Option<String>,
+    pub register_date: Option<DateTime<Utc>>,
+    pub mfa_enabled: bool,
+    /// Bahasa pesan API, `None` = mengikuti `Accept-Language`
+    pub locale: Option<String>,
+}
+
+/// Field yang tidak dikirim tidak diubah
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateProfileRequest {
+    #[validate(custom(function = "valid_name"), length(max = 100))]
+    pub full_name: Option<String>,
+
+    #[validate(custom(function = "valid_phone_number"))]
+    pub mobile_phone: Option<String>,
+
+    /// `id` atau `en`, berlaku di token berikutnya (login / refresh)
+    #[validate(custom(function = "valid_locale"))]
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdatePasswordRequest {
+    #[validate(custom(function = "required"))]
+    pub current_password: String,
+
+    #[validate(custom(function = "required"), custom(function = "valid_password"))]
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UploadAvatarRequest {
+    /// Gambar JPEG/PNG/WebP base64 (boleh dengan prefix data URI), maksimal 5MB
+    #[validate(custom(function = "validate_base64_image"))]
+    pub image: String,
+}
+
+// Region Account Deletion
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct AccountDeletionRequest {
+    /// Opsional, ikut dihapus bersama akun
+    #[validate(length(max = 500))]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccountDeletionStatus {
+    pub deletion_nid: i32,
+    pub requested_time: Option<DateTime<Utc>>,
+    /// Akun dihapus permanen setelah waktu ini, sebelumnya masih bisa dibatalkan
+    pub scheduled_time: Option<DateTime<Utc>>,
+}
+
+// Region KYC
+/// Data tahap `personal_data`
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+#[validate(schema(function = "valid_personal_data"))]
+pub struct KycPersonalData {
+    /// Tanggal lahir & jenis kelamin harus cocok dengan `birth_date` dan `gender`
+    #[validate(custom(function = "required"), custom(function = "valid_nik"))]
+    pub nik: String,
+    #[validate(custom(function = "required"))]
+    pub birth_place: String,
+    /// Format `YYYY-MM-DD`
+    pub birth_date: chrono::NaiveDate,
+    /// `male` atau `female`
+    #[validate(custom(function = "valid_gender"))]
+    pub gender: String,
+    #[validate(custom(function = "required"), custom(function = "valid_name"))]
+    pub mother_maiden_name: String,
+    #[validate(custom(function = "required"), length(max = 500))]
+    pub address: String,
+    /// Opsional, 15 atau 16 digit, disimpan dalam format 16 digit
+    #[validate(custom(function = "valid_npwp"))]
+    pub npwp: Option<String>,
+}
+
+/// Data tahap `occupation`
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct KycOccupation {
+    #[validate(custom(function = "required"))]
+    pub occupation: String,
+    pub employer: Option<String>,
+    #[validate(custom(function = "required"))]
+    pub income_range: String,
+    #[validate(custom(function = "required"))]
+    pub source_of_funds: String,
+    #[validate(custom(function = "required"))]
+    pub investment_objective: String,
+}
+
+/// Data tahap `bank_account`
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+#[validate(schema(function = "valid_bank_account"))]
+pub struct KycBankAccount {
+    /// Kode bank kliring 3 digit, daftar di `/api/v1/validate/banks`
+    #[validate(custom(function = "valid_bank_code"))]
+    pub bank_code: String,
+    #[validate(custom(function = "required"))]
+    pub bank_name: String,
+    /// Panjang mengikuti format `bank_code`
+    #[validate(custom(function = "required"), custom(function = "valid_number_card"))]
+    pub account_number: String,
+    #[validate(custom(function = "required"), custom(function = "valid_name"))]
+    pub account_holder: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct KycOverview {
+    pub kyc_nid: i32,
+    pub email: String,
+    pub full_name: Option<String>,
+    /// `personal_data`, `occupation`, `bank_account`, `confirmation`, `review`, `approved`
+    pub stage: String,
+    /// `draft`, `revision_requested`, `in_review`, `approved`, `rejected`
+    pub status: String,
+    /// Alasan revisi / penolakan terakhir dari reviewer
+    pub reason: Option<String>,
+    /// Isi tiap tahap yang sudah diisi, key = nama tahap
+    #[schema(value_type = Object)]
+    pub data: serde_json::Map<String, serde_json::Value>,
+    /// Aksi client yang boleh dilakukan sekarang, contoh `submit:occupation`, `submit_for_review`
+    pub actions: Vec<String>,
+    pub last_update: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct KycHistory {
+    pub history_nid: i32,
+    pub action: String,
+    pub from_stage: String,
+    pub to_stage: String,
+    pub status: String,
+    pub reason: Option<String>,
+    pub actor_nid: i32,
+    pub actor_email: Option<String>,
+    pub created_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct KycApproveRequest {
+    #[validate(length(max = 1000))]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct KycRejectRequest {
+    #[validate(custom(function = "required"), length(max = 1000))]
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct KycRevisionRequest {
+    /// Tahap yang harus diperbaiki: `personal_data`, `occupation` atau `bank_account`
+    #[validate(custom(function = "required"))]
+    pub stage: String,
+    #[validate(custom(function = "required"), length(max = 1000))]
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct KycDocumentInfo {
+    pub document_nid: i32,
+    /// `id_card`, `selfie` atau `bank_book`
+    pub document_type: String,
+    pub content_type: String,
+    pub file_size: i32,
+    pub width: i32,
+    pub height: i32,
+    pub uploaded_time: Option<DateTime<Utc>>,
+    /// Link sementara, hanya diisi untuk reviewer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DocumentDownloadQuery {
+    pub token: String,
+}
+
+// Region Validation
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ValidateFieldRequest {
+    pub value: String,
+    /// Wajib untuk field `bank_account`
+    pub bank_code: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ValidatedField {
+    pub field: String,
+    /// Nilai yang akan disimpan server (E.164, NPWP 16 digit, tanpa tanda baca)
+    pub normalized: String,
+    /// Informasi tambahan, contoh isi NIK atau nama bank
+    #[schema(value_type = Object)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BankInfo {
+    pub code: String,
+    pub name: String,
+    pub account_lengths: Vec<usize>,
+}
+
+// Region Audit
+/// Sama dengan `TableDataParams` tanpa `tablename`/`nidkey`, tabelnya selalu `AuditLog`
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+pub struct AuditLogParams {
+    #[param(required = false)]
+    pub limit: Option<i32>,
+    #[param(required = false)]
+    pub offset: Option<i32>,
+    /// Grammar sama dengan `/data/get-table`, contoh `{"EventType":"login_failed","CreatedTime":"2025-01-01to2025-01-31"}`
+    /// atau `{"and":[{"field":"EventType","op":"in","value":["login_failed","logout"]},{"field":"ActorNID","op":"eq","value":12}]}`
+    #[param(required = false)]
+    pub filter: Option<String>,
+    #[param(required = false)]
+    pub sort: Option<String>,
+    /// Default `desc` jika `sort` diisi
+    #[param(required = false)]
+    pub order: Option<String>,
+    /// `nextCursor` / `prevCursor` dari response sebelumnya, diabaikan saat export CSV
+    #[param(required = false)]
+    pub cursor: Option<String>,
+    /// `exact` (default), `estimate` atau `none`
+    #[param(required = false)]
+    pub total: Option<String>,
+}
+
+// Region Admin
+#[derive(Debug, Deserialize, Serialize, ToSchema, Clone, Validate)]
+pub struct AssignRoleRequest {
+    #[validate(custom(function = "required"))]
+    pub role: String,
+}
+
 // Region Library
 #[derive(Debug, Deserialize, Serialize, ToSchema, Clone, Validate)]
 pub struct NewNoteRequest {
@@ -150,6 +526,22 @@ pub struct Notes {
     pub last_update: chrono::DateTime<Utc>,
 }
 
+// Region Data View
+/// Satu baris `WEB_DataView`: tabel / view yang boleh dibuka lewat `/data/header` & `/data/get-table`
+#[derive(Debug, Clone)]
+pub struct DataView {
+    pub view_name: String,
+    pub allowed_columns: Vec<String>,
+    pub permission_code: String,
+    pub primary_key: String,
+    pub default_sort: Option<String>,
+    pub default_order: String,
+    /// Kolom berisi AuthUserNID pemilik baris, `None` = semua baris
+    pub owner_column: Option<String>,
+    /// Permission yang boleh melihat semua baris walau ada `owner_column`
+    pub bypass_permission: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, IntoParams)]
 pub struct HeaderParams {
     pub tablename: String,
@@ -160,26 +552,37 @@ pub struct TableDataParams {
     pub tablename: String,
     pub limit: i32,
     pub offset: i32,
+    /// JSON kolom → nilai (format lama) atau kondisi bertipe, contoh
+    /// `{"or":[{"field":"Price","op":"between","value":[100,500]},{"field":"Email","op":"startsWith","value":"budi"}]}`.
+    /// Operator: eq, ne, lt, lte, gt, gte, between, in, contains, startsWith, isNull
     #[param(required = false)]
     pub filter: Option<String>,
     pub sort: Option<String>,
     pub order: Option<String>,
+    /// Diabaikan untuk `/data/get-table`, primary key diambil dari registry `WEB_DataView`
+    #[param(required = false)]
     pub nidkey: Option<String>,
+    /// `nextCursor` / `prevCursor` dari response sebelumnya, jika diisi `offset` diabaikan (keyset pagination)
+    #[param(required = false)]
+    pub cursor: Option<String>,
+    /// `exact` (default), `estimate` (perkiraan dari `sys.partitions`) atau `none`
+    #[param(required = false)]
+    pub total: Option<String>,
+    /// Decimal / money sebagai `string` persis (default) atau `number`
+    #[param(required = false)]
+    pub decimals: Option<String>,
     // pub nidvalue: Option<String>,
 }
 
-#[derive(Debug)]
-pub struct QueryClass {
-    pub query: String,
-    pub query_total_all: String,
-    pub query_total_with_filter: String,
-}
-
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ResultList {
-    pub total_not_filtered: i32,
-    pub total: i32,
+    /// `None` jika total tidak dihitung (`total=none`, atau `estimate` dengan filter)
+    pub total_not_filtered: Option<i32>,
+    pub total: Option<i32>,
     pub rows: Vec<serde_json::Value>, // Pastikan ini bisa dikonversi ke JSON
+    /// Token untuk halaman berikutnya / sebelumnya, `None` jika tidak ada
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
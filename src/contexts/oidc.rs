@@ -0,0 +1,256 @@
+use std::{collections::HashMap, sync::Mutex, time::{Duration as StdDuration, Instant}};
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256};
+use shuttle_runtime::SecretStore;
+
+use crate::services::generic_service::GenericService;
+
+use super::jwt_session::{sign_token, verify_token};
+
+/// 🔑 Satu provider OpenID Connect dari secret `OIDC_PROVIDERS` (JSON array), contoh:
+/// `[{"name":"google","issuer":"https://accounts.google.com","client_id":"...","client_secret":"...","redirect_uri":"https://api.../api/v1/auth/oidc/google/callback"}]`
+/// Issuer `http://localhost:...` boleh dipakai untuk mock provider saat testing.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OidcProvider {
+    pub name: String,
+    pub issuer: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    pub redirect_uri: String,
+    #[serde(default = "default_scopes")]
+    pub scopes: String,
+}
+
+fn default_scopes() -> String {
+    "openid email profile".to_string()
+}
+
+/// Hasil discovery `/.well-known/openid-configuration`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+/// Claims ID token yang dipakai untuk login / linking akun
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdTokenClaims {
+    pub sub: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default, deserialize_with = "bool_or_string")]
+    pub email_verified: bool,
+    #[serde(default)]
+    pub nonce: Option<String>,
+}
+
+// Beberapa provider mengirim `email_verified` sebagai string "true"
+fn bool_or_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Bool(value) => value,
+        serde_json::Value::String(value) => value.eq_ignore_ascii_case("true"),
+        _ => false,
+    })
+}
+
+/// State authorization-code flow, disimpan di cookie bertanda tangan selama redirect ke provider
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OidcState {
+    pub provider: String,
+    pub state: String,
+    pub nonce: String,
+    pub code_verifier: String,
+    pub purpose: String,
+    pub exp: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: Option<String>,
+}
+
+const OIDC_STATE_PURPOSE: &str = "oidc_state";
+pub const OIDC_STATE_MINUTES: i64 = 10;
+pub const OIDC_COOKIE: &str = "snakesystem_oidc";
+
+/// Metadata & JWKS provider di-cache, dirotasi ulang jika kid tidak dikenal
+const METADATA_TTL: StdDuration = StdDuration::from_secs(60 * 60);
+
+static OIDC_PROVIDERS: OnceCell<Vec<OidcProvider>> = OnceCell::new();
+static METADATA_CACHE: Lazy<Mutex<HashMap<String, (Instant, ProviderMetadata)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static JWKS_CACHE: Lazy<Mutex<HashMap<String, (Instant, JwkSet)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Dipanggil sekali saat startup dari `main`, tanpa `OIDC_PROVIDERS` social login nonaktif
+pub fn init_providers(secrets: &SecretStore) -> Result<(), String> {
+    let providers: Vec<OidcProvider> = match secrets.get("OIDC_PROVIDERS") {
+        Some(raw) => serde_json::from_str(&raw).map_err(|e| format!("Invalid OIDC_PROVIDERS: {}", e))?,
+        None => vec![],
+    };
+
+    OIDC_PROVIDERS.set(providers).map_err(|_| "OIDC providers already initialized".to_string())
+}
+
+pub fn provider(name: &str) -> Option<&'static OidcProvider> {
+    OIDC_PROVIDERS.get()?.iter().find(|p| p.name == name)
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(StdDuration::from_secs(10))
+        .build()
+        .unwrap_or_default()
+}
+
+pub async fn discover(provider: &OidcProvider) -> Result<ProviderMetadata, String> {
+    if let Some((fetched, metadata)) = METADATA_CACHE.lock().unwrap().get(&provider.name) {
+        if fetched.elapsed() < METADATA_TTL {
+            return Ok(metadata.clone());
+        }
+    }
+
+    let url = format!("{}/.well-known/openid-configuration", provider.issuer.trim_end_matches('/'));
+    let metadata: ProviderMetadata = http_client().get(&url).send().await
+        .and_then(|res| res.error_for_status())
+        .map_err(|e| format!("OIDC discovery failed: {}", e))?
+        .json().await
+        .map_err(|e| format!("Invalid OIDC discovery document: {}", e))?;
+
+    // Issuer di dokumen wajib sama dengan yang dikonfigurasi (OIDC Discovery 4.3)
+    if metadata.issuer.trim_end_matches('/') != provider.issuer.trim_end_matches('/') {
+        return Err(format!("OIDC issuer mismatch: expected {}, got {}", provider.issuer, metadata.issuer));
+    }
+
+    METADATA_CACHE.lock().unwrap().insert(provider.name.clone(), (Instant::now(), metadata.clone()));
+    Ok(metadata)
+}
+
+async fn jwks(provider: &OidcProvider, metadata: &ProviderMetadata, force_refresh: bool) -> Result<JwkSet, String> {
+    if !force_refresh {
+        if let Some((fetched, jwks)) = JWKS_CACHE.lock().unwrap().get(&provider.name) {
+            if fetched.elapsed() < METADATA_TTL {
+                return Ok(jwks.clone());
+            }
+        }
+    }
+
+    let jwks: JwkSet = http_client().get(&metadata.jwks_uri).send().await
+        .and_then(|res| res.error_for_status())
+        .map_err(|e| format!("Failed to fetch OIDC JWKS: {}", e))?
+        .json().await
+        .map_err(|e| format!("Invalid OIDC JWKS: {}", e))?;
+
+    JWKS_CACHE.lock().unwrap().insert(provider.name.clone(), (Instant::now(), jwks.clone()));
+    Ok(jwks)
+}
+
+/// PKCE S256: (code_verifier, code_challenge)
+pub fn pkce_pair() -> (String, String) {
+    let verifier = GenericService::random_string(64);
+    let challenge = general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    (verifier, challenge)
+}
+
+/// Buat state baru + URL authorize provider
+pub fn authorization_request(provider: &OidcProvider, metadata: &ProviderMetadata) -> Result<(String, OidcState), String> {
+    let (code_verifier, code_challenge) = pkce_pair();
+    let state = OidcState {
+        provider: provider.name.clone(),
+        state: GenericService::random_string(32),
+        nonce: GenericService::random_string(32),
+        code_verifier,
+        purpose: OIDC_STATE_PURPOSE.to_string(),
+        exp: (Utc::now() + Duration::minutes(OIDC_STATE_MINUTES)).timestamp() as usize,
+    };
+
+    let url = reqwest::Url::parse_with_params(&metadata.authorization_endpoint, &[
+        ("response_type", "code"),
+        ("client_id", provider.client_id.as_str()),
+        ("redirect_uri", provider.redirect_uri.as_str()),
+        ("scope", provider.scopes.as_str()),
+        ("state", state.state.as_str()),
+        ("nonce", state.nonce.as_str()),
+        ("code_challenge", code_challenge.as_str()),
+        ("code_challenge_method", "S256"),
+    ]).map_err(|e| format!("Invalid authorization endpoint: {}", e))?;
+
+    Ok((url.to_string(), state))
+}
+
+pub fn sign_state(state: &OidcState) -> Result<String, String> {
+    sign_token(state).map_err(|e| e.to_string())
+}
+
+/// Cocokkan cookie state dengan parameter `state` dari callback
+pub fn verify_state(cookie: &str, provider: &str, state: &str) -> Result<OidcState, String> {
+    let stored = verify_token::<OidcState>(cookie).map_err(|_| "Login session expired, please try again".to_string())?;
+    if stored.purpose != OIDC_STATE_PURPOSE || stored.provider != provider || stored.state != state {
+        return Err("Invalid OIDC state".to_string());
+    }
+    Ok(stored)
+}
+
+/// Tukar authorization code dengan token, lalu validasi ID token-nya
+pub async fn exchange_code(provider: &OidcProvider, metadata: &ProviderMetadata, code: &str, state: &OidcState) -> Result<IdTokenClaims, String> {
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", provider.redirect_uri.as_str()),
+        ("client_id", provider.client_id.as_str()),
+        ("code_verifier", state.code_verifier.as_str()),
+    ];
+    if let Some(secret) = provider.client_secret.as_deref() {
+        form.push(("client_secret", secret));
+    }
+
+    let token: TokenResponse = http_client().post(&metadata.token_endpoint).form(&form).send().await
+        .and_then(|res| res.error_for_status())
+        .map_err(|e| format!("OIDC token exchange failed: {}", e))?
+        .json().await
+        .map_err(|e| format!("Invalid OIDC token response: {}", e))?;
+
+    let id_token = token.id_token.ok_or("OIDC provider did not return an id_token")?;
+    validate_id_token(provider, metadata, &id_token, &state.nonce).await
+}
+
+async fn validate_id_token(provider: &OidcProvider, metadata: &ProviderMetadata, id_token: &str, nonce: &str) -> Result<IdTokenClaims, String> {
+    let header = decode_header(id_token).map_err(|e| format!("Invalid ID token: {}", e))?;
+
+    // Hanya tanda tangan asimetris dari JWKS provider, HS*/none ditolak
+    if matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+        return Err("Unsupported ID token algorithm".to_string());
+    }
+
+    let kid = header.kid.clone().unwrap_or_default();
+    let mut keys = jwks(provider, metadata, false).await?;
+    if keys.find(&kid).is_none() {
+        // Provider baru rotasi key
+        keys = jwks(provider, metadata, true).await?;
+    }
+    let jwk = match header.kid.as_deref() {
+        Some(kid) => keys.find(kid),
+        None if keys.keys.len() == 1 => keys.keys.first(),
+        None => None,
+    }.ok_or("Unknown ID token signing key")?;
+
+    let decoding = DecodingKey::from_jwk(jwk).map_err(|e| format!("Invalid provider key: {}", e))?;
+    let mut validation = Validation::new(header.alg);
+    validation.set_audience(&[&provider.client_id]);
+    validation.set_issuer(&[&metadata.issuer]);
+
+    let claims = decode::<IdTokenClaims>(id_token, &decoding, &validation)
+        .map_err(|e| format!("Invalid ID token: {}", e))?
+        .claims;
+
+    if claims.nonce.as_deref() != Some(nonce) {
+        return Err("Invalid ID token nonce".to_string());
+    }
+
+    Ok(claims)
+}
@@ -0,0 +1,182 @@
+use actix_web::web;
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Algorithm, Argon2, Params, Version,
+};
+use once_cell::sync::Lazy;
+use rand::{rng, Rng};
+
+use super::crypto::encrypt_text;
+
+/// Versi format password di kolom `AuthUser.PasswordVersion`
+/// 0 (atau NULL) = AES lama dari `crypto::encrypt_text`, 1 = Argon2id (PHC string)
+pub const PASSWORD_VERSION_LEGACY: i32 = 0;
+pub const PASSWORD_VERSION_ARGON2ID: i32 = 1;
+
+/// 🔑 Parameter cost Argon2id, bisa di-tuning lewat env tanpa rebuild
+/// Default mengikuti rekomendasi OWASP (19 MiB, 2 iterasi, 1 lane)
+static PASSWORD_PARAMS: Lazy<Params> = Lazy::new(|| params_from(|key| std::env::var(key).ok()));
+
+fn params_from(var: impl Fn(&str) -> Option<String>) -> Params {
+    let env_u32 = |key: &str, default: u32| {
+        var(key).and_then(|v| v.parse::<u32>().ok()).unwrap_or(default)
+    };
+
+    Params::new(
+        env_u32("PASSWORD_MEMORY_KIB", 19 * 1024),
+        env_u32("PASSWORD_ITERATIONS", 2),
+        env_u32("PASSWORD_PARALLELISM", 1),
+        None,
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("❌ Invalid password hash params, fallback to default: {}", err);
+        Params::default()
+    })
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PasswordCheck {
+    Invalid,
+    Valid,
+    /// Password cocok tapi format/parameter lama, harus di-hash ulang
+    ValidNeedsRehash,
+}
+
+fn hasher() -> Argon2<'static> {
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, PASSWORD_PARAMS.clone())
+}
+
+/// 🔐 Hash password dengan Argon2id + salt acak per user
+pub fn hash_password(plain_text: &str) -> Result<String, argon2::password_hash::Error> {
+    let mut salt_bytes = [0u8; 16];
+    rng().fill(&mut salt_bytes);
+    let salt = SaltString::encode_b64(&salt_bytes)?;
+
+    Ok(hasher().hash_password(plain_text.as_bytes(), &salt)?.to_string())
+}
+
+/// 🔓 Cek password sesuai versi format yang tersimpan
+pub fn verify_password(plain_text: &str, stored: &str, version: i32) -> PasswordCheck {
+    if version == PASSWORD_VERSION_LEGACY {
+        // Format lama: bandingkan hasil enkripsi deterministik
+        return if !stored.is_empty() && encrypt_text(plain_text.to_string()) == stored {
+            PasswordCheck::ValidNeedsRehash
+        } else {
+            PasswordCheck::Invalid
+        };
+    }
+
+    let parsed = match PasswordHash::new(stored) {
+        Ok(parsed) => parsed,
+        Err(_) => return PasswordCheck::Invalid,
+    };
+
+    if hasher().verify_password(plain_text.as_bytes(), &parsed).is_err() {
+        return PasswordCheck::Invalid;
+    }
+
+    // Parameter cost berubah sejak hash dibuat, upgrade sekalian
+    let outdated = parsed.algorithm != Algorithm::Argon2id.ident()
+        || Params::try_from(&parsed).map_or(true, |params| {
+            params.m_cost() != PASSWORD_PARAMS.m_cost()
+                || params.t_cost() != PASSWORD_PARAMS.t_cost()
+                || params.p_cost() != PASSWORD_PARAMS.p_cost()
+        });
+
+    if outdated {
+        PasswordCheck::ValidNeedsRehash
+    } else {
+        PasswordCheck::Valid
+    }
+}
+
+/// 🔐 `hash_password` di thread pool blocking, Argon2id terlalu berat untuk worker actix
+pub async fn hash_password_async(plain_text: String) -> Result<String, String> {
+    match web::block(move || hash_password(&plain_text)).await {
+        Ok(Ok(hash)) => Ok(hash),
+        Ok(Err(err)) => Err(err.to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// 🔓 `verify_password` di thread pool blocking, gagal dijadwalkan dianggap `Invalid`
+pub async fn verify_password_async(plain_text: String, stored: String, version: i32) -> PasswordCheck {
+    web::block(move || verify_password(&plain_text, &stored, version)).await.unwrap_or(PasswordCheck::Invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn env_params(vars: &[(&str, &str)]) -> Params {
+        let vars = vars.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect::<HashMap<_, _>>();
+        params_from(|key| vars.get(key).cloned())
+    }
+
+    /// Hash dengan parameter & algoritma lain, seperti hash yang dibuat sebelum tuning
+    fn hash_with(algorithm: Algorithm, params: Params, plain_text: &str) -> String {
+        let salt = SaltString::encode_b64(b"0123456789abcdef").unwrap();
+        Argon2::new(algorithm, Version::V0x13, params).hash_password(plain_text.as_bytes(), &salt).unwrap().to_string()
+    }
+
+    #[test]
+    fn params_default_to_owasp() {
+        let params = env_params(&[]);
+        assert_eq!((params.m_cost(), params.t_cost(), params.p_cost()), (19 * 1024, 2, 1));
+    }
+
+    #[test]
+    fn params_tuned_from_env() {
+        let params = env_params(&[("PASSWORD_MEMORY_KIB", "65536"), ("PASSWORD_ITERATIONS", "3"), ("PASSWORD_PARALLELISM", "4")]);
+        assert_eq!((params.m_cost(), params.t_cost(), params.p_cost()), (65536, 3, 4));
+
+        // Nilai yang tidak bisa dibaca memakai default per key
+        let params = env_params(&[("PASSWORD_ITERATIONS", "banyak")]);
+        assert_eq!(params.t_cost(), 2);
+
+        // Kombinasi yang ditolak argon2 kembali ke default library
+        let params = env_params(&[("PASSWORD_PARALLELISM", "0")]);
+        assert_eq!(params, Params::default());
+    }
+
+    #[test]
+    fn argon2id_round_trip() {
+        let hash = hash_password("rahasia123").unwrap();
+        assert!(hash.starts_with("$argon2id$v=19$m=19456,t=2,p=1$"), "{}", hash);
+        assert_ne!(hash, hash_password("rahasia123").unwrap());
+
+        assert_eq!(verify_password("rahasia123", &hash, PASSWORD_VERSION_ARGON2ID), PasswordCheck::Valid);
+        assert_eq!(verify_password("rahasia124", &hash, PASSWORD_VERSION_ARGON2ID), PasswordCheck::Invalid);
+    }
+
+    #[test]
+    fn invalid_stored_hash() {
+        assert_eq!(verify_password("rahasia123", "", PASSWORD_VERSION_ARGON2ID), PasswordCheck::Invalid);
+        assert_eq!(verify_password("rahasia123", "not-a-phc-string", PASSWORD_VERSION_ARGON2ID), PasswordCheck::Invalid);
+    }
+
+    #[test]
+    fn legacy_aes_needs_rehash() {
+        let stored = encrypt_text("rahasia123".to_string());
+        assert_eq!(verify_password("rahasia123", &stored, PASSWORD_VERSION_LEGACY), PasswordCheck::ValidNeedsRehash);
+        assert_eq!(verify_password("rahasia124", &stored, PASSWORD_VERSION_LEGACY), PasswordCheck::Invalid);
+        // Kolom kosong tidak pernah cocok, termasuk dengan password kosong
+        assert_eq!(verify_password("", "", PASSWORD_VERSION_LEGACY), PasswordCheck::Invalid);
+    }
+
+    #[test]
+    fn outdated_params_need_rehash() {
+        let cheap = Params::new(8 * 1024, 1, 1, None).unwrap();
+        let hash = hash_with(Algorithm::Argon2id, cheap, "rahasia123");
+        assert_eq!(verify_password("rahasia123", &hash, PASSWORD_VERSION_ARGON2ID), PasswordCheck::ValidNeedsRehash);
+        assert_eq!(verify_password("rahasia124", &hash, PASSWORD_VERSION_ARGON2ID), PasswordCheck::Invalid);
+    }
+
+    #[test]
+    fn other_argon2_variant_needs_rehash() {
+        let hash = hash_with(Algorithm::Argon2i, PASSWORD_PARAMS.clone(), "rahasia123");
+        assert_eq!(verify_password("rahasia123", &hash, PASSWORD_VERSION_ARGON2ID), PasswordCheck::ValidNeedsRehash);
+    }
+}
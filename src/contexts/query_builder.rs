@@ -0,0 +1,1314 @@
+//! 🧱 Query builder untuk `/data/get-table`.
+//! Identifier (tabel & kolom) hanya boleh yang ada di `TableSchema` (dari `INFORMATION_SCHEMA`) dan selalu di-quote,
+//! semua nilai dari user dikirim sebagai parameter `@Pn`. Murni string, tidak butuh koneksi database.
+
+use std::fmt;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tiberius::ToSql;
+
+use super::query_filter::{parse_filter, FilterExpr, FilterOp, MAX_IN_VALUES};
+
+/// Kelompok tipe kolom dari `INFORMATION_SCHEMA.COLUMNS.DATA_TYPE`, menentukan operator & tipe nilai filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Text,
+    Int,
+    BigInt,
+    Decimal,
+    Float,
+    Bool,
+    Date,
+    DateTime,
+    Guid,
+    /// Binary, xml, time, dsb: hanya `isNull`
+    Other,
+}
+
+impl ColumnKind {
+    pub fn from_data_type(data_type: &str) -> Self {
+        match data_type.to_ascii_lowercase().as_str() {
+            "char" | "varchar" | "nchar" | "nvarchar" | "text" | "ntext" => Self::Text,
+            "tinyint" | "smallint" | "int" => Self::Int,
+            "bigint" => Self::BigInt,
+            "decimal" | "numeric" | "money" | "smallmoney" => Self::Decimal,
+            "float" | "real" => Self::Float,
+            "bit" => Self::Bool,
+            "date" => Self::Date,
+            "datetime" | "datetime2" | "smalldatetime" | "datetimeoffset" => Self::DateTime,
+            "uniqueidentifier" => Self::Guid,
+            _ => Self::Other,
+        }
+    }
+
+    pub fn supports(self, op: FilterOp) -> bool {
+        match (self, op) {
+            (_, FilterOp::IsNull) => true,
+            (Self::Other, _) => false,
+            (Self::Text, _) => true,
+            (Self::Bool | Self::Guid, op) => matches!(op, FilterOp::Eq | FilterOp::Ne | FilterOp::In),
+            (_, op) => !matches!(op, FilterOp::Contains | FilterOp::StartsWith),
+        }
+    }
+
+    /// Nilai JSON ke parameter sesuai tipe kolom. Angka, boolean & tanggal juga boleh dikirim sebagai string
+    pub fn param(self, value: &Value) -> Option<QueryParam> {
+        let text = value.as_str().map(str::trim);
+        match self {
+            Self::Text | Self::Guid => value.as_str().map(|value| QueryParam::Text(value.to_string())),
+            Self::Int => value.as_i64()
+                .or_else(|| text?.parse().ok())
+                .and_then(|value| i32::try_from(value).ok())
+                .map(QueryParam::Int),
+            Self::BigInt => value.as_i64()
+                .or_else(|| text?.parse().ok())
+                .map(QueryParam::BigInt),
+            // Dikirim sebagai teks supaya presisi decimal tidak hilang, SQL Server yang mengonversi
+            Self::Decimal => match value {
+                Value::Number(number) => Some(QueryParam::Text(number.to_string())),
+                _ => text.filter(|text| text.parse::<f64>().is_ok()).map(|text| QueryParam::Text(text.to_string())),
+            },
+            Self::Float => value.as_f64()
+                .or_else(|| text?.parse().ok())
+                .filter(|value: &f64| value.is_finite())
+                .map(QueryParam::Float),
+            Self::Bool => match value {
+                Value::Bool(value) => Some(*value),
+                Value::Number(number) => match number.as_i64() {
+                    Some(0) => Some(false),
+                    Some(1) => Some(true),
+                    _ => None,
+                },
+                _ => match text? {
+                    "true" | "1" => Some(true),
+                    "false" | "0" => Some(false),
+                    _ => None,
+                },
+            }.map(QueryParam::Bool),
+            Self::Date => NaiveDate::parse_from_str(text?, "%Y-%m-%d").ok().map(QueryParam::Date),
+            Self::DateTime => {
+                let text = text?;
+                ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"].iter()
+                    .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+                    .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok().map(|date| date.and_time(NaiveTime::MIN)))
+                    .map(QueryParam::DateTime)
+            }
+            Self::Other => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TableColumn {
+    pub name: String,
+    pub kind: ColumnKind,
+    /// money / smallmoney, dikirim driver sebagai float jadi di-SELECT sebagai `DECIMAL(19,4)`
+    pub money: bool,
+}
+
+impl TableColumn {
+    pub fn from_data_type(name: &str, data_type: &str) -> Self {
+        TableColumn {
+            name: name.to_string(),
+            kind: ColumnKind::from_data_type(data_type),
+            money: matches!(data_type.to_ascii_lowercase().as_str(), "money" | "smallmoney"),
+        }
+    }
+
+    /// Ekspresi kolom di daftar SELECT, nama hasilnya tetap sama
+    fn select_expr(&self) -> String {
+        let name = quote_ident(&self.name);
+        if self.money {
+            format!("CAST({} AS DECIMAL(19,4)) AS {}", name, name)
+        } else {
+            name
+        }
+    }
+}
+
+/// Tabel / view beserta kolom aslinya
+#[derive(Debug, Clone)]
+pub struct TableSchema {
+    pub schema: String,
+    pub name: String,
+    pub columns: Vec<TableColumn>,
+}
+
+impl TableSchema {
+    /// Kolom sesuai database (case-insensitive seperti collation default SQL Server)
+    pub fn column(&self, name: &str) -> Option<&TableColumn> {
+        self.columns.iter().find(|column| column.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// `[schema].[name]`
+    pub fn quoted_name(&self) -> String {
+        format!("{}.{}", quote_ident(&self.schema), quote_ident(&self.name))
+    }
+
+    /// Hanya kolom yang ada di `allowed`, urutan tetap mengikuti tabel
+    pub fn restricted(&self, allowed: &[String]) -> TableSchema {
+        TableSchema {
+            schema: self.schema.clone(),
+            name: self.name.clone(),
+            columns: self.columns.iter()
+                .filter(|column| allowed.iter().any(|name| name.trim().eq_ignore_ascii_case(&column.name)))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    UnknownTable(String),
+    UnknownColumn(String),
+    InvalidOrder(String),
+    InvalidFilter(String),
+    InvalidValue { column: String, value: String },
+    UnsupportedOperator { column: String, op: String },
+    InvalidSort(String),
+    InvalidTotal(String),
+    InvalidDecimals(String),
+    /// Cursor rusak, atau dibuat untuk tabel / sort / filter yang berbeda
+    InvalidCursor,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownTable(name) => write!(f, "Unknown table '{}'", name),
+            Self::UnknownColumn(name) => write!(f, "Unknown column '{}'", name),
+            Self::InvalidOrder(order) => write!(f, "Invalid order '{}', expected ASC or DESC", order),
+            Self::InvalidFilter(reason) => write!(f, "Invalid filter: {}", reason),
+            Self::InvalidValue { column, value } => write!(f, "Invalid value '{}' for column '{}'", value, column),
+            Self::UnsupportedOperator { column, op } => write!(f, "Operator '{}' is not supported for column '{}'", op, column),
+            Self::InvalidSort(sort) => write!(f, "Invalid sort '{}', expected up to {} comma separated columns", sort, MAX_SORT_COLUMNS),
+            Self::InvalidTotal(total) => write!(f, "Invalid total '{}', expected exact, estimate or none", total),
+            Self::InvalidDecimals(decimals) => write!(f, "Invalid decimals '{}', expected string or number", decimals),
+            Self::InvalidCursor => write!(f, "Invalid or expired cursor, reload the first page"),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn parse(order: &str) -> Result<Self, QueryError> {
+        match order.trim().to_ascii_uppercase().as_str() {
+            "ASC" => Ok(Self::Asc),
+            "DESC" => Ok(Self::Desc),
+            _ => Err(QueryError::InvalidOrder(order.to_string())),
+        }
+    }
+
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            Self::Asc => Self::Desc,
+            Self::Desc => Self::Asc,
+        }
+    }
+}
+
+/// Nilai yang di-bind ke `@Pn`
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryParam {
+    Text(String),
+    Int(i32),
+    BigInt(i64),
+    Float(f64),
+    Bool(bool),
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+impl QueryParam {
+    pub fn as_sql(&self) -> &dyn ToSql {
+        match self {
+            Self::Text(value) => value,
+            Self::Int(value) => value,
+            Self::BigInt(value) => value,
+            Self::Float(value) => value,
+            Self::Bool(value) => value,
+            Self::Date(value) => value,
+            Self::DateTime(value) => value,
+        }
+    }
+
+    /// Bentuk JSON yang bisa dibaca lagi oleh `ColumnKind::param` (dipakai di cursor)
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::Text(value) => json!(value),
+            Self::Int(value) => json!(value),
+            Self::BigInt(value) => json!(value),
+            Self::Float(value) => json!(value),
+            Self::Bool(value) => json!(value),
+            Self::Date(value) => json!(value.format("%Y-%m-%d").to_string()),
+            Self::DateTime(value) => json!(value.format("%Y-%m-%dT%H:%M:%S%.f").to_string()),
+        }
+    }
+}
+
+/// `[name]`, `]` di dalam nama di-escape jadi `]]`
+pub fn quote_ident(name: &str) -> String {
+    format!("[{}]", name.replace(']', "]]"))
+}
+
+/// `%`, `_` dan `[` dari user dicari apa adanya, bukan sebagai wildcard
+pub fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '%' | '_' | '[' => {
+                escaped.push('[');
+                escaped.push(c);
+                escaped.push(']');
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Jumlah kolom maksimal dalam satu `sort`
+pub const MAX_SORT_COLUMNS: usize = 5;
+
+/// `sort` berisi kolom dipisah koma, arah per kolom lewat `Kolom:desc` atau `order` (satu untuk semua / satu per kolom).
+/// Contoh: `sort=Price:desc,Email` atau `sort=Price,Email&order=desc,asc`
+pub fn parse_sort(sort: Option<&str>, order: Option<&str>) -> Result<Vec<(String, SortOrder)>, QueryError> {
+    let sort = match sort.map(str::trim).filter(|sort| !sort.is_empty()) {
+        Some(sort) => sort,
+        None => return Ok(vec![]),
+    };
+
+    let items = sort.split(',').map(str::trim).collect::<Vec<_>>();
+    if items.len() > MAX_SORT_COLUMNS || items.iter().any(|item| item.is_empty()) {
+        return Err(QueryError::InvalidSort(sort.to_string()));
+    }
+
+    let orders = order.map(str::trim)
+        .filter(|order| !order.is_empty())
+        .map(|order| order.split(',').map(str::trim).collect::<Vec<_>>())
+        .unwrap_or_default();
+    if orders.len() > 1 && orders.len() != items.len() {
+        return Err(QueryError::InvalidOrder(order.unwrap_or_default().to_string()));
+    }
+
+    items.iter().enumerate().map(|(index, item)| {
+        let (column, explicit) = match item.split_once(':') {
+            Some((column, order)) => (column.trim(), Some(order)),
+            None => (*item, orders.get(index).or(orders.first().filter(|_| orders.len() == 1)).copied()),
+        };
+        let order = explicit.map(SortOrder::parse).transpose()?.unwrap_or(SortOrder::Asc);
+        Ok((column.to_string(), order))
+    }).collect()
+}
+
+/// Cara menghitung total untuk `/data/get-table`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TotalMode {
+    /// `COUNT(*)`, satu query untuk total & total dengan filter
+    Exact,
+    /// Jumlah baris dari `sys.partitions`, cepat tapi tidak persis & tidak berlaku untuk filter
+    Estimate,
+    /// Tanpa total, cocok untuk paging dengan cursor
+    None,
+}
+
+impl TotalMode {
+    pub fn parse(total: Option<&str>) -> Result<Self, QueryError> {
+        match total.map(|total| total.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") | Some("exact") => Ok(Self::Exact),
+            Some("estimate") => Ok(Self::Estimate),
+            Some("none") => Ok(Self::None),
+            Some(_) => Err(QueryError::InvalidTotal(total.unwrap_or_default().to_string())),
+        }
+    }
+}
+
+/// Perkiraan jumlah baris tabel (heap / clustered index), `NULL` untuk view. `@P1` = nama tabel ber-quote
+pub const ESTIMATE_COUNT_SQL: &str = "SELECT SUM(p.rows) AS total FROM sys.partitions p WHERE p.object_id = OBJECT_ID(@P1) AND p.index_id IN (0, 1)";
+
+/// Posisi halaman untuk keyset pagination: nilai kolom urutan dari baris batas.
+/// Dikirim ke client sebagai token base64 tanpa perlu dipahami isinya
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    /// `true` = halaman sebelum baris ini (`prev_cursor`)
+    #[serde(rename = "b")]
+    pub backward: bool,
+    #[serde(rename = "v")]
+    pub values: Vec<Value>,
+    /// Sidik jari tabel, sort & filter, lihat `TableQuery::spec`
+    #[serde(rename = "s")]
+    pub spec: String,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    pub fn decode(token: &str) -> Result<Self, QueryError> {
+        URL_SAFE_NO_PAD.decode(token.trim())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or(QueryError::InvalidCursor)
+    }
+}
+
+/// Query siap pakai: `select` memakai semua `params`, `count` memakai `filter_params()` (tanpa seek & paging)
+#[derive(Debug, Clone)]
+pub struct TableQuery {
+    /// `[schema].[name]`, parameter untuk `ESTIMATE_COUNT_SQL`
+    pub table: String,
+    /// Ambil `limit + 1` baris, baris lebih menandakan masih ada halaman berikutnya
+    pub select: String,
+    /// Kolom `total` & `totalWithFilter` dalam satu query
+    pub count: String,
+    /// `false` = tanpa filter dari request, `totalWithFilter` sama dengan `total`
+    pub filtered: bool,
+    /// Ada batasan baris (`restrict`), perkiraan dari `sys.partitions` tidak boleh dipakai
+    pub scoped: bool,
+    /// Kolom urutan untuk cursor, kosong jika urutan tidak unik (tanpa `tiebreak`)
+    pub keyset: Vec<TableColumn>,
+    /// Mengambil halaman sebelumnya: urutan dibalik, hasil harus dibalik lagi
+    pub backward: bool,
+    pub limit: i32,
+    /// Sidik jari tabel, sort, filter & scope, cursor dari query lain ditolak
+    pub spec: String,
+    pub params: Vec<QueryParam>,
+    filter_param_count: usize,
+}
+
+impl TableQuery {
+    pub fn cursor(&self, values: Vec<Value>, backward: bool) -> Cursor {
+        Cursor { backward, values, spec: self.spec.clone() }
+    }
+
+    pub fn filter_params(&self) -> Vec<&dyn ToSql> {
+        self.params[..self.filter_param_count].iter().map(QueryParam::as_sql).collect()
+    }
+
+    pub fn select_params(&self) -> Vec<&dyn ToSql> {
+        self.params.iter().map(QueryParam::as_sql).collect()
+    }
+}
+
+pub struct TableQueryBuilder<'a> {
+    table: &'a TableSchema,
+    /// Batasan baris yang juga berlaku untuk total tanpa filter
+    scopes: Vec<String>,
+    conditions: Vec<String>,
+    params: Vec<QueryParam>,
+    order_by: Vec<(&'a TableColumn, SortOrder)>,
+    /// Urutan diakhiri kolom unik, syarat keyset pagination
+    unique_order: bool,
+    /// Nilai cursor (`None` = NULL) & arah
+    seek: Option<(Vec<Option<QueryParam>>, bool)>,
+    offset: i32,
+    limit: i32,
+}
+
+impl<'a> TableQueryBuilder<'a> {
+    pub fn new(table: &'a TableSchema) -> Self {
+        Self {
+            table,
+            scopes: vec![],
+            conditions: vec![],
+            params: vec![],
+            order_by: vec![],
+            unique_order: false,
+            seek: None,
+            offset: 0,
+            limit: 0,
+        }
+    }
+
+    fn bind(&mut self, param: QueryParam) -> String {
+        self.params.push(param);
+        format!("@P{}", self.params.len())
+    }
+
+    fn resolve(&self, column: &str) -> Result<&'a TableColumn, QueryError> {
+        self.table.column(column).ok_or_else(|| QueryError::UnknownColumn(column.to_string()))
+    }
+
+    /// Batasi baris ke `kolom = nilai`, misal hanya milik user login.
+    /// `column` berasal dari konfigurasi server (boleh kolom yang tidak ditampilkan), bukan dari request
+    pub fn restrict(mut self, column: &str, value: QueryParam) -> Self {
+        let condition = format!("{} = {}", quote_ident(column), self.bind(value));
+        self.scopes.push(condition);
+        self
+    }
+
+    /// Satu filter format lama `kolom → nilai` (aturan sama dengan query lama sebelum builder):
+    /// - `yyyy-mm-dd` di kolom `...Date` → satu hari penuh, kolom lain → sama dengan
+    /// - kolom `...Time` dengan `yyyy-mm-ddtoyyyy-mm-dd` → rentang tanggal
+    /// - kolom berawalan `_` atau berakhiran `NID` / `ID` → sama dengan
+    /// - selain itu → `LIKE '%nilai%'`
+    fn legacy_condition(&mut self, column: &str, value: &str) -> Result<String, QueryError> {
+        let quoted = quote_ident(&self.resolve(column)?.name);
+        let name = column.trim();
+        let invalid = || QueryError::InvalidValue { column: name.to_string(), value: value.to_string() };
+
+        let condition = if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            if name.ends_with("Date") {
+                let next_date = date.succ_opt().unwrap_or(date);
+                let from = self.bind(QueryParam::Date(date));
+                let to = self.bind(QueryParam::Date(next_date));
+                format!("{} BETWEEN {} AND {}", quoted, from, to)
+            } else {
+                format!("{} = {}", quoted, self.bind(QueryParam::Text(value.to_string())))
+            }
+        } else if name.ends_with("Time") {
+            let (from, to) = value.split_once("to").ok_or_else(invalid)?;
+            let from = NaiveDate::parse_from_str(from.trim(), "%Y-%m-%d").map_err(|_| invalid())?;
+            let to = NaiveDate::parse_from_str(to.trim(), "%Y-%m-%d").map_err(|_| invalid())?;
+            let from = self.bind(QueryParam::DateTime(from.and_time(NaiveTime::MIN)));
+            let to = self.bind(QueryParam::DateTime(to.and_hms_opt(23, 59, 59).ok_or_else(invalid)?));
+            format!("{} BETWEEN {} AND {}", quoted, from, to)
+        } else if name.starts_with('_') || name.ends_with("NID") || name.ends_with("ID") {
+            format!("{} = {}", quoted, self.bind(QueryParam::Text(value.to_string())))
+        } else {
+            format!("{} LIKE {}", quoted, self.bind(QueryParam::Text(format!("%{}%", escape_like(value)))))
+        };
+
+        Ok(condition)
+    }
+
+    /// Satu kondisi grammar baru, operator & tipe nilai dicek terhadap tipe kolom
+    fn condition(&mut self, field: &str, op: FilterOp, value: &Value) -> Result<String, QueryError> {
+        let column = self.resolve(field)?;
+        let quoted = quote_ident(&column.name);
+        if !column.kind.supports(op) {
+            return Err(QueryError::UnsupportedOperator { column: column.name.clone(), op: op.as_str().to_string() });
+        }
+
+        let invalid = || QueryError::InvalidValue { column: column.name.clone(), value: value.to_string() };
+        let param = |value: &Value| column.kind.param(value).ok_or_else(invalid);
+
+        let condition = match op {
+            FilterOp::IsNull => match value {
+                Value::Null | Value::Bool(true) => format!("{} IS NULL", quoted),
+                Value::Bool(false) => format!("{} IS NOT NULL", quoted),
+                _ => return Err(invalid()),
+            },
+            FilterOp::Between => match value.as_array().map(Vec::as_slice) {
+                Some([from, to]) => {
+                    let from = self.bind(param(from)?);
+                    let to = self.bind(param(to)?);
+                    format!("{} BETWEEN {} AND {}", quoted, from, to)
+                }
+                _ => return Err(invalid()),
+            },
+            FilterOp::In => {
+                let values = value.as_array()
+                    .filter(|values| !values.is_empty() && values.len() <= MAX_IN_VALUES)
+                    .ok_or_else(invalid)?
+                    .iter()
+                    .map(param)
+                    .collect::<Result<Vec<_>, _>>()?;
+                let placeholders = values.into_iter().map(|value| self.bind(value)).collect::<Vec<_>>();
+                format!("{} IN ({})", quoted, placeholders.join(", "))
+            }
+            FilterOp::Contains | FilterOp::StartsWith => {
+                let text = value.as_str().ok_or_else(invalid)?;
+                let pattern = match op {
+                    FilterOp::Contains => format!("%{}%", escape_like(text)),
+                    _ => format!("{}%", escape_like(text)),
+                };
+                format!("{} LIKE {}", quoted, self.bind(QueryParam::Text(pattern)))
+            }
+            op => {
+                let operator = op.comparison_sql().ok_or_else(invalid)?;
+                format!("{} {} {}", quoted, operator, self.bind(param(value)?))
+            }
+        };
+
+        Ok(condition)
+    }
+
+    fn render(&mut self, expr: &FilterExpr) -> Result<String, QueryError> {
+        match expr {
+            FilterExpr::And(items) | FilterExpr::Or(items) => {
+                let separator = if matches!(expr, FilterExpr::Or(_)) { " OR " } else { " AND " };
+                let parts = items.iter().map(|item| self.render(item)).collect::<Result<Vec<_>, _>>()?;
+                Ok(if parts.len() == 1 {
+                    parts.into_iter().next().unwrap_or_default()
+                } else {
+                    format!("({})", parts.join(separator))
+                })
+            }
+            FilterExpr::Condition { field, op, value } => self.condition(field, *op, value),
+            FilterExpr::Legacy { field, value } => self.legacy_condition(field, value),
+        }
+    }
+
+    /// Filter hasil `parse_filter`
+    pub fn filter_expr(mut self, expr: &FilterExpr) -> Result<Self, QueryError> {
+        let condition = self.render(expr)?;
+        self.conditions.push(condition);
+        Ok(self)
+    }
+
+    /// Filter dari query string `filter`, lihat grammar di `query_filter`
+    pub fn filter_json(self, filter: Option<&str>) -> Result<Self, QueryError> {
+        match parse_filter(filter)? {
+            Some(expr) => self.filter_expr(&expr),
+            None => Ok(self),
+        }
+    }
+
+    /// Bisa dipanggil berulang, kolom yang sudah ada di urutan diabaikan
+    pub fn order_by(mut self, column: &str, order: SortOrder) -> Result<Self, QueryError> {
+        let column = self.resolve(column)?;
+        if !self.order_by.iter().any(|(existing, _)| existing.name == column.name) {
+            self.order_by.push((column, order));
+        }
+        Ok(self)
+    }
+
+    /// Kolom unik (primary key) sebagai urutan terakhir: paging stabil & cursor bisa dipakai
+    pub fn tiebreak(mut self, column: &str, order: SortOrder) -> Result<Self, QueryError> {
+        self = self.order_by(column, order)?;
+        self.unique_order = true;
+        Ok(self)
+    }
+
+    /// Mulai dari posisi `cursor` (offset diabaikan). Panggil setelah semua `order_by` / `tiebreak`
+    pub fn seek(mut self, cursor: &Cursor) -> Result<Self, QueryError> {
+        if !self.unique_order || cursor.values.len() != self.order_by.len() || self.order_by.iter().any(|(column, _)| column.kind == ColumnKind::Other) {
+            return Err(QueryError::InvalidCursor);
+        }
+
+        let values = self.order_by.iter().zip(&cursor.values)
+            .map(|((column, _), value)| match value {
+                Value::Null => Ok(None),
+                value => column.kind.param(value).map(Some).ok_or(QueryError::InvalidCursor),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.seek = Some((values, cursor.backward));
+        Ok(self)
+    }
+
+    /// Baris setelah posisi cursor sesuai arah urutan, NULL dianggap paling kecil seperti SQL Server.
+    /// `(a > x) OR (a = x AND b > y) OR ...`
+    fn seek_condition(&mut self, values: Vec<Option<QueryParam>>, backward: bool) -> String {
+        let columns = self.order_by.iter()
+            .map(|(column, order)| (quote_ident(&column.name), if backward { order.reverse() } else { *order }))
+            .collect::<Vec<_>>();
+        let placeholders = values.into_iter()
+            .map(|value| value.map(|value| self.bind(value)))
+            .collect::<Vec<_>>();
+
+        let mut branches = vec![];
+        for (index, ((column, order), placeholder)) in columns.iter().zip(&placeholders).enumerate() {
+            let beyond = match (order, placeholder) {
+                (SortOrder::Asc, Some(placeholder)) => format!("{} > {}", column, placeholder),
+                (SortOrder::Asc, None) => format!("{} IS NOT NULL", column),
+                (SortOrder::Desc, Some(placeholder)) => format!("({} < {} OR {} IS NULL)", column, placeholder, column),
+                // Tidak ada yang lebih kecil dari NULL
+                (SortOrder::Desc, None) => continue,
+            };
+
+            let mut parts = columns[..index].iter().zip(&placeholders)
+                .map(|((column, _), placeholder)| match placeholder {
+                    Some(placeholder) => format!("{} = {}", column, placeholder),
+                    None => format!("{} IS NULL", column),
+                })
+                .collect::<Vec<_>>();
+            parts.push(beyond);
+            branches.push(format!("({})", parts.join(" AND ")));
+        }
+
+        if branches.is_empty() {
+            "1 = 0".to_string()
+        } else {
+            format!("({})", branches.join(" OR "))
+        }
+    }
+
+    /// `limit` <= 0 = tanpa baris, hanya total
+    pub fn page(mut self, offset: i32, limit: i32) -> Self {
+        self.offset = offset.max(0);
+        self.limit = limit.max(0);
+        self
+    }
+
+    pub fn build(mut self) -> TableQuery {
+        let table = self.table.quoted_name();
+        let columns = self.table.columns.iter().map(TableColumn::select_expr).collect::<Vec<_>>().join(", ");
+        let filter_param_count = self.params.len();
+        let where_of = |conditions: &[String]| if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+        let scope_clause = where_of(self.scopes.as_slice());
+
+        let count = if self.conditions.is_empty() {
+            format!("SELECT COUNT(*) AS total, COUNT(*) AS totalWithFilter FROM {}{}", table, scope_clause)
+        } else {
+            format!(
+                "SELECT COUNT(*) AS total, COUNT(CASE WHEN {} THEN 1 END) AS totalWithFilter FROM {}{}",
+                self.conditions.join(" AND "), table, scope_clause
+            )
+        };
+
+        let order_spec = self.order_by.iter()
+            .map(|(column, order)| format!("{} {}", quote_ident(&column.name), order.as_sql()))
+            .collect::<Vec<_>>();
+        let spec = Sha256::digest(format!(
+            "{}|{}|{}|{:?}",
+            table, order_spec.join(", "), [self.scopes.as_slice(), self.conditions.as_slice()].concat().join(" AND "), &self.params
+        ).as_bytes());
+        let spec = spec[..8].iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+
+        // Kolom tanpa tipe yang dikenal (time, xml, ...) tidak bisa dibawa di cursor
+        let keyset_ready = self.unique_order && self.order_by.iter().all(|(column, _)| column.kind != ColumnKind::Other);
+        let backward = self.seek.as_ref().is_some_and(|(_, backward)| *backward);
+        let mut conditions = [self.scopes.as_slice(), self.conditions.as_slice()].concat();
+        let seeking = match self.seek.take() {
+            Some((values, backward)) => {
+                let condition = self.seek_condition(values, backward);
+                conditions.push(condition);
+                true
+            }
+            None => false,
+        };
+        let where_clause = where_of(conditions.as_slice());
+
+        // OFFSET wajib punya ORDER BY, tanpa kolom sort urutan dibiarkan ke database
+        let order_clause = if self.order_by.is_empty() {
+            " ORDER BY (SELECT NULL)".to_string()
+        } else {
+            let columns = self.order_by.iter()
+                .map(|(column, order)| {
+                    let order = if backward { order.reverse() } else { *order };
+                    format!("{} {}", quote_ident(&column.name), order.as_sql())
+                })
+                .collect::<Vec<_>>();
+            format!(" ORDER BY {}", columns.join(", "))
+        };
+
+        let select = if seeking {
+            let fetch = self.bind(QueryParam::Int(self.limit.saturating_add(1)));
+            format!("SELECT TOP ({}) {} FROM {}{}{}", fetch, columns, table, where_clause, order_clause)
+        } else {
+            let offset = self.bind(QueryParam::Int(self.offset));
+            let fetch = self.bind(QueryParam::Int(self.limit.saturating_add(1)));
+            format!("SELECT {} FROM {}{}{} OFFSET {} ROWS FETCH NEXT {} ROWS ONLY", columns, table, where_clause, order_clause, offset, fetch)
+        };
+
+        TableQuery {
+            table,
+            select,
+            count,
+            filtered: !self.conditions.is_empty(),
+            scoped: !self.scopes.is_empty(),
+            keyset: if keyset_ready { self.order_by.iter().map(|(column, _)| (*column).clone()).collect() } else { vec![] },
+            backward,
+            limit: self.limit,
+            spec,
+            params: self.params,
+            filter_param_count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLUMNS: &str = "[NID], [Email], [Price], [CreatedDate], [LastUpdateTime], [Active], [Payload], [Odd]]Name]";
+
+    fn table() -> TableSchema {
+        let column = |name: &str, kind| TableColumn { name: name.to_string(), kind, money: false };
+        TableSchema {
+            schema: "dbo".to_string(),
+            name: "Orders".to_string(),
+            columns: vec![
+                column("NID", ColumnKind::Int),
+                column("Email", ColumnKind::Text),
+                column("Price", ColumnKind::Decimal),
+                column("CreatedDate", ColumnKind::Date),
+                column("LastUpdateTime", ColumnKind::DateTime),
+                column("Active", ColumnKind::Bool),
+                column("Payload", ColumnKind::Other),
+                column("Odd]Name", ColumnKind::Text),
+            ],
+        }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn quote_ident_escapes_closing_bracket() {
+        assert_eq!(quote_ident("Email"), "[Email]");
+        assert_eq!(quote_ident("a]b"), "[a]]b]");
+        assert_eq!(quote_ident("x]; DROP TABLE Users--"), "[x]]; DROP TABLE Users--]");
+        assert_eq!(table().quoted_name(), "[dbo].[Orders]");
+    }
+
+    #[test]
+    fn escape_like_wildcards() {
+        assert_eq!(escape_like("budi"), "budi");
+        assert_eq!(escape_like("50%_[x]"), "50[%][_][[]x]");
+    }
+
+    #[test]
+    fn columns_are_case_insensitive() {
+        let table = table();
+        let name = |column: &str| table.column(column).map(|column| column.name.as_str());
+        assert_eq!(name(" email "), Some("Email"));
+        assert_eq!(name("odd]name"), Some("Odd]Name"));
+        assert_eq!(name("Password"), None);
+        assert_eq!(table.column("PRICE").map(|column| column.kind), Some(ColumnKind::Decimal));
+    }
+
+    #[test]
+    fn money_is_selected_as_decimal() {
+        let table = TableSchema {
+            schema: "dbo".to_string(),
+            name: "Payments".to_string(),
+            columns: vec![TableColumn::from_data_type("NID", "int"), TableColumn::from_data_type("Amount", "money"), TableColumn::from_data_type("Fee", "SMALLMONEY")],
+        };
+        assert_eq!(table.column("Amount").map(|column| column.kind), Some(ColumnKind::Decimal));
+
+        let query = TableQueryBuilder::new(&table).page(0, 10).build();
+        assert_eq!(
+            query.select,
+            "SELECT [NID], CAST([Amount] AS DECIMAL(19,4)) AS [Amount], CAST([Fee] AS DECIMAL(19,4)) AS [Fee] FROM [dbo].[Payments] ORDER BY (SELECT NULL) OFFSET @P1 ROWS FETCH NEXT @P2 ROWS ONLY"
+        );
+    }
+
+    #[test]
+    fn unfiltered_page() {
+        let table = table();
+        let query = TableQueryBuilder::new(&table).page(20, 10).build();
+
+        assert_eq!(
+            query.select,
+            format!("SELECT {} FROM [dbo].[Orders] ORDER BY (SELECT NULL) OFFSET @P1 ROWS FETCH NEXT @P2 ROWS ONLY", COLUMNS)
+        );
+        assert_eq!(query.count, "SELECT COUNT(*) AS total, COUNT(*) AS totalWithFilter FROM [dbo].[Orders]");
+        assert_eq!(query.params, vec![QueryParam::Int(20), QueryParam::Int(11)]);
+        assert!(query.filter_params().is_empty());
+        assert!(!query.filtered);
+        assert!(!query.scoped);
+    }
+
+    #[test]
+    fn page_clamps_offset_and_limit() {
+        let table = table();
+        let query = TableQueryBuilder::new(&table).page(-5, -1).build();
+        assert_eq!(query.params, vec![QueryParam::Int(0), QueryParam::Int(1)]);
+        assert_eq!(query.limit, 0);
+
+        let query = TableQueryBuilder::new(&table).page(0, i32::MAX).build();
+        assert_eq!(query.params, vec![QueryParam::Int(0), QueryParam::Int(i32::MAX)]);
+    }
+
+    #[test]
+    fn legacy_filter() {
+        let table = table();
+        let filter = r#"{"NID":"7","Email":"50%_off","CreatedDate":"2024-01-02","LastUpdateTime":"2024-01-01to2024-01-31"}"#;
+        let query = TableQueryBuilder::new(&table)
+            .filter_json(Some(filter)).unwrap()
+            .order_by("email", SortOrder::Desc).unwrap()
+            .page(0, 25)
+            .build();
+
+        // Urut nama kolom: CreatedDate, Email, LastUpdateTime, NID
+        let conditions = "([CreatedDate] BETWEEN @P1 AND @P2 AND [Email] LIKE @P3 AND [LastUpdateTime] BETWEEN @P4 AND @P5 AND [NID] = @P6)";
+        assert_eq!(
+            query.select,
+            format!("SELECT {} FROM [dbo].[Orders] WHERE {} ORDER BY [Email] DESC OFFSET @P7 ROWS FETCH NEXT @P8 ROWS ONLY", COLUMNS, conditions)
+        );
+        assert_eq!(
+            query.count,
+            format!("SELECT COUNT(*) AS total, COUNT(CASE WHEN {} THEN 1 END) AS totalWithFilter FROM [dbo].[Orders]", conditions)
+        );
+        assert_eq!(query.params, vec![
+            QueryParam::Date(date(2024, 1, 2)),
+            QueryParam::Date(date(2024, 1, 3)),
+            QueryParam::Text("%50[%][_]off%".to_string()),
+            QueryParam::DateTime(date(2024, 1, 1).and_time(NaiveTime::MIN)),
+            QueryParam::DateTime(date(2024, 1, 31).and_hms_opt(23, 59, 59).unwrap()),
+            QueryParam::Text("7".to_string()),
+            QueryParam::Int(0),
+            QueryParam::Int(26),
+        ]);
+        assert_eq!(query.filter_params().len(), 6);
+        assert!(query.filtered);
+    }
+
+    #[test]
+    fn order_by_appends_unique_columns() {
+        let table = table();
+        let query = TableQueryBuilder::new(&table)
+            .order_by("Email", SortOrder::Asc).unwrap()
+            .order_by("nid", SortOrder::Desc).unwrap()
+            .order_by("EMAIL", SortOrder::Desc).unwrap()
+            .build();
+        assert!(query.select.contains(" ORDER BY [Email] ASC, [NID] DESC OFFSET "));
+    }
+
+    #[test]
+    fn empty_filter_is_ignored() {
+        let table = table();
+        for filter in [None, Some(""), Some("{}"), Some("{filter:undefined}")] {
+            let query = TableQueryBuilder::new(&table).filter_json(filter).unwrap().build();
+            assert!(query.filter_params().is_empty());
+        }
+
+        let result = TableQueryBuilder::new(&table).filter_json(Some("[1, 2]"));
+        assert!(matches!(result, Err(QueryError::InvalidFilter(_))));
+    }
+
+    #[test]
+    fn legacy_date_on_other_column_is_equality() {
+        let table = table();
+        let query = TableQueryBuilder::new(&table).filter_json(Some(r#"{"Email":"2024-01-02"}"#)).unwrap().build();
+        assert_eq!(query.count, "SELECT COUNT(*) AS total, COUNT(CASE WHEN [Email] = @P1 THEN 1 END) AS totalWithFilter FROM [dbo].[Orders]");
+        assert_eq!(query.params[0], QueryParam::Text("2024-01-02".to_string()));
+    }
+
+    #[test]
+    fn legacy_time_range_must_be_valid() {
+        let table = table();
+        let result = TableQueryBuilder::new(&table).filter_json(Some(r#"{"LastUpdateTime":"yesterday"}"#));
+        assert_eq!(
+            result.err(),
+            Some(QueryError::InvalidValue { column: "LastUpdateTime".to_string(), value: "yesterday".to_string() })
+        );
+    }
+
+    #[test]
+    fn unknown_columns_are_rejected() {
+        let table = table();
+        let result = TableQueryBuilder::new(&table).filter_json(Some(r#"{"Password":"x"}"#));
+        assert_eq!(result.err(), Some(QueryError::UnknownColumn("Password".to_string())));
+
+        let result = TableQueryBuilder::new(&table).order_by("[NID]; DROP TABLE Orders", SortOrder::Asc);
+        assert_eq!(result.err(), Some(QueryError::UnknownColumn("[NID]; DROP TABLE Orders".to_string())));
+    }
+
+    #[test]
+    fn restrict_applies_to_both_totals() {
+        let table = table();
+        let query = TableQueryBuilder::new(&table)
+            .restrict("OwnerNID", QueryParam::Int(5))
+            .filter_json(Some(r#"{"Email":"budi"}"#)).unwrap()
+            .build();
+
+        assert_eq!(
+            query.count,
+            "SELECT COUNT(*) AS total, COUNT(CASE WHEN [Email] LIKE @P2 THEN 1 END) AS totalWithFilter FROM [dbo].[Orders] WHERE [OwnerNID] = @P1"
+        );
+        assert!(query.select.contains(" WHERE [OwnerNID] = @P1 AND [Email] LIKE @P2 "));
+        assert_eq!(query.filter_params().len(), 2);
+        assert!(query.scoped);
+    }
+
+    #[test]
+    fn restricted_schema_keeps_table_order() {
+        let restricted = table().restricted(&["email".to_string(), " NID ".to_string(), "Password".to_string()]);
+        let names = restricted.columns.iter().map(|column| column.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["NID", "Email"]);
+        assert_eq!(restricted.quoted_name(), "[dbo].[Orders]");
+    }
+
+    /// Kondisi & parameter hasil render, diambil dari query `count`
+    fn render(filter: &str) -> Result<(String, Vec<QueryParam>), QueryError> {
+        let table = table();
+        let query = TableQueryBuilder::new(&table).filter_json(Some(filter))?.build();
+        let condition = query.count
+            .strip_prefix("SELECT COUNT(*) AS total, COUNT(CASE WHEN ")
+            .and_then(|count| count.strip_suffix(" THEN 1 END) AS totalWithFilter FROM [dbo].[Orders]"))
+            .unwrap()
+            .to_string();
+        Ok((condition, query.params[..query.filter_params().len()].to_vec()))
+    }
+
+    fn invalid_value(column: &str, value: &str) -> QueryError {
+        QueryError::InvalidValue { column: column.to_string(), value: value.to_string() }
+    }
+
+    fn unsupported(column: &str, op: &str) -> QueryError {
+        QueryError::UnsupportedOperator { column: column.to_string(), op: op.to_string() }
+    }
+
+    fn text(value: &str) -> QueryParam {
+        QueryParam::Text(value.to_string())
+    }
+
+    #[test]
+    fn column_kind_from_data_type() {
+        assert_eq!(ColumnKind::from_data_type("NVARCHAR"), ColumnKind::Text);
+        assert_eq!(ColumnKind::from_data_type("tinyint"), ColumnKind::Int);
+        assert_eq!(ColumnKind::from_data_type("bigint"), ColumnKind::BigInt);
+        assert_eq!(ColumnKind::from_data_type("money"), ColumnKind::Decimal);
+        assert_eq!(ColumnKind::from_data_type("real"), ColumnKind::Float);
+        assert_eq!(ColumnKind::from_data_type("bit"), ColumnKind::Bool);
+        assert_eq!(ColumnKind::from_data_type("date"), ColumnKind::Date);
+        assert_eq!(ColumnKind::from_data_type("datetimeoffset"), ColumnKind::DateTime);
+        assert_eq!(ColumnKind::from_data_type("uniqueidentifier"), ColumnKind::Guid);
+        assert_eq!(ColumnKind::from_data_type("varbinary"), ColumnKind::Other);
+    }
+
+    #[test]
+    fn operator_support_per_kind() {
+        use FilterOp::*;
+
+        let all = [Eq, Ne, Lt, Lte, Gt, Gte, Between, In, Contains, StartsWith, IsNull];
+        let supported = |kind: ColumnKind| all.iter().copied().filter(|op| kind.supports(*op)).collect::<Vec<_>>();
+
+        assert_eq!(supported(ColumnKind::Text), all.to_vec());
+        for kind in [ColumnKind::Int, ColumnKind::BigInt, ColumnKind::Decimal, ColumnKind::Float, ColumnKind::Date, ColumnKind::DateTime] {
+            assert_eq!(supported(kind), vec![Eq, Ne, Lt, Lte, Gt, Gte, Between, In, IsNull], "{:?}", kind);
+        }
+        assert_eq!(supported(ColumnKind::Bool), vec![Eq, Ne, In, IsNull]);
+        assert_eq!(supported(ColumnKind::Guid), vec![Eq, Ne, In, IsNull]);
+        assert_eq!(supported(ColumnKind::Other), vec![IsNull]);
+    }
+
+    #[test]
+    fn unsupported_operators_are_rejected() {
+        assert_eq!(render(r#"{"field":"Price","op":"contains","value":"1"}"#).err(), Some(unsupported("Price", "contains")));
+        assert_eq!(render(r#"{"field":"Active","op":"gt","value":true}"#).err(), Some(unsupported("Active", "gt")));
+        assert_eq!(render(r#"{"field":"Payload","op":"eq","value":"x"}"#).err(), Some(unsupported("Payload", "eq")));
+    }
+
+    #[test]
+    fn param_conversion() {
+        assert_eq!(ColumnKind::Int.param(&json!(" 42 ")), Some(QueryParam::Int(42)));
+        assert_eq!(ColumnKind::Int.param(&json!(3_000_000_000_i64)), None);
+        assert_eq!(ColumnKind::BigInt.param(&json!("3000000000")), Some(QueryParam::BigInt(3_000_000_000)));
+        assert_eq!(ColumnKind::Decimal.param(&json!(12.50)), Some(text("12.5")));
+        assert_eq!(ColumnKind::Decimal.param(&json!("12.50")), Some(text("12.50")));
+        assert_eq!(ColumnKind::Float.param(&json!("NaN")), None);
+        assert_eq!(ColumnKind::Bool.param(&json!(1)), Some(QueryParam::Bool(true)));
+        assert_eq!(ColumnKind::Bool.param(&json!("false")), Some(QueryParam::Bool(false)));
+        assert_eq!(ColumnKind::Bool.param(&json!(2)), None);
+        assert_eq!(ColumnKind::Date.param(&json!("2024-01-02")), Some(QueryParam::Date(date(2024, 1, 2))));
+        assert_eq!(
+            ColumnKind::DateTime.param(&json!("2024-01-02 10:30:00")),
+            Some(QueryParam::DateTime(date(2024, 1, 2).and_hms_opt(10, 30, 0).unwrap()))
+        );
+        assert_eq!(
+            ColumnKind::DateTime.param(&json!("2024-01-02")),
+            Some(QueryParam::DateTime(date(2024, 1, 2).and_time(NaiveTime::MIN)))
+        );
+        assert_eq!(ColumnKind::Text.param(&json!(5)), None);
+        assert_eq!(ColumnKind::Other.param(&json!("x")), None);
+    }
+
+    #[test]
+    fn comparison_operators() {
+        for (op, sql) in [("eq", "="), ("ne", "<>"), ("lt", "<"), ("lte", "<="), ("gt", ">"), ("gte", ">=")] {
+            let filter = format!(r#"{{"field":"NID","op":"{}","value":"7"}}"#, op);
+            assert_eq!(render(&filter), Ok((format!("[NID] {} @P1", sql), vec![QueryParam::Int(7)])));
+        }
+    }
+
+    #[test]
+    fn like_operators_escape_wildcards() {
+        assert_eq!(
+            render(r#"{"field":"Email","op":"contains","value":"a_b"}"#),
+            Ok(("[Email] LIKE @P1".to_string(), vec![text("%a[_]b%")]))
+        );
+        assert_eq!(
+            render(r#"{"field":"Email","op":"startsWith","value":"100%"}"#),
+            Ok(("[Email] LIKE @P1".to_string(), vec![text("100[%]%")]))
+        );
+        assert_eq!(render(r#"{"field":"Email","op":"contains","value":5}"#).err(), Some(invalid_value("Email", "5")));
+    }
+
+    #[test]
+    fn between_requires_two_values() {
+        assert_eq!(
+            render(r#"{"field":"CreatedDate","op":"between","value":["2024-01-01","2024-01-31"]}"#),
+            Ok((
+                "[CreatedDate] BETWEEN @P1 AND @P2".to_string(),
+                vec![QueryParam::Date(date(2024, 1, 1)), QueryParam::Date(date(2024, 1, 31))]
+            ))
+        );
+        assert_eq!(render(r#"{"field":"NID","op":"between","value":[1]}"#).err(), Some(invalid_value("NID", "[1]")));
+        assert_eq!(render(r#"{"field":"NID","op":"between","value":[1,2,3]}"#).err(), Some(invalid_value("NID", "[1,2,3]")));
+        assert_eq!(render(r#"{"field":"NID","op":"between","value":1}"#).err(), Some(invalid_value("NID", "1")));
+    }
+
+    #[test]
+    fn in_list_limits() {
+        let values = (1..=MAX_IN_VALUES).collect::<Vec<_>>();
+        let (condition, params) = render(&json!({"field": "NID", "op": "in", "value": values}).to_string()).unwrap();
+        let placeholders = (1..=MAX_IN_VALUES).map(|index| format!("@P{}", index)).collect::<Vec<_>>();
+        assert_eq!(condition, format!("[NID] IN ({})", placeholders.join(", ")));
+        assert_eq!(params.len(), MAX_IN_VALUES);
+
+        let too_many = (1..=MAX_IN_VALUES + 1).collect::<Vec<_>>();
+        assert!(matches!(
+            render(&json!({"field": "NID", "op": "in", "value": too_many}).to_string()),
+            Err(QueryError::InvalidValue { .. })
+        ));
+        assert_eq!(render(r#"{"field":"NID","op":"in","value":[]}"#).err(), Some(invalid_value("NID", "[]")));
+        assert_eq!(render(r#"{"field":"NID","op":"in","value":7}"#).err(), Some(invalid_value("NID", "7")));
+        assert_eq!(render(r#"{"field":"NID","op":"in","value":[1,"x"]}"#).err(), Some(invalid_value("NID", "[1,\"x\"]")));
+    }
+
+    #[test]
+    fn is_null_flag() {
+        assert_eq!(render(r#"{"field":"Payload","op":"isNull"}"#), Ok(("[Payload] IS NULL".to_string(), vec![])));
+        assert_eq!(render(r#"{"field":"Payload","op":"isNull","value":true}"#), Ok(("[Payload] IS NULL".to_string(), vec![])));
+        assert_eq!(render(r#"{"field":"Price","op":"isNull","value":false}"#), Ok(("[Price] IS NOT NULL".to_string(), vec![])));
+        assert_eq!(render(r#"{"field":"Price","op":"isNull","value":"yes"}"#).err(), Some(invalid_value("Price", "\"yes\"")));
+    }
+
+    #[test]
+    fn nested_groups() {
+        let filter = r#"{"or":[{"field":"Email","op":"eq","value":"budi"},{"and":[{"field":"Price","op":"gte","value":100},{"field":"Price","op":"lt","value":"200.5"}]},[{"field":"Active","op":"eq","value":true}]]}"#;
+        assert_eq!(
+            render(filter),
+            Ok((
+                "([Email] = @P1 OR ([Price] >= @P2 AND [Price] < @P3) OR [Active] = @P4)".to_string(),
+                vec![text("budi"), text("100"), text("200.5"), QueryParam::Bool(true)]
+            ))
+        );
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert_eq!(render(r#"{"field":"NID","op":"eq","value":"abc"}"#).err(), Some(invalid_value("NID", "\"abc\"")));
+        assert_eq!(render(r#"{"field":"NID","op":"eq","value":3000000000}"#).err(), Some(invalid_value("NID", "3000000000")));
+        assert_eq!(render(r#"{"field":"Price","op":"eq","value":"12,5"}"#).err(), Some(invalid_value("Price", "\"12,5\"")));
+        assert_eq!(render(r#"{"field":"Active","op":"eq","value":"maybe"}"#).err(), Some(invalid_value("Active", "\"maybe\"")));
+        assert_eq!(
+            render(r#"{"field":"CreatedDate","op":"eq","value":"2024-13-01"}"#).err(),
+            Some(invalid_value("CreatedDate", "\"2024-13-01\""))
+        );
+    }
+
+    fn seek_query(sort: &[(&str, SortOrder)], values: Vec<Value>, backward: bool) -> Result<TableQuery, QueryError> {
+        let table = table();
+        let mut builder = TableQueryBuilder::new(&table);
+        for (index, (column, order)) in sort.iter().enumerate() {
+            builder = if index + 1 == sort.len() { builder.tiebreak(column, *order)? } else { builder.order_by(column, *order)? };
+        }
+        let cursor = Cursor { backward, values, spec: String::new() };
+        Ok(builder.seek(&cursor)?.page(0, 10).build())
+    }
+
+    /// Kondisi seek dari query `select` berbentuk `SELECT TOP (..) .. WHERE <seek> ORDER BY ..`
+    fn seek_condition(query: &TableQuery) -> &str {
+        let (_, condition) = query.select.split_once(" WHERE ").unwrap();
+        condition.split_once(" ORDER BY ").unwrap().0
+    }
+
+    #[test]
+    fn parse_sort_columns_and_orders() {
+        assert_eq!(parse_sort(None, Some("desc")), Ok(vec![]));
+        assert_eq!(parse_sort(Some(" "), None), Ok(vec![]));
+        assert_eq!(
+            parse_sort(Some("Price:desc, Email"), None),
+            Ok(vec![("Price".to_string(), SortOrder::Desc), ("Email".to_string(), SortOrder::Asc)])
+        );
+        assert_eq!(
+            parse_sort(Some("Price,Email"), Some("desc")),
+            Ok(vec![("Price".to_string(), SortOrder::Desc), ("Email".to_string(), SortOrder::Desc)])
+        );
+        assert_eq!(
+            parse_sort(Some("Price,Email:desc"), Some("desc,asc")),
+            Ok(vec![("Price".to_string(), SortOrder::Desc), ("Email".to_string(), SortOrder::Desc)])
+        );
+        assert_eq!(parse_sort(Some("Price,Email"), Some("asc,desc,asc")), Err(QueryError::InvalidOrder("asc,desc,asc".to_string())));
+        assert_eq!(parse_sort(Some("Price:up"), None), Err(QueryError::InvalidOrder("up".to_string())));
+        assert_eq!(parse_sort(Some("Price,,Email"), None), Err(QueryError::InvalidSort("Price,,Email".to_string())));
+        assert_eq!(parse_sort(Some("a,b,c,d,e,f"), None), Err(QueryError::InvalidSort("a,b,c,d,e,f".to_string())));
+    }
+
+    #[test]
+    fn total_mode_parse() {
+        assert_eq!(TotalMode::parse(None), Ok(TotalMode::Exact));
+        assert_eq!(TotalMode::parse(Some(" Estimate ")), Ok(TotalMode::Estimate));
+        assert_eq!(TotalMode::parse(Some("none")), Ok(TotalMode::None));
+        assert_eq!(TotalMode::parse(Some("all")), Err(QueryError::InvalidTotal("all".to_string())));
+    }
+
+    #[test]
+    fn multi_column_order() {
+        let table = table();
+        let query = TableQueryBuilder::new(&table)
+            .order_by("Price", SortOrder::Desc).unwrap()
+            .order_by("price", SortOrder::Asc).unwrap()
+            .tiebreak("NID", SortOrder::Asc).unwrap()
+            .build();
+
+        assert!(query.select.ends_with(" ORDER BY [Price] DESC, [NID] ASC OFFSET @P1 ROWS FETCH NEXT @P2 ROWS ONLY"));
+        let keyset = query.keyset.iter().map(|column| column.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(keyset, vec!["Price", "NID"]);
+    }
+
+    #[test]
+    fn keyset_needs_unique_known_order() {
+        let table = table();
+        let query = TableQueryBuilder::new(&table).order_by("Price", SortOrder::Asc).unwrap().build();
+        assert!(query.keyset.is_empty());
+
+        let query = TableQueryBuilder::new(&table)
+            .order_by("Payload", SortOrder::Asc).unwrap()
+            .tiebreak("NID", SortOrder::Asc).unwrap()
+            .build();
+        assert!(query.keyset.is_empty());
+    }
+
+    #[test]
+    fn seek_ascending() {
+        let query = seek_query(&[("Price", SortOrder::Asc), ("NID", SortOrder::Asc)], vec![json!("12.50"), json!(7)], false).unwrap();
+        assert_eq!(
+            query.select,
+            format!(
+                "SELECT TOP (@P3) {} FROM [dbo].[Orders] WHERE (([Price] > @P1) OR ([Price] = @P1 AND [NID] > @P2)) ORDER BY [Price] ASC, [NID] ASC",
+                COLUMNS
+            )
+        );
+        assert_eq!(query.params, vec![text("12.50"), QueryParam::Int(7), QueryParam::Int(11)]);
+        assert!(query.filter_params().is_empty());
+        assert!(!query.backward);
+    }
+
+    #[test]
+    fn seek_ascending_from_null() {
+        // NULL paling kecil: setelahnya semua yang tidak NULL
+        let query = seek_query(&[("Price", SortOrder::Asc), ("NID", SortOrder::Asc)], vec![Value::Null, json!(7)], false).unwrap();
+        assert_eq!(seek_condition(&query), "(([Price] IS NOT NULL) OR ([Price] IS NULL AND [NID] > @P1))");
+        assert_eq!(query.params, vec![QueryParam::Int(7), QueryParam::Int(11)]);
+    }
+
+    #[test]
+    fn seek_descending() {
+        let query = seek_query(&[("Price", SortOrder::Desc), ("NID", SortOrder::Asc)], vec![json!("12.50"), json!(7)], false).unwrap();
+        assert_eq!(seek_condition(&query), "((([Price] < @P1 OR [Price] IS NULL)) OR ([Price] = @P1 AND [NID] > @P2))");
+        assert!(query.select.ends_with(" ORDER BY [Price] DESC, [NID] ASC"));
+    }
+
+    #[test]
+    fn seek_descending_from_null() {
+        // Tidak ada yang lebih kecil dari NULL, hanya lanjut di dalam grup NULL
+        let query = seek_query(&[("Price", SortOrder::Desc), ("NID", SortOrder::Asc)], vec![Value::Null, json!(7)], false).unwrap();
+        assert_eq!(seek_condition(&query), "(([Price] IS NULL AND [NID] > @P1))");
+
+        let query = seek_query(&[("Price", SortOrder::Desc)], vec![Value::Null], false).unwrap();
+        assert_eq!(seek_condition(&query), "1 = 0");
+    }
+
+    #[test]
+    fn seek_three_column_prefixes() {
+        let query = seek_query(
+            &[("Active", SortOrder::Asc), ("Price", SortOrder::Desc), ("NID", SortOrder::Asc)],
+            vec![json!(true), json!(1), json!(3)],
+            false,
+        ).unwrap();
+        assert_eq!(
+            seek_condition(&query),
+            "(([Active] > @P1) OR ([Active] = @P1 AND ([Price] < @P2 OR [Price] IS NULL)) OR ([Active] = @P1 AND [Price] = @P2 AND [NID] > @P3))"
+        );
+        assert_eq!(query.params, vec![QueryParam::Bool(true), text("1"), QueryParam::Int(3), QueryParam::Int(11)]);
+    }
+
+    #[test]
+    fn seek_backward_reverses_order() {
+        let query = seek_query(&[("Price", SortOrder::Asc), ("NID", SortOrder::Asc)], vec![json!("12.50"), json!(7)], true).unwrap();
+        assert_eq!(
+            seek_condition(&query),
+            "((([Price] < @P1 OR [Price] IS NULL)) OR ([Price] = @P1 AND ([NID] < @P2 OR [NID] IS NULL)))"
+        );
+        assert!(query.select.ends_with(" ORDER BY [Price] DESC, [NID] DESC"));
+        assert!(query.backward);
+    }
+
+    #[test]
+    fn seek_keeps_filter_params_first() {
+        let table = table();
+        let cursor = Cursor { backward: false, values: vec![json!(7)], spec: String::new() };
+        let query = TableQueryBuilder::new(&table)
+            .filter_json(Some(r#"{"field":"Active","op":"eq","value":true}"#)).unwrap()
+            .tiebreak("NID", SortOrder::Asc).unwrap()
+            .seek(&cursor).unwrap()
+            .page(0, 10)
+            .build();
+
+        assert!(query.select.contains(" WHERE [Active] = @P1 AND (([NID] > @P2)) ORDER BY "));
+        assert_eq!(query.filter_params().len(), 1);
+        assert_eq!(query.params, vec![QueryParam::Bool(true), QueryParam::Int(7), QueryParam::Int(11)]);
+    }
+
+    #[test]
+    fn seek_rejects_mismatched_cursor() {
+        let order = [("Price", SortOrder::Asc), ("NID", SortOrder::Asc)];
+        assert_eq!(seek_query(&order, vec![json!(7)], false).err(), Some(QueryError::InvalidCursor));
+        assert_eq!(seek_query(&order, vec![json!("12.50"), json!("x")], false).err(), Some(QueryError::InvalidCursor));
+        assert_eq!(
+            seek_query(&[("Payload", SortOrder::Asc), ("NID", SortOrder::Asc)], vec![json!("x"), json!(7)], false).err(),
+            Some(QueryError::InvalidCursor)
+        );
+
+        // Tanpa tiebreak urutan tidak unik
+        let table = table();
+        let cursor = Cursor { backward: false, values: vec![json!("12.50")], spec: String::new() };
+        let result = TableQueryBuilder::new(&table).order_by("Price", SortOrder::Asc).unwrap().seek(&cursor);
+        assert_eq!(result.err(), Some(QueryError::InvalidCursor));
+    }
+
+    #[test]
+    fn spec_follows_table_sort_and_filter() {
+        let table = table();
+        let build = |filter: &str, order: SortOrder| TableQueryBuilder::new(&table)
+            .filter_json(Some(filter)).unwrap()
+            .tiebreak("NID", order).unwrap()
+            .build();
+
+        let query = build(r#"{"Email":"budi"}"#, SortOrder::Asc);
+        let cursor = query.cursor(vec![json!(7)], false);
+        let next = TableQueryBuilder::new(&table)
+            .filter_json(Some(r#"{"Email":"budi"}"#)).unwrap()
+            .tiebreak("NID", SortOrder::Asc).unwrap()
+            .seek(&cursor).unwrap()
+            .page(10, 10)
+            .build();
+
+        // Paging & nilai cursor tidak mengubah spec, filter & sort mengubahnya
+        assert_eq!(next.spec, cursor.spec);
+        assert_ne!(build(r#"{"Email":"andi"}"#, SortOrder::Asc).spec, cursor.spec);
+        assert_ne!(build(r#"{"Email":"budi"}"#, SortOrder::Desc).spec, cursor.spec);
+    }
+
+    #[test]
+    fn cursor_token_round_trip() {
+        let cursor = Cursor { backward: true, values: vec![json!("12.50"), Value::Null, json!(7)], spec: "0123456789abcdef".to_string() };
+        let token = cursor.encode();
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(Cursor::decode(&token), Ok(cursor));
+        assert_eq!(Cursor::decode("not a cursor"), Err(QueryError::InvalidCursor));
+        assert_eq!(Cursor::decode(&URL_SAFE_NO_PAD.encode("[1,2]")), Err(QueryError::InvalidCursor));
+    }
+}
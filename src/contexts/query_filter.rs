@@ -0,0 +1,262 @@
+//! 🔎 Grammar filter JSON untuk `/data/get-table`, hasil parse dirender `TableQueryBuilder::filter_expr`.
+//!
+//! - Kondisi: `{"field":"Price","op":"gte","value":100}`
+//! - Grup: `{"and":[...]}` / `{"or":[...]}`, boleh bersarang, array di level mana pun = `and`
+//! - Format lama (flat map) tetap didukung: `{"Email":"budi","LastUpdate":"2025-01-01"}`
+//!
+//! Operator: `eq` `ne` `lt` `lte` `gt` `gte` `between` (`value` = `[dari, sampai]`), `in` (`value` = array),
+//! `contains` `startsWith` (hanya kolom teks), `isNull` (`value` = `true` / `false`, default `true`).
+
+use serde_json::Value;
+
+use super::query_builder::QueryError;
+
+/// Kedalaman grup maksimal
+pub const MAX_FILTER_DEPTH: usize = 5;
+/// Jumlah kondisi maksimal dalam satu filter
+pub const MAX_FILTER_CONDITIONS: usize = 50;
+/// Jumlah nilai maksimal untuk `in`
+pub const MAX_IN_VALUES: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Between,
+    In,
+    Contains,
+    StartsWith,
+    IsNull,
+}
+
+impl FilterOp {
+    pub fn parse(op: &str) -> Option<Self> {
+        match op {
+            "eq" => Some(Self::Eq),
+            "ne" => Some(Self::Ne),
+            "lt" => Some(Self::Lt),
+            "lte" => Some(Self::Lte),
+            "gt" => Some(Self::Gt),
+            "gte" => Some(Self::Gte),
+            "between" => Some(Self::Between),
+            "in" => Some(Self::In),
+            "contains" => Some(Self::Contains),
+            "startsWith" => Some(Self::StartsWith),
+            "isNull" => Some(Self::IsNull),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Eq => "eq",
+            Self::Ne => "ne",
+            Self::Lt => "lt",
+            Self::Lte => "lte",
+            Self::Gt => "gt",
+            Self::Gte => "gte",
+            Self::Between => "between",
+            Self::In => "in",
+            Self::Contains => "contains",
+            Self::StartsWith => "startsWith",
+            Self::IsNull => "isNull",
+        }
+    }
+
+    /// Operator SQL untuk perbandingan satu nilai
+    pub fn comparison_sql(self) -> Option<&'static str> {
+        match self {
+            Self::Eq => Some("="),
+            Self::Ne => Some("<>"),
+            Self::Lt => Some("<"),
+            Self::Lte => Some("<="),
+            Self::Gt => Some(">"),
+            Self::Gte => Some(">="),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterExpr {
+    And(Vec<FilterExpr>),
+    Or(Vec<FilterExpr>),
+    Condition { field: String, op: FilterOp, value: Value },
+    /// Satu pasangan format lama, operator ditebak dari nama kolom
+    Legacy { field: String, value: String },
+}
+
+fn invalid(reason: &str) -> QueryError {
+    QueryError::InvalidFilter(reason.to_string())
+}
+
+/// `None` jika tanpa filter (kosong, `{}` atau `{filter:undefined}` dari frontend lama)
+pub fn parse_filter(filter: Option<&str>) -> Result<Option<FilterExpr>, QueryError> {
+    let filter = match filter.map(str::trim) {
+        None | Some("") | Some("{filter:undefined}") => return Ok(None),
+        Some(filter) => filter,
+    };
+
+    let value = serde_json::from_str::<Value>(filter)
+        .map_err(|_| invalid("expected a JSON object, array or condition"))?;
+    if value.as_object().is_some_and(|map| map.is_empty()) {
+        return Ok(None);
+    }
+
+    let mut count = 0;
+    parse_node(&value, 0, &mut count).map(Some)
+}
+
+fn parse_group(items: &Value, depth: usize, count: &mut usize) -> Result<Vec<FilterExpr>, QueryError> {
+    let items = items.as_array().ok_or_else(|| invalid("'and' / 'or' expects an array"))?;
+    if items.is_empty() {
+        return Err(invalid("empty 'and' / 'or' group"));
+    }
+
+    items.iter().map(|item| parse_node(item, depth + 1, count)).collect()
+}
+
+fn parse_node(value: &Value, depth: usize, count: &mut usize) -> Result<FilterExpr, QueryError> {
+    if depth > MAX_FILTER_DEPTH {
+        return Err(invalid(&format!("groups nested deeper than {}", MAX_FILTER_DEPTH)));
+    }
+
+    let map = match value {
+        Value::Array(_) => return parse_group(value, depth, count).map(FilterExpr::And),
+        Value::Object(map) => map,
+        _ => return Err(invalid("expected a JSON object, array or condition")),
+    };
+
+    if map.len() == 1 {
+        if let Some(items) = map.get("and") {
+            return parse_group(items, depth, count).map(FilterExpr::And);
+        }
+        if let Some(items) = map.get("or") {
+            return parse_group(items, depth, count).map(FilterExpr::Or);
+        }
+    }
+
+    if let Some(op) = map.get("op") {
+        *count += 1;
+        if *count > MAX_FILTER_CONDITIONS {
+            return Err(invalid(&format!("more than {} conditions", MAX_FILTER_CONDITIONS)));
+        }
+
+        let op = op.as_str().ok_or_else(|| invalid("'op' must be a string"))?;
+        let op = FilterOp::parse(op).ok_or_else(|| invalid(&format!("unknown operator '{}'", op)))?;
+        let field = map.get("field")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid("condition requires a 'field' string"))?;
+
+        return Ok(FilterExpr::Condition {
+            field: field.to_string(),
+            op,
+            value: map.get("value").cloned().unwrap_or(Value::Null),
+        });
+    }
+
+    // Format lama: semua nilai string, urut nama kolom supaya nomor parameter stabil
+    *count += map.len();
+    if *count > MAX_FILTER_CONDITIONS {
+        return Err(invalid(&format!("more than {} conditions", MAX_FILTER_CONDITIONS)));
+    }
+
+    let items = map.iter()
+        .map(|(field, value)| match value.as_str() {
+            Some(value) => Ok(FilterExpr::Legacy { field: field.to_string(), value: value.to_string() }),
+            None => Err(invalid(&format!("value of '{}' must be a string, use a condition for typed values", field))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(FilterExpr::And(items))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const CONDITION: &str = r#"{"field":"Email","op":"eq","value":"budi"}"#;
+
+    fn condition(field: &str, op: FilterOp, value: Value) -> FilterExpr {
+        FilterExpr::Condition { field: field.to_string(), op, value }
+    }
+
+    fn nested(depth: usize) -> String {
+        format!("{}{}{}", r#"{"and":["#.repeat(depth), CONDITION, "]}".repeat(depth))
+    }
+
+    fn is_invalid(filter: &str) -> bool {
+        matches!(parse_filter(Some(filter)), Err(QueryError::InvalidFilter(_)))
+    }
+
+    #[test]
+    fn empty_filters() {
+        for filter in [None, Some(""), Some("  "), Some("{}"), Some("{filter:undefined}")] {
+            assert_eq!(parse_filter(filter), Ok(None), "{:?}", filter);
+        }
+    }
+
+    #[test]
+    fn operators_round_trip() {
+        for op in ["eq", "ne", "lt", "lte", "gt", "gte", "between", "in", "contains", "startsWith", "isNull"] {
+            assert_eq!(FilterOp::parse(op).map(FilterOp::as_str), Some(op));
+        }
+        assert_eq!(FilterOp::parse("like"), None);
+        assert_eq!(FilterOp::parse("EQ"), None);
+        assert_eq!(FilterOp::Between.comparison_sql(), None);
+    }
+
+    #[test]
+    fn condition_and_groups() {
+        let filter = r#"{"or":[{"field":"Email","op":"contains","value":"budi"},[{"field":"Price","op":"gte","value":100},{"field":"Price","op":"isNull"}]]}"#;
+        assert_eq!(parse_filter(Some(filter)), Ok(Some(FilterExpr::Or(vec![
+            condition("Email", FilterOp::Contains, json!("budi")),
+            FilterExpr::And(vec![
+                condition("Price", FilterOp::Gte, json!(100)),
+                condition("Price", FilterOp::IsNull, Value::Null),
+            ]),
+        ]))));
+    }
+
+    #[test]
+    fn legacy_map_sorted_by_column() {
+        assert_eq!(parse_filter(Some(r#"{"Name":"budi","Email":"x"}"#)), Ok(Some(FilterExpr::And(vec![
+            FilterExpr::Legacy { field: "Email".to_string(), value: "x".to_string() },
+            FilterExpr::Legacy { field: "Name".to_string(), value: "budi".to_string() },
+        ]))));
+        assert!(is_invalid(r#"{"Price":100}"#));
+    }
+
+    #[test]
+    fn malformed_filters() {
+        assert!(is_invalid("not json"));
+        assert!(is_invalid("42"));
+        assert!(is_invalid(r#"{"and":[]}"#));
+        assert!(is_invalid(r#"{"or":{"field":"Email","op":"eq"}}"#));
+        assert!(is_invalid(r#"{"field":"Email","op":"like","value":"x"}"#));
+        assert!(is_invalid(r#"{"field":"Email","op":1}"#));
+        assert!(is_invalid(r#"{"op":"eq","value":"x"}"#));
+    }
+
+    #[test]
+    fn depth_limit() {
+        assert!(parse_filter(Some(&nested(MAX_FILTER_DEPTH))).is_ok());
+        assert!(is_invalid(&nested(MAX_FILTER_DEPTH + 1)));
+    }
+
+    #[test]
+    fn condition_limit() {
+        let filter = |count: usize| format!("[{}]", vec![CONDITION; count].join(","));
+        assert!(parse_filter(Some(&filter(MAX_FILTER_CONDITIONS))).is_ok());
+        assert!(is_invalid(&filter(MAX_FILTER_CONDITIONS + 1)));
+
+        let legacy = (0..=MAX_FILTER_CONDITIONS).map(|index| format!(r#""C{}":"x""#, index)).collect::<Vec<_>>();
+        assert!(is_invalid(&format!("{{{}}}", legacy.join(","))));
+    }
+}
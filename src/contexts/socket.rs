@@ -1,103 +1,70 @@
-// use actix::{Actor, StreamHandler, AsyncContext, Handler, Message, Addr};
-// use actix_web_actors::ws::{self, WebsocketContext, Message as WsMessage};
-// use serde_json::Value;
-// use lazy_static::lazy_static;
-// use std::sync::Mutex;
-
-// lazy_static! {
-//     static ref CLIENTS: Mutex<Vec<Addr<WsSession>>> = Mutex::new(vec![]);
-// }
-
-// pub fn register(addr: Addr<WsSession>) {
-//     let mut clients = CLIENTS.lock().unwrap();
-//     clients.push(addr);
-// }
-
-// pub fn broadcast(message: WsPushEvent) {
-//     let clients = CLIENTS.lock().unwrap();
-//     for client in clients.iter() {
-//         let _ = client.do_send(message.clone());
-//     }
-// }
-
-// pub struct WsSession;
-
-// impl WsSession {
-//     pub fn new() -> Self {
-//         WsSession
-//     }
-// }
-
-// impl Actor for WsSession {
-//     type Context = WebsocketContext<Self>;
-
-//     fn started(&mut self, ctx: &mut Self::Context) {
-//         register(ctx.address());
-//     }
-// }
-
-// impl StreamHandler<Result<WsMessage, ws::ProtocolError>> for WsSession {
-//     fn handle(&mut self, msg: Result<WsMessage, ws::ProtocolError>, _ctx: &mut WebsocketContext<Self>) {
-//         if let Ok(WsMessage::Text(text)) = msg {
-//             println!("Client sent: {}", text);
-//         }
-//     }
-// }
-
-// impl Handler<WsPushEvent> for WsSession {
-//     type Result = ();
-
-//     fn handle(&mut self, msg: WsPushEvent, ctx: &mut WebsocketContext<Self>) {
-//         let json = serde_json::json!({
-//             "event": msg.event,
-//             "data": msg.data
-//         });
-//         ctx.text(json.to_string());
-//     }
-// }
-
-// #[derive(Message, Clone)]
-// #[rtype(result = "()")]
-// pub struct WsPushEvent {
-//     pub event: String,
-//     pub data: Value,
-// }
-
-// // Fungsi utama yang bisa dipanggil dari luar
-// pub fn send_ws_event(event: &str, data: impl serde::Serialize) {
-//     if let Ok(json) = serde_json::to_value(data) {
-//         broadcast(WsPushEvent {
-//             event: event.to_string(),
-//             data: json,
-//         });
-//     }
-// }
-
-use actix::{Actor, StreamHandler, AsyncContext, Handler, Message, Addr};
-use actix_web_actors::ws::{self, WebsocketContext, Message as WsMessage};
+use actix::{Actor, ActorContext, ActorFutureExt, StreamHandler, AsyncContext, Handler, Message, Addr, WrapFuture};
+use actix_web::web;
+use actix_web_actors::ws::{self, CloseCode, CloseReason, WebsocketContext, Message as WsMessage};
+use bb8::Pool;
+use bb8_tiberius::ConnectionManager;
 use serde_json::Value;
 use lazy_static::lazy_static;
 use std::sync::Mutex;
 
+use crate::services::auth_service::AuthService;
+
+use super::{jwt_session::Claims, model::ActionResult};
+
+/// Koneksi aktif beserta pemiliknya (jika handshake membawa token login)
+struct WsClient {
+    addr: Addr<WsSession>,
+    auth_usernid: Option<i32>,
+    permissions: Vec<String>,
+}
+
 lazy_static! {
-    static ref CLIENTS: Mutex<Vec<Addr<WsSession>>> = Mutex::new(vec![]);
+    static ref CLIENTS: Mutex<Vec<WsClient>> = Mutex::new(vec![]);
 }
 
-pub fn register(addr: Addr<WsSession>) {
+pub fn register(addr: Addr<WsSession>, auth_usernid: Option<i32>, permissions: Vec<String>) {
     let mut clients = CLIENTS.lock().unwrap();
-    clients.push(addr);
+    clients.push(WsClient { addr, auth_usernid, permissions });
 }
 
 pub fn broadcast(message: WsPushEvent) {
+    send_where(message, |_| true);
+}
+
+/// Permission hasil cek ulang sesi, supaya role yang dicabut tidak terus dikirimi event
+fn update_permissions(addr: &Addr<WsSession>, permissions: &[String]) {
     let mut clients = CLIENTS.lock().unwrap();
-    clients.retain(|client| client.try_send(message.clone()).is_ok());
+    if let Some(client) = clients.iter_mut().find(|client| &client.addr == addr) {
+        client.permissions = permissions.to_vec();
+    }
 }
 
-pub struct WsSession;
+// Client yang mailbox-nya sudah tertutup sekalian dibuang
+fn send_where(message: WsPushEvent, filter: impl Fn(&WsClient) -> bool) {
+    let mut clients = CLIENTS.lock().unwrap();
+    clients.retain(|client| !filter(client) || client.addr.try_send(message.clone()).is_ok());
+}
+
+#[derive(Default)]
+pub struct WsSession {
+    pub auth_usernid: Option<i32>,
+    pub permissions: Vec<String>,
+    /// Pool & SessionID pemilik socket, sesi dicek ulang sebelum event privat dikirim
+    session: Option<(web::Data<Pool<ConnectionManager>>, String)>,
+}
 
 impl WsSession {
     pub fn new() -> Self {
-        WsSession
+        WsSession::default()
+    }
+
+    /// Sesi milik user login, bisa menerima event privat (`send_ws_event_to_user`)
+    pub fn for_user(connection: web::Data<Pool<ConnectionManager>>, claims: Claims) -> Self {
+        WsSession {
+            auth_usernid: Some(claims.auth_usernid),
+            permissions: claims.permissions,
+            session: Some((connection, claims.session_id)),
+        }
     }
 }
 
@@ -105,13 +72,13 @@ impl Actor for WsSession {
     type Context = WebsocketContext<Self>;
 
     fn started(&mut self, ctx: &mut Self::Context) {
-        register(ctx.address());
+        register(ctx.address(), self.auth_usernid, self.permissions.clone());
     }
 
     fn stopped(&mut self, ctx: &mut Self::Context) {
         let addr = ctx.address();
         let mut clients = CLIENTS.lock().unwrap();
-        clients.retain(|client| client != &addr);
+        clients.retain(|client| client.addr != addr);
     }
 }
 
@@ -123,11 +90,20 @@ impl StreamHandler<Result<WsMessage, ws::ProtocolError>> for WsSession {
     }
 }
 
+/// Penerima event, yang privat dicek ulang ke `WEB_Cookies` oleh tiap socket
+#[derive(Clone)]
+pub enum WsAudience {
+    All,
+    User,
+    Permission(String),
+}
+
 #[derive(Message, Clone)]
 #[rtype(result = "()")]
 pub struct WsPushEvent {
     pub event: String,
     pub data: Value,
+    pub audience: WsAudience,
 }
 
 impl Handler<WsPushEvent> for WsSession {
@@ -137,8 +113,40 @@ impl Handler<WsPushEvent> for WsSession {
         let json = serde_json::json!({
             "event": msg.event,
             "data": msg.data
-        });
-        ctx.text(json.to_string());
+        }).to_string();
+
+        let (auth_usernid, connection, session_id) = match (&msg.audience, self.auth_usernid, &self.session) {
+            (WsAudience::All, _, _) => {
+                ctx.text(json);
+                return;
+            }
+            (_, Some(auth_usernid), Some((connection, session_id))) => (auth_usernid, connection.clone(), session_id.clone()),
+            _ => return,
+        };
+
+        // `wait` menahan event berikutnya sampai cek selesai, urutan event tetap terjaga
+        let access = AuthService::session_permissions(connection, auth_usernid, session_id);
+        ctx.wait(access.into_actor(self).map(move |access, actor, ctx| match access {
+            response if response.error.is_some() => {
+                eprintln!("❌ Failed to check WebSocket session: {:?}", response.error);
+            }
+            ActionResult { result: true, data: Some(permissions), .. } => {
+                let allowed = match &msg.audience {
+                    WsAudience::Permission(permission) => permissions.iter().any(|p| p == permission),
+                    _ => true,
+                };
+                update_permissions(&ctx.address(), &permissions);
+                actor.permissions = permissions;
+                if allowed {
+                    ctx.text(json);
+                }
+            }
+            _ => {
+                // Sesi sudah logout / dicabut, putuskan socket
+                ctx.close(Some(CloseReason::from(CloseCode::Policy)));
+                ctx.stop();
+            }
+        }));
     }
 }
 
@@ -147,6 +155,23 @@ pub fn send_ws_event(event: &str, data: impl serde::Serialize) {
         broadcast(WsPushEvent {
             event: event.to_string(),
             data: json,
+            audience: WsAudience::All,
         });
     }
 }
+
+/// Event privat, hanya ke koneksi milik user tersebut
+pub fn send_ws_event_to_user(auth_usernid: i32, event: &str, data: impl serde::Serialize) {
+    if let Ok(json) = serde_json::to_value(data) {
+        let message = WsPushEvent { event: event.to_string(), data: json, audience: WsAudience::User };
+        send_where(message, |client| client.auth_usernid == Some(auth_usernid));
+    }
+}
+
+/// Event untuk semua user login yang punya permission tertentu, misalnya reviewer
+pub fn send_ws_event_to_permission(permission: &str, event: &str, data: impl serde::Serialize) {
+    if let Ok(json) = serde_json::to_value(data) {
+        let message = WsPushEvent { event: event.to_string(), data: json, audience: WsAudience::Permission(permission.to_string()) };
+        send_where(message, |client| client.permissions.iter().any(|p| p == permission));
+    }
+}
@@ -0,0 +1,243 @@
+//! 🧬 Nilai kolom SQL Server (`ColumnData` tiberius) ke JSON tanpa kehilangan data.
+//! Murni konversi nilai, tidak butuh koneksi database.
+//!
+//! - Decimal / numeric → string persis (default) atau angka (`DecimalFormat::Number`)
+//! - money / smallmoney dikirim driver sebagai float, hanya persis sampai ±9×10¹¹ (15 digit signifikan).
+//!   get-table meng-CAST kolom money ke `DECIMAL(19,4)` (lihat `TableColumn::select_expr`) sehingga sampai di sini sebagai numeric
+//! - bigint di luar rentang aman JavaScript (±2^53 - 1) → string
+//! - binary → base64, uniqueidentifier → string huruf besar seperti tampilan SQL Server
+//! - date / time / datetime / datetime2 → ISO 8601, datetimeoffset → RFC 3339 dengan offset
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use serde_json::{json, Value};
+use tiberius::{numeric::Numeric, ColumnData, ColumnType, FromSql};
+
+/// Bilangan bulat terbesar yang masih persis di `Number` JavaScript
+pub const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// Bentuk decimal, numeric & money di JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecimalFormat {
+    /// `"12.50"`, persis sesuai scale kolom
+    #[default]
+    String,
+    /// `12.5`, bisa kehilangan presisi di atas 15-17 digit
+    Number,
+}
+
+impl DecimalFormat {
+    pub fn parse(format: &str) -> Option<Self> {
+        match format.trim().to_ascii_lowercase().as_str() {
+            "" | "string" => Some(Self::String),
+            "number" => Some(Self::Number),
+            _ => None,
+        }
+    }
+}
+
+/// Decimal persis sebagai teks sesuai scale, misal `-0.50`
+pub fn numeric_to_string(num: Numeric) -> String {
+    let raw = num.value();
+    let scale = num.scale() as u32;
+    let divisor = 10i128.pow(scale);
+    let int_part = raw / divisor;
+    let frac_raw = raw.abs() % divisor;
+
+    if scale == 0 {
+        int_part.to_string()
+    } else if raw < 0 && int_part == 0 {
+        format!("-0.{:0>width$}", frac_raw, width = scale as usize)
+    } else {
+        format!("{}.{:0>width$}", int_part, frac_raw, width = scale as usize)
+    }
+}
+
+fn decimal_to_json(text: String, format: DecimalFormat) -> Value {
+    match format {
+        DecimalFormat::String => json!(text),
+        DecimalFormat::Number => text.parse::<f64>().ok().filter(|value| value.is_finite()).map_or(Value::Null, |value| json!(value)),
+    }
+}
+
+fn float_to_json(value: f64) -> Value {
+    if value.is_finite() { json!(value) } else { Value::Null }
+}
+
+/// Nilai temporal lewat konversi chrono bawaan tiberius, `None` = NULL
+fn temporal<'a, T: FromSql<'a>>(data: &'a ColumnData<'static>, to_json: impl FnOnce(T) -> Value) -> Value {
+    T::from_sql(data).ok().flatten().map_or(Value::Null, to_json)
+}
+
+/// Satu nilai kolom ke JSON. `column_type` dibutuhkan untuk money, yang dikirim driver sebagai float
+pub fn column_to_json(column_type: ColumnType, data: &ColumnData<'static>, decimals: DecimalFormat) -> Value {
+    match data {
+        ColumnData::U8(value) => value.map_or(Value::Null, |value| json!(value)),
+        ColumnData::I16(value) => value.map_or(Value::Null, |value| json!(value)),
+        ColumnData::I32(value) => value.map_or(Value::Null, |value| json!(value)),
+        ColumnData::I64(value) => value.map_or(Value::Null, |value| {
+            if (-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&value) { json!(value) } else { json!(value.to_string()) }
+        }),
+        // Money selalu 4 digit desimal, lewat f64 jadi digit di atas ~15 angka signifikan bisa hilang
+        ColumnData::F64(value) if matches!(column_type, ColumnType::Money | ColumnType::Money4) => {
+            value.filter(|value| value.is_finite()).map_or(Value::Null, |value| decimal_to_json(format!("{:.4}", value), decimals))
+        }
+        // Lewat representasi terpendek f32 supaya 0.1 tidak jadi 0.10000000149011612
+        ColumnData::F32(value) => value.and_then(|value| value.to_string().parse::<f64>().ok()).map_or(Value::Null, float_to_json),
+        ColumnData::F64(value) => value.map_or(Value::Null, float_to_json),
+        ColumnData::Bit(value) => value.map_or(Value::Null, |value| json!(value)),
+        ColumnData::String(value) => value.as_deref().map_or(Value::Null, |value| json!(value)),
+        ColumnData::Guid(value) => value.map_or(Value::Null, |value| json!(value.to_string().to_uppercase())),
+        ColumnData::Binary(value) => value.as_deref().map_or(Value::Null, |value| json!(STANDARD.encode(value))),
+        ColumnData::Numeric(value) => value.map_or(Value::Null, |value| decimal_to_json(numeric_to_string(value), decimals)),
+        ColumnData::Xml(value) => value.as_deref().map_or(Value::Null, |value| json!(value.to_string())),
+        ColumnData::DateTime(_) | ColumnData::SmallDateTime(_) | ColumnData::DateTime2(_) => {
+            temporal(data, |value: NaiveDateTime| json!(value))
+        }
+        ColumnData::Date(_) => temporal(data, |value: NaiveDate| json!(value)),
+        ColumnData::Time(_) => temporal(data, |value: NaiveTime| json!(value)),
+        ColumnData::DateTimeOffset(_) => temporal(data, |value: DateTime<FixedOffset>| json!(value.to_rfc3339())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use chrono::{NaiveDate, TimeZone};
+    use tiberius::{time, xml::XmlData, IntoSql, Uuid};
+
+    use super::*;
+
+    fn to_json(column_type: ColumnType, data: ColumnData<'static>) -> Value {
+        column_to_json(column_type, &data, DecimalFormat::String)
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()
+    }
+
+    #[test]
+    fn numeric_to_string_keeps_scale_and_sign() {
+        assert_eq!(numeric_to_string(Numeric::new_with_scale(1250, 2)), "12.50");
+        assert_eq!(numeric_to_string(Numeric::new_with_scale(-1234, 2)), "-12.34");
+        assert_eq!(numeric_to_string(Numeric::new_with_scale(-5, 2)), "-0.05");
+        assert_eq!(numeric_to_string(Numeric::new_with_scale(5, 2)), "0.05");
+        assert_eq!(numeric_to_string(Numeric::new_with_scale(-42, 0)), "-42");
+        assert_eq!(numeric_to_string(Numeric::new_with_scale(0, 4)), "0.0000");
+        assert_eq!(
+            numeric_to_string(Numeric::new_with_scale(12_345_678_901_234_567_890_123_456_789_012_345_678, 10)),
+            "1234567890123456789012345678.9012345678"
+        );
+    }
+
+    #[test]
+    fn decimal_format_parse() {
+        assert_eq!(DecimalFormat::parse(""), Some(DecimalFormat::String));
+        assert_eq!(DecimalFormat::parse(" Number "), Some(DecimalFormat::Number));
+        assert_eq!(DecimalFormat::parse("float"), None);
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(to_json(ColumnType::Int1, ColumnData::U8(Some(255))), json!(255));
+        assert_eq!(to_json(ColumnType::Int2, ColumnData::I16(Some(-32768))), json!(-32768));
+        assert_eq!(to_json(ColumnType::Int4, ColumnData::I32(Some(2_147_483_647))), json!(2_147_483_647));
+        assert_eq!(to_json(ColumnType::Int8, ColumnData::I64(Some(MAX_SAFE_INTEGER))), json!(MAX_SAFE_INTEGER));
+        assert_eq!(to_json(ColumnType::Int8, ColumnData::I64(Some(-MAX_SAFE_INTEGER))), json!(-MAX_SAFE_INTEGER));
+    }
+
+    #[test]
+    fn bigint_beyond_safe_range_is_string() {
+        assert_eq!(to_json(ColumnType::Int8, ColumnData::I64(Some(MAX_SAFE_INTEGER + 1))), json!("9007199254740992"));
+        assert_eq!(to_json(ColumnType::Int8, ColumnData::I64(Some(i64::MIN))), json!("-9223372036854775808"));
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(to_json(ColumnType::Float4, ColumnData::F32(Some(0.1))), json!(0.1));
+        assert_eq!(to_json(ColumnType::Float8, ColumnData::F64(Some(0.1))), json!(0.1));
+        assert_eq!(to_json(ColumnType::Float8, ColumnData::F64(Some(f64::NAN))), Value::Null);
+        assert_eq!(to_json(ColumnType::Float4, ColumnData::F32(Some(f32::INFINITY))), Value::Null);
+    }
+
+    #[test]
+    fn money_has_four_decimals() {
+        assert_eq!(to_json(ColumnType::Money, ColumnData::F64(Some(12.5))), json!("12.5000"));
+        assert_eq!(to_json(ColumnType::Money4, ColumnData::F64(Some(-0.01))), json!("-0.0100"));
+        assert_eq!(column_to_json(ColumnType::Money, &ColumnData::F64(Some(12.5)), DecimalFormat::Number), json!(12.5));
+    }
+
+    #[test]
+    fn decimal_as_string_or_number() {
+        let data = ColumnData::Numeric(Some(Numeric::new_with_scale(-5, 2)));
+        assert_eq!(column_to_json(ColumnType::Decimaln, &data, DecimalFormat::String), json!("-0.05"));
+        assert_eq!(column_to_json(ColumnType::Decimaln, &data, DecimalFormat::Number), json!(-0.05));
+    }
+
+    #[test]
+    fn text_bit_guid_binary_xml() {
+        assert_eq!(to_json(ColumnType::Bit, ColumnData::Bit(Some(true))), json!(true));
+        assert_eq!(to_json(ColumnType::NVarchar, ColumnData::String(Some(Cow::Borrowed("Budi")))), json!("Budi"));
+
+        let guid = Uuid::parse_str("6f9619ff-8b86-d011-b42d-00c04fc964ff").unwrap();
+        assert_eq!(to_json(ColumnType::Guid, ColumnData::Guid(Some(guid))), json!("6F9619FF-8B86-D011-B42D-00C04FC964FF"));
+
+        assert_eq!(to_json(ColumnType::BigVarBin, ColumnData::Binary(Some(Cow::Borrowed(&[1, 2, 3])))), json!("AQID"));
+        assert_eq!(to_json(ColumnType::Xml, ColumnData::Xml(Some(Cow::Owned(XmlData::new("<a>1</a>"))))), json!("<a>1</a>"));
+    }
+
+    #[test]
+    fn temporal_types() {
+        let datetime = date().and_hms_opt(10, 30, 0).unwrap();
+        // datetime: hari sejak 1900-01-01 & 1/300 detik, smalldatetime: menit
+        let days = (date() - NaiveDate::from_ymd_opt(1900, 1, 1).unwrap()).num_days();
+        assert_eq!(
+            to_json(ColumnType::Datetime, ColumnData::DateTime(Some(time::DateTime::new(days as i32, 37_800 * 300)))),
+            json!("2024-01-02T10:30:00")
+        );
+        assert_eq!(
+            to_json(ColumnType::Datetime4, ColumnData::SmallDateTime(Some(time::SmallDateTime::new(days as u16, 630)))),
+            json!("2024-01-02T10:30:00")
+        );
+        assert_eq!(to_json(ColumnType::Datetime2, datetime.into_sql()), json!("2024-01-02T10:30:00"));
+        assert_eq!(to_json(ColumnType::Daten, date().into_sql()), json!("2024-01-02"));
+        assert_eq!(to_json(ColumnType::Timen, datetime.time().into_sql()), json!("10:30:00"));
+    }
+
+    #[test]
+    fn datetimeoffset_keeps_offset() {
+        let offset = FixedOffset::east_opt(7 * 3600).unwrap();
+        let value = offset.from_local_datetime(&date().and_hms_opt(10, 30, 0).unwrap()).unwrap();
+        assert_eq!(to_json(ColumnType::DatetimeOffsetn, value.into_sql()), json!("2024-01-02T10:30:00+07:00"));
+    }
+
+    #[test]
+    fn nulls() {
+        let nulls: Vec<(ColumnType, ColumnData<'static>)> = vec![
+            (ColumnType::Int1, ColumnData::U8(None)),
+            (ColumnType::Int2, ColumnData::I16(None)),
+            (ColumnType::Int4, ColumnData::I32(None)),
+            (ColumnType::Int8, ColumnData::I64(None)),
+            (ColumnType::Float4, ColumnData::F32(None)),
+            (ColumnType::Float8, ColumnData::F64(None)),
+            (ColumnType::Money, ColumnData::F64(None)),
+            (ColumnType::Bit, ColumnData::Bit(None)),
+            (ColumnType::NVarchar, ColumnData::String(None)),
+            (ColumnType::Guid, ColumnData::Guid(None)),
+            (ColumnType::BigVarBin, ColumnData::Binary(None)),
+            (ColumnType::Decimaln, ColumnData::Numeric(None)),
+            (ColumnType::Xml, ColumnData::Xml(None)),
+            (ColumnType::Datetime, ColumnData::DateTime(None)),
+            (ColumnType::Datetime4, ColumnData::SmallDateTime(None)),
+            (ColumnType::Timen, ColumnData::Time(None)),
+            (ColumnType::Daten, ColumnData::Date(None)),
+            (ColumnType::Datetime2, ColumnData::DateTime2(None)),
+            (ColumnType::DatetimeOffsetn, ColumnData::DateTimeOffset(None)),
+        ];
+
+        for (column_type, data) in nulls {
+            assert_eq!(to_json(column_type, data.clone()), Value::Null, "{:?}", data);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::path::{Component, Path, PathBuf};
+use bb8::Pool;
+use bb8_tiberius::ConnectionManager;
+use futures::future::{BoxFuture, FutureExt};
+use once_cell::sync::OnceCell;
+use shuttle_runtime::SecretStore;
+
+/// 🗄️ Backend penyimpanan file sensitif (dokumen KYC). Dipilih dari secret `DOCUMENT_STORAGE`:
+/// - `database` (default): tabel `WEB_StoredFile`, aman untuk container yang filesystem-nya tidak persisten
+/// - `local:<folder>`: file di disk, contoh `local:./storage`
+pub trait DocumentStorage: Send + Sync {
+    /// Disimpan di kolom `StorageBackend` supaya file lama tetap bisa dibaca setelah ganti backend
+    fn name(&self) -> &'static str;
+    fn put<'a>(&'a self, key: &'a str, data: &'a [u8]) -> BoxFuture<'a, Result<(), String>>;
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Vec<u8>>, String>>;
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), String>>;
+}
+
+pub struct DatabaseStorage {
+    pool: Pool<ConnectionManager>,
+}
+
+impl DocumentStorage for DatabaseStorage {
+    fn name(&self) -> &'static str {
+        "database"
+    }
+
+    fn put<'a>(&'a self, key: &'a str, data: &'a [u8]) -> BoxFuture<'a, Result<(), String>> {
+        async move {
+            let mut conn = self.pool.get().await.map_err(|e| format!("Internal Server error: {:?}", e))?;
+            conn.execute(
+                r#"MERGE WEB_StoredFile AS t
+                USING (SELECT @P1 AS StorageKey) AS s ON t.StorageKey = s.StorageKey
+                WHEN MATCHED THEN UPDATE SET Data = @P2, CreatedTime = GETDATE()
+                WHEN NOT MATCHED THEN INSERT (StorageKey, Data, CreatedTime) VALUES (@P1, @P2, GETDATE());"#,
+                &[&key, &data],
+            ).await.map_err(|e| format!("Failed to store file: {:?}", e))?;
+            Ok(())
+        }.boxed()
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Vec<u8>>, String>> {
+        async move {
+            let mut conn = self.pool.get().await.map_err(|e| format!("Internal Server error: {:?}", e))?;
+            let row = conn.query("SELECT Data FROM WEB_StoredFile WHERE StorageKey = @P1", &[&key]).await
+                .map_err(|e| format!("Query execution failed: {:?}", e))?
+                .into_row().await
+                .map_err(|e| format!("Failed to read file: {:?}", e))?;
+            Ok(row.and_then(|row| row.get::<&[u8], _>("Data").map(|data| data.to_vec())))
+        }.boxed()
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), String>> {
+        async move {
+            let mut conn = self.pool.get().await.map_err(|e| format!("Internal Server error: {:?}", e))?;
+            conn.execute("DELETE FROM WEB_StoredFile WHERE StorageKey = @P1", &[&key]).await
+                .map_err(|e| format!("Failed to delete file: {:?}", e))?;
+            Ok(())
+        }.boxed()
+    }
+}
+
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    /// Key selalu dibuat server (`kyc/12/abc.jpg`), tapi tetap tolak `..` / path absolut
+    fn path(&self, key: &str) -> Result<PathBuf, String> {
+        let relative = Path::new(key);
+        if relative.components().any(|c| !matches!(c, Component::Normal(_))) {
+            return Err(format!("Invalid storage key: {}", key));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+impl DocumentStorage for LocalStorage {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn put<'a>(&'a self, key: &'a str, data: &'a [u8]) -> BoxFuture<'a, Result<(), String>> {
+        async move {
+            let path = self.path(key)?;
+            if let Some(dir) = path.parent() {
+                tokio::fs::create_dir_all(dir).await.map_err(|e| format!("Failed to create dir: {}", e))?;
+            }
+            tokio::fs::write(&path, data).await.map_err(|e| format!("Failed to write file: {}", e))
+        }.boxed()
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Vec<u8>>, String>> {
+        async move {
+            match tokio::fs::read(self.path(key)?).await {
+                Ok(data) => Ok(Some(data)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(format!("Failed to read file: {}", e)),
+            }
+        }.boxed()
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), String>> {
+        async move {
+            match tokio::fs::remove_file(self.path(key)?).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to delete file: {}", e)),
+                _ => Ok(()),
+            }
+        }.boxed()
+    }
+}
+
+static STORAGES: OnceCell<Vec<Box<dyn DocumentStorage>>> = OnceCell::new();
+static ACTIVE_STORAGE: OnceCell<&'static str> = OnceCell::new();
+
+/// Dipanggil sekali saat startup dari `main`. Semua backend didaftarkan supaya file lama tetap terbaca,
+/// upload baru masuk ke backend yang dipilih `DOCUMENT_STORAGE`.
+pub fn init_storage(secrets: &SecretStore, pool: Pool<ConnectionManager>) -> Result<(), String> {
+    let config = secrets.get("DOCUMENT_STORAGE").unwrap_or_else(|| "database".to_string());
+    let (active, local_root) = match config.split_once(':') {
+        Some(("local", root)) if !root.trim().is_empty() => ("local", root.trim().to_string()),
+        None if config == "local" => ("local", "./storage".to_string()),
+        None if config == "database" => ("database", "./storage".to_string()),
+        _ => return Err(format!("Invalid DOCUMENT_STORAGE: {}", config)),
+    };
+
+    let storages: Vec<Box<dyn DocumentStorage>> = vec![
+        Box::new(DatabaseStorage { pool }),
+        Box::new(LocalStorage { root: PathBuf::from(local_root) }),
+    ];
+
+    STORAGES.set(storages).map_err(|_| "Document storage already initialized".to_string())?;
+    ACTIVE_STORAGE.set(active).map_err(|_| "Document storage already initialized".to_string())
+}
+
+/// Backend untuk upload baru
+pub fn active_storage() -> Result<&'static dyn DocumentStorage, String> {
+    let name = ACTIVE_STORAGE.get().ok_or("Document storage not initialized")?;
+    storage(name)
+}
+
+/// Backend sesuai kolom `StorageBackend`
+pub fn storage(name: &str) -> Result<&'static dyn DocumentStorage, String> {
+    STORAGES.get()
+        .ok_or("Document storage not initialized")?
+        .iter()
+        .find(|s| s.name() == name)
+        .map(|s| s.as_ref())
+        .ok_or_else(|| format!("Unknown storage backend: {}", name))
+}
@@ -0,0 +1,201 @@
+use std::io::Cursor;
+use hmac::{Hmac, Mac};
+use image::{ImageFormat, Luma};
+use qrcode::QrCode;
+use rand::{rng, Rng};
+use sha1::Sha1;
+
+/// ⏱️ Parameter standar RFC 6238 (kompatibel Google Authenticator, Authy, dll)
+const TOTP_STEP_SECONDS: i64 = 30;
+const TOTP_DIGITS: u32 = 6;
+/// Toleransi jam HP yang tidak sinkron, ±1 step
+const TOTP_SKEW: i64 = 1;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+fn base32_encode(data: &[u8]) -> String {
+    let mut output = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            output.push(BASE32_ALPHABET[((buffer >> (bits - 5)) & 0x1f) as usize] as char);
+            bits -= 5;
+        }
+    }
+    if bits > 0 {
+        output.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    output
+}
+
+fn base32_decode(value: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for c in value.chars().filter(|c| *c != '=' && !c.is_whitespace()) {
+        let index = BASE32_ALPHABET.iter().position(|&a| a as char == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | index as u32;
+        bits += 5;
+        if bits >= 8 {
+            output.push(((buffer >> (bits - 8)) & 0xff) as u8);
+            bits -= 8;
+        }
+    }
+
+    Some(output)
+}
+
+/// 🔑 Secret baru 160-bit dalam format base32
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; 20];
+    rng().fill(&mut secret);
+    base32_encode(&secret)
+}
+
+/// URI untuk aplikasi authenticator (isi QR code)
+pub fn otpauth_uri(secret: &str, account: &str, issuer: &str) -> String {
+    let encode = |s: &str| {
+        s.bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+                _ => format!("%{:02X}", b),
+            })
+            .collect::<String>()
+    };
+
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        encode(issuer), encode(account), secret, encode(issuer), TOTP_DIGITS, TOTP_STEP_SECONDS
+    )
+}
+
+/// QR code PNG dari otpauth URI
+pub fn qr_code_png(uri: &str) -> Result<Vec<u8>, String> {
+    let code = QrCode::new(uri.as_bytes()).map_err(|e| e.to_string())?;
+    let image = code.render::<Luma<u8>>().min_dimensions(240, 240).build();
+
+    let mut buffer = Cursor::new(Vec::new());
+    image.write_to(&mut buffer, ImageFormat::Png).map_err(|e| e.to_string())?;
+    Ok(buffer.into_inner())
+}
+
+fn hotp(key: &[u8], counter: u64) -> Option<u32> {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).ok()?;
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    // Dynamic truncation (RFC 4226 section 5.3)
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = ((hash[offset] as u32 & 0x7f) << 24)
+        | ((hash[offset + 1] as u32) << 16)
+        | ((hash[offset + 2] as u32) << 8)
+        | (hash[offset + 3] as u32);
+
+    Some(binary % 10u32.pow(TOTP_DIGITS))
+}
+
+/// ✅ Cek kode TOTP, kembalikan step yang cocok supaya kode yang sama tidak bisa dipakai ulang.
+/// Step <= `last_step` ditolak.
+pub fn verify_code(secret: &str, code: &str, unix_time: i64, last_step: Option<i64>) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let expected: u32 = code.parse().ok()?;
+    let key = base32_decode(secret)?;
+    let current_step = unix_time / TOTP_STEP_SECONDS;
+
+    (-TOTP_SKEW..=TOTP_SKEW)
+        .map(|skew| current_step + skew)
+        .filter(|step| *step >= 0 && last_step.is_none_or(|last| *step > last))
+        .find(|step| hotp(&key, *step as u64) == Some(expected))
+}
+
+/// Recovery code sekali pakai, format XXXXX-XXXXX
+pub fn generate_recovery_codes(count: usize) -> Vec<String> {
+    const CHARS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+    let mut rng = rng();
+
+    (0..count)
+        .map(|_| {
+            let raw: String = (0..10).map(|_| CHARS[rng.random_range(0..CHARS.len())] as char).collect();
+            format!("{}-{}", &raw[..5], &raw[5..])
+        })
+        .collect()
+}
+
+/// Normalisasi input recovery code sebelum di-hash (huruf besar, tanpa spasi)
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.trim().to_ascii_uppercase().replace(' ', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Secret ASCII "12345678901234567890" dari RFC 6238 Appendix B, dalam base32
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn base32_round_trip() {
+        let data = b"12345678901234567890";
+        assert_eq!(base32_encode(data), RFC_SECRET);
+        assert_eq!(base32_decode(RFC_SECRET).as_deref(), Some(&data[..]));
+        assert_eq!(base32_decode(&generate_secret()).map(|key| key.len()), Some(20));
+    }
+
+    #[test]
+    fn rfc6238_vectors() {
+        // 6 digit terakhir dari vektor SHA1 8 digit
+        assert_eq!(verify_code(RFC_SECRET, "287082", 59, None), Some(1));
+        assert_eq!(verify_code(RFC_SECRET, "081804", 1111111109, None), Some(37037036));
+        assert_eq!(verify_code(RFC_SECRET, "050471", 1111111111, None), Some(37037037));
+    }
+
+    #[test]
+    fn malformed_code_is_rejected() {
+        assert_eq!(verify_code(RFC_SECRET, " 287082 ", 59, None), Some(1));
+        assert_eq!(verify_code(RFC_SECRET, "28708", 59, None), None);
+        assert_eq!(verify_code(RFC_SECRET, "28708a", 59, None), None);
+        assert_eq!(verify_code(RFC_SECRET, "287083", 59, None), None);
+        assert_eq!(verify_code("bukan-base32!", "287082", 59, None), None);
+    }
+
+    #[test]
+    fn one_step_of_clock_skew_is_accepted() {
+        // Kode step 37037036 (t = 1111111109) masih diterima satu step sebelum/sesudahnya
+        let step_start = 37037036 * TOTP_STEP_SECONDS;
+        assert_eq!(verify_code(RFC_SECRET, "081804", step_start - TOTP_STEP_SECONDS, None), Some(37037036));
+        assert_eq!(verify_code(RFC_SECRET, "081804", step_start + 2 * TOTP_STEP_SECONDS - 1, None), Some(37037036));
+
+        // Dua step di luar jendela ditolak
+        assert_eq!(verify_code(RFC_SECRET, "081804", step_start - 2 * TOTP_STEP_SECONDS, None), None);
+        assert_eq!(verify_code(RFC_SECRET, "081804", step_start + 2 * TOTP_STEP_SECONDS, None), None);
+    }
+
+    #[test]
+    fn used_step_is_not_replayable() {
+        let step = verify_code(RFC_SECRET, "081804", 1111111109, None).unwrap();
+        assert_eq!(verify_code(RFC_SECRET, "081804", 1111111109, Some(step)), None);
+
+        // Kode dari step sebelumnya juga ditolak setelah step yang lebih baru dipakai
+        assert_eq!(verify_code(RFC_SECRET, "081804", 1111111109 + TOTP_STEP_SECONDS, Some(step + 1)), None);
+
+        // Step berikutnya tetap boleh
+        assert_eq!(verify_code(RFC_SECRET, "050471", 1111111111, Some(step)), Some(step + 1));
+    }
+
+    #[test]
+    fn recovery_codes_are_normalized() {
+        let codes = generate_recovery_codes(8);
+        assert_eq!(codes.len(), 8);
+        assert!(codes.iter().all(|code| code.len() == 11 && &code[5..6] == "-"));
+        assert_eq!(normalize_recovery_code(" abcde-fgh jk "), "ABCDE-FGHJK");
+    }
+}
@@ -0,0 +1,113 @@
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder, Scope};
+use bb8::Pool;
+use bb8_tiberius::ConnectionManager;
+use serde_json::json;
+use validator::Validate;
+
+use crate::{
+    contexts::{auth_guard::{require_permission, AuthenticatedUser}, i18n::t, model::{ActionResult, AssignRoleRequest}},
+    services::{account_service::AccountService, audit_service::{AuditEvent, AuditService}, generic_service::GenericService, role_service::RoleService},
+    utils::validation::validator::validation_failed
+};
+
+pub fn admin_scope() -> Scope {
+    web::scope("/admin")
+        .service(get_roles)
+        .service(get_user_roles)
+        .service(assign_role)
+        .service(revoke_role)
+        .service(
+            web::scope("/accounts")
+                .wrap(require_permission("account:delete"))
+                .service(delete_account)
+        )
+}
+
+#[get("/roles")]
+async fn get_roles(connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let result: ActionResult<Vec<serde_json::Value>, String> = RoleService::get_roles(connection).await;
+
+    match result {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response => HttpResponse::Ok().json(response),
+    }
+}
+
+#[get("/users/{auth_usernid}/roles")]
+async fn get_user_roles(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: web::Path<i32>) -> impl Responder {
+
+    let result: ActionResult<Vec<serde_json::Value>, String> = RoleService::get_user_roles(connection, auth_usernid.into_inner()).await;
+
+    match result {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response => HttpResponse::Ok().json(response),
+    }
+}
+
+#[post("/users/{auth_usernid}/roles")]
+async fn assign_role(req: HttpRequest, user: AuthenticatedUser, connection: web::Data<Pool<ConnectionManager>>, auth_usernid: web::Path<i32>, request: web::Json<AssignRoleRequest>) -> impl Responder {
+    if let Err(err) = request.validate() {
+        return validation_failed(&err);
+    }
+
+    let auth_usernid = auth_usernid.into_inner();
+    let role = request.into_inner().role;
+    let result: ActionResult<(), String> = RoleService::assign_role(connection.clone(), auth_usernid, role.clone()).await;
+    if result.error.is_none() {
+        let target = format!("user:{}", auth_usernid);
+        AuditService::record(&connection, &req, AuditEvent::RoleAssign, Some(&user.claims), Some(&target), result.result, json!({ "role": role })).await;
+    }
+
+    match result {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response if response.result => HttpResponse::Ok().json(response),
+        response => HttpResponse::BadRequest().json(response),
+    }
+}
+
+#[delete("/users/{auth_usernid}/roles/{role}")]
+async fn revoke_role(req: HttpRequest, user: AuthenticatedUser, connection: web::Data<Pool<ConnectionManager>>, path: web::Path<(i32, String)>) -> impl Responder {
+
+    let (auth_usernid, role) = path.into_inner();
+    let result: ActionResult<(), String> = RoleService::revoke_role(connection.clone(), auth_usernid, role.clone()).await;
+    if result.error.is_none() {
+        let target = format!("user:{}", auth_usernid);
+        AuditService::record(&connection, &req, AuditEvent::RoleRevoke, Some(&user.claims), Some(&target), result.result, json!({ "role": role })).await;
+    }
+
+    match result {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response if response.result => HttpResponse::Ok().json(response),
+        response => HttpResponse::BadRequest().json(response),
+    }
+}
+
+// Hapus akun langsung tanpa masa tenggang, event `account_delete` dicatat di transaksi penghapusan
+#[delete("/{auth_usernid}")]
+async fn delete_account(req: HttpRequest, user: AuthenticatedUser, connection: web::Data<Pool<ConnectionManager>>, auth_usernid: web::Path<i32>) -> impl Responder {
+
+    let auth_usernid = auth_usernid.into_inner();
+    if auth_usernid == user.claims.auth_usernid {
+        return HttpResponse::BadRequest().json(ActionResult::<(), String> {
+            result: false,
+            message: t("account.delete_self"),
+            data: None,
+            error: None,
+        });
+    }
+
+    let result: ActionResult<(), String> = AccountService::delete_account(
+        connection,
+        auth_usernid,
+        Some(&user.claims),
+        &GenericService::get_ip_address(&req),
+        &GenericService::get_user_agent(&req),
+    ).await;
+
+    match result {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response if response.result => HttpResponse::Ok().json(response),
+        response => HttpResponse::NotFound().json(response),
+    }
+}
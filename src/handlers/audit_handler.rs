@@ -0,0 +1,61 @@
+use actix_web::{get, http::StatusCode, web, HttpRequest, HttpResponse, Responder, Scope};
+use bb8::Pool;
+use bb8_tiberius::ConnectionManager;
+use serde_json::json;
+
+use crate::{
+    contexts::{auth_guard::AuthenticatedUser, i18n::t, model::{ActionResult, AuditLogParams, ResultList}},
+    services::audit_service::{AuditEvent, AuditService}
+};
+
+use super::data_handler::data_error_status;
+
+pub fn audit_scope() -> Scope {
+    web::scope("/audit")
+        .service(get_audit_logs)
+        .service(export_audit_logs)
+}
+
+#[get("/logs")]
+async fn get_audit_logs(params: web::Query<AuditLogParams>, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let data: Result<ResultList, Box<dyn std::error::Error>> = AuditService::get_logs(connection, params.into_inner()).await;
+
+    match data {
+        Ok(response) => HttpResponse::Ok().json(json!({
+            "total": response.total,
+            "totalNotFiltered": response.total_not_filtered,
+            "rows": response.rows,
+            "nextCursor": response.next_cursor,
+            "prevCursor": response.prev_cursor
+        })),
+        Err(e) => HttpResponse::build(data_error_status(e.as_ref())).json(json!({"error": e.to_string()})),
+    }
+}
+
+#[get("/logs/csv")]
+async fn export_audit_logs(req: HttpRequest, user: AuthenticatedUser, params: web::Query<AuditLogParams>, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let params = params.into_inner();
+    let details = json!({ "format": "CSV", "table": "AuditLog", "filter": params.filter });
+    let result: Result<String, Box<dyn std::error::Error>> = AuditService::export_csv(connection.clone(), params).await;
+
+    AuditService::record(&connection, &req, AuditEvent::Export, Some(&user.claims), None, result.is_ok(), details).await;
+
+    match result {
+        Ok(csv) => HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .append_header(("Content-Disposition", "attachment; filename=\"audit-log.csv\""))
+            .body(csv),
+        Err(err) => {
+            let status = data_error_status(err.as_ref());
+            let message = if status == StatusCode::BAD_REQUEST { t("common.invalid_request") } else { t("common.query_failed") };
+            HttpResponse::build(status).json(ActionResult::<(), String> {
+                result: false,
+                message,
+                data: None,
+                error: Some(err.to_string()),
+            })
+        }
+    }
+}
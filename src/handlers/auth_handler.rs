@@ -1,83 +1,411 @@
-use actix_web::{cookie::{time, Cookie, SameSite}, get, post, web, HttpRequest, HttpResponse, Responder, Scope};
+use actix_web::{cookie::{time, Cookie, SameSite}, delete, get, http::header, patch, post, web, HttpRequest, HttpResponse, Responder, Scope};
 use bb8::Pool;
 use bb8_tiberius::ConnectionManager;
 use serde_json::json;
+use shuttle_runtime::SecretStore;
+use validator::Validate;
 use crate::{
-    contexts::{jwt_session::{create_jwt, validate_jwt, Claims}, 
-    model::{ActionResult, ChangePasswordRequest, LoginRequest, RegisterRequest, ResetPasswordRequest}}, 
-    services::{auth_service::AuthService, generic_service::GenericService}
+    contexts::{auth_guard::{extract_token, AuthenticatedUser, SessionUser}, avatar, csrf::{csrf_cookie, current_or_new_token, CSRF_HEADER}, i18n::t, jwt_session::{create_jwt, create_mfa_challenge, public_jwks, validate_jwt, Claims, TokenPair, ACCESS_COOKIE, ACCESS_TOKEN_MINUTES, MFA_CHALLENGE_MINUTES, REFRESH_COOKIE, REFRESH_TOKEN_DAYS}, oidc::{self, OIDC_COOKIE, OIDC_STATE_MINUTES}, 
+    model::{AccountDeletionRequest, AccountDeletionStatus, ActionResult, ApiKeyInfo, ChangePasswordRequest, CreateApiKeyRequest, CreatedApiKey, CsrfToken, LoginRequest, MagicLinkLoginRequest, MagicLinkRequest, MfaLoginRequest, OidcCallbackQuery, RegisterRequest, RenameSessionRequest, ResendActivationRequest, ResetPasswordRequest, TotpCodeRequest, TotpEnrollment, UpdatePasswordRequest, UpdateProfileRequest, UploadAvatarRequest, UserProfile, UserSession}}, 
+    services::{account_service::AccountService, api_key_service::ApiKeyService, audit_service::{AuditEvent, AuditService}, auth_service::AuthService, generic_service::GenericService, mfa_service::MfaService, profile_service::ProfileService, session_service::SessionService},
+    utils::validation::validator::{normalize_phone, validation_failed}
 };
 
 const APP_NAME: &str = "snakesystem-web-api";
 
+/// Base64 avatar maksimal 5MB (~6.7MB setelah encode), lebih besar dari limit JSON default
+const AVATAR_JSON_LIMIT: usize = 8 * 1024 * 1024;
+
+fn access_cookie(token: String) -> Cookie<'static> {
+    Cookie::build(ACCESS_COOKIE, token)
+        .path("/")
+        .http_only(true)
+        .same_site(SameSite::None) // ❗ WAJIB None agar cookie cross-site
+        .secure(true)
+        .max_age(time::Duration::minutes(ACCESS_TOKEN_MINUTES))
+        .finish()
+}
+
+// Refresh token cuma dikirim ke endpoint auth, tidak ikut ke semua request
+fn refresh_cookie(token: String) -> Cookie<'static> {
+    Cookie::build(REFRESH_COOKIE, token)
+        .path("/api/v1/auth")
+        .http_only(true)
+        .same_site(SameSite::None)
+        .secure(true)
+        .max_age(time::Duration::days(REFRESH_TOKEN_DAYS))
+        .finish()
+}
+
+fn expired_cookie(name: &'static str, path: &'static str) -> Cookie<'static> {
+    Cookie::build(name, "")
+        .path(path)
+        .http_only(true)
+        .same_site(SameSite::None)
+        .secure(true)
+        .max_age(time::Duration::days(-1))
+        .finish()
+}
+
 pub fn auth_scope() -> Scope {
     
     web::scope("/auth")
         .service(login)
+        .service(login_mfa)
+        .service(request_magic_link)
+        .service(login_magic_link)
+        .service(oidc_authorize)
+        .service(oidc_callback)
         .service(register)
+        .service(csrf_token)
         .service(check_session)
+        .service(refresh)
         .service(logout)
+        .service(get_sessions)
+        .service(rename_session)
+        .service(revoke_other_sessions)
+        .service(revoke_session)
+        .service(get_api_keys)
+        .service(create_api_key)
+        .service(revoke_api_key)
         .service(activation_user)
+        .service(resend_activation)
         .service(forget_password)
         .service(change_password)
+        .service(get_profile)
+        .service(update_profile)
+        .service(update_password)
+        .service(export_personal_data)
+        .service(get_account_deletion)
+        .service(request_account_deletion)
+        .service(cancel_account_deletion)
+        .service(
+            web::resource("/me/avatar")
+                .app_data(web::JsonConfig::default().limit(AVATAR_JSON_LIMIT).error_handler(GenericService::json_error_handler))
+                .route(web::post().to(upload_avatar))
+        )
+        .service(get_avatar)
+        .service(enroll_totp)
+        .service(confirm_totp)
+        .service(disable_totp)
+}
+
+/// Buat sesi device baru: refresh token, access token, lalu simpan sebagai cookie.
+/// `method` (password, mfa, oidc:google) dicatat di audit log
+async fn start_session(req: &HttpRequest, connection: web::Data<Pool<ConnectionManager>>, mut response: ActionResult<Claims, String>, method: &str) -> HttpResponse {
+    let mut user = match response.data.take() {
+        Some(user) => user,
+        None => return HttpResponse::BadRequest().json(response), // Jika tidak ada user, return 400
+    };
+
+    // ✅ Refresh token untuk memperpanjang sesi tanpa login ulang, family-nya jadi ID sesi
+    user.session_id = GenericService::random_string(32);
+    let refresh_token = match AuthService::create_refresh_token(connection.clone(), user.auth_usernid, &user.session_id, req).await {
+        created if created.result => created.data.unwrap_or_default(),
+        created => return HttpResponse::InternalServerError().json(created),
+    };
+
+    // ✅ Buat token JWT
+    let token = match create_jwt(user.clone()) {
+        Ok(token) => token,
+        Err(err) => {
+            println!("❌ Failed to create JWT: {}", err);
+            return HttpResponse::InternalServerError().json(response);
+        }
+    };
+
+    // ✅ Simpan sesi device ini
+    let session = SessionService::create(connection.clone(), &user, &token, req).await;
+    if !session.result {
+        return HttpResponse::InternalServerError().json(session);
+    }
+
+    AuditService::record(&connection, req, AuditEvent::LoginSuccess, Some(&user), None, true, json!({ "method": method })).await;
+
+    response.data = Some(user);
+
+    HttpResponse::Ok()
+        .cookie(access_cookie(token))
+        .cookie(refresh_cookie(refresh_token))
+        .json(response)
+}
+
+/// Setelah identitas user terbukti: minta kode 2FA jika aktif, kalau tidak langsung buat sesi
+async fn finish_login(req: &HttpRequest, connection: web::Data<Pool<ConnectionManager>>, response: ActionResult<Claims, String>, method: &str) -> HttpResponse {
+    let auth_usernid = response.data.as_ref().map_or(0, |user| user.auth_usernid);
+
+    // 🔐 2FA aktif: belum ada sesi, kirim challenge untuk langkah kedua
+    match MfaService::is_enabled(connection.clone(), auth_usernid).await {
+        mfa if mfa.error.is_some() => HttpResponse::InternalServerError().json(mfa),
+        ActionResult { data: Some(true), .. } => match create_mfa_challenge(auth_usernid) {
+            Ok(mfa_token) => HttpResponse::Ok().json(json!({
+                "result": true,
+                "message": t("auth.mfa_required"),
+                "data": {
+                    "mfa_required": true,
+                    "mfa_token": mfa_token,
+                    "expires_in": MFA_CHALLENGE_MINUTES * 60
+                }
+            })),
+            Err(err) => {
+                println!("❌ Failed to create MFA challenge: {}", err);
+                HttpResponse::InternalServerError().json(response)
+            }
+        },
+        _ => start_session(req, connection, response, method).await,
+    }
 }
 
 #[post("/login")]
 async fn login(req: HttpRequest, connection: web::Data<Pool<ConnectionManager>>, request: web::Json<LoginRequest>) -> impl Responder {
 
-    let mut result: ActionResult<Claims, _> = AuthService::login(connection.clone(), request.into_inner(), req.clone(), APP_NAME).await;
+    let email = request.email.clone();
+    let result: ActionResult<Claims, _> = AuthService::login(connection.clone(), request.into_inner(), req.clone(), APP_NAME).await;
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result => finish_login(&req, connection, response, "password").await,
+        response => {
+            AuditService::record(&connection, &req, AuditEvent::LoginFailed, None, email.as_deref(), false, json!({ "method": "password" })).await;
+            HttpResponse::BadRequest().json(response) // Jika gagal login, HTTP 400
+        }
+    }
+}
+
+#[post("/login/mfa")]
+async fn login_mfa(req: HttpRequest, connection: web::Data<Pool<ConnectionManager>>, request: web::Json<MfaLoginRequest>) -> impl Responder {
 
-    // let token_cookie = req.cookie("snakesystem").map(|c| c.value().to_string()).unwrap_or_default();
+    let result: ActionResult<Claims, _> = AuthService::complete_mfa_login(connection.clone(), request.into_inner(), req.clone(), APP_NAME).await;
 
     match result {
         response if response.error.is_some() => {
             HttpResponse::InternalServerError().json(response)
         }, // Jika error, HTTP 500
-        response if response.result => {
-            if let Some(user) = &response.data {
-                // ✅ Buat token JWT
-                match create_jwt(user.clone()) {
-                    Ok(token) => {
-                        // ✅ Simpan token dalam cookie
-                        result = AuthService::check_session(connection, user.clone(), token.clone(), "".to_string(), false, false, false).await;
+        response if response.result => start_session(&req, connection, response, "mfa").await,
+        response => {
+            AuditService::record(&connection, &req, AuditEvent::LoginFailed, None, None, false, json!({ "method": "mfa" })).await;
+            HttpResponse::Unauthorized().json(response) // Kode salah / challenge expired
+        }
+    }
+}
 
-                        // ✅ Jika berhasil, kembalikan JSON response
-                        if !result.result {
-                            return HttpResponse::InternalServerError().json(result);
-                        }
-                            
-                        let cookie = Cookie::build("snakesystem", token)
-                            .path("/")
-                            .http_only(true)
-                            .same_site(SameSite::None) // ❗ WAJIB None agar cookie cross-site
-                            .secure(true)     
-                            .expires(time::OffsetDateTime::now_utc() + time::Duration::days(2)) // Set expired 2 hari
-                            .finish();
+#[post("/magic-link")]
+async fn request_magic_link(req: HttpRequest, connection: web::Data<Pool<ConnectionManager>>, secrets: web::Data<SecretStore>, request: web::Json<MagicLinkRequest>) -> impl Responder {
 
-                        return HttpResponse::Ok()
-                            .cookie(cookie)
-                            .json(response);
-                    }
-                    Err(err) => {
-                        println!("❌ Failed to create JWT: {}", err);
-                        return HttpResponse::InternalServerError().json(response);
-                    }
-                }
-            }
+    if let Err(err) = request.validate() {
+        return validation_failed(&err);
+    }
 
-            HttpResponse::BadRequest().json(response) // Jika tidak ada user, return 400
-        },
-        response => HttpResponse::BadRequest().json(response), // Jika gagal login, HTTP 400
+    let email = request.email.clone();
+    let result: ActionResult<(), _> = AuthService::send_magic_link(connection.clone(), request.into_inner(), req.clone(), secrets).await;
+    if result.error.is_none() {
+        AuditService::record(&connection, &req, AuditEvent::MagicLinkRequest, None, email.as_deref(), result.result, json!(null)).await;
+    }
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result => HttpResponse::Ok().json(response), // Jika berhasil, HTTP 200
+        response => HttpResponse::TooManyRequests().json(response), // Akun / IP terkunci
+    }
+}
+
+// Token dari link email ditukar dengan sesi, sama seperti login password (termasuk 2FA)
+#[post("/magic-link/login")]
+async fn login_magic_link(req: HttpRequest, connection: web::Data<Pool<ConnectionManager>>, request: web::Json<MagicLinkLoginRequest>) -> impl Responder {
+
+    if let Err(err) = request.validate() {
+        return validation_failed(&err);
+    }
+
+    let result: ActionResult<Claims, _> = AuthService::magic_link_login(connection.clone(), request.into_inner(), req.clone(), APP_NAME).await;
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result => finish_login(&req, connection, response, "magic_link").await,
+        response => {
+            AuditService::record(&connection, &req, AuditEvent::LoginFailed, None, None, false, json!({ "method": "magic_link" })).await;
+            HttpResponse::Unauthorized().json(response) // Link tidak valid / akun terkunci
+        }
+    }
+}
+
+#[get("/oidc/{provider}/authorize")]
+async fn oidc_authorize(provider: web::Path<String>) -> impl Responder {
+
+    let mut result: ActionResult<(), String> = ActionResult::default();
+
+    let provider = match oidc::provider(&provider) {
+        Some(provider) => provider,
+        None => {
+            result.message = t("auth.provider_unknown");
+            return HttpResponse::NotFound().json(result);
+        }
+    };
+
+    let authorization = oidc::discover(provider).await
+        .and_then(|metadata| oidc::authorization_request(provider, &metadata))
+        .and_then(|(url, state)| oidc::sign_state(&state).map(|cookie| (url, cookie)));
+
+    match authorization {
+        Ok((url, state_cookie)) => {
+            // SameSite Lax supaya cookie ikut terkirim saat provider redirect balik ke callback
+            let cookie = Cookie::build(OIDC_COOKIE, state_cookie)
+                .path("/api/v1/auth/oidc")
+                .http_only(true)
+                .same_site(SameSite::Lax)
+                .secure(true)
+                .max_age(time::Duration::minutes(OIDC_STATE_MINUTES))
+                .finish();
+
+            HttpResponse::Found()
+                .append_header((header::LOCATION, url))
+                .cookie(cookie)
+                .finish()
+        }
+        Err(err) => {
+            result.error = Some(err);
+            HttpResponse::BadGateway().json(result)
+        }
+    }
+}
+
+#[get("/oidc/{provider}/callback")]
+async fn oidc_callback(req: HttpRequest, connection: web::Data<Pool<ConnectionManager>>, provider: web::Path<String>, query: web::Query<OidcCallbackQuery>) -> impl Responder {
+
+    let mut result: ActionResult<Claims, String> = ActionResult::default();
+    let query = query.into_inner();
+
+    let provider = match oidc::provider(&provider) {
+        Some(provider) => provider,
+        None => {
+            result.message = t("auth.provider_unknown");
+            return HttpResponse::NotFound().json(result);
+        }
+    };
+
+    if let Some(error) = query.error {
+        result.message = t("auth.provider_cancelled");
+        result.error = Some(error);
+        return HttpResponse::BadRequest().json(result);
+    }
+
+    // State sekali pakai, cookie langsung dihapus apa pun hasilnya
+    let state = match (req.cookie(OIDC_COOKIE), query.state.as_deref(), query.code.as_deref()) {
+        (Some(cookie), Some(state), Some(_)) => oidc::verify_state(cookie.value(), &provider.name, state),
+        _ => Err("Missing OIDC state or code".to_string()),
+    };
+    let state = match state {
+        Ok(state) => state,
+        Err(err) => {
+            result.message = err;
+            return HttpResponse::BadRequest()
+                .cookie(expired_cookie(OIDC_COOKIE, "/api/v1/auth/oidc"))
+                .json(result);
+        }
+    };
+
+    let identity = match oidc::discover(provider).await {
+        Ok(metadata) => oidc::exchange_code(provider, &metadata, &query.code.unwrap_or_default(), &state).await,
+        Err(err) => Err(err),
+    };
+    let identity = match identity {
+        Ok(identity) => identity,
+        Err(err) => {
+            result.message = t("auth.provider_failed");
+            result.error = Some(err);
+            return HttpResponse::Unauthorized()
+                .cookie(expired_cookie(OIDC_COOKIE, "/api/v1/auth/oidc"))
+                .json(result);
+        }
+    };
+
+    let logged_in: ActionResult<Claims, _> = AuthService::oidc_login(connection.clone(), &provider.name, identity, req.clone(), APP_NAME).await;
+
+    let method = format!("oidc:{}", provider.name);
+    let mut response = match logged_in {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response if response.result => finish_login(&req, connection, response, &method).await,
+        response => {
+            AuditService::record(&connection, &req, AuditEvent::LoginFailed, None, None, false, json!({ "method": method })).await;
+            HttpResponse::BadRequest().json(response)
+        }
+    };
+    let _ = response.add_cookie(&expired_cookie(OIDC_COOKIE, "/api/v1/auth/oidc"));
+    response
+}
+
+#[post("/2fa/enroll")]
+async fn enroll_totp(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let result: ActionResult<TotpEnrollment, _> = MfaService::enroll(connection, user.claims).await;
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result => HttpResponse::Ok().json(response), // Jika berhasil, HTTP 200
+        response => HttpResponse::BadRequest().json(response), // Jika gagal, HTTP 400
+    }
+}
+
+#[post("/2fa/confirm")]
+async fn confirm_totp(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>, request: web::Json<TotpCodeRequest>) -> impl Responder {
+
+    let result: ActionResult<Vec<String>, _> = MfaService::confirm(connection, user.claims.auth_usernid, request.into_inner().code).await;
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result => HttpResponse::Ok().json(response), // Jika berhasil, HTTP 200
+        response => HttpResponse::BadRequest().json(response), // Jika gagal, HTTP 400
+    }
+}
+
+#[post("/2fa/disable")]
+async fn disable_totp(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>, request: web::Json<TotpCodeRequest>) -> impl Responder {
+
+    let result: ActionResult<(), _> = MfaService::disable(connection, user.claims.auth_usernid, request.into_inner().code).await;
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result => HttpResponse::Ok().json(response), // Jika berhasil, HTTP 200
+        response => HttpResponse::BadRequest().json(response), // Jika gagal, HTTP 400
     }
 }
 
+// Token CSRF untuk frontend, wajib dikirim di header `X-CSRF-Token` pada request yang mengubah data
+#[get("/csrf")]
+async fn csrf_token(req: HttpRequest) -> impl Responder {
+
+    let token = current_or_new_token(&req);
+
+    HttpResponse::Ok()
+        .append_header((header::CACHE_CONTROL, "no-store"))
+        .cookie(csrf_cookie(token.clone()))
+        .json(ActionResult::<CsrfToken, String> {
+            result: true,
+            message: t("csrf.issued"),
+            data: Some(CsrfToken { csrf_token: token, header: CSRF_HEADER.to_string() }),
+            error: None,
+        })
+}
+
 #[get("/session")]
 async fn check_session(req: HttpRequest, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
 
     let mut result: ActionResult<Claims, _> = ActionResult::default();
 
     // Ambil cookie "token"
-    let token_cookie = req.cookie("snakesystem");
+    let token_cookie = req.cookie(ACCESS_COOKIE);
 
     // Cek apakah token ada di cookie
     let token = match token_cookie {
@@ -117,32 +445,209 @@ async fn check_session(req: HttpRequest, connection: web::Data<Pool<ConnectionMa
     }
 }
 
+#[post("/refresh")]
+async fn refresh(req: HttpRequest, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let mut result: ActionResult<Claims, String> = ActionResult::default();
+
+    let refresh_token = match req.cookie(REFRESH_COOKIE) {
+        Some(cookie) => cookie.value().to_string(),
+        None => {
+            result.error = Some("Refresh token not found".to_string());
+            return HttpResponse::Unauthorized().json(result);
+        }
+    };
+
+    let refreshed: ActionResult<TokenPair, String> = AuthService::refresh_session(connection, refresh_token, req.clone(), APP_NAME).await;
+
+    match refreshed {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(ActionResult::<(), _> {
+                result: false,
+                message: response.message,
+                data: None,
+                error: response.error,
+            })
+        },
+        ActionResult { result: true, message, data: Some(pair), .. } => {
+            result.result = true;
+            result.message = message;
+            result.data = Some(pair.claims);
+
+            HttpResponse::Ok()
+                .cookie(access_cookie(pair.access_token))
+                .cookie(refresh_cookie(pair.refresh_token))
+                .json(result)
+        },
+        response => {
+            // Refresh gagal, paksa login ulang
+            result.message = response.message;
+            HttpResponse::Unauthorized()
+                .cookie(expired_cookie(ACCESS_COOKIE, "/"))
+                .cookie(expired_cookie(REFRESH_COOKIE, "/api/v1/auth"))
+                .json(result)
+        }
+    }
+}
+
 #[post("/logout")]
-async fn logout() -> impl Responder {
+async fn logout(req: HttpRequest, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
 
-    // Hapus cookie dengan setting expired date
-    let cookie = Cookie::build("token", "")
-        .path("/")
-        .http_only(true)
-        .same_site(SameSite::None)
-        .secure(true) // Ubah ke true jika pakai HTTPS
-        .max_age(time::Duration::days(-1)) // Set expired
-        .finish();
+    // Hapus sesi device ini, token yang sudah expired tetap bisa logout lewat refresh token
+    let claims = extract_token(&req).and_then(|token| validate_jwt(&token).ok().map(|claims| (token, claims)));
+    if let Some((token, claims)) = &claims {
+        let deleted = AuthService::check_session(connection.clone(), claims.clone(), token.clone(), "".to_string(), true, false, false).await;
+        if deleted.error.is_some() {
+            return HttpResponse::InternalServerError().json(deleted);
+        }
+    }
+
+    // Cabut refresh token supaya sesi tidak bisa diperpanjang lagi
+    if let Some(refresh_token) = req.cookie(REFRESH_COOKIE) {
+        let revoked = AuthService::revoke_refresh_token(connection.clone(), refresh_token.value().to_string()).await;
+        if revoked.error.is_some() {
+            return HttpResponse::InternalServerError().json(revoked);
+        }
+    }
+
+    AuditService::record(&connection, &req, AuditEvent::Logout, claims.as_ref().map(|(_, claims)| claims), None, true, json!(null)).await;
 
     HttpResponse::Ok()
-        .cookie(cookie) // Hapus cookie dengan expired
+        .cookie(expired_cookie(ACCESS_COOKIE, "/")) // Hapus cookie dengan expired
+        .cookie(expired_cookie(REFRESH_COOKIE, "/api/v1/auth"))
         .json(serde_json::json!({
             "result": true,
-            "message": "Logout successful, cookie deleted"
+            "message": t("auth.logout")
         }))
 }
 
+#[get("/sessions")]
+async fn get_sessions(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let result: ActionResult<Vec<UserSession>, _> = SessionService::get_sessions(connection, &user.claims).await;
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response => HttpResponse::Ok().json(response),
+    }
+}
+
+#[patch("/sessions/{session_id}")]
+async fn rename_session(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>, session_id: web::Path<String>, request: web::Json<RenameSessionRequest>) -> impl Responder {
+
+    if let Err(err) = request.validate() {
+        return validation_failed(&err);
+    }
+
+    let result: ActionResult<(), _> = SessionService::rename_session(connection, user.claims.auth_usernid, session_id.into_inner(), request.into_inner().device_name).await;
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result => HttpResponse::Ok().json(response), // Jika berhasil, HTTP 200
+        response => HttpResponse::NotFound().json(response),
+    }
+}
+
+#[delete("/sessions/{session_id}")]
+async fn revoke_session(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>, session_id: web::Path<String>) -> impl Responder {
+
+    let session_id = session_id.into_inner();
+    let is_current = session_id == user.claims.session_id;
+
+    let result: ActionResult<(), _> = SessionService::revoke_session(connection, user.claims.auth_usernid, session_id).await;
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result && is_current => {
+            // Sesi sendiri dicabut = logout
+            HttpResponse::Ok()
+                .cookie(expired_cookie(ACCESS_COOKIE, "/"))
+                .cookie(expired_cookie(REFRESH_COOKIE, "/api/v1/auth"))
+                .json(response)
+        },
+        response if response.result => HttpResponse::Ok().json(response), // Jika berhasil, HTTP 200
+        response => HttpResponse::NotFound().json(response),
+    }
+}
+
+#[delete("/sessions")]
+async fn revoke_other_sessions(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let result: ActionResult<u64, _> = SessionService::revoke_other_sessions(connection, &user.claims).await;
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response => HttpResponse::Ok().json(response),
+    }
+}
+
+#[get("/api-keys")]
+async fn get_api_keys(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let result: ActionResult<Vec<ApiKeyInfo>, _> = ApiKeyService::get_api_keys(connection, user.claims.auth_usernid).await;
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response => HttpResponse::Ok().json(response),
+    }
+}
+
+#[post("/api-keys")]
+async fn create_api_key(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>, request: web::Json<CreateApiKeyRequest>) -> impl Responder {
+
+    if let Err(err) = request.validate() {
+        return validation_failed(&err);
+    }
+
+    let result: ActionResult<CreatedApiKey, _> = ApiKeyService::create_api_key(connection, &user.claims, request.into_inner()).await;
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result => HttpResponse::Ok().json(response), // Jika berhasil, HTTP 200
+        response => HttpResponse::BadRequest().json(response), // Jika gagal, HTTP 400
+    }
+}
+
+#[delete("/api-keys/{api_key_nid}")]
+async fn revoke_api_key(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>, api_key_nid: web::Path<i32>) -> impl Responder {
+
+    let result: ActionResult<(), _> = ApiKeyService::revoke_api_key(connection, user.claims.auth_usernid, api_key_nid.into_inner()).await;
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result => HttpResponse::Ok().json(response), // Jika berhasil, HTTP 200
+        response => HttpResponse::NotFound().json(response),
+    }
+}
+
 #[post("/register")]
-async fn register(req: HttpRequest, pool: web::Data<Pool<ConnectionManager>>, mut request: web::Json<RegisterRequest>) -> impl Responder {
+async fn register(req: HttpRequest, pool: web::Data<Pool<ConnectionManager>>, secrets: web::Data<SecretStore>, mut request: web::Json<RegisterRequest>) -> impl Responder {
 
     request.app_ipaddress = GenericService::get_ip_address(&req);
+    // Nomor HP disimpan dalam format E.164 (+62...)
+    if let Some(Ok(phone)) = request.mobile_phone.as_deref().map(normalize_phone) {
+        request.mobile_phone = Some(phone);
+    }
 
-    let result: ActionResult<(), _> = AuthService::register(pool, request.into_inner()).await;
+    let email = request.email.clone();
+    let result: ActionResult<(), _> = AuthService::register(pool.clone(), request.into_inner(), secrets).await;
+    if result.error.is_none() {
+        AuditService::record(&pool, &req, AuditEvent::Register, None, email.as_deref(), result.result, json!(null)).await;
+    }
 
     match result {
         response if response.error.is_some() => {
@@ -154,9 +659,30 @@ async fn register(req: HttpRequest, pool: web::Data<Pool<ConnectionManager>>, mu
 }
 
 #[get("/activation/{otp_link}")]
-async fn activation_user(pool: web::Data<Pool<ConnectionManager>>, otp_link: web::Path<String>) -> impl Responder {
+async fn activation_user(req: HttpRequest, pool: web::Data<Pool<ConnectionManager>>, otp_link: web::Path<String>) -> impl Responder {
+
+    let result: ActionResult<(), _> = AuthService::activation_user(pool.clone(), otp_link.into_inner()).await;
+    if result.error.is_none() {
+        AuditService::record(&pool, &req, AuditEvent::Activation, None, None, result.result, json!(null)).await;
+    }
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result => HttpResponse::Ok().json(response), // Jika berhasil, HTTP 200
+        response => HttpResponse::BadRequest().json(response), // Jika gagal, HTTP 400
+    }
+}
 
-    let result: ActionResult<(), _> = AuthService::activation_user(pool, otp_link.into_inner()).await;
+#[post("/activation/resend")]
+async fn resend_activation(req: HttpRequest, pool: web::Data<Pool<ConnectionManager>>, secrets: web::Data<SecretStore>, request: web::Json<ResendActivationRequest>) -> impl Responder {
+
+    if let Err(err) = request.validate() {
+        return validation_failed(&err);
+    }
+
+    let result: ActionResult<(), _> = AuthService::resend_activation(pool, request.into_inner(), req, secrets).await;
 
     match result {
         response if response.error.is_some() => {
@@ -168,9 +694,13 @@ async fn activation_user(pool: web::Data<Pool<ConnectionManager>>, otp_link: web
 }
 
 #[post("/reset-password")]
-async fn forget_password(pool: web::Data<Pool<ConnectionManager>>, request: web::Json<ResetPasswordRequest>) -> impl Responder {
+async fn forget_password(req: HttpRequest, pool: web::Data<Pool<ConnectionManager>>, secrets: web::Data<SecretStore>, request: web::Json<ResetPasswordRequest>) -> impl Responder {
 
-    let result: ActionResult<(), _> = AuthService::forget_password(pool, request.into_inner()).await;
+    let email = request.email.clone();
+    let result: ActionResult<(), _> = AuthService::forget_password(pool.clone(), request.into_inner(), req.clone(), secrets).await;
+    if result.error.is_none() {
+        AuditService::record(&pool, &req, AuditEvent::PasswordResetRequest, None, email.as_deref(), result.result, json!(null)).await;
+    }
 
     match result {
         response if response.error.is_some() => {
@@ -182,9 +712,13 @@ async fn forget_password(pool: web::Data<Pool<ConnectionManager>>, request: web:
 }
 
 #[post("/change-password")]
-async fn change_password(pool: web::Data<Pool<ConnectionManager>>, request: web::Json<ChangePasswordRequest>) -> impl Responder {
+async fn change_password(req: HttpRequest, pool: web::Data<Pool<ConnectionManager>>, request: web::Json<ChangePasswordRequest>) -> impl Responder {
 
-    let result: ActionResult<(), _> = AuthService::change_password(pool, request.into_inner()).await;
+    let email = request.email.clone();
+    let result: ActionResult<(), _> = AuthService::change_password(pool.clone(), request.into_inner()).await;
+    if result.error.is_none() {
+        AuditService::record(&pool, &req, AuditEvent::PasswordReset, None, email.as_deref(), result.result, json!(null)).await;
+    }
 
     match result {
         response if response.error.is_some() => {
@@ -193,4 +727,169 @@ async fn change_password(pool: web::Data<Pool<ConnectionManager>>, request: web:
         response if response.result => HttpResponse::Ok().json(response), // Jika berhasil, HTTP 200
         response => HttpResponse::BadRequest().json(response), // Jika gagal, HTTP 400
     }
-}
\ No newline at end of file
+}
+
+#[get("/me")]
+async fn get_profile(user: AuthenticatedUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let result: ActionResult<UserProfile, _> = ProfileService::get_profile(connection, user.claims.auth_usernid).await;
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result => HttpResponse::Ok().json(response), // Jika berhasil, HTTP 200
+        response => HttpResponse::NotFound().json(response),
+    }
+}
+
+#[patch("/me")]
+async fn update_profile(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>, request: web::Json<UpdateProfileRequest>) -> impl Responder {
+
+    if let Err(err) = request.validate() {
+        return validation_failed(&err);
+    }
+
+    let result: ActionResult<UserProfile, _> = ProfileService::update_profile(connection, user.claims.auth_usernid, request.into_inner()).await;
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result => HttpResponse::Ok().json(response), // Jika berhasil, HTTP 200
+        response => HttpResponse::BadRequest().json(response), // Jika gagal, HTTP 400
+    }
+}
+
+#[post("/me/password")]
+async fn update_password(req: HttpRequest, SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>, request: web::Json<UpdatePasswordRequest>) -> impl Responder {
+
+    if let Err(err) = request.validate() {
+        return validation_failed(&err);
+    }
+
+    let result: ActionResult<(), _> = ProfileService::change_password(connection.clone(), &user.claims, request.into_inner()).await;
+    if result.error.is_none() {
+        AuditService::record(&connection, &req, AuditEvent::PasswordChange, Some(&user.claims), None, result.result, json!(null)).await;
+    }
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result => HttpResponse::Ok().json(response), // Jika berhasil, HTTP 200
+        response => HttpResponse::BadRequest().json(response), // Jika gagal, HTTP 400
+    }
+}
+
+// Seluruh data pribadi user sebagai file JSON
+#[get("/me/export")]
+async fn export_personal_data(req: HttpRequest, SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let result: ActionResult<serde_json::Value, _> = AccountService::export_data(connection.clone(), user.claims.auth_usernid).await;
+    AuditService::record(&connection, &req, AuditEvent::DataExport, Some(&user.claims), None, result.result, json!(null)).await;
+
+    match result {
+        ActionResult { result: true, data: Some(bundle), .. } => HttpResponse::Ok()
+            .append_header(("Content-Disposition", format!("attachment; filename=\"snakesystem-data-{}.json\"", user.claims.auth_usernid)))
+            .append_header((header::CACHE_CONTROL, "no-store"))
+            .json(bundle),
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response => HttpResponse::NotFound().json(response),
+    }
+}
+
+#[get("/me/deletion")]
+async fn get_account_deletion(user: AuthenticatedUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let result: ActionResult<AccountDeletionStatus, _> = AccountService::deletion_status(connection, user.claims.auth_usernid).await;
+
+    match result {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response if response.result => HttpResponse::Ok().json(response),
+        response => HttpResponse::NotFound().json(response),
+    }
+}
+
+// Akun tetap bisa dipakai selama masa tenggang, setelah itu dihapus worker
+#[post("/me/deletion")]
+async fn request_account_deletion(req: HttpRequest, SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>, request: web::Json<AccountDeletionRequest>) -> impl Responder {
+
+    if let Err(err) = request.validate() {
+        return validation_failed(&err);
+    }
+
+    let result: ActionResult<AccountDeletionStatus, _> = AccountService::request_deletion(connection.clone(), user.claims.auth_usernid, request.into_inner().reason).await;
+    if result.error.is_none() {
+        let scheduled_time = result.data.as_ref().and_then(|status| status.scheduled_time);
+        AuditService::record(&connection, &req, AuditEvent::DeletionRequest, Some(&user.claims), None, result.result, json!({ "scheduled_time": scheduled_time })).await;
+    }
+
+    match result {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response if response.result => HttpResponse::Ok().json(response),
+        response => HttpResponse::BadRequest().json(response),
+    }
+}
+
+#[delete("/me/deletion")]
+async fn cancel_account_deletion(req: HttpRequest, SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let result: ActionResult<(), _> = AccountService::cancel_deletion(connection.clone(), user.claims.auth_usernid).await;
+    if result.result {
+        AuditService::record(&connection, &req, AuditEvent::DeletionCancel, Some(&user.claims), None, true, json!(null)).await;
+    }
+
+    match result {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response if response.result => HttpResponse::Ok().json(response),
+        response => HttpResponse::NotFound().json(response),
+    }
+}
+
+// Didaftarkan lewat `web::resource` di `auth_scope` karena butuh limit JSON lebih besar
+async fn upload_avatar(req: HttpRequest, SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>, request: web::Json<UploadAvatarRequest>) -> impl Responder {
+
+    if let Err(err) = request.validate() {
+        return validation_failed(&err);
+    }
+
+    let base_url = GenericService::api_base_url(&req);
+    let result: ActionResult<String, _> = ProfileService::upload_avatar(connection, user.claims.auth_usernid, request.into_inner().image, &base_url).await;
+
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result => HttpResponse::Ok().json(response), // Jika berhasil, HTTP 200
+        response => HttpResponse::BadRequest().json(response), // Jika gagal, HTTP 400
+    }
+}
+
+// Publik supaya bisa dipakai langsung di <img>, key acak berganti setiap upload
+#[get("/avatar/{avatar_key}/{file}")]
+async fn get_avatar(connection: web::Data<Pool<ConnectionManager>>, path: web::Path<(String, String)>) -> impl Responder {
+
+    let (avatar_key, file) = path.into_inner();
+    let (size, format) = match avatar::parse_file_name(&file) {
+        Some(parsed) => parsed,
+        None => return HttpResponse::NotFound().finish(),
+    };
+
+    match ProfileService::get_avatar(connection, &avatar_key, size, format).await {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        ActionResult { data: Some((content_type, data)), .. } => HttpResponse::Ok()
+            .content_type(content_type)
+            .append_header(("Cache-Control", "public, max-age=31536000, immutable"))
+            .body(data),
+        _ => HttpResponse::NotFound().finish(),
+    }
+}
+
+// Public key untuk verifikasi token oleh service lain, di luar scope /api/v1
+#[get("/.well-known/jwks.json")]
+pub async fn jwks() -> impl Responder {
+    HttpResponse::Ok()
+        .append_header(("Cache-Control", "public, max-age=300"))
+        .json(public_jwks())
+}
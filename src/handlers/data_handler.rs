@@ -1,9 +1,12 @@
-use actix_web::{get, post, web, HttpResponse, Responder, Scope};
+use actix_web::{get, http::StatusCode, post, web, HttpRequest, HttpResponse, Responder, Scope};
 use bb8::Pool;
 use bb8_tiberius::ConnectionManager;
 use serde_json::json;
 
-use crate::{contexts::model::{ActionResult, HeaderParams, ResultList, TableDataParams}, services::data_service::DataService};
+use crate::{
+    contexts::{auth_guard::{require_permission, AuthenticatedUser}, i18n::t, model::{ActionResult, HeaderParams, ResultList, TableDataParams}, query_builder::QueryError},
+    services::{audit_service::{AuditEvent, AuditService}, data_service::{DataService, DataViewForbidden}}
+};
 
 pub fn data_scope() -> Scope {
     web::scope("/data")
@@ -12,10 +15,41 @@ pub fn data_scope() -> Scope {
         .service(clear_data)
 }
 
+/// 403 permission registry, 400 tabel / kolom / filter tidak valid, selain itu 500
+pub(crate) fn data_error_status(err: &(dyn std::error::Error + 'static)) -> StatusCode {
+    if err.downcast_ref::<DataViewForbidden>().is_some() {
+        StatusCode::FORBIDDEN
+    } else if err.downcast_ref::<QueryError>().is_some() {
+        StatusCode::BAD_REQUEST
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
 #[get("/header")]
-pub async fn get_header(pool: web::Data<Pool<ConnectionManager>>, params: web::Query<HeaderParams>) -> impl Responder {
+pub async fn get_header(user: AuthenticatedUser, pool: web::Data<Pool<ConnectionManager>>, params: web::Query<HeaderParams>) -> impl Responder {
 
-    let result: ActionResult<Vec<serde_json::Value>, String> = DataService::get_header(pool, params.into_inner().tablename).await;
+    let view = match DataService::find_view(pool.clone(), &params.tablename, &user.claims).await {
+        Ok(view) => view,
+        Err(err) => {
+            let status = data_error_status(err.as_ref());
+            let message = if status == StatusCode::FORBIDDEN {
+                t("common.forbidden")
+            } else if status == StatusCode::BAD_REQUEST {
+                t("data.view_unavailable")
+            } else {
+                t("common.query_failed")
+            };
+            return HttpResponse::build(status).json(ActionResult::<(), String> {
+                result: false,
+                message,
+                data: None,
+                error: Some(err.to_string()),
+            });
+        }
+    };
+
+    let result: ActionResult<Vec<serde_json::Value>, String> = DataService::get_header(pool, &view).await;
 
     match result {
         response if response.error.is_some() => {
@@ -31,20 +65,26 @@ pub async fn get_header(pool: web::Data<Pool<ConnectionManager>>, params: web::Q
 }
 
 #[get("/get-table")]
-async fn get_table_data(params: web::Query<TableDataParams>, pool: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+async fn get_table_data(user: AuthenticatedUser, params: web::Query<TableDataParams>, pool: web::Data<Pool<ConnectionManager>>) -> impl Responder {
 
-    let data: Result<ResultList, Box<dyn std::error::Error>> = DataService::get_table_data(params.into_inner(), pool).await;
+    let params = params.into_inner();
+    let data: Result<ResultList, Box<dyn std::error::Error>> = match DataService::find_view(pool.clone(), &params.tablename, &user.claims).await {
+        Ok(view) => DataService::get_view_data(&view, params, &user.claims, pool).await,
+        Err(err) => Err(err),
+    };
 
     match data {
         Ok(response) => {
             return HttpResponse::Ok().json(serde_json::json!({
                 "total": response.total,
                 "totalNotFiltered": response.total_not_filtered,
-                "rows": response.rows
+                "rows": response.rows,
+                "nextCursor": response.next_cursor,
+                "prevCursor": response.prev_cursor
             }));
         },
         Err(e) => {
-            return HttpResponse::InternalServerError().json(
+            return HttpResponse::build(data_error_status(e.as_ref())).json(
                 json!({"error": e.to_string()})
             );
         },
@@ -52,16 +92,18 @@ async fn get_table_data(params: web::Query<TableDataParams>, pool: web::Data<Poo
     }
 }
 
-#[post("/clear")]
-async fn clear_data(connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+// ❗ Hanya admin: menghapus seluruh isi TempImport
+#[post("/clear", wrap = "require_permission(\"data:clear\")")]
+async fn clear_data(req: HttpRequest, user: AuthenticatedUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
 
     match connection.clone().get().await {
         Ok(mut conn) => {
             match conn.query("TRUNCATE TABLE TempImport", &[]).await {
                 Ok(_) => {
+                    AuditService::record(&connection, &req, AuditEvent::DataClear, Some(&user.claims), Some("TempImport"), true, json!(null)).await;
                     return HttpResponse::Ok().json(json!({
                         "result": true,
-                        "message": "Data berhasil dibersihkan"
+                        "message": t("data.cleared")
                     }));
                 },
                 Err(e) => {
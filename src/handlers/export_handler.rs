@@ -1,10 +1,14 @@
 use std::path::PathBuf;
 
-use actix_web::{get, HttpResponse, Responder, web, Scope};
+use actix_web::{get, HttpRequest, HttpResponse, Responder, web, Scope};
 use bb8::Pool;
 use bb8_tiberius::ConnectionManager;
+use serde_json::json;
 
-use crate::{contexts::model::ActionResult, services::export_service::ExportService};
+use crate::{
+    contexts::{auth_guard::{require_permission, AuthenticatedUser}, i18n::t_args, model::ActionResult},
+    services::{audit_service::{AuditEvent, AuditService}, export_service::ExportService}
+};
 
 pub fn export_scope() -> Scope {
     
@@ -17,8 +21,12 @@ pub fn export_scope() -> Scope {
         .service(download_emails)
 }
 
-#[get("/csv")]
-pub async fn download_csv_handler(connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+async fn audit_export(req: &HttpRequest, user: &AuthenticatedUser, connection: &web::Data<Pool<ConnectionManager>>, format: &str, success: bool) {
+    AuditService::record(connection, req, AuditEvent::Export, Some(&user.claims), Some("TempImport"), success, json!({ "format": format })).await;
+}
+
+#[get("/csv", wrap = "require_permission(\"export:read\")")]
+pub async fn download_csv_handler(req: HttpRequest, user: AuthenticatedUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
     // Tentukan path output
     let output_path = PathBuf::from("./exports/tempimport.csv");
 
@@ -26,6 +34,8 @@ pub async fn download_csv_handler(connection: web::Data<Pool<ConnectionManager>>
     let res: ActionResult<String, String> =
         ExportService::export_to_csv_file(connection.clone(), output_path.clone()).await;
 
+    audit_export(&req, &user, &connection, "CSV", res.result).await;
+
     if !res.result {
         return HttpResponse::InternalServerError().json(res);
     }
@@ -38,15 +48,15 @@ pub async fn download_csv_handler(connection: web::Data<Pool<ConnectionManager>>
             .body(bytes),
         Err(e) => HttpResponse::InternalServerError().json(ActionResult {
             result: false,
-            message: "Failed to read CSV file".into(),
+            message: t_args("export.read_failed", &[("format", &"CSV")]),
             data: "".into(),
             error: Some(e.to_string()),
         }),
     }
 }
 
-#[get("/txt")]
-pub async fn download_txt_handler(connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+#[get("/txt", wrap = "require_permission(\"export:read\")")]
+pub async fn download_txt_handler(req: HttpRequest, user: AuthenticatedUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
     // Tentukan path output
     let output_path = PathBuf::from("./exports/tempimport.txt");
 
@@ -54,6 +64,8 @@ pub async fn download_txt_handler(connection: web::Data<Pool<ConnectionManager>>
     let res: ActionResult<String, String> =
         ExportService::export_to_txt_file(connection.clone(), output_path.clone()).await;
 
+    audit_export(&req, &user, &connection, "TXT", res.result).await;
+
     if !res.result {
         return HttpResponse::InternalServerError().json(res);
     }
@@ -66,15 +78,15 @@ pub async fn download_txt_handler(connection: web::Data<Pool<ConnectionManager>>
             .body(bytes),
         Err(e) => HttpResponse::InternalServerError().json(ActionResult {
             result: false,
-            message: "Failed to read TXT file".into(),
+            message: t_args("export.read_failed", &[("format", &"TXT")]),
             data: "".into(),
             error: Some(e.to_string()),
         }),
     }
 }
 
-#[get("/xlsx")]
-pub async fn download_xlsx_handler(connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+#[get("/xlsx", wrap = "require_permission(\"export:read\")")]
+pub async fn download_xlsx_handler(req: HttpRequest, user: AuthenticatedUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
     // Tentukan path output
     let output_path = PathBuf::from("./exports/tempimport.xlsx");
 
@@ -82,6 +94,8 @@ pub async fn download_xlsx_handler(connection: web::Data<Pool<ConnectionManager>
     let res: ActionResult<String, String> =
         ExportService::export_to_xlsx_file(connection.clone(), output_path.clone()).await;
 
+    audit_export(&req, &user, &connection, "XLSX", res.result).await;
+
     if !res.result {
         return HttpResponse::InternalServerError().json(res);
     }
@@ -94,15 +108,15 @@ pub async fn download_xlsx_handler(connection: web::Data<Pool<ConnectionManager>
             .body(bytes),
         Err(e) => HttpResponse::InternalServerError().json(ActionResult {
             result: false,
-            message: "Failed to read TXT file".into(),
+            message: t_args("export.read_failed", &[("format", &"TXT")]),
             data: "".into(),
             error: Some(e.to_string()),
         }),
     }
 }
 
-#[get("/xml")]
-pub async fn download_xml_handler(connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+#[get("/xml", wrap = "require_permission(\"export:read\")")]
+pub async fn download_xml_handler(req: HttpRequest, user: AuthenticatedUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
     // Tentukan path output
     let output_path = PathBuf::from("./exports/tempimport.xml");
 
@@ -110,6 +124,8 @@ pub async fn download_xml_handler(connection: web::Data<Pool<ConnectionManager>>
     let res: ActionResult<String, String> =
         ExportService::export_to_xml_file(connection.clone(), output_path.clone()).await;
 
+    audit_export(&req, &user, &connection, "XML", res.result).await;
+
     if !res.result {
         return HttpResponse::InternalServerError().json(res);
     }
@@ -122,19 +138,21 @@ pub async fn download_xml_handler(connection: web::Data<Pool<ConnectionManager>>
             .body(bytes),
         Err(e) => HttpResponse::InternalServerError().json(ActionResult {
             result: false,
-            message: "Failed to read DBF file".into(),
+            message: t_args("export.read_failed", &[("format", &"DBF")]),
             data: "".into(),
             error: Some(e.to_string()),
         }),
     }
 }
 
-#[get("/pdf")]
-pub async fn download_pdf_handler(connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+#[get("/pdf", wrap = "require_permission(\"export:read\")")]
+pub async fn download_pdf_handler(req: HttpRequest, user: AuthenticatedUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
     let output_path = PathBuf::from("./exports/tempimport.pdf");
 
     let res = ExportService::export_to_pdf_file(connection.clone(), output_path.clone()).await;
 
+    audit_export(&req, &user, &connection, "PDF", res.result).await;
+
     if !res.result {
         return HttpResponse::InternalServerError().json(res);
     }
@@ -146,14 +164,14 @@ pub async fn download_pdf_handler(connection: web::Data<Pool<ConnectionManager>>
             .body(bytes),
         Err(e) => HttpResponse::InternalServerError().json(ActionResult {
             result: false,
-            message: "Failed to read PDF file".into(),
+            message: t_args("export.read_failed", &[("format", &"PDF")]),
             data: "".into(),
             error: Some(e.to_string()),
         }),
     }
 }
 
-#[get("/download/emails")]
+#[get("/download/emails", wrap = "require_permission(\"export:read\")")]
 async fn download_emails() -> Result<HttpResponse, actix_web::Error> {
     let emails: Vec<String> = (0..1000)
         .map(|i| format!("user{}@example.com", i))
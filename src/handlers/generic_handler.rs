@@ -3,7 +3,7 @@ use actix_web_actors::ws;
 use bb8::Pool;
 use bb8_tiberius::ConnectionManager;
 
-use crate::{contexts::{model::{ActionResult, Company, MyRow}, socket::WsSession}, services::generic_service::GenericService};
+use crate::{contexts::{auth_guard::authenticate, csrf::AllowedOrigins, i18n::t, model::{ActionResult, Company, MyRow}, socket::WsSession}, services::generic_service::GenericService};
 
 pub fn generic_scope() -> Scope {
     web::scope("/generic")
@@ -30,8 +30,24 @@ pub async fn get_company(pool: web::Data<Pool<ConnectionManager>>) -> impl Respo
 }
 
 #[get("/ws/")]
-pub async fn ws_route(req: HttpRequest, stream: web::Payload) -> actix_web::Result<HttpResponse> {
-    ws::start(WsSession::new(), &req, stream)
+pub async fn ws_route(req: HttpRequest, stream: web::Payload, pool: web::Data<Pool<ConnectionManager>>, origins: web::Data<AllowedOrigins>) -> actix_web::Result<HttpResponse> {
+    // Handshake WebSocket tidak dilindungi CORS, halaman situs lain bisa membuka socket dengan cookie korban
+    if !origins.allows(&req) {
+        return Ok(HttpResponse::Forbidden().json(ActionResult::<(), _> {
+            result: false,
+            message: t("common.forbidden"),
+            data: None,
+            error: Some("Origin not allowed".to_string()),
+        }));
+    }
+
+    // Tanpa login tetap bisa connect, tapi hanya menerima event broadcast.
+    // API key tidak punya sesi `WEB_Cookies` untuk dicek ulang, jadi diperlakukan sebagai anonim
+    let session = match authenticate(&req).await {
+        Ok(user) if !user.api_key => WsSession::for_user(pool, user.claims),
+        _ => WsSession::new(),
+    };
+    ws::start(session, &req, stream)
 }
 
 #[post("/import")]
@@ -1,12 +1,16 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use actix_multipart::Multipart;
-use actix_web::{post, web, HttpResponse, Responder, Scope};
+use actix_web::{post, web, HttpRequest, HttpResponse, Responder, Scope};
 use bb8::Pool;
 use bb8_tiberius::ConnectionManager;
 use futures::StreamExt;
+use serde_json::json;
 use tokio::{fs::File, io::AsyncWriteExt};
 
-use crate::services::{generic_service::GenericService, import_service::ImportService};
+use crate::{
+    contexts::{auth_guard::{require_permission, AuthenticatedUser}, i18n::{current_locale, t, t_args, with_locale}},
+    services::{audit_service::{AuditEvent, AuditService}, generic_service::GenericService, import_service::ImportService}
+};
 
 pub fn import_scope() -> Scope {
     
@@ -18,13 +22,20 @@ pub fn import_scope() -> Scope {
         .service(import_xml_handler)
 }
 
-#[post("/csv")]
-pub async fn import_csv_handler(mut payload: Multipart, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+/// File sudah tersimpan & import dijalankan di background, hasil per baris dikirim lewat WebSocket
+async fn audit_import(req: &HttpRequest, user: &AuthenticatedUser, connection: &web::Data<Pool<ConnectionManager>>, format: &str, file: &Path) {
+    let file_name = file.file_name().map(|name| name.to_string_lossy().to_string());
+    AuditService::record(connection, req, AuditEvent::Import, Some(&user.claims), Some("TempImport"), true, json!({ "format": format, "file": file_name })).await;
+}
+
+#[post("/csv", wrap = "require_permission(\"import:write\")")]
+pub async fn import_csv_handler(req: HttpRequest, user: AuthenticatedUser, mut payload: Multipart, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
     let tmp_path = PathBuf::from("./templates/uploads");
     if let Err(e) = tokio::fs::create_dir_all(&tmp_path).await {
         return HttpResponse::InternalServerError().json(serde_json::json!({
             "result": false,
-            "message": format!("Failed to create temp dir: {}", e)
+            "message": t("import.upload_failed"),
+            "error": e.to_string()
         }));
     }
 
@@ -36,7 +47,7 @@ pub async fn import_csv_handler(mut payload: Multipart, connection: web::Data<Po
             Err(_) => {
                 return HttpResponse::BadRequest().json(serde_json::json!({
                     "result": false,
-                    "message": "Failed to read field"
+                    "message": t("document.read_field_failed")
                 }));
             }
         };
@@ -55,14 +66,15 @@ pub async fn import_csv_handler(mut payload: Multipart, connection: web::Data<Po
                             if let Err(e) = f.write_all(&data).await {
                                 return HttpResponse::InternalServerError().json(serde_json::json!({
                                     "result": false,
-                                    "message": format!("Failed to write file: {}", e)
+                                    "message": t("import.upload_failed"),
+                                    "error": e.to_string()
                                 }));
                             }
                         }
                         Err(_) => {
                             return HttpResponse::BadRequest().json(serde_json::json!({
                                 "result": false,
-                                "message": "Failed to read chunk"
+                                "message": t("document.read_failed")
                             }));
                         }
                     }
@@ -72,43 +84,46 @@ pub async fn import_csv_handler(mut payload: Multipart, connection: web::Data<Po
             Err(e) => {
                 return HttpResponse::InternalServerError().json(serde_json::json!({
                     "result": false,
-                    "message": format!("Failed to create file: {}", e)
+                    "message": t("import.upload_failed"),
+                    "error": e.to_string()
                 }));
             }
         }
     }
 
     if let Some(csv_file) = file_path {
+        audit_import(&req, &user, &connection, "CSV", &csv_file).await;
         let connection_clone = connection.clone();
         let file_clone = csv_file.clone();
 
         // 🚀 Jalankan import di background
-        tokio::spawn(async move {
+        tokio::spawn(with_locale(current_locale(), async move {
             let _ = ImportService::import_csv_from_file(file_clone.clone(), connection_clone).await;
             let _ = tokio::fs::remove_file(file_clone).await;
-        });
+        }));
 
         // ⏱️ Balas langsung, proses jalan di background
         return HttpResponse::Ok().json(serde_json::json!({
             "result": true,
             "status": "processing",
-            "message": "File berhasil diupload, sedang diproses."
+            "message": t_args("import.uploaded", &[("format", &"CSV")])
         }));
     }
 
     HttpResponse::BadRequest().json(serde_json::json!({
         "result": false,
-        "message": "File not found"
+        "message": t("import.file_not_found")
     }))
 }
 
-#[post("/txt")]
-pub async fn import_txt_handler(mut payload: Multipart, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+#[post("/txt", wrap = "require_permission(\"import:write\")")]
+pub async fn import_txt_handler(req: HttpRequest, user: AuthenticatedUser, mut payload: Multipart, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
     let tmp_dir = PathBuf::from("./templates/uploads");
     if let Err(e) = tokio::fs::create_dir_all(&tmp_dir).await {
         return HttpResponse::InternalServerError().json(serde_json::json!({
             "result": false,
-            "message": format!("Failed to create temp dir: {}", e)
+            "message": t("import.upload_failed"),
+            "error": e.to_string()
         }));
     }
 
@@ -121,7 +136,7 @@ pub async fn import_txt_handler(mut payload: Multipart, connection: web::Data<Po
             Err(_) => {
                 return HttpResponse::BadRequest().json(serde_json::json!({
                     "result": false,
-                    "message": "Failed to read field"
+                    "message": t("document.read_field_failed")
                 }));
             }
         };
@@ -142,7 +157,8 @@ pub async fn import_txt_handler(mut payload: Multipart, connection: web::Data<Po
             Err(e) => {
                 return HttpResponse::InternalServerError().json(serde_json::json!({
                     "result": false,
-                    "message": format!("Failed to create file: {}", e)
+                    "message": t("import.upload_failed"),
+                    "error": e.to_string()
                 }));
             }
         }
@@ -150,33 +166,35 @@ pub async fn import_txt_handler(mut payload: Multipart, connection: web::Data<Po
 
     // Jika sukses upload, jalankan background import
     if let Some(txt_file) = file_path {
+        audit_import(&req, &user, &connection, "TXT", &txt_file).await;
         let conn_clone = connection.clone();
         let file_clone = txt_file.clone();
-        tokio::spawn(async move {
+        tokio::spawn(with_locale(current_locale(), async move {
             let _ = ImportService::import_txt_from_file(file_clone.clone(), conn_clone).await;
             let _ = tokio::fs::remove_file(file_clone).await;
-        });
+        }));
 
         return HttpResponse::Ok().json(serde_json::json!({
             "result": true,
             "status": "processing",
-            "message": "File TXT berhasil diupload, sedang diproses."
+            "message": t_args("import.uploaded", &[("format", &"TXT")])
         }));
     }
 
     HttpResponse::BadRequest().json(serde_json::json!({
         "result": false,
-        "message": "File not found"
+        "message": t("import.file_not_found")
     }))
 }
 
-#[post("/xlsx")]
-pub async fn import_xlsx_handler(mut payload: Multipart, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+#[post("/xlsx", wrap = "require_permission(\"import:write\")")]
+pub async fn import_xlsx_handler(req: HttpRequest, user: AuthenticatedUser, mut payload: Multipart, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
     let tmp_dir = PathBuf::from("./templates/uploads");
     if let Err(e) = tokio::fs::create_dir_all(&tmp_dir).await {
         return HttpResponse::InternalServerError().json(serde_json::json!({
             "result": false,
-            "message": format!("Failed to create temp dir: {}", e)
+            "message": t("import.upload_failed"),
+            "error": e.to_string()
         }));
     }
 
@@ -189,7 +207,7 @@ pub async fn import_xlsx_handler(mut payload: Multipart, connection: web::Data<P
             Err(_) => {
                 return HttpResponse::BadRequest().json(serde_json::json!({
                     "result": false,
-                    "message": "Failed to read field"
+                    "message": t("document.read_field_failed")
                 }));
             }
         };
@@ -210,7 +228,8 @@ pub async fn import_xlsx_handler(mut payload: Multipart, connection: web::Data<P
             Err(e) => {
                 return HttpResponse::InternalServerError().json(serde_json::json!({
                     "result": false,
-                    "message": format!("Failed to create file: {}", e)
+                    "message": t("import.upload_failed"),
+                    "error": e.to_string()
                 }));
             }
         }
@@ -218,34 +237,36 @@ pub async fn import_xlsx_handler(mut payload: Multipart, connection: web::Data<P
 
     // Jalankan import
     if let Some(xlsx_file) = file_path {
+        audit_import(&req, &user, &connection, "XLSX", &xlsx_file).await;
         let conn_clone = connection.clone();
         let file_clone = xlsx_file.clone();
-        tokio::spawn(async move {
+        tokio::spawn(with_locale(current_locale(), async move {
             let _ = ImportService::import_xlsx_from_file(file_clone.clone(), conn_clone, true).await;
             let _ = tokio::fs::remove_file(file_clone).await;
-        });
+        }));
 
         return HttpResponse::Ok().json(serde_json::json!({
             "result": true,
             "status": "processing",
-            "message": "File XLSX berhasil diupload, sedang diproses."
+            "message": t_args("import.uploaded", &[("format", &"XLSX")])
         }));
     }
 
     HttpResponse::BadRequest().json(serde_json::json!({
         "result": false,
-        "message": "File not found"
+        "message": t("import.file_not_found")
     }))
 }
 
-#[post("/dbf")]
-pub async fn import_dbf_handler(mut payload: Multipart, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+#[post("/dbf", wrap = "require_permission(\"import:write\")")]
+pub async fn import_dbf_handler(req: HttpRequest, user: AuthenticatedUser, mut payload: Multipart, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
     // 1. Buat temp dir
     let tmp_dir = PathBuf::from("./templates/uploads");
     if let Err(e) = tokio::fs::create_dir_all(&tmp_dir).await {
         return HttpResponse::InternalServerError().json(serde_json::json!({
             "result": false,
-            "message": format!("Failed to create temp dir: {}", e)
+            "message": t("import.upload_failed"),
+            "error": e.to_string()
         }));
     }
 
@@ -275,34 +296,36 @@ pub async fn import_dbf_handler(mut payload: Multipart, connection: web::Data<Po
 
     // 3. Proses DBF di background
     if let Some(file_clone) = file_path {
+        audit_import(&req, &user, &connection, "DBF", &file_clone).await;
         let conn = connection.clone();
-        tokio::spawn(async move {
+        tokio::spawn(with_locale(current_locale(), async move {
             // Buka DBF dengan crate `dbase`
             let _ = ImportService::import_dbf_from_file(file_clone.clone(), conn).await;
             let _ = tokio::fs::remove_file(file_clone).await;
-        });
+        }));
 
         HttpResponse::Ok().json(serde_json::json!({
             "result": true,
             "status": "processing",
-            "message": "File DBF berhasil diupload, sedang diproses."
+            "message": t_args("import.uploaded", &[("format", &"DBF")])
         }))
     } else {
         HttpResponse::BadRequest().json(serde_json::json!({
             "result": false,
-            "message": "Tidak ada file DBF ditemukan"
+            "message": t_args("import.no_file", &[("format", &"DBF")])
         }))
     }
 }
 
-#[post("/xml")]
-pub async fn import_xml_handler(mut payload: Multipart, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+#[post("/xml", wrap = "require_permission(\"import:write\")")]
+pub async fn import_xml_handler(req: HttpRequest, user: AuthenticatedUser, mut payload: Multipart, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
     // Buat direktori upload kalau belum ada
     let tmp_dir = PathBuf::from("./templates/uploads");
     if let Err(e) = tokio::fs::create_dir_all(&tmp_dir).await {
         return HttpResponse::InternalServerError().json(serde_json::json!({
             "result": false,
-            "message": format!("Failed to create temp dir: {}", e)
+            "message": t("import.upload_failed"),
+            "error": e.to_string()
         }));
     }
 
@@ -315,7 +338,7 @@ pub async fn import_xml_handler(mut payload: Multipart, connection: web::Data<Po
             Err(_) => {
                 return HttpResponse::BadRequest().json(serde_json::json!({
                     "result": false,
-                    "message": "Failed to read multipart field"
+                    "message": t("document.read_field_failed")
                 }));
             }
         };
@@ -343,7 +366,8 @@ pub async fn import_xml_handler(mut payload: Multipart, connection: web::Data<Po
             Err(e) => {
                 return HttpResponse::InternalServerError().json(serde_json::json!({
                     "result": false,
-                    "message": format!("Failed to create file: {}", e)
+                    "message": t("import.upload_failed"),
+                    "error": e.to_string()
                 }));
             }
         }
@@ -351,23 +375,24 @@ pub async fn import_xml_handler(mut payload: Multipart, connection: web::Data<Po
 
     // Jika ada file, jalankan import di background
     if let Some(xml_file) = file_path {
+        audit_import(&req, &user, &connection, "XML", &xml_file).await;
         let conn_clone = connection.clone();
         let file_clone = xml_file.clone();
-        tokio::spawn(async move {
+        tokio::spawn(with_locale(current_locale(), async move {
             // Panggil service import_xml_from_file
             let _ = ImportService::import_xml_from_file(file_clone.clone(), conn_clone).await;
             let _ = tokio::fs::remove_file(file_clone).await;
-        });
+        }));
 
         HttpResponse::Ok().json(serde_json::json!({
             "result": true,
             "status": "processing",
-            "message": "File XML berhasil diupload, sedang diproses."
+            "message": t_args("import.uploaded", &[("format", &"XML")])
         }))
     } else {
         HttpResponse::BadRequest().json(serde_json::json!({
             "result": false,
-            "message": "No XML file uploaded"
+            "message": t_args("import.no_file", &[("format", &"XML")])
         }))
     }
 }
\ No newline at end of file
@@ -0,0 +1,339 @@
+use actix_multipart::Multipart;
+use actix_web::{get, post, put, web, HttpRequest, HttpResponse, Responder, Scope};
+use bb8::Pool;
+use bb8_tiberius::ConnectionManager;
+use futures::StreamExt;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::json;
+use validator::Validate;
+
+use crate::{
+    contexts::{
+        auth_guard::{require_permission, AuthenticatedUser, SessionUser}, i18n::{t, t_args}, document::{KycDocumentType, MAX_DOCUMENT_BYTES}, kyc::{KycAction, KycStage},
+        model::{ActionResult, DocumentDownloadQuery, KycApproveRequest, KycBankAccount, KycDocumentInfo, KycHistory, KycOccupation, KycOverview, KycPersonalData, KycRejectRequest, KycRevisionRequest}
+    },
+    services::{generic_service::GenericService, kyc_document_service::KycDocumentService, kyc_service::{KycService, KYC_REVIEW_PERMISSION}},
+    utils::validation::validator::{bank_rule, normalize_npwp, validation_failed}
+};
+
+pub fn kyc_scope() -> Scope {
+    web::scope("/kyc")
+        // 🔒 Reviewer, didaftarkan lebih dulu supaya `/review/...` tidak tertangkap route client
+        .service(
+            web::scope("/review")
+                .wrap(require_permission(KYC_REVIEW_PERMISSION))
+                .service(get_review_queue)
+                .service(get_review_detail)
+                .service(get_review_history)
+                .service(get_review_documents)
+                .service(approve_kyc)
+                .service(reject_kyc)
+                .service(request_revision)
+        )
+        .service(get_kyc)
+        .service(get_kyc_history)
+        .service(submit_stage)
+        .service(submit_for_review)
+        // Publik, otorisasi lewat token bertanda tangan di query string
+        .service(download_document)
+        .service(get_documents)
+        .service(upload_document)
+}
+
+fn invalid_body(err: impl Serialize) -> HttpResponse {
+    HttpResponse::BadRequest().json(json!({
+        "result": false,
+        "message": t("validation.failed"),
+        "error": err
+    }))
+}
+
+/// Parse + validasi body sesuai struct tahapnya, dinormalisasi lalu disimpan kembali sebagai JSON yang sudah bersih
+fn parse_stage<T: DeserializeOwned + Validate + Serialize>(body: serde_json::Value, normalize: fn(&mut T)) -> Result<serde_json::Value, HttpResponse> {
+    let mut data: T = serde_json::from_value(body).map_err(|err| invalid_body(err.to_string()))?;
+    data.validate().map_err(|err| validation_failed(&err))?;
+    normalize(&mut data);
+    serde_json::to_value(&data).map_err(|err| HttpResponse::InternalServerError().json(json!({
+        "result": false,
+        "message": t("common.internal_error"),
+        "error": err.to_string()
+    })))
+}
+
+/// KYC milik user yang login
+async fn own_kyc_nid(connection: &web::Data<Pool<ConnectionManager>>, user: &AuthenticatedUser) -> Result<i32, HttpResponse> {
+    match KycService::kyc_nid_for_user(connection.clone(), user.claims.auth_usernid).await {
+        response if response.error.is_some() => Err(HttpResponse::InternalServerError().json(response)),
+        ActionResult { data: Some(kyc_nid), .. } => Ok(kyc_nid),
+        response => Err(HttpResponse::NotFound().json(response)),
+    }
+}
+
+fn overview_response(result: ActionResult<KycOverview, String>) -> HttpResponse {
+    match result {
+        response if response.error.is_some() => {
+            HttpResponse::InternalServerError().json(response)
+        }, // Jika error, HTTP 500
+        response if response.result => HttpResponse::Ok().json(response), // Jika berhasil, HTTP 200
+        response => HttpResponse::BadRequest().json(response), // Jika transisi ditolak, HTTP 400
+    }
+}
+
+#[get("")]
+async fn get_kyc(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let kyc_nid = match own_kyc_nid(&connection, &user).await {
+        Ok(kyc_nid) => kyc_nid,
+        Err(response) => return response,
+    };
+
+    overview_response(KycService::get_overview(connection, kyc_nid).await)
+}
+
+#[get("/history")]
+async fn get_kyc_history(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let kyc_nid = match own_kyc_nid(&connection, &user).await {
+        Ok(kyc_nid) => kyc_nid,
+        Err(response) => return response,
+    };
+
+    let result: ActionResult<Vec<KycHistory>, _> = KycService::get_history(connection, kyc_nid).await;
+
+    match result {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response => HttpResponse::Ok().json(response),
+    }
+}
+
+#[put("/stages/{stage}")]
+async fn submit_stage(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>, stage: web::Path<String>, body: web::Json<serde_json::Value>) -> impl Responder {
+
+    let stage = match KycStage::from_slug(&stage.into_inner()) {
+        Some(stage) => stage,
+        None => return HttpResponse::NotFound().json(json!({
+            "result": false,
+            "message": t("kyc.unknown_stage")
+        })),
+    };
+
+    let data = match stage {
+        KycStage::PersonalData => parse_stage::<KycPersonalData>(body.into_inner(), |data| {
+            data.npwp = data.npwp.as_deref().and_then(|npwp| normalize_npwp(npwp).ok());
+        }),
+        KycStage::Occupation => parse_stage::<KycOccupation>(body.into_inner(), |_| {}),
+        KycStage::BankAccount => parse_stage::<KycBankAccount>(body.into_inner(), |data| {
+            if let Some(rule) = bank_rule(&data.bank_code) {
+                data.bank_name = rule.name.to_string();
+            }
+        }),
+        _ => unreachable!("from_slug hanya mengembalikan tahap data"),
+    };
+    let data = match data {
+        Ok(data) => data,
+        Err(response) => return response,
+    };
+
+    let kyc_nid = match own_kyc_nid(&connection, &user).await {
+        Ok(kyc_nid) => kyc_nid,
+        Err(response) => return response,
+    };
+
+    overview_response(KycService::submit_stage(connection, user.claims.auth_usernid, kyc_nid, stage, data).await)
+}
+
+#[post("/submit")]
+async fn submit_for_review(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let kyc_nid = match own_kyc_nid(&connection, &user).await {
+        Ok(kyc_nid) => kyc_nid,
+        Err(response) => return response,
+    };
+
+    overview_response(KycService::submit_for_review(connection, user.claims.auth_usernid, kyc_nid).await)
+}
+
+#[get("/documents")]
+async fn get_documents(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let kyc_nid = match own_kyc_nid(&connection, &user).await {
+        Ok(kyc_nid) => kyc_nid,
+        Err(response) => return response,
+    };
+
+    let result: ActionResult<Vec<KycDocumentInfo>, _> = KycDocumentService::get_documents(connection, kyc_nid, None).await;
+
+    match result {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response => HttpResponse::Ok().json(response),
+    }
+}
+
+/// Ambil file pertama dari multipart, dibaca per chunk supaya upload raksasa langsung diputus
+async fn read_first_file(mut payload: Multipart) -> Result<Vec<u8>, HttpResponse> {
+    while let Some(field_res) = payload.next().await {
+        let mut field = field_res.map_err(|_| HttpResponse::BadRequest().json(json!({
+            "result": false,
+            "message": t("document.read_field_failed")
+        })))?;
+
+        if field.content_disposition().and_then(|cd| cd.get_filename()).is_none() {
+            continue;
+        }
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.map_err(|_| HttpResponse::BadRequest().json(json!({
+                "result": false,
+                "message": t("document.read_failed")
+            })))?;
+
+            if bytes.len() + data.len() > MAX_DOCUMENT_BYTES {
+                return Err(HttpResponse::PayloadTooLarge().json(json!({
+                    "result": false,
+                    "message": t_args("document.too_large", &[("max", &(MAX_DOCUMENT_BYTES / 1024 / 1024))])
+                })));
+            }
+            bytes.extend_from_slice(&data);
+        }
+
+        return Ok(bytes);
+    }
+
+    Err(HttpResponse::BadRequest().json(json!({
+        "result": false,
+        "message": t("document.no_file")
+    })))
+}
+
+#[put("/documents/{document_type}")]
+async fn upload_document(SessionUser(user): SessionUser, connection: web::Data<Pool<ConnectionManager>>, document_type: web::Path<String>, payload: Multipart) -> impl Responder {
+
+    let document_type = match KycDocumentType::from_slug(&document_type.into_inner()) {
+        Some(document_type) => document_type,
+        None => return HttpResponse::NotFound().json(json!({
+            "result": false,
+            "message": t("document.unknown_type")
+        })),
+    };
+
+    let kyc_nid = match own_kyc_nid(&connection, &user).await {
+        Ok(kyc_nid) => kyc_nid,
+        Err(response) => return response,
+    };
+
+    let bytes = match read_first_file(payload).await {
+        Ok(bytes) => bytes,
+        Err(response) => return response,
+    };
+
+    match KycDocumentService::upload(connection, user.claims.auth_usernid, kyc_nid, document_type, bytes).await {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response if response.result => HttpResponse::Ok().json(response),
+        response => HttpResponse::BadRequest().json(response),
+    }
+}
+
+#[get("/documents/file")]
+async fn download_document(connection: web::Data<Pool<ConnectionManager>>, query: web::Query<DocumentDownloadQuery>) -> impl Responder {
+
+    match KycDocumentService::download(connection, &query.token).await {
+        ActionResult { data: Some(file), .. } => HttpResponse::Ok()
+            .content_type(file.content_type)
+            // Dokumen identitas tidak boleh tersimpan di cache browser / proxy
+            .append_header(("Cache-Control", "private, no-store"))
+            .append_header(("Content-Disposition", format!("inline; filename=\"{}\"", file.file_name)))
+            .append_header(("X-Content-Type-Options", "nosniff"))
+            .body(file.data),
+        // Isi file tidak ikut di JSON, hanya pesan & error
+        ActionResult { message, error, .. } => {
+            let response = ActionResult::<(), String> { result: false, message, data: None, error };
+            if response.error.is_some() {
+                HttpResponse::InternalServerError().json(response)
+            } else {
+                HttpResponse::NotFound().json(response)
+            }
+        }
+    }
+}
+
+#[get("")]
+async fn get_review_queue(connection: web::Data<Pool<ConnectionManager>>) -> impl Responder {
+
+    let result: ActionResult<Vec<KycOverview>, _> = KycService::get_review_queue(connection).await;
+
+    match result {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response => HttpResponse::Ok().json(response),
+    }
+}
+
+#[get("/{kyc_nid}")]
+async fn get_review_detail(connection: web::Data<Pool<ConnectionManager>>, kyc_nid: web::Path<i32>) -> impl Responder {
+
+    match KycService::get_overview(connection, kyc_nid.into_inner()).await {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response if response.result => HttpResponse::Ok().json(response),
+        response => HttpResponse::NotFound().json(response),
+    }
+}
+
+#[get("/{kyc_nid}/history")]
+async fn get_review_history(connection: web::Data<Pool<ConnectionManager>>, kyc_nid: web::Path<i32>) -> impl Responder {
+
+    let result: ActionResult<Vec<KycHistory>, _> = KycService::get_history(connection, kyc_nid.into_inner()).await;
+
+    match result {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response => HttpResponse::Ok().json(response),
+    }
+}
+
+#[get("/{kyc_nid}/documents")]
+async fn get_review_documents(req: HttpRequest, user: AuthenticatedUser, connection: web::Data<Pool<ConnectionManager>>, kyc_nid: web::Path<i32>) -> impl Responder {
+
+    let base_url = GenericService::api_base_url(&req);
+    let result: ActionResult<Vec<KycDocumentInfo>, _> = KycDocumentService::get_documents(connection, kyc_nid.into_inner(), Some((user.claims.auth_usernid, &base_url))).await;
+
+    match result {
+        response if response.error.is_some() => HttpResponse::InternalServerError().json(response),
+        response => HttpResponse::Ok().json(response),
+    }
+}
+
+#[post("/{kyc_nid}/approve")]
+async fn approve_kyc(user: AuthenticatedUser, connection: web::Data<Pool<ConnectionManager>>, kyc_nid: web::Path<i32>, request: web::Json<KycApproveRequest>) -> impl Responder {
+
+    if let Err(err) = request.validate() {
+        return validation_failed(&err);
+    }
+
+    let reason = request.into_inner().reason.filter(|reason| !reason.trim().is_empty());
+    overview_response(KycService::review(connection, user.claims.auth_usernid, kyc_nid.into_inner(), KycAction::Approve, reason).await)
+}
+
+#[post("/{kyc_nid}/reject")]
+async fn reject_kyc(user: AuthenticatedUser, connection: web::Data<Pool<ConnectionManager>>, kyc_nid: web::Path<i32>, request: web::Json<KycRejectRequest>) -> impl Responder {
+
+    if let Err(err) = request.validate() {
+        return validation_failed(&err);
+    }
+
+    overview_response(KycService::review(connection, user.claims.auth_usernid, kyc_nid.into_inner(), KycAction::Reject, Some(request.into_inner().reason)).await)
+}
+
+#[post("/{kyc_nid}/revision")]
+async fn request_revision(user: AuthenticatedUser, connection: web::Data<Pool<ConnectionManager>>, kyc_nid: web::Path<i32>, request: web::Json<KycRevisionRequest>) -> impl Responder {
+
+    if let Err(err) = request.validate() {
+        return validation_failed(&err);
+    }
+
+    let request = request.into_inner();
+    let stage = match KycStage::from_slug(&request.stage) {
+        Some(stage) => stage,
+        None => return invalid_body(json!({ "stage": t("kyc.unknown_stage") })),
+    };
+
+    overview_response(KycService::review(connection, user.claims.auth_usernid, kyc_nid.into_inner(), KycAction::RequestRevision(stage), Some(request.reason)).await)
+}
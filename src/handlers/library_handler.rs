@@ -3,8 +3,9 @@ use bb8::Pool;
 use bb8_tiberius::ConnectionManager;
 use validator::Validate;
 use crate::{
-    contexts::model::{ActionResult, NewNoteRequest, Notes}, 
-    services::{generic_service::GenericService, library_service::LibraryService}
+    contexts::{auth_guard::AuthenticatedUser, i18n::t, model::{ActionResult, NewNoteRequest, Notes}}, 
+    services::{generic_service::GenericService, library_service::LibraryService},
+    utils::validation::validator::validation_failed
 };
 
 pub fn library_scope() -> Scope {
@@ -15,13 +16,9 @@ pub fn library_scope() -> Scope {
 }
 
 #[post("/create")]
-async fn create_libary(req: HttpRequest, connection: web::Data<Pool<ConnectionManager>>, request: web::Json<NewNoteRequest>) -> impl Responder {
+async fn create_libary(_user: AuthenticatedUser, req: HttpRequest, connection: web::Data<Pool<ConnectionManager>>, request: web::Json<NewNoteRequest>) -> impl Responder {
     if let Err(err) = request.validate() {
-        return HttpResponse::BadRequest().json(serde_json::json!({
-            "result": false,
-            "message": "Invalid request",
-            "error": err
-        }));
+        return validation_failed(&err);
     }
 
     // ambil ownership dan ubah
@@ -44,7 +41,7 @@ async fn create_libary(req: HttpRequest, connection: web::Data<Pool<ConnectionMa
 async fn get_libraries(connection: web::Data<Pool<ConnectionManager>>, category: web::Path<String>,) -> impl Responder {
 
     if category.is_empty() {
-        return HttpResponse::BadRequest().json(serde_json::json!({"result": false, "message": "Category is empty"}));
+        return HttpResponse::BadRequest().json(serde_json::json!({"result": false, "message": t("library.category_empty")}));
     }
 
     let result: ActionResult<Vec<serde_json::Value>, String> = LibraryService::get_libraries(connection, category.into_inner()).await;
@@ -58,7 +55,7 @@ async fn get_libraries(connection: web::Data<Pool<ConnectionManager>>, category:
 async fn get_library(connection: web::Data<Pool<ConnectionManager>>, slug: web::Path<String>,) -> impl Responder {
 
     if slug.is_empty() {
-        return HttpResponse::BadRequest().json(serde_json::json!({"result": false, "message": "Slug is empty"}));
+        return HttpResponse::BadRequest().json(serde_json::json!({"result": false, "message": t("library.slug_empty")}));
     }
 
     let result: ActionResult<Notes, String> = LibraryService::get_library(connection, slug.into_inner()).await;
@@ -9,7 +9,7 @@ use serde_json::json;
 use shuttle_runtime::SecretStore;
 use validator::Validate;
 
-use crate::{contexts::model::{ActionResult, ContactRequest, EmailRequest}, services::mail_service::MailService};
+use crate::{contexts::{auth_guard::AuthenticatedUser, i18n::t, model::{ActionResult, ContactRequest, EmailRequest}}, services::mail_service::MailService, utils::validation::validator::validation_failed};
 
 pub fn mail_scope() -> Scope {
     
@@ -24,11 +24,7 @@ pub fn mail_scope() -> Scope {
 async fn contact_form(req: HttpRequest, connection: web::Data<Pool<ConnectionManager>>, form: web::Json<EmailRequest>, secrets: web::Data<SecretStore>) -> impl Responder {
 
     if let Err(err) = form.validate() {
-        return HttpResponse::BadRequest().json(json!({
-            "result": false,
-            "message": "Form tidak valid",
-            "error": err
-        }));
+        return validation_failed(&err);
     }
 
     let mut sender: EmailRequest = form.clone().into();
@@ -49,15 +45,15 @@ async fn contact_form(req: HttpRequest, connection: web::Data<Pool<ConnectionMan
             }, secrets).await;
             if email_receiver.result {
                 result.result = true;
-                result.message = String::from("Email berhasil dikirim.");
+                result.message = t("mail.sent");
             } else {
                 result.result = false;
-                result.message = String::from("Email gagal dikirim.");
+                result.message = t("mail.send_failed");
                 result.error = Some(email_receiver.message);
             }
         } else {
             result.result = false;
-            result.message = String::from("Email gagal dikirim.");
+            result.message = t("mail.send_failed");
             result.error = Some(email_sender.message);
         }
     } else {
@@ -151,7 +147,7 @@ async fn preview_email_from() -> impl Responder {
 }
 
 #[post("/send-campaign")]
-async fn send_campaign(req: web::Json<ContactRequest>) -> impl Responder {
+async fn send_campaign(_user: AuthenticatedUser, req: web::Json<ContactRequest>) -> impl Responder {
     let smtp_username = ""; // Login dari Brevo
     let smtp_password = "";           // Ambil dari SMTP Brevo
     let smtp_server = "";
@@ -0,0 +1,73 @@
+use actix_web::{get, post, web, HttpResponse, Responder, Scope};
+use serde_json::json;
+use validator::ValidationError;
+
+use crate::{
+    contexts::{i18n::t, model::{ActionResult, BankInfo, ValidateFieldRequest, ValidatedField}},
+    utils::validation::validator::{bank_rule, normalize_bank_account, normalize_npwp, normalize_phone, parse_nik, validation_message, BANK_RULES}
+};
+
+/// Validasi per field untuk form frontend, aturan sama dengan `#[validate(custom(...))]` di model
+pub fn validation_scope() -> Scope {
+    web::scope("/validate")
+        .service(get_banks)
+        .service(validate_field)
+}
+
+#[get("/banks")]
+async fn get_banks() -> impl Responder {
+    let banks: Vec<BankInfo> = BANK_RULES.iter().map(|rule| BankInfo {
+        code: rule.code.to_string(),
+        name: rule.name.to_string(),
+        account_lengths: rule.account_lengths.to_vec(),
+    }).collect();
+
+    HttpResponse::Ok().json(ActionResult::<Vec<BankInfo>, String> {
+        result: true,
+        message: t("validation.banks"),
+        data: Some(banks),
+        error: None,
+    })
+}
+
+fn check_field(field: &str, request: &ValidateFieldRequest) -> Option<Result<(String, Option<serde_json::Value>), ValidationError>> {
+    let value = request.value.as_str();
+    let checked = match field {
+        "nik" => parse_nik(value).map(|info| (value.trim().to_string(), serde_json::to_value(info).ok())),
+        "npwp" => normalize_npwp(value).map(|npwp| (npwp, None)),
+        "phone" => normalize_phone(value).map(|phone| (phone, None)),
+        "bank_account" => {
+            let bank_code = request.bank_code.as_deref().unwrap_or("");
+            normalize_bank_account(bank_code, value).map(|account| {
+                let bank = bank_rule(bank_code).map(|rule| json!({ "bank_code": rule.code, "bank_name": rule.name }));
+                (account, bank)
+            })
+        }
+        _ => return None,
+    };
+    Some(checked)
+}
+
+#[post("/{field}")]
+async fn validate_field(field: web::Path<String>, request: web::Json<ValidateFieldRequest>) -> impl Responder {
+
+    let field = field.into_inner();
+    let mut result: ActionResult<ValidatedField, String> = ActionResult::default();
+
+    match check_field(&field, &request) {
+        Some(Ok((normalized, details))) => {
+            result.result = true;
+            result.message = t("validation.valid");
+            result.data = Some(ValidatedField { field, normalized, details });
+            HttpResponse::Ok().json(result)
+        }
+        Some(Err(err)) => {
+            result.message = validation_message(&field, &err);
+            HttpResponse::BadRequest().json(result)
+        }
+        None => {
+            result.message = t("validation.unknown_field");
+            HttpResponse::NotFound().json(result)
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use actix_cors::Cors;
+use actix_web::{http, middleware::from_fn, web::{self, route, ServiceConfig}};
+use contexts::{auth_guard::{require_auth, require_permission}, connection::{create_pool, DbPool}, csrf::{require_csrf, AllowedOrigins, CSRF_HEADER}, i18n::resolve_locale, jwt_session::init_keys, oidc::init_providers, storage::init_storage};
+use handlers::{
+    admin_handler::admin_scope, audit_handler::audit_scope, auth_handler::{auth_scope, jwks}, data_handler::data_scope, export_handler::export_scope, generic_handler::generic_scope, import_handler::import_scope, kyc_handler::kyc_scope, library_handler::library_scope, mail_handler::mail_scope, validation_handler::validation_scope
+};
+use services::{account_service::AccountService, generic_service::GenericService};
+use shuttle_runtime::SecretStore;
+use utils::api_doc::{health_check, ApiDoc};
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+pub mod contexts {
+    pub(crate) mod connection;
+    pub(crate) mod model;
+    pub(crate) mod crypto;
+    pub(crate) mod csrf;
+    pub(crate) mod password;
+    pub mod jwt_session;
+    pub(crate) mod totp;
+    pub(crate) mod avatar;
+    pub(crate) mod kyc;
+    pub(crate) mod i18n;
+    pub(crate) mod document;
+    pub(crate) mod storage;
+    pub(crate) mod query_builder;
+    pub(crate) mod query_filter;
+    pub(crate) mod sql_json;
+    pub mod oidc;
+    pub(crate) mod auth_guard;
+    pub(crate) mod socket;
+}
+
+mod services {
+    pub mod generic_service;
+    pub mod auth_service;
+    pub mod mail_service;
+    pub mod library_service;
+    pub mod data_service;
+    pub mod import_service;
+    pub mod export_service;
+    pub mod role_service;
+    pub mod mfa_service;
+    pub mod throttle_service;
+    pub mod session_service;
+    pub mod api_key_service;
+    pub mod profile_service;
+    pub mod kyc_service;
+    pub mod kyc_document_service;
+    pub mod audit_service;
+    pub mod account_service;
+}
+
+mod handlers {
+    pub mod generic_handler;
+    pub mod auth_handler;
+    pub mod mail_handler;
+    pub mod library_handler;
+    pub mod data_handler;
+    pub mod import_handler;
+    pub mod export_handler;
+    pub mod admin_handler;
+    pub mod kyc_handler;
+    pub mod validation_handler;
+    pub mod audit_handler;
+}
+
+mod utils {
+    pub mod validation;
+    pub mod api_doc;
+}
+
+/// ⚙️ Konfigurasi aplikasi (pool database, key JWT, route), dipakai binary Shuttle di `main.rs`
+pub async fn app_config(secrets: SecretStore) -> impl FnOnce(&mut ServiceConfig) + Send + Clone + 'static {
+    let db_server = secrets.get("DATABASE_SERVER").expect("secret was not found");
+    let db_user = secrets.get("DATABASE_USER").expect("secret was not found");
+    let db_password = secrets.get("DATABASE_PASSWORD").expect("secret was not found");
+
+    let db_pool: DbPool = create_pool(db_server.as_str(), db_user.as_str(), db_password.as_str(), "db12877").await.unwrap();
+
+    init_keys(&secrets).expect("failed to load JWT signing keys");
+    init_providers(&secrets).expect("failed to load OIDC providers");
+    init_storage(&secrets, db_pool.clone()).expect("failed to initialize document storage");
+    GenericService::init_trusted_proxies(&secrets).expect("failed to load trusted proxies");
+
+    // 🗑️ Hapus akun yang masa tenggang permintaan hapusnya sudah lewat
+    tokio::spawn(AccountService::run_deletion_worker(db_pool.clone()));
+
+    // Cookie sesi ikut terkirim cross-site (SameSite=None), jadi hanya origin frontend yang boleh membaca respon
+    let allowed_origins: Vec<String> = secrets.get("CORS_ALLOWED_ORIGINS")
+        .unwrap_or_else(|| "https://snakesystem.github.io,http://localhost:5173".to_string())
+        .split(',')
+        .map(|origin| origin.trim().trim_end_matches('/').to_string())
+        .filter(|origin| !origin.is_empty())
+        .collect();
+
+    let config = move |cfg: &mut ServiceConfig| {
+        let cors = allowed_origins.iter().fold(Cors::default(), |cors, origin| cors.allowed_origin(origin))
+            .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"])
+            .allowed_headers(vec![http::header::CONTENT_TYPE, http::header::AUTHORIZATION, http::header::ACCEPT_LANGUAGE])
+            .allowed_header(CSRF_HEADER)
+            .max_age(3600)
+            .supports_credentials();
+        
+        cfg
+        .service(health_check)
+        .service(jwks)
+        .service(
+            web::scope("/api/v1")
+            // 🛡️ Request yang mengubah data wajib membawa header X-CSRF-Token
+            .wrap(from_fn(require_csrf))
+            .wrap(cors)
+            // 🌐 Bahasa pesan dari preferensi user / Accept-Language
+            .wrap(from_fn(resolve_locale))
+            .service(generic_scope())
+            .service(auth_scope())
+            .service(mail_scope())
+            .service(library_scope())
+            .service(kyc_scope())
+            .service(validation_scope())
+            // 🔒 Seluruh route di scope ini wajib login
+            .service(data_scope().wrap(from_fn(require_auth)))
+            .service(import_scope().wrap(from_fn(require_auth)))
+            .service(export_scope().wrap(from_fn(require_auth)))
+            .service(admin_scope().wrap(require_permission("admin:roles")))
+            .service(audit_scope().wrap(require_permission("audit:read")))
+        )
+        .service(
+            SwaggerUi::new("/docs/{_:.*}")
+                .url("/api-docs/openapi.json", ApiDoc::openapi())
+        )
+        .app_data(web::Data::new(db_pool.clone()))
+        .app_data(web::Data::new(secrets.clone()))
+        .app_data(web::Data::new(AllowedOrigins(allowed_origins.clone())))
+        .app_data(web::JsonConfig::default().error_handler(GenericService::json_error_handler))
+        .default_service(route().to(GenericService::not_found));
+    };
+
+    config
+}
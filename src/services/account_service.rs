@@ -0,0 +1,489 @@
+use actix_web::{rt::time, web};
+use bb8::{Pool, PooledConnection};
+use bb8_tiberius::ConnectionManager;
+use chrono::{NaiveDateTime, Utc};
+use serde_json::{json, Map, Value};
+use tiberius::ToSql;
+
+use crate::contexts::{
+    connection::Transaction,
+    i18n::{t, t_args},
+    jwt_session::Claims,
+    model::{AccountDeletionStatus, ActionResult}
+};
+
+use super::{
+    audit_service::AuditEvent, data_service::DataService, kyc_document_service::KycDocumentService, throttle_service::ThrottleService
+};
+
+/// Masa tenggang sebelum akun benar-benar dihapus, selama itu user masih bisa login & membatalkan
+pub const DELETION_GRACE_DAYS: i32 = 14;
+/// Interval worker yang menghapus akun yang masa tenggangnya sudah habis
+const DELETION_WORKER_MINUTES: u64 = 60;
+
+/// Versi struktur file export, naikkan jika bagian bundle berubah
+const EXPORT_FORMAT: &str = "snakesystem-personal-data/1";
+/// Kolom `AuthUser` yang tidak ikut diexport (hash password & token rahasia)
+const SECRET_USER_COLUMNS: [&str; 5] = ["Password", "OTPGeneratedLink", "ResetPasswordKey", "TotpSecret", "TotpLastStep"];
+/// Scope `WEB_LoginAttempt` yang memakai email sebagai key
+const EMAIL_THROTTLE_SCOPES: [&str; 4] = ["login", "reset", "activation", "magic"];
+
+/// Akun yang dihapus, file dokumen KYC di luar backend `database` dihapus setelah commit
+struct PurgedAccount {
+    /// (StorageBackend, StorageKey / ThumbnailKey)
+    files: Vec<(String, String)>,
+}
+
+/// 🗑️ Export data pribadi & hapus akun (hak untuk dilupakan)
+pub struct AccountService;
+
+impl AccountService {
+    async fn query_rows(conn: &mut PooledConnection<'_, ConnectionManager>, query: &str, params: &[&dyn ToSql]) -> Result<Vec<Value>, tiberius::error::Error> {
+        let rows = conn.query(query, params).await?.into_first_result().await?;
+        Ok(rows.iter().map(DataService::row_to_json).collect())
+    }
+
+    /// Isi bundle export, `None` jika user tidak ditemukan
+    async fn collect_export(conn: &mut PooledConnection<'_, ConnectionManager>, auth_usernid: i32) -> Result<Option<Value>, tiberius::error::Error> {
+        let mut account = match Self::query_rows(conn, "SELECT * FROM AuthUser WHERE AuthUserNID = @P1", &[&auth_usernid]).await?.pop() {
+            Some(Value::Object(account)) => account,
+            _ => return Ok(None),
+        };
+        for column in SECRET_USER_COLUMNS {
+            account.remove(column);
+        }
+
+        let email = account.get("Email").and_then(Value::as_str).unwrap_or("").to_string();
+        let kyc_nid = account.get("WebCIFNID").and_then(Value::as_i64).map(|nid| nid as i32);
+
+        let mut kyc_data = Self::query_rows(conn, "SELECT Stage, Data, UpdatedTime FROM UserKycData WHERE KycNID = @P1 ORDER BY Stage", &[&kyc_nid]).await?;
+        // Isi tiap tahap disimpan sebagai teks JSON, export sebagai object
+        for row in kyc_data.iter_mut() {
+            if let Some(data) = row.get("Data").and_then(Value::as_str).and_then(|data| serde_json::from_str::<Value>(data).ok()) {
+                row["Data"] = data;
+            }
+        }
+
+        let mut bundle = Map::new();
+        bundle.insert("format".into(), json!(EXPORT_FORMAT));
+        bundle.insert("generated_time".into(), json!(Utc::now()));
+        bundle.insert("account".into(), Value::Object(account));
+        bundle.insert("roles".into(), json!(Self::query_rows(conn,
+            "SELECT r.RoleName FROM AuthUserRole ur JOIN Roles r ON r.RoleNID = ur.RoleNID WHERE ur.AuthUserNID = @P1",
+            &[&auth_usernid]).await?));
+        bundle.insert("external_logins".into(), json!(Self::query_rows(conn,
+            "SELECT Provider, Subject, Email, CreatedTime FROM AuthUserExternalLogin WHERE AuthUserNID = @P1",
+            &[&auth_usernid]).await?));
+        // Tanpa kolom Cookies (token sesi) & hash refresh token / API key
+        bundle.insert("sessions".into(), json!(Self::query_rows(conn,
+            r#"SELECT SessionID, DeviceName, AppName, AppComputerName, AppIPAddress, UserAgent, CreatedTime, LastSeen
+            FROM WEB_Cookies WHERE AuthUserNID = @P1 ORDER BY LastSeen DESC"#,
+            &[&auth_usernid]).await?));
+        bundle.insert("api_keys".into(), json!(Self::query_rows(conn,
+            r#"SELECT Name, KeyPrefix, Scopes, ExpiredTime, LastUsedTime, LastUsedIP, IsRevoked, CreatedTime
+            FROM WEB_ApiKey WHERE AuthUserNID = @P1"#,
+            &[&auth_usernid]).await?));
+        bundle.insert("kyc".into(), Self::query_rows(conn, "SELECT * FROM UserKyc WHERE AutoNID = @P1", &[&kyc_nid]).await?
+            .pop().unwrap_or(Value::Null));
+        bundle.insert("kyc_data".into(), json!(kyc_data));
+        bundle.insert("kyc_history".into(), json!(Self::query_rows(conn,
+            r#"SELECT Action, FromStage, ToStage, Status, Reason, CreatedTime
+            FROM UserKycHistory WHERE KycNID = @P1 ORDER BY HistoryNID"#,
+            &[&kyc_nid]).await?));
+        // Hanya metadata, file bisa diunduh lewat endpoint dokumen KYC
+        bundle.insert("kyc_documents".into(), json!(Self::query_rows(conn,
+            r#"SELECT DocumentNID, DocumentType, ContentType, FileSize, Width, Height, Sha256, UploadedTime, IsDeleted, DeletedTime
+            FROM KycDocument WHERE KycNID = @P1 ORDER BY DocumentNID"#,
+            &[&kyc_nid]).await?));
+        bundle.insert("table_request".into(), json!(Self::query_rows(conn,
+            "SELECT * FROM TableRequest WHERE WebCIFNID = @P1",
+            &[&kyc_nid]).await?));
+        bundle.insert("emails".into(), json!(Self::query_rows(conn,
+            "SELECT * FROM EmailHistory WHERE Receiver = @P1",
+            &[&email]).await?));
+        bundle.insert("audit_events".into(), json!(Self::query_rows(conn,
+            r#"SELECT EventType, Success, IPAddress, UserAgent, Target, Details, CreatedTime
+            FROM AuditLog WHERE ActorNID = @P1 OR Target = @P2 ORDER BY AuditLogNID"#,
+            &[&auth_usernid, &email]).await?));
+        bundle.insert("deletion_requests".into(), json!(Self::query_rows(conn,
+            "SELECT Reason, RequestedTime, ScheduledTime, CancelledTime FROM AuthUserDeletion WHERE AuthUserNID = @P1",
+            &[&auth_usernid]).await?));
+
+        Ok(Some(Value::Object(bundle)))
+    }
+
+    /// Seluruh data pribadi user dalam satu dokumen JSON
+    pub async fn export_data(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32) -> ActionResult<Value, String> {
+        let mut result: ActionResult<Value, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => match Self::collect_export(&mut conn, auth_usernid).await {
+                Ok(Some(bundle)) => {
+                    result.result = true;
+                    result.message = t("common.data_retrieved");
+                    result.data = Some(bundle);
+                }
+                Ok(None) => {
+                    result.message = t("auth.user_not_found");
+                }
+                Err(err) => {
+                    result.message = t("common.query_failed");
+                    result.error = Some(format!("Failed to export personal data: {:?}", err));
+                }
+            },
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    async fn read_pending(conn: &mut PooledConnection<'_, ConnectionManager>, auth_usernid: i32) -> Result<Option<AccountDeletionStatus>, tiberius::error::Error> {
+        let row = conn.query(
+            r#"SELECT DeletionNID, RequestedTime, ScheduledTime FROM AuthUserDeletion
+            WHERE AuthUserNID = @P1 AND CancelledTime IS NULL AND CompletedTime IS NULL"#,
+            &[&auth_usernid],
+        ).await?.into_row().await?;
+
+        Ok(row.map(|row| AccountDeletionStatus {
+            deletion_nid: row.get("DeletionNID").unwrap_or(0),
+            requested_time: row.get::<NaiveDateTime, _>("RequestedTime").map(|dt| dt.and_utc()),
+            scheduled_time: row.get::<NaiveDateTime, _>("ScheduledTime").map(|dt| dt.and_utc()),
+        }))
+    }
+
+    fn scheduled_date(status: &AccountDeletionStatus) -> String {
+        status.scheduled_time.map(|dt| dt.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default()
+    }
+
+    /// Permintaan hapus akun yang masih menunggu masa tenggang
+    pub async fn deletion_status(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32) -> ActionResult<AccountDeletionStatus, String> {
+        let mut result: ActionResult<AccountDeletionStatus, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => match Self::read_pending(&mut conn, auth_usernid).await {
+                Ok(Some(status)) => {
+                    result.result = true;
+                    result.message = t_args("account.deletion_pending", &[("date", &Self::scheduled_date(&status))]);
+                    result.data = Some(status);
+                }
+                Ok(None) => {
+                    result.message = t("account.deletion_none");
+                }
+                Err(err) => {
+                    result.error = Some(format!("Query execution failed: {:?}", err));
+                }
+            },
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    /// Jadwalkan hapus akun setelah `DELETION_GRACE_DAYS` hari
+    pub async fn request_deletion(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, reason: Option<String>) -> ActionResult<AccountDeletionStatus, String> {
+        let mut result: ActionResult<AccountDeletionStatus, String> = ActionResult::default();
+        let reason = reason.map(|reason| reason.trim().to_string()).filter(|reason| !reason.is_empty());
+
+        let mut conn = match connection.get().await {
+            Ok(conn) => conn,
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+                return result;
+            }
+        };
+
+        match Self::read_pending(&mut conn, auth_usernid).await {
+            Ok(Some(status)) => {
+                result.message = t_args("account.deletion_pending", &[("date", &Self::scheduled_date(&status))]);
+                result.data = Some(status);
+                return result;
+            }
+            Ok(None) => {}
+            Err(err) => {
+                result.error = Some(format!("Query execution failed: {:?}", err));
+                return result;
+            }
+        }
+
+        // Unique index UX_AuthUserDeletion_Pending menolak permintaan ganda yang balapan
+        if let Err(err) = conn.execute(
+            r#"INSERT INTO AuthUserDeletion (AuthUserNID, RequestedBy, Reason, RequestedTime, ScheduledTime)
+            VALUES (@P1, @P1, @P2, GETDATE(), DATEADD(DAY, @P3, GETDATE()))"#,
+            &[&auth_usernid, &reason, &DELETION_GRACE_DAYS],
+        ).await {
+            result.error = Some(format!("Failed to insert AuthUserDeletion: {:?}", err));
+            return result;
+        }
+
+        match Self::read_pending(&mut conn, auth_usernid).await {
+            Ok(Some(status)) => {
+                result.result = true;
+                result.message = t_args("account.deletion_requested", &[("date", &Self::scheduled_date(&status))]);
+                result.data = Some(status);
+            }
+            Ok(None) => {
+                result.error = Some("Deletion request not found after insert".into());
+            }
+            Err(err) => {
+                result.error = Some(format!("Query execution failed: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    pub async fn cancel_deletion(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32) -> ActionResult<(), String> {
+        let mut result: ActionResult<(), String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.execute(
+                    r#"UPDATE AuthUserDeletion SET CancelledTime = GETDATE()
+                    WHERE AuthUserNID = @P1 AND CancelledTime IS NULL AND CompletedTime IS NULL"#,
+                    &[&auth_usernid],
+                ).await {
+                    Ok(res) if res.rows_affected().iter().sum::<u64>() > 0 => {
+                        result.result = true;
+                        result.message = t("account.deletion_cancelled");
+                    }
+                    Ok(_) => {
+                        result.message = t("account.deletion_none");
+                    }
+                    Err(err) => {
+                        result.error = Some(format!("Failed to cancel deletion: {:?}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    /// Hapus seluruh data user dalam transaksi yang sedang berjalan, `None` jika user tidak ditemukan.
+    /// AuditLog tidak dihapus, hanya data pribadinya yang dikosongkan
+    async fn purge(
+        conn: &mut PooledConnection<'_, ConnectionManager>,
+        auth_usernid: i32,
+        actor: Option<&Claims>,
+        ip_address: &str,
+        user_agent: &str,
+        mode: &str,
+    ) -> Result<Option<PurgedAccount>, tiberius::error::Error> {
+        // 🔒 Kunci baris user supaya tidak balapan dengan login / update profil
+        let (email, kyc_nid) = match conn.query(
+            "SELECT Email, WebCIFNID FROM AuthUser WITH (UPDLOCK, ROWLOCK) WHERE AuthUserNID = @P1",
+            &[&auth_usernid],
+        ).await?.into_row().await? {
+            Some(row) => (
+                row.get::<&str, _>("Email").unwrap_or("").to_string(),
+                row.get::<i32, _>("WebCIFNID"),
+            ),
+            None => return Ok(None),
+        };
+
+        let files: Vec<(String, String)> = conn.query(
+            "SELECT StorageBackend, StorageKey, ThumbnailKey FROM KycDocument WHERE KycNID = @P1 AND IsDeleted = 0",
+            &[&kyc_nid],
+        ).await?.into_first_result().await?
+            .iter()
+            .flat_map(|row| {
+                let backend = row.get::<&str, _>("StorageBackend").unwrap_or("").to_string();
+                [row.get::<&str, _>("StorageKey"), row.get::<&str, _>("ThumbnailKey")]
+                    .into_iter()
+                    .flatten()
+                    .map(move |key| (backend.clone(), key.to_string()))
+            })
+            .filter(|(backend, _)| backend != "database")
+            .collect();
+
+        let throttle_keys: Vec<String> = EMAIL_THROTTLE_SCOPES.iter().map(|scope| ThrottleService::key(scope, "email", &email)).collect();
+
+        conn.execute(
+            r#"DELETE FROM WEB_StoredFile WHERE StorageKey IN (
+                SELECT StorageKey FROM KycDocument WHERE KycNID = @P2 AND StorageBackend = 'database'
+                UNION SELECT ThumbnailKey FROM KycDocument WHERE KycNID = @P2 AND StorageBackend = 'database'
+            );
+            DELETE FROM KycDocument WHERE KycNID = @P2;
+            DELETE FROM UserKycData WHERE KycNID = @P2;
+            DELETE FROM UserKycHistory WHERE KycNID = @P2;
+            DELETE FROM TableRequest WHERE WebCIFNID = @P2;
+            DELETE FROM UserKyc WHERE AutoNID = @P2;
+            DELETE FROM WEB_Cookies WHERE AuthUserNID = @P1;
+            DELETE FROM WEB_RefreshToken WHERE AuthUserNID = @P1;
+            DELETE FROM WEB_MagicLink WHERE AuthUserNID = @P1;
+            DELETE FROM WEB_ApiKey WHERE AuthUserNID = @P1;
+            DELETE FROM AuthUserRole WHERE AuthUserNID = @P1;
+            DELETE FROM AuthUserRecoveryCode WHERE AuthUserNID = @P1;
+            DELETE FROM AuthUserAvatar WHERE AuthUserNID = @P1;
+            DELETE FROM AuthUserExternalLogin WHERE AuthUserNID = @P1;
+            DELETE FROM EmailHistory WHERE Receiver = @P3;
+            DELETE FROM WEB_LoginAttempt WHERE AttemptKey IN (@P4, @P5, @P6, @P7);
+            DELETE FROM AuthUser WHERE AuthUserNID = @P1;"#,
+            &[&auth_usernid, &kyc_nid, &email, &throttle_keys[0], &throttle_keys[1], &throttle_keys[2], &throttle_keys[3]],
+        ).await?;
+
+        // 📜 Trigger TR_AuditLog_AppendOnly hanya mengizinkan UPDATE ini selama session context aktif.
+        // IP & user agent dikosongkan untuk event milik user, termasuk event tanpa login yang menargetkan email-nya
+        conn.execute(
+            r#"EXEC sp_set_session_context @key = N'audit_anonymize', @value = 1;
+            BEGIN TRY
+                UPDATE AuditLog SET
+                    ActorEmail = CASE WHEN ActorNID = @P1 THEN NULL ELSE ActorEmail END,
+                    IPAddress = CASE WHEN ActorNID = @P1 OR ActorNID IS NULL THEN '' ELSE IPAddress END,
+                    UserAgent = CASE WHEN ActorNID = @P1 OR ActorNID IS NULL THEN '' ELSE UserAgent END,
+                    Target = CASE WHEN Target = @P2 THEN NULL ELSE Target END
+                WHERE ActorNID = @P1 OR Target = @P2;
+                EXEC sp_set_session_context @key = N'audit_anonymize', @value = NULL;
+            END TRY
+            BEGIN CATCH
+                EXEC sp_set_session_context @key = N'audit_anonymize', @value = NULL;
+                THROW;
+            END CATCH"#,
+            &[&auth_usernid, &email],
+        ).await?;
+
+        let actor_nid = actor.map(|claims| claims.auth_usernid).unwrap_or(auth_usernid);
+        let completed = conn.execute(
+            r#"UPDATE AuthUserDeletion SET CompletedTime = GETDATE(), Reason = NULL
+            WHERE AuthUserNID = @P1 AND CancelledTime IS NULL AND CompletedTime IS NULL"#,
+            &[&auth_usernid],
+        ).await?;
+        // Hapus langsung oleh admin tanpa permintaan dari user
+        if completed.total() == 0 {
+            conn.execute(
+                r#"INSERT INTO AuthUserDeletion (AuthUserNID, RequestedBy, RequestedTime, ScheduledTime, CompletedTime)
+                VALUES (@P1, @P2, GETDATE(), GETDATE(), GETDATE())"#,
+                &[&auth_usernid, &actor_nid],
+            ).await?;
+        }
+
+        // Dicatat di transaksi yang sama supaya penghapusan tidak pernah terjadi tanpa jejak audit
+        let target = format!("user:{}", auth_usernid);
+        let details = json!({ "mode": mode }).to_string();
+        conn.execute(
+            r#"INSERT INTO AuditLog (EventType, Success, ActorNID, ActorEmail, IPAddress, UserAgent, Target, Details)
+            VALUES (@P1, 1, @P2, @P3, @P4, @P5, @P6, @P7)"#,
+            &[
+                &AuditEvent::AccountDelete.as_str(),
+                &actor.map(|claims| claims.auth_usernid),
+                &actor.map(|claims| claims.email.as_str()),
+                &ip_address,
+                &user_agent,
+                &target,
+                &details,
+            ],
+        ).await?;
+
+        Ok(Some(PurgedAccount { files }))
+    }
+
+    /// Hapus akun sekarang juga. `actor` = admin untuk penghapusan langsung, `None` untuk worker
+    pub async fn delete_account(
+        connection: web::Data<Pool<ConnectionManager>>,
+        auth_usernid: i32,
+        actor: Option<&Claims>,
+        ip_address: &str,
+        user_agent: &str,
+    ) -> ActionResult<(), String> {
+        let mut result: ActionResult<(), String> = ActionResult::default();
+        let mode = if actor.is_some() { "admin" } else { "scheduled" };
+
+        let trans = match Transaction::begin(&connection).await {
+            Ok(trans) => trans,
+            Err(err) => {
+                result.error = Some(format!("Failed to start transaction: {:?}", err));
+                return result;
+            }
+        };
+
+        let purged = match trans.conn.lock().await.as_mut() {
+            Some(conn) => Self::purge(conn, auth_usernid, actor, ip_address, user_agent, mode).await
+                .map_err(|err| format!("Failed to delete account: {:?}", err)),
+            None => Err("Failed to get database connection".to_string()),
+        };
+
+        let purged = match purged {
+            Ok(Some(purged)) => purged,
+            Ok(None) => {
+                if let Err(err) = trans.rollback().await {
+                    eprintln!("❌ Failed to rollback account deletion: {:?}", err);
+                }
+                result.message = t("auth.user_not_found");
+                return result;
+            }
+            Err(err) => {
+                if let Err(rollback_err) = trans.rollback().await {
+                    eprintln!("❌ Failed to rollback account deletion: {:?}", rollback_err);
+                }
+                result.error = Some(err);
+                return result;
+            }
+        };
+
+        // 🔵 Commit transaksi
+        if let Err(err) = trans.commit().await {
+            result.error = Some(format!("Failed to commit transaction: {:?}", err));
+            return result;
+        }
+
+        // File di storage lokal tidak ikut transaksi, gagal hapus cukup di-log
+        for (backend, key) in purged.files {
+            KycDocumentService::delete_files(&backend, &[key]).await;
+        }
+
+        result.result = true;
+        result.message = t("account.deleted");
+        result
+    }
+
+    /// Hapus semua akun yang masa tenggangnya sudah lewat, return jumlah akun yang terhapus
+    pub async fn process_due_deletions(connection: web::Data<Pool<ConnectionManager>>) -> usize {
+        let due: Vec<i32> = match connection.get().await {
+            Ok(mut conn) => match Self::query_rows(&mut conn,
+                r#"SELECT AuthUserNID FROM AuthUserDeletion
+                WHERE CancelledTime IS NULL AND CompletedTime IS NULL AND ScheduledTime <= GETDATE()"#,
+                &[]).await {
+                Ok(rows) => rows.iter().filter_map(|row| row["AuthUserNID"].as_i64()).map(|nid| nid as i32).collect(),
+                Err(err) => {
+                    eprintln!("❌ Failed to read due account deletions: {:?}", err);
+                    return 0;
+                }
+            },
+            Err(err) => {
+                eprintln!("❌ Failed to read due account deletions: {:?}", err);
+                return 0;
+            }
+        };
+
+        let mut deleted = 0;
+        for auth_usernid in due {
+            match Self::delete_account(connection.clone(), auth_usernid, None, "", "").await {
+                response if response.result => deleted += 1,
+                response => eprintln!("❌ Failed to delete account {}: {:?}", auth_usernid, response.error.unwrap_or(response.message)),
+            }
+        }
+
+        deleted
+    }
+
+    /// Dijalankan sekali saat startup, cek permintaan yang jatuh tempo setiap `DELETION_WORKER_MINUTES`
+    pub async fn run_deletion_worker(pool: Pool<ConnectionManager>) {
+        let connection = web::Data::new(pool);
+        let mut interval = time::interval(std::time::Duration::from_secs(DELETION_WORKER_MINUTES * 60));
+
+        loop {
+            interval.tick().await;
+            let deleted = Self::process_due_deletions(connection.clone()).await;
+            if deleted > 0 {
+                println!("🗑️ Deleted {} account(s) after grace period", deleted);
+            }
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use actix_web::{web, HttpRequest};
+use bb8::Pool;
+use bb8_tiberius::ConnectionManager;
+use chrono::{Duration, NaiveDateTime, Utc};
+
+use crate::contexts::{crypto::hash_token, i18n::{t, t_args}, jwt_session::Claims, model::{ActionResult, ApiKeyInfo, CreateApiKeyRequest, CreatedApiKey}};
+
+use super::{auth_service::AuthService, generic_service::GenericService};
+
+/// Personal API key untuk akses machine-to-machine (`Authorization: ApiKey ssk_...`)
+pub struct ApiKeyService;
+
+pub const API_KEY_PREFIX: &str = "ssk_";
+const API_KEY_APP_NAME: &str = "api-key";
+const DEFAULT_EXPIRY_DAYS: i64 = 90;
+const MAX_EXPIRY_DAYS: i64 = 365;
+
+impl ApiKeyService {
+    fn api_key_from_row(row: &tiberius::Row) -> ApiKeyInfo {
+        ApiKeyInfo {
+            api_key_nid: row.get("ApiKeyNID").unwrap_or(0),
+            name: row.get::<&str, _>("Name").unwrap_or_default().to_string(),
+            prefix: row.get::<&str, _>("KeyPrefix").unwrap_or_default().to_string(),
+            scopes: Self::parse_scopes(row.get::<&str, _>("Scopes").unwrap_or_default()),
+            expired_time: row.get::<NaiveDateTime, _>("ExpiredTime").map(|dt| dt.and_utc()),
+            last_used_time: row.get::<NaiveDateTime, _>("LastUsedTime").map(|dt| dt.and_utc()),
+            last_used_ip: row.get::<&str, _>("LastUsedIP").map(|s| s.to_string()),
+            created_time: row.get::<NaiveDateTime, _>("CreatedTime").map(|dt| dt.and_utc()),
+        }
+    }
+
+    fn parse_scopes(scopes: &str) -> Vec<String> {
+        scopes.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+    }
+
+    /// Key hanya dikembalikan sekali di sini, yang disimpan cuma hash-nya
+    pub async fn create_api_key(connection: web::Data<Pool<ConnectionManager>>, user: &Claims, request: CreateApiKeyRequest) -> ActionResult<CreatedApiKey, String> {
+        let mut result: ActionResult<CreatedApiKey, String> = ActionResult::default();
+
+        // Scope tidak boleh melebihi permission user sendiri
+        let mut scopes: Vec<String> = request.scopes.iter().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+        scopes.sort();
+        scopes.dedup();
+        if scopes.is_empty() {
+            result.message = t("api_key.scope_required");
+            return result;
+        }
+        if let Some(scope) = scopes.iter().find(|scope| !user.has_permission(scope)) {
+            result.message = t_args("api_key.scope_forbidden", &[("scope", scope)]);
+            return result;
+        }
+
+        let expires_in_days = request.expires_in_days.unwrap_or(DEFAULT_EXPIRY_DAYS);
+        if !(1..=MAX_EXPIRY_DAYS).contains(&expires_in_days) {
+            result.message = t_args("api_key.expiry_range", &[("max", &MAX_EXPIRY_DAYS)]);
+            return result;
+        }
+        let expired_time = Utc::now() + Duration::days(expires_in_days);
+
+        let prefix = format!("{}{}", API_KEY_PREFIX, GenericService::random_string(8));
+        let key = format!("{}_{}", prefix, GenericService::random_string(40));
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query(
+                    r#"INSERT INTO WEB_ApiKey (AuthUserNID, Name, KeyPrefix, KeyHash, Scopes, ExpiredTime, IsRevoked, CreatedTime)
+                    OUTPUT INSERTED.ApiKeyNID
+                    VALUES (@P1, @P2, @P3, @P4, @P5, @P6, 0, GETDATE())"#,
+                    &[&user.auth_usernid, &request.name.trim(), &prefix, &hash_token(&key), &scopes.join(","), &expired_time.naive_utc()],
+                ).await {
+                    Ok(rows) => {
+                        let api_key_nid = match rows.into_row().await {
+                            Ok(Some(row)) => row.get::<i32, _>("ApiKeyNID").unwrap_or(0),
+                            _ => 0,
+                        };
+
+                        result.result = true;
+                        result.message = t("api_key.created");
+                        result.data = Some(CreatedApiKey {
+                            api_key_nid,
+                            name: request.name.trim().to_string(),
+                            key,
+                            scopes,
+                            expired_time,
+                        });
+                    }
+                    Err(err) => {
+                        result.error = Some(format!("Failed to create API key: {:?}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    pub async fn get_api_keys(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32) -> ActionResult<Vec<ApiKeyInfo>, String> {
+        let mut result: ActionResult<Vec<ApiKeyInfo>, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query(
+                    r#"SELECT ApiKeyNID, Name, KeyPrefix, Scopes, ExpiredTime, LastUsedTime, LastUsedIP, CreatedTime
+                    FROM WEB_ApiKey WHERE AuthUserNID = @P1 AND IsRevoked = 0 ORDER BY CreatedTime DESC"#,
+                    &[&auth_usernid],
+                ).await {
+                    Ok(rows) => match rows.into_first_result().await {
+                        Ok(rows) => {
+                            result.result = true;
+                            result.message = t("api_key.list");
+                            result.data = Some(rows.iter().map(Self::api_key_from_row).collect());
+                        }
+                        Err(err) => {
+                            result.error = Some(format!("Failed to read API keys: {:?}", err));
+                        }
+                    },
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    pub async fn revoke_api_key(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, api_key_nid: i32) -> ActionResult<(), String> {
+        let mut result: ActionResult<(), String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.execute(
+                    "UPDATE WEB_ApiKey SET IsRevoked = 1 WHERE ApiKeyNID = @P1 AND AuthUserNID = @P2 AND IsRevoked = 0",
+                    &[&api_key_nid, &auth_usernid],
+                ).await {
+                    Ok(res) if res.rows_affected().iter().sum::<u64>() > 0 => {
+                        result.result = true;
+                        result.message = t("api_key.revoked");
+                    }
+                    Ok(_) => result.message = t("api_key.not_found"),
+                    Err(err) => result.error = Some(format!("Query execution failed: {:?}", err)),
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    /// Dipakai auth layer: key valid → claims pemilik key dengan permission dibatasi scope key
+    pub async fn authenticate(connection: web::Data<Pool<ConnectionManager>>, key: &str, req: &HttpRequest) -> ActionResult<Claims, String> {
+        let mut result: ActionResult<Claims, String> = ActionResult::default();
+
+        if !key.starts_with(API_KEY_PREFIX) {
+            result.message = t("api_key.invalid");
+            return result;
+        }
+
+        let (api_key_nid, auth_usernid, scopes) = match connection.clone().get().await {
+            Ok(mut conn) => {
+                let row = match conn.query(
+                    r#"SELECT ApiKeyNID, AuthUserNID, Scopes FROM WEB_ApiKey
+                    WHERE KeyHash = @P1 AND IsRevoked = 0 AND (ExpiredTime IS NULL OR ExpiredTime > GETDATE())"#,
+                    &[&hash_token(key)],
+                ).await {
+                    Ok(rows) => rows.into_row().await.ok().flatten(),
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                        return result;
+                    }
+                };
+
+                let row = match row {
+                    Some(row) => row,
+                    None => {
+                        result.message = t("api_key.invalid_or_expired");
+                        return result;
+                    }
+                };
+                let api_key_nid: i32 = row.get("ApiKeyNID").unwrap_or(0);
+
+                // Last used cukup presisi per menit
+                let _ = conn.execute(
+                    r#"UPDATE WEB_ApiKey SET LastUsedTime = GETDATE(), LastUsedIP = @P2
+                    WHERE ApiKeyNID = @P1 AND (LastUsedTime IS NULL OR LastUsedTime < DATEADD(MINUTE, -1, GETDATE()))"#,
+                    &[&api_key_nid, &GenericService::get_ip_address(req)],
+                ).await;
+
+                (
+                    api_key_nid,
+                    row.get::<i32, _>("AuthUserNID").unwrap_or(0),
+                    Self::parse_scopes(row.get::<&str, _>("Scopes").unwrap_or_default()),
+                )
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+                return result;
+            }
+        };
+
+        let mut loaded = AuthService::load_claims(connection, auth_usernid, req, API_KEY_APP_NAME).await;
+        if let Some(claims) = loaded.data.as_mut() {
+            // Permission yang sudah dicabut dari user ikut hilang dari key-nya
+            claims.permissions.retain(|permission| scopes.contains(permission));
+            claims.roles.clear();
+            claims.session_id = format!("apikey:{}", api_key_nid);
+        }
+
+        loaded
+    }
+}
@@ -0,0 +1,140 @@
+use actix_web::{web, HttpRequest};
+use bb8::Pool;
+use bb8_tiberius::ConnectionManager;
+use serde_json::Value;
+
+use crate::contexts::{jwt_session::Claims, model::{AuditLogParams, ResultList, TableDataParams}};
+
+use super::{data_service::DataService, export_service::ExportService, generic_service::GenericService};
+
+/// 📜 Audit log event keamanan, tabel `AuditLog` hanya di-INSERT (UPDATE/DELETE ditolak trigger)
+pub struct AuditService;
+
+/// Kolom `AuditLog` yang boleh dipakai untuk filter, sort & export CSV
+pub const AUDIT_COLUMNS: [&str; 10] = [
+    "AuditLogNID", "EventType", "Success", "ActorNID", "ActorEmail", "IPAddress", "UserAgent", "Target", "Details", "CreatedTime",
+];
+
+/// Batas baris sekali export CSV, persempit dengan filter `CreatedTime` jika lebih
+pub const MAX_AUDIT_EXPORT_ROWS: i32 = 10_000;
+const DEFAULT_PAGE_SIZE: i32 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditEvent {
+    LoginSuccess,
+    LoginFailed,
+    Logout,
+    Register,
+    Activation,
+    MagicLinkRequest,
+    PasswordResetRequest,
+    PasswordReset,
+    PasswordChange,
+    RoleAssign,
+    RoleRevoke,
+    DataClear,
+    Import,
+    Export,
+    DataExport,
+    DeletionRequest,
+    DeletionCancel,
+    AccountDelete,
+}
+
+impl AuditEvent {
+    /// Nilai kolom `AuditLog.EventType`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::LoginSuccess => "login_success",
+            Self::LoginFailed => "login_failed",
+            Self::Logout => "logout",
+            Self::Register => "register",
+            Self::Activation => "activation",
+            Self::MagicLinkRequest => "magic_link_request",
+            Self::PasswordResetRequest => "password_reset_request",
+            Self::PasswordReset => "password_reset",
+            Self::PasswordChange => "password_change",
+            Self::RoleAssign => "role_assign",
+            Self::RoleRevoke => "role_revoke",
+            Self::DataClear => "data_clear",
+            Self::Import => "import",
+            Self::Export => "export",
+            Self::DataExport => "personal_data_export",
+            Self::DeletionRequest => "account_deletion_request",
+            Self::DeletionCancel => "account_deletion_cancel",
+            Self::AccountDelete => "account_delete",
+        }
+    }
+}
+
+impl AuditService {
+    /// Catat satu event. Gagal menulis audit tidak menggagalkan request, cukup di-log
+    pub async fn record(
+        connection: &web::Data<Pool<ConnectionManager>>,
+        req: &HttpRequest,
+        event: AuditEvent,
+        actor: Option<&Claims>,
+        target: Option<&str>,
+        success: bool,
+        details: Value,
+    ) {
+        let details = (!details.is_null()).then(|| details.to_string());
+        let actor_nid = actor.map(|claims| claims.auth_usernid);
+        let actor_email = actor.map(|claims| claims.email.as_str());
+
+        match connection.get().await {
+            Ok(mut conn) => {
+                if let Err(err) = conn.execute(
+                    r#"INSERT INTO AuditLog (EventType, Success, ActorNID, ActorEmail, IPAddress, UserAgent, Target, Details)
+                    VALUES (@P1, @P2, @P3, @P4, @P5, @P6, @P7, @P8)"#,
+                    &[
+                        &event.as_str(),
+                        &success,
+                        &actor_nid,
+                        &actor_email,
+                        &GenericService::get_ip_address(req),
+                        &GenericService::get_user_agent(req),
+                        &target,
+                        &details,
+                    ],
+                ).await {
+                    eprintln!("❌ Failed to write audit log '{}': {:?}", event.as_str(), err);
+                }
+            }
+            Err(err) => eprintln!("❌ Failed to write audit log '{}': {:?}", event.as_str(), err),
+        }
+    }
+
+    /// Ubah query audit ke `TableDataParams`, kolom di luar `AUDIT_COLUMNS` ditolak `DataService::get_table_data`
+    fn table_params(params: AuditLogParams, limit: i32, offset: i32) -> TableDataParams {
+        let order = params.order.or_else(|| params.sort.as_ref().map(|_| "desc".to_string()));
+
+        TableDataParams {
+            tablename: "AuditLog".to_string(),
+            limit,
+            offset,
+            filter: params.filter,
+            order,
+            sort: params.sort,
+            nidkey: Some("AuditLogNID".to_string()),
+            cursor: params.cursor,
+            total: params.total,
+            decimals: None,
+        }
+    }
+
+    pub async fn get_logs(connection: web::Data<Pool<ConnectionManager>>, params: AuditLogParams) -> Result<ResultList, Box<dyn std::error::Error>> {
+        let limit = params.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_AUDIT_EXPORT_ROWS);
+        let offset = params.offset.unwrap_or(0).max(0);
+
+        DataService::get_table_data(Self::table_params(params, limit, offset), &AUDIT_COLUMNS, connection).await
+    }
+
+    /// CSV seluruh baris yang cocok dengan filter (maksimal `MAX_AUDIT_EXPORT_ROWS`), terbaru dulu
+    pub async fn export_csv(connection: web::Data<Pool<ConnectionManager>>, params: AuditLogParams) -> Result<String, Box<dyn std::error::Error>> {
+        let params = AuditLogParams { cursor: None, total: Some("none".to_string()), ..params };
+        let list = DataService::get_table_data(Self::table_params(params, MAX_AUDIT_EXPORT_ROWS, 0), &AUDIT_COLUMNS, connection).await?;
+
+        Ok(ExportService::rows_to_csv(&AUDIT_COLUMNS, &list.rows))
+    }
+}
@@ -1,53 +1,133 @@
 use actix_web::{web, HttpRequest};
-use bb8::Pool;
+use bb8::{Pool, PooledConnection};
 use bb8_tiberius::ConnectionManager;
-use chrono::{NaiveDateTime, TimeZone, Utc};
-use tiberius::QueryStream;
+use chrono::{Duration, NaiveDateTime, TimeZone, Utc};
+use shuttle_runtime::SecretStore;
+use tiberius::{QueryStream, Row};
 use crate::contexts::{
-    connection::Transaction, crypto::encrypt_text, jwt_session::{validate_jwt, Claims}, model::{ActionResult, ChangePasswordRequest, LoginRequest, RegisterRequest, ResetPasswordRequest}
+    connection::Transaction, crypto::hash_token, i18n::{t, t_args}, oidc::IdTokenClaims, jwt_session::{create_jwt, create_magic_link, validate_magic_link, validate_mfa_challenge, Claims, TokenPair, MAGIC_LINK_MINUTES, REFRESH_TOKEN_DAYS}, model::{ActionResult, ChangePasswordRequest, LinkEmail, LoginRequest, MagicLinkLoginRequest, MagicLinkRequest, MfaLoginRequest, RegisterRequest, ResendActivationRequest, ResetPasswordRequest},
+    password::{hash_password_async, verify_password_async, PasswordCheck, PASSWORD_VERSION_ARGON2ID}
 };
-use super::generic_service::GenericService;
+use super::{generic_service::GenericService, mail_service::MailService, mfa_service::MfaService, throttle_service::{ThrottleService, MAX_ACCOUNT_ATTEMPTS, MAX_IP_ATTEMPTS, MAX_RESET_REQUESTS}};
 
 pub struct AuthService;
 
+/// Role yang otomatis diberikan saat register
+pub const DEFAULT_ROLE: &str = "client";
+
+/// Masa berlaku link aktivasi & reset password, token sekali pakai
+const ACTIVATION_TOKEN_HOURS: i64 = 24;
+const RESET_TOKEN_MINUTES: i64 = 60;
+
+/// Hash Argon2id valid tanpa pemilik, dipakai agar email tidak terdaftar tetap melewati verifikasi
+const DUMMY_PASSWORD_HASH: &str = "$argon2id$v=19$m=19456,t=2,p=1$c25ha2VzeXN0ZW1kdW1teQ$2b5Y9IWn5tkUIZlZ3kYUmfJ7Et5YyAOGdSxFHv/Vwkg";
+
 impl AuthService {
     pub async fn login(connection: web::Data<Pool<ConnectionManager>>,request: LoginRequest, req: HttpRequest, app_name: &str) -> ActionResult<Claims, String> {
         
         let mut result: ActionResult<Claims, String> = ActionResult::default();
-        let enc_password = encrypt_text(request.password.unwrap_or_default());
+        let password = request.password.clone().unwrap_or_default();
+        let email = request.email.clone().unwrap_or_default();
+
+        // 🔒 Cek kunci per akun & per IP sebelum menyentuh password
+        let account_key = ThrottleService::key("login", "email", &email);
+        let ip_key = ThrottleService::key("login", "ip", &GenericService::get_ip_address(&req));
+        match ThrottleService::locked_seconds(connection.clone(), &[account_key.clone(), ip_key.clone()]).await {
+            locked if locked.error.is_some() => {
+                result.error = locked.error;
+                return result;
+            }
+            ActionResult { data: Some(Some(seconds)), .. } => {
+                result.message = ThrottleService::locked_message(seconds);
+                return result;
+            }
+            _ => {}
+        }
 
         match connection.clone().get().await {
             Ok(mut conn) => {
-                let query_result: Result<QueryStream, _> = conn.query(
-                    r#"SELECT AuthUserNID, Email, Handphone, disableLogin, Picture, RegisterDate FROM AuthUser 
-                    WHERE Email = @P1 AND Password = @P2"#, &[&request.email, &enc_password]).await;
+                // Baris dibaca di sini supaya stream selesai sebelum `conn` dipakai lagi
+                let query_result: Result<Option<Row>, _> = match conn.query(
+                    r#"SELECT AuthUserNID, Email, Handphone, disableLogin, Picture, RegisterDate, PreferredLocale, Password, PasswordVersion FROM AuthUser 
+                    WHERE Email = @P1"#, &[&request.email]).await {
+                    Ok(rows) => rows.into_row().await,
+                    Err(err) => Err(err),
+                };
                 match query_result {
-                    Ok(rows) => {
-                        if let Ok(Some(row)) = rows.into_row().await {
+                    Ok(user_row) => {
+                        let user_exists = user_row.is_some();
+
+                        let row = match user_row {
+                            Some(row) => {
+                                let stored = row.get::<&str, _>("Password").unwrap_or("").to_string();
+                                let version: i32 = row.get("PasswordVersion").unwrap_or(0);
+
+                                match verify_password_async(password.clone(), stored, version).await {
+                                    PasswordCheck::Invalid => None,
+                                    PasswordCheck::Valid => Some(row),
+                                    PasswordCheck::ValidNeedsRehash => {
+                                        // 🔵 Upgrade hash lama ke Argon2id
+                                        match hash_password_async(password.clone()).await {
+                                            Ok(new_hash) => {
+                                                if let Err(err) = conn.execute(
+                                                    "UPDATE AuthUser SET Password = @P2, PasswordVersion = @P3 WHERE AuthUserNID = @P1",
+                                                    &[&row.get::<i32, _>("AuthUserNID").unwrap_or(0), &new_hash, &PASSWORD_VERSION_ARGON2ID],
+                                                ).await {
+                                                    eprintln!("❌ Failed to upgrade password hash: {:?}", err);
+                                                }
+                                            }
+                                            Err(err) => eprintln!("❌ Failed to hash password: {}", err),
+                                        }
+                                        Some(row)
+                                    }
+                                }
+                            }
+                            None => {
+                                // Samakan waktu respon dengan email yang terdaftar
+                                let _ = verify_password_async(password.clone(), DUMMY_PASSWORD_HASH.to_string(), PASSWORD_VERSION_ARGON2ID).await;
+                                None
+                            }
+                        };
+
+                        if let Some(row) = row {
+                            let mut claims = Self::claims_from_row(&row, &req, app_name);
+                            match Self::load_user_access(&mut conn, claims.auth_usernid).await {
+                                Ok((roles, permissions)) => {
+                                    claims.roles = roles;
+                                    claims.permissions = permissions;
+                                }
+                                Err(err) => {
+                                    result.error = format!("Failed to load user roles: {:?}", err).into();
+                                    return result;
+                                }
+                            }
+
+                            ThrottleService::reset(connection.clone(), &account_key).await;
+
                             result.result = true;
-                            result.message = format!("Welcome {}", request.email.unwrap_or_default());
-                            result.data = Some(Claims {
-                                auth_usernid: row.get("AuthUserNID").unwrap_or(0),
-                                email: row.get::<&str, _>("Email").map_or_else(|| "".to_string(), |s| s.to_string()),
-                                mobile_phone: row.get::<&str, _>("Handphone").map_or_else(|| "".to_string(), |s| s.to_string()),
-                                disabled_login: row.get("disableLogin").unwrap_or(false),
-                                picture: Some(row.get::<&str, _>("Picture").map_or_else(|| "".to_string(), |s| s.to_string())),
-                                register_date: row
-                                    .get::<NaiveDateTime, _>("RegisterDate")
-                                    .map(|dt| dt.and_utc()) // 🔥 Konversi ke DateTime<Utc>
-                                    .unwrap_or_else(|| Utc.timestamp_opt(0, 0).unwrap()),
-                                result: true,
-                                expired_token: 0,
-                                expired_date: "".to_string(),
-                                exp: 0, // Default jika kosong,
-                                comp_name: Some(GenericService::get_device_name(&req)),
-                                ip_address: Some(GenericService::get_ip_address(&req)),
-                                app_name: Some(app_name.to_string()),
-                            }); 
+                            result.message = t_args("auth.welcome", &[("email", &email)]);
+                            result.data = Some(claims);
 
                             return result;
                         } else {
-                            result.message = format!("No user found for email: {}", request.email.unwrap_or_default());
+                            let failed = ThrottleService::register_failure(connection.clone(), &account_key, MAX_ACCOUNT_ATTEMPTS).await;
+                            if failed.error.is_some() {
+                                result.error = failed.error;
+                                return result;
+                            }
+                            let ip_failed = ThrottleService::register_failure(connection.clone(), &ip_key, MAX_IP_ATTEMPTS).await;
+                            if ip_failed.error.is_some() {
+                                result.error = ip_failed.error;
+                                return result;
+                            }
+
+                            let attempt = failed.data.unwrap_or_default();
+                            if user_exists && attempt.lockout_started(MAX_ACCOUNT_ATTEMPTS) {
+                                Self::notify_lockout(&req, email.clone(), attempt.lock_seconds.unwrap_or_default());
+                            }
+
+                            // Pesan sama untuk email tidak terdaftar & password salah
+                            result.message = t("auth.invalid_credentials");
                             return result;
                         } 
                     },
@@ -64,10 +144,397 @@ impl AuthService {
         }
     }
 
-    pub async fn register(connection: web::Data<Pool<ConnectionManager>>, request: RegisterRequest) -> ActionResult<(), String> {
+    /// Email pemberitahuan akun dikunci, dikirim di background supaya respon login tidak menunggu SMTP
+    fn notify_lockout(req: &HttpRequest, email: String, lock_seconds: i64) {
+        let secrets = match req.app_data::<web::Data<SecretStore>>() {
+            Some(secrets) => secrets.clone(),
+            None => return,
+        };
+
+        Self::send_email_background(LinkEmail {
+            recipient: email.clone(),
+            name: email,
+            subject: t("mail.lockout_subject"),
+            message: t_args("mail.lockout_message", &[("ip", &GenericService::get_ip_address(req)), ("minutes", &((lock_seconds + 59) / 60))]),
+            link: Some(GenericService::frontend_url(&secrets, "/forgot-password")),
+            link_label: t("mail.reset_button"),
+            expires_in: None,
+        }, secrets);
+    }
+
+    /// Kirim email tanpa menahan respon, supaya waktu respon tidak membocorkan apakah email terdaftar
+    fn send_email_background(email: LinkEmail, secrets: web::Data<SecretStore>) {
+        actix_web::rt::spawn(async move {
+            let sent = MailService::send_link_email(email, secrets).await;
+            if !sent.result {
+                eprintln!("❌ Failed to send email: {:?}", sent.error);
+            }
+        });
+    }
+
+    fn activation_email(secrets: &SecretStore, email: &str, name: &str, token: &str) -> LinkEmail {
+        LinkEmail {
+            recipient: email.to_string(),
+            name: name.to_string(),
+            subject: t("mail.activation_subject"),
+            message: t("mail.activation_message"),
+            link: Some(GenericService::frontend_url(secrets, &format!("/activation/{}", token))),
+            link_label: t("mail.activation_button"),
+            expires_in: Some(t_args("mail.expires_hours", &[("hours", &ACTIVATION_TOKEN_HOURS)])),
+        }
+    }
+
+    /// Langkah kedua login untuk user dengan 2FA aktif
+    pub async fn complete_mfa_login(connection: web::Data<Pool<ConnectionManager>>, request: MfaLoginRequest, req: HttpRequest, app_name: &str) -> ActionResult<Claims, String> {
+
+        let mut result: ActionResult<Claims, String> = ActionResult::default();
+
+        let auth_usernid = match validate_mfa_challenge(&request.mfa_token) {
+            Ok(nid) => nid,
+            Err(_) => {
+                result.message = t("auth.mfa_expired");
+                return result;
+            }
+        };
+
+        // 🔒 Kode 6 digit mudah ditebak tanpa batas percobaan
+        let mfa_key = ThrottleService::key("mfa", "user", &auth_usernid.to_string());
+        match ThrottleService::locked_seconds(connection.clone(), std::slice::from_ref(&mfa_key)).await {
+            locked if locked.error.is_some() => {
+                result.error = locked.error;
+                return result;
+            }
+            ActionResult { data: Some(Some(seconds)), .. } => {
+                result.message = ThrottleService::locked_message(seconds);
+                return result;
+            }
+            _ => {}
+        }
+
+        let verified = MfaService::verify_second_factor(connection.clone(), auth_usernid, request.code).await;
+        if !verified.result {
+            if verified.error.is_none() {
+                let failed = ThrottleService::register_failure(connection.clone(), &mfa_key, MAX_ACCOUNT_ATTEMPTS).await;
+                if failed.error.is_some() {
+                    result.error = failed.error;
+                    return result;
+                }
+            }
+            result.message = verified.message;
+            result.error = verified.error;
+            return result;
+        }
+        ThrottleService::reset(connection.clone(), &mfa_key).await;
+
+        Self::load_claims(connection, auth_usernid, &req, app_name).await
+    }
+
+    /// Login lewat OpenID Connect: cari akun yang sudah tertaut, atau tautkan ke AuthUser dengan email terverifikasi
+    pub async fn oidc_login(connection: web::Data<Pool<ConnectionManager>>, provider: &str, identity: IdTokenClaims, req: HttpRequest, app_name: &str) -> ActionResult<Claims, String> {
+
+        let mut result: ActionResult<Claims, String> = ActionResult::default();
+
+        let auth_usernid = match connection.clone().get().await {
+            Ok(mut conn) => {
+                let linked = match conn.query(
+                    "SELECT AuthUserNID FROM AuthUserExternalLogin WHERE Provider = @P1 AND Subject = @P2",
+                    &[&provider, &identity.sub],
+                ).await {
+                    Ok(rows) => rows.into_row().await.ok().flatten().and_then(|row| row.get::<i32, _>("AuthUserNID")),
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                        return result;
+                    }
+                };
+
+                match linked {
+                    Some(nid) => nid,
+                    None => {
+                        // Email dari provider hanya dipercaya jika sudah diverifikasi provider
+                        let email = match identity.email.as_deref() {
+                            Some(email) if identity.email_verified => email.to_string(),
+                            _ => {
+                                result.message = t("auth.provider_email_unverified");
+                                return result;
+                            }
+                        };
+
+                        let existing = match conn.query("SELECT AuthUserNID FROM AuthUser WHERE Email = @P1", &[&email]).await {
+                            Ok(rows) => rows.into_row().await.ok().flatten().and_then(|row| row.get::<i32, _>("AuthUserNID")),
+                            Err(err) => {
+                                result.error = Some(format!("Query execution failed: {:?}", err));
+                                return result;
+                            }
+                        };
+
+                        let nid = match existing {
+                            Some(nid) => nid,
+                            None => {
+                                result.message = t_args("auth.provider_no_account", &[("email", &email)]);
+                                return result;
+                            }
+                        };
+
+                        if let Err(err) = conn.execute(
+                            r#"INSERT INTO AuthUserExternalLogin (AuthUserNID, Provider, Subject, Email, CreatedTime)
+                            VALUES (@P1, @P2, @P3, @P4, GETDATE())"#,
+                            &[&nid, &provider, &identity.sub, &email],
+                        ).await {
+                            result.error = Some(format!("Failed to link account: {:?}", err));
+                            return result;
+                        }
+
+                        nid
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+                return result;
+            }
+        };
+
+        Self::load_claims(connection, auth_usernid, &req, app_name).await
+    }
+
+    /// Claims lengkap (termasuk role & permission) untuk user yang sudah terverifikasi
+    pub async fn load_claims(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, req: &HttpRequest, app_name: &str) -> ActionResult<Claims, String> {
+
+        let mut result: ActionResult<Claims, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                let query_result: Result<Option<Row>, _> = match conn.query(
+                    r#"SELECT AuthUserNID, Email, Handphone, disableLogin, Picture, RegisterDate, PreferredLocale FROM AuthUser
+                    WHERE AuthUserNID = @P1"#, &[&auth_usernid]).await {
+                    Ok(rows) => rows.into_row().await,
+                    Err(err) => Err(err),
+                };
+                match query_result {
+                    Ok(row) => {
+                        match row {
+                            Some(row) => {
+                                let mut claims = Self::claims_from_row(&row, req, app_name);
+                                match Self::load_user_access(&mut conn, claims.auth_usernid).await {
+                                    Ok((roles, permissions)) => {
+                                        claims.roles = roles;
+                                        claims.permissions = permissions;
+                                    }
+                                    Err(err) => {
+                                        result.error = format!("Failed to load user roles: {:?}", err).into();
+                                        return result;
+                                    }
+                                }
+
+                                result.result = true;
+                                result.message = t_args("auth.welcome", &[("email", &claims.email)]);
+                                result.data = Some(claims);
+                            }
+                            None => {
+                                result.message = t("auth.user_not_found");
+                            }
+                        }
+                        result
+                    },
+                    Err(err) => {
+                        result.error = format!("Query execution failed: {:?}", err).into();
+                        result
+                    },
+                }
+            },
+            Err(err) => {
+                result.error = format!("Internal Server error: {:?}", err).into();
+                result
+            },
+        }
+    }
+
+    /// Kirim link login sekali pakai ke email. Respon selalu sama supaya tidak membocorkan email terdaftar
+    pub async fn send_magic_link(connection: web::Data<Pool<ConnectionManager>>, request: MagicLinkRequest, req: HttpRequest, secrets: web::Data<SecretStore>) -> ActionResult<(), String> {
+
+        let mut result: ActionResult<(), String> = ActionResult::default();
+        let email = request.email.unwrap_or_default();
+        let ip_address = GenericService::get_ip_address(&req);
+
+        // 🔒 Akun / IP yang terkunci karena gagal login juga tidak boleh minta link,
+        // dan permintaan link dibatasi per email & per IP seperti reset password
+        let login_keys = [ThrottleService::key("login", "email", &email), ThrottleService::key("login", "ip", &ip_address)];
+        let email_key = ThrottleService::key("magic", "email", &email);
+        let ip_key = ThrottleService::key("magic", "ip", &ip_address);
+        match ThrottleService::locked_seconds(connection.clone(), &[login_keys[0].clone(), login_keys[1].clone(), email_key.clone(), ip_key.clone()]).await {
+            locked if locked.error.is_some() => {
+                result.error = locked.error;
+                return result;
+            }
+            ActionResult { data: Some(Some(seconds)), .. } => {
+                result.message = ThrottleService::locked_message(seconds);
+                return result;
+            }
+            _ => {}
+        }
+        for (key, max_attempts) in [(&email_key, MAX_RESET_REQUESTS), (&ip_key, MAX_IP_ATTEMPTS)] {
+            let counted = ThrottleService::register_failure(connection.clone(), key, max_attempts).await;
+            if counted.error.is_some() {
+                result.error = counted.error;
+                return result;
+            }
+        }
+
+        let auth_usernid = match connection.clone().get().await {
+            Ok(mut conn) => match conn.query("SELECT AuthUserNID FROM AuthUser WHERE Email = @P1", &[&email]).await {
+                Ok(rows) => rows.into_row().await.ok().flatten().and_then(|row| row.get::<i32, _>("AuthUserNID")),
+                Err(err) => {
+                    result.error = Some(format!("Query execution failed: {:?}", err));
+                    return result;
+                }
+            },
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+                return result;
+            }
+        };
+
+        if let Some(auth_usernid) = auth_usernid {
+            let nonce = GenericService::random_string(32);
+            let token = match create_magic_link(auth_usernid, &nonce) {
+                Ok(token) => token,
+                Err(err) => {
+                    result.error = Some(format!("Failed to create magic link: {}", err));
+                    return result;
+                }
+            };
+
+            match connection.clone().get().await {
+                Ok(mut conn) => {
+                    // Hanya link terbaru yang berlaku
+                    if let Err(err) = conn.execute(
+                        r#"DELETE FROM WEB_MagicLink WHERE AuthUserNID = @P1 AND UsedTime IS NULL;
+                        INSERT INTO WEB_MagicLink (AuthUserNID, NonceHash, ExpiredTime, IPAddress, CreatedTime)
+                        VALUES (@P1, @P2, DATEADD(MINUTE, @P3, GETDATE()), @P4, GETDATE())"#,
+                        &[&auth_usernid, &hash_token(&nonce), &(MAGIC_LINK_MINUTES as i32), &ip_address],
+                    ).await {
+                        result.error = Some(format!("Failed to insert magic link: {:?}", err));
+                        return result;
+                    }
+                }
+                Err(err) => {
+                    result.error = Some(format!("Internal Server error: {:?}", err));
+                    return result;
+                }
+            }
+
+            let link = reqwest::Url::parse_with_params(
+                &GenericService::frontend_url(&secrets, "/magic-link"),
+                &[("token", token.as_str())],
+            ).map(|url| url.to_string()).ok();
+            Self::send_email_background(LinkEmail {
+                recipient: email.clone(),
+                name: email,
+                subject: t("mail.magic_link_subject"),
+                message: t_args("mail.magic_link_message", &[("ip", &ip_address)]),
+                link,
+                link_label: t("mail.magic_link_button"),
+                expires_in: Some(t_args("mail.expires_minutes", &[("minutes", &MAGIC_LINK_MINUTES)])),
+            }, secrets);
+        }
+
+        result.result = true;
+        result.message = t("auth.magic_link_sent");
+        result
+    }
+
+    /// Link palsu / kedaluwarsa / sudah dipakai dihitung sebagai gagal login dari IP ini
+    async fn magic_link_invalid(connection: web::Data<Pool<ConnectionManager>>, ip_key: &str) -> ActionResult<Claims, String> {
+        let mut result: ActionResult<Claims, String> = ActionResult::default();
+        let failed = ThrottleService::register_failure(connection, ip_key, MAX_IP_ATTEMPTS).await;
+        result.error = failed.error;
+        result.message = t("auth.magic_link_invalid");
+        result
+    }
+
+    /// Tukar link login dengan claims, lanjut ke 2FA / sesi baru seperti login password
+    pub async fn magic_link_login(connection: web::Data<Pool<ConnectionManager>>, request: MagicLinkLoginRequest, req: HttpRequest, app_name: &str) -> ActionResult<Claims, String> {
+
+        let mut result: ActionResult<Claims, String> = ActionResult::default();
+        let ip_key = ThrottleService::key("login", "ip", &GenericService::get_ip_address(&req));
+
+        let (auth_usernid, nonce) = match validate_magic_link(&request.token) {
+            Ok(link) => link,
+            Err(_) => return Self::magic_link_invalid(connection, &ip_key).await,
+        };
+
+        let mut conn = match connection.get().await {
+            Ok(conn) => conn,
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+                return result;
+            }
+        };
+
+        let email = match conn.query("SELECT Email FROM AuthUser WHERE AuthUserNID = @P1", &[&auth_usernid]).await {
+            Ok(rows) => match rows.into_row().await {
+                Ok(Some(row)) => row.get::<&str, _>("Email").unwrap_or("").to_string(),
+                Ok(None) => return Self::magic_link_invalid(connection.clone(), &ip_key).await,
+                Err(err) => {
+                    result.error = Some(format!("Failed to read user: {:?}", err));
+                    return result;
+                }
+            },
+            Err(err) => {
+                result.error = Some(format!("Query execution failed: {:?}", err));
+                return result;
+            }
+        };
+
+        // 🔒 Akun yang sedang terkunci tidak bisa masuk lewat link, link tetap berlaku sampai kedaluwarsa
+        let account_key = ThrottleService::key("login", "email", &email);
+        match ThrottleService::locked_seconds(connection.clone(), &[account_key.clone(), ip_key.clone()]).await {
+            locked if locked.error.is_some() => {
+                result.error = locked.error;
+                return result;
+            }
+            ActionResult { data: Some(Some(seconds)), .. } => {
+                result.message = ThrottleService::locked_message(seconds);
+                return result;
+            }
+            _ => {}
+        }
+
+        // Tandai terpakai secara atomik, link yang sama tidak bisa dipakai dua kali
+        let consumed = match conn.execute(
+            r#"UPDATE WEB_MagicLink SET UsedTime = GETDATE()
+            WHERE NonceHash = @P1 AND AuthUserNID = @P2 AND UsedTime IS NULL AND ExpiredTime > GETDATE()"#,
+            &[&hash_token(&nonce), &auth_usernid],
+        ).await {
+            Ok(res) => res.total() > 0,
+            Err(err) => {
+                result.error = Some(format!("Failed to consume magic link: {:?}", err));
+                return result;
+            }
+        };
+        drop(conn);
+
+        if !consumed {
+            return Self::magic_link_invalid(connection, &ip_key).await;
+        }
+
+        ThrottleService::reset(connection.clone(), &account_key).await;
+
+        Self::load_claims(connection, auth_usernid, &req, app_name).await
+    }
+
+    pub async fn register(connection: web::Data<Pool<ConnectionManager>>, request: RegisterRequest, secrets: web::Data<SecretStore>) -> ActionResult<(), String> {
         
         let mut result: ActionResult<(), String> = ActionResult::default();
-        let enc_password = encrypt_text(request.password.unwrap_or_default());
+        // Token asli hanya ada di email, database menyimpan hash-nya
+        let activation_token = GenericService::random_string(64);
+        let enc_password = match hash_password_async(request.password.unwrap_or_default()).await {
+            Ok(hash) => hash,
+            Err(err) => {
+                result.error = Some(format!("Failed to hash password: {}", err));
+                return result;
+            }
+        };
 
         match connection.clone().get().await {
             Ok(mut conn) => {
@@ -148,13 +615,13 @@ impl AuthService {
                         if let Err(err) = conn.execute(
                             r#"INSERT INTO [dbo].[AuthUser] 
                             ([WebCIFNID],[Email],[Handphone],[ActivateCode],[Password],[RegisterDate],
-                            [disableLogin],[OTPGeneratedLink],[OTPGeneratedLinkDate],[Picture],[Sub], [ClientNCategory])
-                            VALUES (@P1,@P2,@P3,@P4,@P5,@P6,@P7,@P8,@P9,@P10,@P11,@P12)"#,
+                            [disableLogin],[OTPGeneratedLink],[OTPGeneratedLinkDate],[Picture],[Sub], [ClientNCategory], [PasswordVersion])
+                            VALUES (@P1,@P2,@P3,@P4,@P5,@P6,@P7,@P8,@P9,@P10,@P11,@P12,@P13)"#,
                             &[
                                 &auto_nid, &request.email, &request.mobile_phone, &GenericService::random_string(20),
                                 &enc_password, &chrono::Utc::now(), &true,
-                                &GenericService::random_string(70), &chrono::Utc::now(),
-                                &"", &"", &request.client_category,
+                                &hash_token(&activation_token), &chrono::Utc::now(),
+                                &"", &"", &request.client_category, &PASSWORD_VERSION_ARGON2ID,
                             ],
                         ).await {
                             result.error = Some(format!("Failed to insert AuthUser: {:?}", err));
@@ -184,6 +651,26 @@ impl AuthService {
                     }
                 }
 
+                // 🔴 Scope keempat: Role default untuk client baru
+                match trans.conn.lock().await.as_mut() {
+                    Some(conn) => {
+                        if let Err(err) = conn.execute(
+                            r#"INSERT INTO [dbo].[AuthUserRole] ([AuthUserNID], [RoleNID])
+                            SELECT u.AuthUserNID, r.RoleNID FROM [dbo].[AuthUser] u
+                            CROSS JOIN [dbo].[Roles] r
+                            WHERE u.WebCIFNID = @P1 AND r.RoleName = @P2"#,
+                            &[&auto_nid, &DEFAULT_ROLE],
+                        ).await {
+                            result.error = Some(format!("Failed to insert AuthUserRole: {:?}", err));
+                            return result;
+                        }
+                    }
+                    None => {
+                        result.error = Some("Failed to get database connection".into());
+                        return result;
+                    }
+                }
+
                 // 🔵 Commit transaksi
                 if let Err(err) = trans.commit().await {
                     result.error = Some(format!("Failed to commit transaction: {:?}", err));
@@ -191,7 +678,15 @@ impl AuthService {
                 }
 
                 result.result = true;
-                result.message = "User registered successfully".to_string();
+                result.message = t("auth.registered");
+
+                let email = request.email.clone().unwrap_or_default();
+                let name = request.full_name.clone().unwrap_or_else(|| email.clone());
+                let sent = MailService::send_link_email(Self::activation_email(&secrets, &email, &name, &activation_token), secrets).await;
+                if !sent.result {
+                    eprintln!("❌ Failed to send activation email: {:?}", sent.error);
+                    result.message = t("auth.registered_mail_failed");
+                }
             }
             Err(err) => {
                 result.error = Some(format!("Failed to start transaction: {:?}", err));
@@ -204,59 +699,49 @@ impl AuthService {
     pub async fn activation_user(connection: web::Data<Pool<ConnectionManager>>, otp_link: String) -> ActionResult<(), String> {
 
         let mut result: ActionResult<(), String> = ActionResult::default();
+        let token_hash = hash_token(&otp_link);
 
         match connection.clone().get().await {
             Ok(mut conn) => {
-                let query_result: Result<QueryStream, _> = conn.query(
-                    r#"SELECT AuthUserNID 
+                let query_result: Result<Option<Row>, _> = match conn.query(
+                    r#"SELECT AuthUserNID, OTPGeneratedLinkDate
                     FROM AuthUser 
-                    WHERE OTPGeneratedLink = @P1"#, &[&otp_link]).await;
+                    WHERE OTPGeneratedLink = @P1"#, &[&token_hash]).await {
+                    Ok(rows) => rows.into_row().await,
+                    Err(err) => Err(err),
+                };
                 match query_result {
-                    Ok(rows) => {
-                        if let Ok(Some(row)) = rows.into_row().await {
-                            match Transaction::begin(&connection).await {
-                                Ok(trans) => {
-                                    // 🔴 Scope ketiga: Insert ke TableRequest
-                                    match trans.conn.lock().await.as_mut() {
-                                        Some(conn) => {
-                                            if let Err(err) = conn.execute(
-                                                r#"UPDATE [dbo].[AuthUser]
-                                                    set [OTPGeneratedLink] = @P2, [disableLogin] = @P3,
-                                                    [ActivateTime] = @P4
-                                                    WHERE AuthUserNID = @P1"#,
-                                                &[
-                                                    &row.get("AuthUserNID").unwrap_or(0),
-                                                    &otp_link,
-                                                    &false,
-                                                    &chrono::Utc::now(),
-                                                ],
-                                            ).await {
-                                                result.error = Some(format!("Fauled: {:?}", err));
-                                                return result;
-                                            }
-                                        }
-                                        None => {
-                                            result.error = Some("Failed to get database connection".into());
-                                            return result;
-                                        }
-                                    }
-                    
-                                    // 🔵 Commit transaksi
-                                    if let Err(err) = trans.commit().await {
-                                        result.error = Some(format!("Failed to commit transaction: {:?}", err));
-                                        return result;
-                                    }
-                    
+                    Ok(row) => {
+                        if let Some(row) = row {
+                            let auth_usernid: i32 = row.get("AuthUserNID").unwrap_or(0);
+                            let issued: Option<NaiveDateTime> = row.get("OTPGeneratedLinkDate");
+
+                            if issued.is_none_or(|issued| issued + Duration::hours(ACTIVATION_TOKEN_HOURS) < Utc::now().naive_utc()) {
+                                result.message = t("auth.activation_expired");
+                                return result;
+                            }
+
+                            // 🔵 Token dihapus saat dipakai, WHERE hash mencegah dipakai dua kali secara paralel
+                            match conn.execute(
+                                r#"UPDATE [dbo].[AuthUser]
+                                    set [OTPGeneratedLink] = NULL, [disableLogin] = @P3,
+                                    [ActivateTime] = @P4
+                                    WHERE AuthUserNID = @P1 AND OTPGeneratedLink = @P2"#,
+                                &[&auth_usernid, &token_hash, &false, &chrono::Utc::now()],
+                            ).await {
+                                Ok(res) if res.rows_affected().iter().sum::<u64>() > 0 => {
                                     result.result = true;
-                                    result.message = "Activation successfully".to_string();
+                                    result.message = t("auth.activated");
+                                }
+                                Ok(_) => {
+                                    result.message = t("auth.activation_invalid");
                                 }
                                 Err(err) => {
-                                    result.error = Some(format!("Failed to start transaction: {:?}", err));
+                                    result.error = Some(format!("Failed: {:?}", err));
                                 }
                             }
-                    
                         } else {
-                            result.message = format!("No user found for email");
+                            result.message = t("auth.activation_invalid");
                             return result;
                         }
                     },
@@ -276,9 +761,92 @@ impl AuthService {
         
     }
 
-    pub async  fn forget_password(connection: web::Data<Pool<ConnectionManager>>, request: ResetPasswordRequest) -> ActionResult<(), String> {
+    /// Kirim ulang link aktivasi, respon selalu sama supaya tidak membocorkan status email
+    pub async fn resend_activation(connection: web::Data<Pool<ConnectionManager>>, request: ResendActivationRequest, req: HttpRequest, secrets: web::Data<SecretStore>) -> ActionResult<(), String> {
+
+        let mut result: ActionResult<(), String> = ActionResult::default();
+        let email = request.email.unwrap_or_default();
+
+        // 🔒 Sama seperti reset password, dibatasi per email & per IP
+        let email_key = ThrottleService::key("activation", "email", &email);
+        let ip_key = ThrottleService::key("activation", "ip", &GenericService::get_ip_address(&req));
+        match ThrottleService::locked_seconds(connection.clone(), &[email_key.clone(), ip_key.clone()]).await {
+            locked if locked.error.is_some() => {
+                result.error = locked.error;
+                return result;
+            }
+            ActionResult { data: Some(Some(seconds)), .. } => {
+                result.message = ThrottleService::locked_message(seconds);
+                return result;
+            }
+            _ => {}
+        }
+        for (key, max_attempts) in [(&email_key, MAX_RESET_REQUESTS), (&ip_key, MAX_IP_ATTEMPTS)] {
+            let counted = ThrottleService::register_failure(connection.clone(), key, max_attempts).await;
+            if counted.error.is_some() {
+                result.error = counted.error;
+                return result;
+            }
+        }
+
+        let activation_token = GenericService::random_string(64);
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                // Hanya akun yang belum pernah aktivasi
+                match conn.execute(
+                    r#"UPDATE [dbo].[AuthUser]
+                        SET [OTPGeneratedLink] = @P2, [OTPGeneratedLinkDate] = @P3
+                        WHERE Email = @P1 AND ActivateTime IS NULL AND disableLogin = 1"#,
+                    &[&email, &hash_token(&activation_token), &chrono::Utc::now()],
+                ).await {
+                    Ok(res) if res.rows_affected().iter().sum::<u64>() > 0 => {
+                        Self::send_email_background(Self::activation_email(&secrets, &email, &email, &activation_token), secrets);
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                        return result;
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = format!("Internal Server error: {:?}", err).into();
+                return result;
+            }
+        }
+
+        result.result = true;
+        result.message = t("auth.activation_resent");
+        result
+    }
+
+    pub async  fn forget_password(connection: web::Data<Pool<ConnectionManager>>, request: ResetPasswordRequest, req: HttpRequest, secrets: web::Data<SecretStore>) -> ActionResult<(), String> {
 
         let mut result: ActionResult<(), String> = ActionResult::default();
+        let reset_token = GenericService::random_string(64);
+
+        // 🔒 Batasi permintaan reset per email & per IP, setiap request dihitung
+        let email_key = ThrottleService::key("reset", "email", &request.email.clone().unwrap_or_default());
+        let ip_key = ThrottleService::key("reset", "ip", &GenericService::get_ip_address(&req));
+        match ThrottleService::locked_seconds(connection.clone(), &[email_key.clone(), ip_key.clone()]).await {
+            locked if locked.error.is_some() => {
+                result.error = locked.error;
+                return result;
+            }
+            ActionResult { data: Some(Some(seconds)), .. } => {
+                result.message = ThrottleService::locked_message(seconds);
+                return result;
+            }
+            _ => {}
+        }
+        for (key, max_attempts) in [(&email_key, MAX_RESET_REQUESTS), (&ip_key, MAX_IP_ATTEMPTS)] {
+            let counted = ThrottleService::register_failure(connection.clone(), key, max_attempts).await;
+            if counted.error.is_some() {
+                result.error = counted.error;
+                return result;
+            }
+        }
 
         match connection.clone().get().await {
             Ok(mut conn) => {
@@ -300,7 +868,7 @@ impl AuthService {
                                                     WHERE AuthUserNID = @P1"#,
                                                 &[
                                                     &row.get("AuthUserNID").unwrap_or(0),
-                                                    &GenericService::random_string(70),
+                                                    &hash_token(&reset_token),
                                                     &true,
                                                     &chrono::Utc::now(),
                                                 ],
@@ -321,8 +889,23 @@ impl AuthService {
                                         return result;
                                     }
                     
+                                    let email = request.email.clone().unwrap_or_default();
+                                    let link = reqwest::Url::parse_with_params(
+                                        &GenericService::frontend_url(&secrets, "/reset-password"),
+                                        &[("email", email.as_str()), ("key", reset_token.as_str())],
+                                    ).map(|url| url.to_string()).ok();
+                                    Self::send_email_background(LinkEmail {
+                                        recipient: email.clone(),
+                                        name: email,
+                                        subject: t("mail.reset_subject"),
+                                        message: t("mail.reset_message"),
+                                        link,
+                                        link_label: t("mail.reset_button"),
+                                        expires_in: Some(t_args("mail.expires_minutes", &[("minutes", &RESET_TOKEN_MINUTES)])),
+                                    }, secrets);
+
                                     result.result = true;
-                                    result.message = "Reset password successfully".to_string();
+                                    result.message = t("auth.reset_sent");
                                 }
                                 Err(err) => {
                                     result.error = Some(format!("Failed to start transaction: {:?}", err));
@@ -330,7 +913,9 @@ impl AuthService {
                             }
                     
                         } else {
-                            result.message = format!("No user found for email");
+                            // Respon sama seperti email terdaftar
+                            result.result = true;
+                            result.message = t("auth.reset_sent");
                             return result;
                         }
                     },
@@ -353,41 +938,80 @@ impl AuthService {
     pub async  fn change_password(connection: web::Data<Pool<ConnectionManager>>, request: ChangePasswordRequest) -> ActionResult<(), String> {
 
         let mut result: ActionResult<(), String> = ActionResult::default();
-        let enc_password = encrypt_text(request.password.unwrap_or_default());
+        let enc_password = match hash_password_async(request.password.unwrap_or_default()).await {
+            Ok(hash) => hash,
+            Err(err) => {
+                result.error = Some(format!("Failed to hash password: {}", err));
+                return result;
+            }
+        };
+        let key_hash = hash_token(&request.reset_password_key);
 
         match connection.clone().get().await {
             Ok(mut conn) => {
                 let query_result: Result<QueryStream, _> = conn.query(
-                    r#"SELECT AuthUserNID 
+                    r#"SELECT AuthUserNID, ResetPasswordDate
                     FROM AuthUser 
-                    WHERE Email = @P1 and ResetPasswordKey = @P2"#, &[&request.email, &request.reset_password_key]).await;
+                    WHERE Email = @P1 and ResetPasswordKey = @P2 and ResetPasswordFlag = 1"#, &[&request.email, &key_hash]).await;
                 match query_result {
                     Ok(rows) => {
                         if let Ok(Some(row)) = rows.into_row().await {
+                            let auth_usernid: i32 = row.get("AuthUserNID").unwrap_or(0);
+                            let issued: Option<NaiveDateTime> = row.get("ResetPasswordDate");
+
+                            if issued.is_none_or(|issued| issued + Duration::minutes(RESET_TOKEN_MINUTES) < Utc::now().naive_utc()) {
+                                result.message = t("auth.reset_expired");
+                                return result;
+                            }
+
                             match Transaction::begin(&connection).await {
                                 Ok(trans) => {
-                                    // 🔴 Scope ketiga: Insert ke TableRequest
-                                    match trans.conn.lock().await.as_mut() {
-                                        Some(conn) => {
-                                            if let Err(err) = conn.execute(
+                                    let updated: Result<bool, tiberius::error::Error> = match trans.conn.lock().await.as_mut() {
+                                        Some(conn) => async {
+                                            // 🔴 Token dikonsumsi bersamaan dengan ganti password
+                                            let changed = conn.execute(
                                                 r#"UPDATE [dbo].[AuthUser]
-                                                    set [ResetPasswordKey] = @P2, [ResetPasswordFlag] = @P3, [Password] = @P4
-                                                    WHERE AuthUserNID = @P1"#,
+                                                    set [ResetPasswordKey] = NULL, [ResetPasswordFlag] = 0, [Password] = @P3, [PasswordVersion] = @P4
+                                                    WHERE AuthUserNID = @P1 AND ResetPasswordKey = @P2"#,
                                                 &[
-                                                    &row.get("AuthUserNID").unwrap_or(0),
-                                                    &request.reset_password_key,
-                                                    &true,
+                                                    &auth_usernid,
+                                                    &key_hash,
                                                     &enc_password,
+                                                    &PASSWORD_VERSION_ARGON2ID,
                                                 ],
-                                            ).await {
-                                                result.error = Some(format!("Fauled: {:?}", err));
-                                                return result;
+                                            ).await?.total();
+
+                                            if changed == 0 {
+                                                return Ok(false);
                                             }
-                                        }
+
+                                            // 🔴 Password lama mungkin bocor, semua sesi & refresh token dicabut
+                                            conn.execute(
+                                                r#"DELETE FROM WEB_Cookies WHERE AuthUserNID = @P1;
+                                                UPDATE WEB_RefreshToken SET IsRevoked = 1 WHERE AuthUserNID = @P1 AND IsRevoked = 0"#,
+                                                &[&auth_usernid],
+                                            ).await?;
+
+                                            Ok(true)
+                                        }.await,
                                         None => {
                                             result.error = Some("Failed to get database connection".into());
                                             return result;
                                         }
+                                    };
+
+                                    match updated {
+                                        Ok(true) => {}
+                                        Ok(false) => {
+                                            let _ = trans.rollback().await;
+                                            result.message = t("auth.reset_invalid");
+                                            return result;
+                                        }
+                                        Err(err) => {
+                                            let _ = trans.rollback().await;
+                                            result.error = Some(format!("Failed to reset password: {:?}", err));
+                                            return result;
+                                        }
                                     }
                     
                                     // 🔵 Commit transaksi
@@ -397,7 +1021,7 @@ impl AuthService {
                                     }
                     
                                     result.result = true;
-                                    result.message = "Change password successfully".to_string();
+                                    result.message = t("auth.password_reset");
                                 }
                                 Err(err) => {
                                     result.error = Some(format!("Failed to start transaction: {:?}", err));
@@ -405,7 +1029,7 @@ impl AuthService {
                             }
                     
                         } else {
-                            result.message = format!("No user found for email");
+                            result.message = t("auth.reset_invalid");
                             return result;
                         }
                     },
@@ -436,142 +1060,431 @@ impl AuthService {
     ) -> ActionResult<Claims, String> {
         let mut result: ActionResult<Claims, String> = ActionResult::default();
     
-        match Transaction::begin(&connection).await {
-            Ok(trans) => {
-                match trans.conn.lock().await.as_mut() {
-                    Some(conn) => {
-                        let active_token = if cookies.is_empty() { token.clone() } else { cookies.clone() };
-    
-                        if exist_session {
-                            // 🔵 Check apakah user dan cookies/token cocok
-                            println!("🔵 Check apakah user dan cookies/token cocok");
-                            let row_count = match conn.query(
-                                "SELECT COUNT(*) as count FROM WEB_Cookies WHERE AuthUserNID = @P1 AND Cookies = @P2",
-                                &[&session.auth_usernid, &active_token],
-                            ).await {
-                                Ok(query_result) => match query_result.into_row().await {
-                                    Ok(Some(row)) => row.get::<i32, _>("count").unwrap_or(0),
-                                    _ => 0,
-                                },
-                                Err(e) => {
-                                    result.error = Some(format!("Query error: {:?}", e));
-                                    0
-                                }
-                            };
-    
-                            if row_count == 0 {
-                                result.message = "Session has expired.".to_string();
-                                return result;
-                            }
+        let trans = match Transaction::begin(&connection).await {
+            Ok(trans) => trans,
+            Err(err) => {
+                result.error = Some(format!("Failed to start transaction: {:?}", err));
+                return result;
+            }
+        };
+
+        // Ok(Some(pesan)) = sesi tidak valid, tidak ada yang perlu di-commit
+        let applied: Result<Option<String>, String> = match trans.conn.lock().await.as_mut() {
+            Some(conn) => async {
+                let active_token = if cookies.is_empty() { token.clone() } else { cookies.clone() };
+
+                if exist_session {
+                    // 🔵 Check apakah sesi device ini dan cookies/token cocok
+                    let row_count = match conn.query(
+                        "SELECT COUNT(*) as count FROM WEB_Cookies WHERE AuthUserNID = @P1 AND SessionID = @P2 AND Cookies = @P3",
+                        &[&session.auth_usernid, &session.session_id, &active_token],
+                    ).await {
+                        Ok(query_result) => match query_result.into_row().await {
+                            Ok(Some(row)) => row.get::<i32, _>("count").unwrap_or(0),
+                            _ => 0,
+                        },
+                        Err(e) => return Err(format!("Query error: {:?}", e)),
+                    };
+
+                    if row_count == 0 {
+                        return Ok(Some(t("auth.session_expired")));
+                    }
+
+                    if update_session {
+                        let _ = conn.execute(
+                            "UPDATE WEB_Cookies SET Cookies = @P1, LastUpdate = GETDATE() WHERE AuthUserNID = @P2 AND SessionID = @P3",
+                            &[&active_token, &session.auth_usernid, &session.session_id],
+                        ).await;
+                    }
+
+                    // Last seen cukup presisi per menit, hindari write di setiap request
+                    let _ = conn.execute(
+                        "UPDATE WEB_Cookies SET LastSeen = GETDATE() WHERE AuthUserNID = @P1 AND SessionID = @P2 AND (LastSeen IS NULL OR LastSeen < DATEADD(MINUTE, -1, GETDATE()))",
+                        &[&session.auth_usernid, &session.session_id],
+                    ).await;
+                } else if delete_session {
+                    // 🔵 Delete sesi device ini saja
+                    let _ = conn.execute(
+                        "DELETE FROM WEB_Cookies WHERE AuthUserNID = @P1 AND SessionID = @P2",
+                        &[&session.auth_usernid, &session.session_id],
+                    ).await;
+                } else if !cookies.is_empty() {
+                    // 🔵 Update cookies sesi device ini (setelah refresh)
+                    let updated = conn.execute(
+                        "UPDATE WEB_Cookies SET Cookies = @P1, LastUpdate = GETDATE(), LastSeen = GETDATE() WHERE AuthUserNID = @P2 AND SessionID = @P3",
+                        &[&cookies, &session.auth_usernid, &session.session_id],
+                    ).await.map_err(|e| format!("Query error: {:?}", e))?.total();
+
+                    if updated == 0 {
+                        return Ok(Some(t("auth.session_revoked")));
+                    }
+                } else {
+                    // Sesi baru dibuat lewat `SessionService::create`
+                    return Ok(Some(t("auth.session_not_found")));
+                }
+
+                Ok(None)
+            }.await,
+            None => Err("Failed to get connection from pool".into()),
+        };
+
+        match applied {
+            Ok(None) => {}
+            Ok(Some(message)) => {
+                let _ = trans.rollback().await;
+                result.message = message;
+                return result;
+            }
+            Err(err) => {
+                let _ = trans.rollback().await;
+                result.error = Some(err);
+                return result;
+            }
+        }
+
+        // 🔵 Commit transaksi
+        if let Err(err) = trans.commit().await {
+            result.error = Some(format!("Failed to commit transaction: {:?}", err));
+            return result;
+        }
+
+        result.result = true;
+        result.message = t("auth.active_login");
+
+        result
+    }
     
-                            if update_session {
-                                let _ = conn.execute(
-                                    "UPDATE WEB_Cookies SET Cookies = @P1, LastUpdate = GETDATE() WHERE AuthUserNID = @P2",
-                                    &[&active_token, &session.auth_usernid],
-                                ).await;
-                            }
     
-                            result.result = true;
-                        } else if delete_session {
-                            // 🔵 Delete session
-                            let _ = conn.execute(
-                                "DELETE FROM WEB_Cookies WHERE AuthUserNID = @P1 AND Cookies = @P2",
-                                &[&session.auth_usernid, &token],
-                            ).await;
-                            result.result = true;
-                        } else {
-                            if !cookies.is_empty() {
-                                // 🔵 Update cookies
-                                let _ = conn.execute(
-                                    "UPDATE WEB_Cookies SET Cookies = @P1, LastUpdate = GETDATE() WHERE AuthUserNID = @P2",
-                                    &[&cookies, &session.auth_usernid],
-                                ).await;
-                                result.result = true;
-                            } else {
-                                // 🔵 Cari cookies existing
-                                let row_option = {
-                                    let query_result = conn.query(
-                                        "SELECT Cookies, LastUpdate FROM WEB_Cookies WHERE AuthUserNID = @P1",
-                                        &[&session.auth_usernid],
-                                    ).await;
-
-                                    match query_result {
-                                        Ok(rows) => rows.into_row().await.ok().flatten(),
-                                        Err(e) => {
-                                            result.error = Some(format!("Query error: {:?}", e));
-                                            return result;
-                                        }
-                                    }
-                                }; // <- ❗️disini conn borrow selesai
+    fn claims_from_row(row: &Row, req: &HttpRequest, app_name: &str) -> Claims {
+        Claims {
+            auth_usernid: row.get("AuthUserNID").unwrap_or(0),
+            email: row.get::<&str, _>("Email").map_or_else(|| "".to_string(), |s| s.to_string()),
+            mobile_phone: row.get::<&str, _>("Handphone").map_or_else(|| "".to_string(), |s| s.to_string()),
+            disabled_login: row.get("disableLogin").unwrap_or(false),
+            picture: Some(row.get::<&str, _>("Picture").map_or_else(|| "".to_string(), |s| s.to_string())),
+            register_date: row
+                .get::<NaiveDateTime, _>("RegisterDate")
+                .map(|dt| dt.and_utc()) // 🔥 Konversi ke DateTime<Utc>
+                .unwrap_or_else(|| Utc.timestamp_opt(0, 0).unwrap()),
+            result: true,
+            expired_token: 0,
+            expired_date: "".to_string(),
+            exp: 0, // Default jika kosong,
+            comp_name: Some(GenericService::get_device_name(req)),
+            ip_address: Some(GenericService::get_ip_address(req)),
+            app_name: Some(app_name.to_string()),
+            roles: vec![],
+            permissions: vec![],
+            session_id: "".to_string(),
+            locale: row.get::<&str, _>("PreferredLocale").map(|s| s.to_string()),
+        }
+    }
 
-                                // lanjut bebas pakai conn lagi disini
-                                if let Some(row) = row_option {
-                                    let user_cookies: String = row.get::<&str, _>("Cookies").unwrap_or_default().to_string();
-                                    let last_update: chrono::NaiveDateTime = row.get("LastUpdate").unwrap_or_else(|| chrono::Utc::now().naive_utc());
+    /// Role & permission user untuk diisi ke claims JWT
+    async fn load_user_access(conn: &mut PooledConnection<'_, ConnectionManager>, auth_usernid: i32) -> Result<(Vec<String>, Vec<String>), tiberius::error::Error> {
+        let rows = conn.query(
+            r#"SELECT r.RoleName, p.PermissionCode
+            FROM AuthUserRole ur
+            JOIN Roles r ON r.RoleNID = ur.RoleNID
+            LEFT JOIN RolePermission rp ON rp.RoleNID = r.RoleNID
+            LEFT JOIN Permissions p ON p.PermissionNID = rp.PermissionNID
+            WHERE ur.AuthUserNID = @P1"#, &[&auth_usernid]).await?.into_first_result().await?;
 
-                                    let mut user_session = session.clone();
-                                    if let Ok(decoded_session) = validate_jwt(&user_cookies) {
-                                        user_session = decoded_session;
-                                    }
+        let mut roles: Vec<String> = vec![];
+        let mut permissions: Vec<String> = vec![];
 
-                                    let expired_dt = NaiveDateTime::parse_from_str(
-                                        &user_session.expired_date,
-                                        "%Y-%m-%d %H:%M:%S"
-                                    ).unwrap_or_else(|_| chrono::Utc::now().naive_utc());
-
-                                    if expired_dt > chrono::Utc::now().naive_utc() {
-                                        result.message = format!(
-                                            "This user ({}) with IP:{} is already logged in from another browser/machine (LastUpdate: {}), are you sure you want to kick this logged in user?",
-                                            session.email,
-                                            session.ip_address.clone().expect("IP address not found"),
-                                            last_update.format("%Y-%m-%d %H:%M")
-                                        );
-                                        
-                                        result.data = Some(session);
-                                        return result;
-                                    } else {
-                                        // 🔵 Expired, Update ke token baru
-                                        let _ = conn.execute(
-                                            "UPDATE WEB_Cookies SET Cookies = @P1, LastUpdate = GETDATE() WHERE AuthUserNID = @P2",
-                                            &[&token, &session.auth_usernid],
-                                        ).await;
-                                        result.result = true;
-                                    }
-                                } else {
-                                    // 🔵 Insert baru
-                                    print!("🔵 Insert baru");
-                                    let _ = conn.execute(
-                                        "INSERT INTO WEB_Cookies (AuthUserNID, Cookies, AppComputerName, AppIPAddress, LastUpdate) VALUES (@P1, @P2, @P3, @P4, GETDATE())",
-                                        &[&session.auth_usernid, &token, &session.comp_name, &session.ip_address],
-                                    ).await;
-                                    result.result = true;
-                                }
+        for row in rows {
+            if let Some(role) = row.get::<&str, _>("RoleName") {
+                if !roles.iter().any(|r| r == role) {
+                    roles.push(role.to_string());
+                }
+            }
+            if let Some(permission) = row.get::<&str, _>("PermissionCode") {
+                if !permissions.iter().any(|p| p == permission) {
+                    permissions.push(permission.to_string());
+                }
+            }
+        }
 
-                            }
-                        }
+        Ok((roles, permissions))
+    }
+
+    /// Permission terbaru jika sesi device masih ada di `WEB_Cookies`, dipakai WebSocket yang hidup lebih lama dari JWT.
+    /// `result = false` berarti sesi sudah dicabut / logout
+    pub async fn session_permissions(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, session_id: String) -> ActionResult<Vec<String>, String> {
+        let mut result: ActionResult<Vec<String>, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                let active = match conn.query(
+                    "SELECT COUNT(*) AS count FROM WEB_Cookies WHERE AuthUserNID = @P1 AND SessionID = @P2",
+                    &[&auth_usernid, &session_id],
+                ).await {
+                    Ok(rows) => match rows.into_row().await {
+                        Ok(Some(row)) => row.get::<i32, _>("count").unwrap_or(0) > 0,
+                        _ => false,
+                    },
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                        return result;
                     }
-                    None => {
-                        result.error = Some("Failed to get connection from pool".into());
+                };
+
+                if !active {
+                    result.message = t("auth.session_revoked");
+                    return result;
+                }
+
+                match Self::load_user_access(&mut conn, auth_usernid).await {
+                    Ok((_, permissions)) => {
+                        result.result = true;
+                        result.data = Some(permissions);
+                    }
+                    Err(err) => {
+                        result.error = Some(format!("Failed to load user roles: {:?}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    async fn insert_refresh_token(conn: &mut PooledConnection<'_, ConnectionManager>, auth_usernid: i32, family_id: &str, ip_address: &str) -> Result<String, tiberius::error::Error> {
+        let refresh_token = GenericService::random_string(64);
+
+        conn.execute(
+            r#"INSERT INTO [dbo].[WEB_RefreshToken] ([AuthUserNID], [TokenHash], [FamilyID], [IsRevoked], [ExpiredTime], [IPAddress], [CreatedTime])
+            VALUES (@P1, @P2, @P3, @P4, @P5, @P6, GETDATE())"#,
+            &[
+                &auth_usernid,
+                &hash_token(&refresh_token),
+                &family_id,
+                &false,
+                &(Utc::now() + Duration::days(REFRESH_TOKEN_DAYS)).naive_utc(),
+                &ip_address,
+            ],
+        ).await?;
+
+        Ok(refresh_token)
+    }
+
+    /// Buat refresh token baru (family baru) setelah login berhasil, `family_id` sekaligus jadi ID sesi device
+    pub async fn create_refresh_token(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, family_id: &str, req: &HttpRequest) -> ActionResult<String, String> {
+        let mut result: ActionResult<String, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match Self::insert_refresh_token(&mut conn, auth_usernid, family_id, &GenericService::get_ip_address(req)).await {
+                    Ok(token) => {
+                        result.result = true;
+                        result.message = t("auth.refresh_created");
+                        result.data = Some(token);
+                    }
+                    Err(err) => {
+                        result.error = Some(format!("Failed to insert refresh token: {:?}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    /// Rotasi refresh token: token lama ditandai terpakai, token baru diterbitkan di family yang sama.
+    /// Token yang sudah terpakai/dicabut dipakai lagi = indikasi dicuri, seluruh family dicabut.
+    pub async fn refresh_session(connection: web::Data<Pool<ConnectionManager>>, refresh_token: String, req: HttpRequest, app_name: &str) -> ActionResult<TokenPair, String> {
+        let mut result: ActionResult<TokenPair, String> = ActionResult::default();
+        let token_hash = hash_token(&refresh_token);
+
+        let stored = match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query(
+                    r#"SELECT RefreshTokenNID, AuthUserNID, FamilyID, UsedTime, IsRevoked, ExpiredTime
+                    FROM WEB_RefreshToken WHERE TokenHash = @P1"#, &[&token_hash]).await {
+                    Ok(rows) => rows.into_row().await.ok().flatten(),
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
                         return result;
                     }
                 }
-    
-                // 🔵 Commit transaksi
-                if let Err(err) = trans.commit().await {
-                    result.error = Some(format!("Failed to commit transaction: {:?}", err));
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+                return result;
+            }
+        };
+
+        let row = match stored {
+            Some(row) => row,
+            None => {
+                result.message = t("auth.refresh_invalid");
+                return result;
+            }
+        };
+
+        let token_nid: i32 = row.get("RefreshTokenNID").unwrap_or(0);
+        let auth_usernid: i32 = row.get("AuthUserNID").unwrap_or(0);
+        let family_id: String = row.get::<&str, _>("FamilyID").unwrap_or_default().to_string();
+        let already_used = row.get::<NaiveDateTime, _>("UsedTime").is_some();
+        let is_revoked: bool = row.get("IsRevoked").unwrap_or(true);
+        let expired_time: NaiveDateTime = row.get("ExpiredTime").unwrap_or_else(|| Utc::now().naive_utc());
+
+        if already_used || is_revoked {
+            Self::revoke_token_family(connection.clone(), &family_id, auth_usernid).await;
+            result.message = t("auth.refresh_reuse");
+            return result;
+        }
+
+        if expired_time <= Utc::now().naive_utc() {
+            result.message = t("auth.refresh_expired");
+            return result;
+        }
+
+        let trans = match Transaction::begin(&connection).await {
+            Ok(trans) => trans,
+            Err(err) => {
+                result.error = Some(format!("Failed to start transaction: {:?}", err));
+                return result;
+            }
+        };
+
+        let rotated: Result<Option<(Claims, String)>, tiberius::error::Error> = match trans.conn.lock().await.as_mut() {
+            Some(conn) => async {
+                // 🔵 Tandai token lama terpakai, hanya boleh sukses sekali
+                let marked = conn.execute(
+                    "UPDATE WEB_RefreshToken SET UsedTime = GETDATE() WHERE RefreshTokenNID = @P1 AND UsedTime IS NULL AND IsRevoked = 0",
+                    &[&token_nid],
+                ).await?.total();
+
+                if marked == 0 {
+                    return Ok(None);
+                }
+
+                let user_row = conn.query(
+                    r#"SELECT AuthUserNID, Email, Handphone, disableLogin, Picture, RegisterDate, PreferredLocale FROM AuthUser
+                    WHERE AuthUserNID = @P1"#, &[&auth_usernid]).await?.into_row().await?;
+
+                match user_row {
+                    Some(user_row) => {
+                        let mut claims = Self::claims_from_row(&user_row, &req, app_name);
+                        (claims.roles, claims.permissions) = Self::load_user_access(conn, auth_usernid).await?;
+                        claims.session_id = family_id.clone();
+                        let new_refresh = Self::insert_refresh_token(conn, auth_usernid, &family_id, &GenericService::get_ip_address(&req)).await?;
+                        Ok(Some((claims, new_refresh)))
+                    }
+                    None => Ok(None),
+                }
+            }.await,
+            None => {
+                result.error = Some("Failed to get connection from pool".into());
+                return result;
+            }
+        };
+
+        let (claims, new_refresh) = match rotated {
+            Ok(Some(rotated)) => rotated,
+            Ok(None) => {
+                let _ = trans.rollback().await;
+                // Kalah balapan dengan request lain yang memakai token yang sama
+                Self::revoke_token_family(connection.clone(), &family_id, auth_usernid).await;
+                result.message = t("auth.refresh_reuse");
+                return result;
+            }
+            Err(err) => {
+                let _ = trans.rollback().await;
+                result.error = Some(format!("Failed to rotate refresh token: {:?}", err));
+                return result;
+            }
+        };
+
+        // 🔵 Commit transaksi
+        if let Err(err) = trans.commit().await {
+            result.error = Some(format!("Failed to commit transaction: {:?}", err));
+            return result;
+        }
+
+        let access_token = match create_jwt(claims.clone()) {
+            Ok(token) => token,
+            Err(err) => {
+                result.error = Some(format!("Failed to create JWT: {}", err));
+                return result;
+            }
+        };
+
+        // 🔵 Simpan access token baru ke sesi device yang sama
+        let session = Self::check_session(connection, claims.clone(), access_token.clone(), access_token.clone(), false, false, false).await;
+        if session.error.is_some() {
+            result.error = session.error;
+            return result;
+        }
+        if !session.result {
+            result.message = session.message;
+            return result;
+        }
+
+        result.result = true;
+        result.message = t("auth.session_refreshed");
+        result.data = Some(TokenPair {
+            claims: Claims::new(claims),
+            access_token,
+            refresh_token: new_refresh,
+        });
+
+        result
+    }
+
+    /// Cabut refresh token (dan seluruh family-nya), dipakai saat logout
+    pub async fn revoke_refresh_token(connection: web::Data<Pool<ConnectionManager>>, refresh_token: String) -> ActionResult<(), String> {
+        let mut result: ActionResult<(), String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                // Sesi device milik family ini ikut dihapus
+                if let Err(err) = conn.execute(
+                    r#"DELETE FROM WEB_Cookies
+                    WHERE SessionID = (SELECT FamilyID FROM WEB_RefreshToken WHERE TokenHash = @P1);
+                    UPDATE WEB_RefreshToken SET IsRevoked = 1
+                    WHERE FamilyID = (SELECT FamilyID FROM WEB_RefreshToken WHERE TokenHash = @P1)"#,
+                    &[&hash_token(&refresh_token)],
+                ).await {
+                    result.error = Some(format!("Query execution failed: {:?}", err));
                     return result;
                 }
-    
                 result.result = true;
-                result.message = "User active login".to_string();
+                result.message = t("auth.refresh_revoked");
             }
             Err(err) => {
-                result.error = Some(format!("Failed to start transaction: {:?}", err));
+                result.error = Some(format!("Internal Server error: {:?}", err));
             }
         }
-    
+
         result
     }
-    
-    
+
+    async fn revoke_token_family(connection: web::Data<Pool<ConnectionManager>>, family_id: &str, auth_usernid: i32) {
+        match connection.get().await {
+            Ok(mut conn) => {
+                if let Err(err) = conn.execute(
+                    "UPDATE WEB_RefreshToken SET IsRevoked = 1 WHERE FamilyID = @P1",
+                    &[&family_id],
+                ).await {
+                    eprintln!("❌ Failed to revoke token family: {:?}", err);
+                }
+                // Access token device ini ikut dimatikan, sesi device lain tidak terpengaruh
+                let _ = conn.execute(
+                    "DELETE FROM WEB_Cookies WHERE AuthUserNID = @P1 AND SessionID = @P2",
+                    &[&auth_usernid, &family_id],
+                ).await;
+            }
+            Err(err) => eprintln!("❌ Failed to revoke token family: {:?}", err),
+        }
+    }
+
 }
\ No newline at end of file
@@ -1,111 +1,85 @@
-use std::collections::HashMap;
-use std::fmt::Write;
 use actix_web::web;
-use bb8::Pool;
+use bb8::{Pool, PooledConnection};
 use bb8_tiberius::ConnectionManager;
-use chrono::{NaiveDate, NaiveDateTime};
-use serde_json::{json, Value};
-use tiberius::{numeric::Numeric, ColumnType, Row};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
+use serde_json::Value;
+use tiberius::{numeric::Numeric, Row};
 
-use crate::contexts::{model::{ActionResult, QueryClass, ResultList, TableDataParams}};
+use crate::contexts::{
+    i18n::t,
+    jwt_session::Claims,
+    model::{ActionResult, DataView, ResultList, TableDataParams},
+    query_builder::{
+        parse_sort, ColumnKind, Cursor, QueryError, QueryParam, SortOrder, TableColumn, TableQuery, TableQueryBuilder, TableSchema, TotalMode,
+        ESTIMATE_COUNT_SQL
+    },
+    sql_json::{column_to_json, numeric_to_string, DecimalFormat}
+};
+
+/// User tidak punya `PermissionCode` dari registry `WEB_DataView`
+#[derive(Debug)]
+pub struct DataViewForbidden(pub String);
+
+impl std::fmt::Display for DataViewForbidden {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Missing permission '{}'", self.0)
+    }
+}
+
+impl std::error::Error for DataViewForbidden {}
 
 pub struct DataService;
 
 impl DataService {
+    /// Satu baris ke JSON object, decimal sebagai string persis (lihat `sql_json`)
     pub fn row_to_json(row: &Row) -> Value {
-        let mut json_obj = serde_json::Map::new();
-
-        for (i, col) in row.columns().iter().enumerate() {
-            let col_name = col.name();
-            let column_type = &col.column_type();
-
-            // println!("Column name: {}, Column type: {:?}", col_name, column_type);
+        Self::row_to_json_with(row, DecimalFormat::String)
+    }
 
-            match column_type {
-                ColumnType::NVarchar | ColumnType::NChar | ColumnType::BigChar | ColumnType::BigVarChar | ColumnType::Text => {
-                    if let Ok(Some(value)) = row.try_get::<&str, _>(i) {
-                        json_obj.insert(col_name.to_string(), json!(value));
-                    } else {
-                        json_obj.insert(col_name.to_string(), json!(null));
-                    }
-                },
-                ColumnType::Int4 | ColumnType::Int8 | ColumnType::Intn => {
-                    if let Ok(value) = row.try_get::<i32, _>(i) {
-                        json_obj.insert(col_name.to_string(), json!(value));
-                    } else {
-                        json_obj.insert(col_name.to_string(), json!(null));
-                    }
-                },
-                ColumnType::Bit => {
-                    if let Ok(value) = row.try_get::<bool, _>(i) {
-                        json_obj.insert(col_name.to_string(), json!(value));
-                    } else {
-                        json_obj.insert(col_name.to_string(), json!(null));
-                    }
-                },
-                ColumnType::Datetimen => {
-                    if let Ok(value) = row.try_get::<NaiveDateTime, _>(i) {
-                        json_obj.insert(col_name.to_string(), json!(value));
-                    } else {
-                        json_obj.insert(col_name.to_string(), json!(null));
-                    }
-                },
-                ColumnType::BigBinary => {
-                    if let Ok(value) = row.try_get::<&[u8], _>(i) {
-                        json_obj.insert(col_name.to_string(), json!(value));
-                    } else {
-                        json_obj.insert(col_name.to_string(), json!(null));
-                    }
-                },
-                ColumnType::Numericn => {
-                    if let Ok(Some(numeric)) = row.try_get::<Numeric, _>(i) {
-                        let raw_value = numeric.value();
-                        let scale = numeric.scale();
-                        let divisor = 10i128.pow(scale as u32);
-                        let int_part = raw_value / divisor;
-                        let frac_raw = raw_value.abs() % divisor;
-
-                        // Tentukan presisi berdasarkan scale
-                        let frac_str = format!("{:0>width$}", frac_raw, width = scale as usize);
-
-                        // Gabungkan bagian integer dan fraction (desimal)
-                        let formatted = format!("{}.{}", int_part, frac_str);
-                        
-                        // Jika ingin menyimpan sebagai float dengan presisi, kita convert ke f64
-                        let float_value = formatted.parse::<f64>().unwrap_or(0.0);  // Parsing string ke f64
-
-                        json_obj.insert(col_name.to_string(), json!(float_value));
-                    } else {
-                        json_obj.insert(col_name.to_string(), json!(null));
-                    }
-                },
-                _ => {
-                    json_obj.insert(col_name.to_string(), json!(null));
-                }
-            }
-        }
+    pub fn row_to_json_with(row: &Row, decimals: DecimalFormat) -> Value {
+        let json_obj = row.cells()
+            .map(|(column, data)| (column.name().to_string(), column_to_json(column.column_type(), data, decimals)))
+            .collect::<serde_json::Map<_, _>>();
 
         Value::Object(json_obj)
     }
 
     pub fn numeric_to_f64(num: &tiberius::numeric::Numeric) -> Option<f64> {
-        let raw = num.value();
-        let scale = num.scale() as u32;
-        let divisor = 10i128.checked_pow(scale)?;
-        let int_part = raw / divisor;
-        let frac_raw = raw.abs() % divisor;
-        let frac_str = format!("{:0>width$}", frac_raw, width = scale as usize);
-
-        let s = if raw < 0 && int_part == 0 {
-            format!("-0.{}", frac_str)
-        } else {
-            format!("{}.{}", int_part, frac_str)
+        numeric_to_string(*num).parse::<f64>().ok()
+    }
+
+    /// Nilai kolom urutan untuk cursor, dibaca langsung dari `Row` supaya presisi (datetime, decimal) tidak hilang
+    fn cursor_value(row: &Row, column: &TableColumn) -> Value {
+        let name = column.name.as_str();
+        let param = match column.kind {
+            ColumnKind::Text => row.try_get::<&str, _>(name).ok().flatten().map(|value| QueryParam::Text(value.to_string())),
+            ColumnKind::Guid => row.try_get::<tiberius::Uuid, _>(name).ok().flatten().map(|value| QueryParam::Text(value.to_string())),
+            ColumnKind::Int => row.try_get::<i32, _>(name).ok().flatten()
+                .or_else(|| row.try_get::<i16, _>(name).ok().flatten().map(i32::from))
+                .or_else(|| row.try_get::<u8, _>(name).ok().flatten().map(i32::from))
+                .map(QueryParam::Int),
+            ColumnKind::BigInt => row.try_get::<i64, _>(name).ok().flatten().map(QueryParam::BigInt),
+            ColumnKind::Decimal => row.try_get::<Numeric, _>(name).ok().flatten()
+                .map(|value| QueryParam::Text(numeric_to_string(value)))
+                // Money tanpa CAST (lihat `TableColumn::select_expr`) dikirim driver sebagai float dengan 4 digit desimal
+                .or_else(|| row.try_get::<f64, _>(name).ok().flatten().map(|value| QueryParam::Text(format!("{:.4}", value)))),
+            ColumnKind::Float => row.try_get::<f64, _>(name).ok().flatten()
+                .or_else(|| row.try_get::<f32, _>(name).ok().flatten().map(f64::from))
+                .map(QueryParam::Float),
+            ColumnKind::Bool => row.try_get::<bool, _>(name).ok().flatten().map(QueryParam::Bool),
+            ColumnKind::Date => row.try_get::<NaiveDate, _>(name).ok().flatten().map(QueryParam::Date),
+            ColumnKind::DateTime => row.try_get::<NaiveDateTime, _>(name).ok().flatten()
+                // datetimeoffset dibandingkan SQL Server dalam UTC
+                .or_else(|| row.try_get::<DateTime<FixedOffset>, _>(name).ok().flatten().map(|value| value.naive_utc()))
+                .map(QueryParam::DateTime),
+            ColumnKind::Other => None,
         };
 
-        s.parse::<f64>().ok()
+        param.map(|param| param.to_json()).unwrap_or(Value::Null)
     }
 
-    pub async fn get_header(connection: web::Data<Pool<ConnectionManager>>, tablename: String) -> ActionResult<Vec<serde_json::Value>, String> {
+    /// Definisi kolom dari `Web_CreateTableObject`, hanya kolom yang diizinkan registry
+    pub async fn get_header(connection: web::Data<Pool<ConnectionManager>>, view: &DataView) -> ActionResult<Vec<serde_json::Value>, String> {
         let mut result: ActionResult<Vec<serde_json::Value>, String> = ActionResult::default();
     
         match connection.clone().get().await {
@@ -116,7 +90,7 @@ impl DataService {
                     SELECT @Result AS Result;
                 "#;
     
-                match conn.query(sql, &[&tablename]).await {
+                match conn.query(sql, &[&view.view_name]).await {
                     Ok(rows) => {
                         let all_results = rows.into_results().await.unwrap();
     
@@ -129,12 +103,17 @@ impl DataService {
                         // Parse isi @Result ke Vec<serde_json::Value>
                         match serde_json::from_str::<Vec<serde_json::Value>>(output_result) {
                             Ok(parsed_json) => {
-                                result.data = Some(parsed_json);
+                                let columns = parsed_json.into_iter()
+                                    .filter(|column| column.get("field")
+                                        .and_then(Value::as_str)
+                                        .is_some_and(|field| view.allowed_columns.iter().any(|name| name.eq_ignore_ascii_case(field))))
+                                    .collect();
+                                result.data = Some(columns);
                                 result.result = true;
-                                result.message = "Data retrieved successfully".to_string();
+                                result.message = t("common.data_retrieved");
                             }
                             Err(e) => {
-                                result.message = "Failed to parse JSON".to_string();
+                                result.message = t("data.parse_failed");
                                 result.error = Some(e.to_string());
                             }
                         }
@@ -142,161 +121,336 @@ impl DataService {
                         return result;
                     }
                     Err(e) => {
-                        result.message = "Query failed".to_string();
+                        result.message = t("common.query_failed");
                         result.error = Some(e.to_string());
                         return result;
                     }
                 }
             }
             Err(e) => {
-                result.message = "Connection failed".to_string();
+                result.message = t("common.connection_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
         }
     }
 
-    pub async fn get_table_data(allparams: TableDataParams, connection: web::Data<Pool<ConnectionManager>>) -> Result<ResultList, Box<dyn std::error::Error>> {
-        let mut result = ResultList {
-            total_not_filtered: 0,
-            total: 0,
-            rows: vec![],
-        };
-    
-        let query = Self::get_query_table(allparams.clone(), false);
-    
-        let mut client = connection.get().await?;
-    
-        if !allparams.tablename.is_empty() {
-            let row: Option<Row> = client.query(query.query_total_all.clone(), &[]).await?.into_row().await?;
-            if let Some(r) = row {
-                result.total_not_filtered = r.try_get::<i32, _>(0)?.unwrap_or(0);
+    /// Kolom asli tabel / view beserta tipenya dari `INFORMATION_SCHEMA`, schema `dbo` didahulukan jika nama sama
+    pub async fn table_schema(conn: &mut PooledConnection<'_, ConnectionManager>, tablename: &str) -> Result<Option<TableSchema>, tiberius::error::Error> {
+        let rows = conn.query(
+            r#"SELECT TABLE_SCHEMA, TABLE_NAME, COLUMN_NAME, DATA_TYPE FROM INFORMATION_SCHEMA.COLUMNS
+            WHERE TABLE_NAME = @P1
+            ORDER BY CASE WHEN TABLE_SCHEMA = 'dbo' THEN 0 ELSE 1 END, TABLE_SCHEMA, ORDINAL_POSITION"#,
+            &[&tablename.trim()],
+        ).await?.into_first_result().await?;
+
+        let mut table: Option<TableSchema> = None;
+        for row in &rows {
+            let schema = row.get::<&str, _>("TABLE_SCHEMA").unwrap_or("dbo");
+            let column = TableColumn::from_data_type(
+                row.get::<&str, _>("COLUMN_NAME").unwrap_or(""),
+                row.get::<&str, _>("DATA_TYPE").unwrap_or(""),
+            );
+            match table.as_mut() {
+                Some(table) if table.schema == schema => table.columns.push(column),
+                Some(_) => break,
+                None => table = Some(TableSchema {
+                    schema: schema.to_string(),
+                    name: row.get::<&str, _>("TABLE_NAME").unwrap_or("").to_string(),
+                    columns: vec![column],
+                }),
             }
         }
-    
-        // Hitung total data yang sesuai filter
-        if let Some(filter) = &allparams.filter {
-            if filter != "{filter:undefined}" {
-                let row: Option<Row> = client.query(query.query_total_with_filter.clone(), &[]).await?.into_row().await?;
-                if let Some(r) = row {
-                    result.total = r.try_get::<i32, _>(0)?.unwrap_or(0);
-                }
-            }
-        } else {
-            result.total = result.total_not_filtered;
+
+        Ok(table)
+    }
+
+    /// Entri registry `WEB_DataView` yang aktif, sekaligus cek permission user.
+    /// Tabel yang tidak terdaftar dilaporkan sama dengan tabel yang tidak ada
+    pub async fn find_view(connection: web::Data<Pool<ConnectionManager>>, tablename: &str, claims: &Claims) -> Result<DataView, Box<dyn std::error::Error>> {
+        let mut conn = connection.get().await?;
+
+        let row = conn.query(
+            r#"SELECT ViewName, AllowedColumns, PermissionCode, PrimaryKey, DefaultSort, DefaultOrder, OwnerColumn, BypassPermission
+            FROM WEB_DataView WHERE ViewName = @P1 AND IsActive = 1"#,
+            &[&tablename.trim()],
+        ).await?.into_row().await?;
+
+        let view = row.map(|row| DataView {
+            view_name: row.get::<&str, _>("ViewName").unwrap_or_default().to_string(),
+            allowed_columns: row.get::<&str, _>("AllowedColumns").unwrap_or_default()
+                .split(',')
+                .map(|column| column.trim().to_string())
+                .filter(|column| !column.is_empty())
+                .collect(),
+            permission_code: row.get::<&str, _>("PermissionCode").unwrap_or_default().to_string(),
+            primary_key: row.get::<&str, _>("PrimaryKey").unwrap_or_default().to_string(),
+            default_sort: row.get::<&str, _>("DefaultSort").map(str::to_string),
+            default_order: row.get::<&str, _>("DefaultOrder").unwrap_or("DESC").to_string(),
+            owner_column: row.get::<&str, _>("OwnerColumn").map(str::to_string),
+            bypass_permission: row.get::<&str, _>("BypassPermission").map(str::to_string),
+        }).ok_or_else(|| QueryError::UnknownTable(tablename.to_string()))?;
+
+        if !claims.has_permission(&view.permission_code) {
+            return Err(Box::new(DataViewForbidden(view.permission_code)));
         }
-    
-        let rows = client.query(query.query.clone(), &[]).await?.into_results().await?;
-        result.rows = rows.into_iter()
-            .flat_map(|r| r.into_iter())
-            .map(|row| Self::row_to_json(&row))  // 🔥 Ubah `Row` ke JSON
-            .collect();
-    
-        Ok(result)
+
+        Ok(view)
     }
-    
-    fn get_query_table(allparams: TableDataParams, bypass_skip: bool) -> QueryClass {
-        let mut result = QueryClass {
-            query: String::new(),
-            query_total_all: String::new(),
-            query_total_with_filter: String::new(),
-        };
-    
-        if allparams.limit == 0 {
-            return result;
+
+    /// `sort` + `order` dari request (bisa beberapa kolom), atau `default` jika request tanpa sort
+    fn request_order<'a>(mut builder: TableQueryBuilder<'a>, params: &TableDataParams, default: Option<(&str, SortOrder)>) -> Result<TableQueryBuilder<'a>, QueryError> {
+        let sort = parse_sort(params.sort.as_deref(), params.order.as_deref())?;
+
+        if sort.is_empty() {
+            if let Some((column, order)) = default {
+                builder = builder.order_by(column, order)?;
+            }
         }
-    
-        let tablename = format!("[{}]", allparams.tablename);
-        let mut query_total_all = format!("SELECT count(*) as total FROM {}", tablename);
-        let mut q_and_where = String::from(" WHERE 1=1 ");
-        let mut q_order_by = String::new();
-        let mut q_skip_row = String::new();
-        let mut q_and_where_for_total_with_filter = String::from(" WHERE 1=1 ");
-    
-        // Gunakan `nidkey` sebagai primary key jika tersedia
-        let q_primary_key = allparams.nidkey.clone().unwrap_or_else(|| "AutoNID".to_string());
-        let q_primary_key_order = q_primary_key.clone();
-    
-        // Tambahkan filter jika ada
-        if let Some(filter) = &allparams.filter {
-            if filter != "{filter:undefined}" {
-                if let Ok(filter_name) = serde_json::from_str::<HashMap<String, String>>(filter) {
-                    if !filter_name.is_empty() {
-                        let q_and_where_result = Self::get_query_table_where(q_and_where.clone(), filter_name);
-    
-                        q_and_where = q_and_where_result.clone();
-                        q_and_where_for_total_with_filter = q_and_where_result.clone();
-                    }
-                }
+        for (column, order) in &sort {
+            builder = builder.order_by(column, *order)?;
+        }
+
+        Ok(builder)
+    }
+
+    /// Lanjutkan dari `cursor` request jika ada, cursor dari query lain (sort / filter berubah) ditolak
+    fn request_seek<'a>(builder: TableQueryBuilder<'a>, params: &TableDataParams) -> Result<(TableQueryBuilder<'a>, Option<Cursor>), QueryError> {
+        match params.cursor.as_deref().filter(|cursor| !cursor.trim().is_empty()) {
+            Some(token) => {
+                let cursor = Cursor::decode(token)?;
+                Ok((builder.seek(&cursor)?, Some(cursor)))
             }
+            None => Ok((builder, None)),
         }
-    
-        query_total_all.push_str(&q_and_where);
-    
-        let query_total_with_filter = format!(
-            "SELECT count(*) as totalWithFilter FROM {} {}",
-            tablename, q_and_where_for_total_with_filter
-        );
-    
-        result.query_total_with_filter = query_total_with_filter;
-    
-        // Sorting & pagination
-        if !bypass_skip {
-            if let Some(sort) = &allparams.sort {
-                if let Some(order) = &allparams.order {
-                    let _ = write!(q_order_by, " ORDER BY {} {}", sort, order);
-                }
-            } else {
-                let _ = write!(q_order_by, " ORDER BY {} DESC", q_primary_key_order);
+    }
+
+    fn checked_query(builder: TableQueryBuilder<'_>, cursor: Option<Cursor>) -> Result<TableQuery, QueryError> {
+        let query = builder.build();
+        match cursor {
+            Some(cursor) if cursor.spec != query.spec => Err(QueryError::InvalidCursor),
+            _ => Ok(query),
+        }
+    }
+
+    /// Query internal (tabel dipilih server, misal `AuditLog`). Identifier dicek ke `table`, semua nilai jadi parameter
+    pub fn build_table_query(table: &TableSchema, params: &TableDataParams) -> Result<TableQuery, QueryError> {
+        let mut builder = TableQueryBuilder::new(table)
+            .filter_json(params.filter.as_deref())?
+            .page(params.offset, params.limit);
+
+        // Tanpa sort: primary key `nidkey` terbaru dulu, dengan sort: `nidkey` jadi urutan terakhir
+        let nidkey = params.nidkey.as_deref().filter(|nidkey| !nidkey.trim().is_empty());
+        builder = Self::request_order(builder, params, nidkey.map(|nidkey| (nidkey, SortOrder::Desc)))?;
+        if let Some(nidkey) = nidkey {
+            builder = builder.tiebreak(nidkey, SortOrder::Desc)?;
+        }
+
+        let (builder, cursor) = Self::request_seek(builder, params)?;
+        Self::checked_query(builder, cursor)
+    }
+
+    /// Query `/data/get-table` untuk view terdaftar: kolom dibatasi `allowed_columns`,
+    /// baris dibatasi `owner_column`, primary key jadi urutan terakhir supaya paging stabil
+    pub fn build_view_query(table: &TableSchema, view: &DataView, params: &TableDataParams, claims: &Claims) -> Result<TableQuery, Box<dyn std::error::Error>> {
+        let visible = table.restricted(&view.allowed_columns);
+        if visible.columns.is_empty() {
+            return Err(format!("Data view '{}' has no allowed columns", view.view_name).into());
+        }
+
+        let mut builder = TableQueryBuilder::new(&visible);
+
+        let see_all = view.bypass_permission.as_deref().is_some_and(|permission| claims.has_permission(permission));
+        if let (Some(owner), false) = (view.owner_column.as_deref(), see_all) {
+            let owner = table.column(owner)
+                .ok_or_else(|| format!("Data view '{}' owner column '{}' not found", view.view_name, owner))?;
+            builder = builder.restrict(&owner.name, QueryParam::Int(claims.auth_usernid));
+        }
+
+        builder = builder
+            .filter_json(params.filter.as_deref())?
+            .page(params.offset, params.limit);
+
+        let default_order = SortOrder::parse(&view.default_order)?;
+        let default_sort = view.default_sort.as_deref().unwrap_or(&view.primary_key);
+        builder = Self::request_order(builder, params, Some((default_sort, default_order)))?
+            .tiebreak(&view.primary_key, default_order)?;
+
+        let (builder, cursor) = Self::request_seek(builder, params)?;
+        Ok(Self::checked_query(builder, cursor)?)
+    }
+
+    /// `total` & `totalWithFilter` sesuai `TotalMode`, `None` = tidak dihitung
+    async fn query_totals(client: &mut PooledConnection<'_, ConnectionManager>, query: &TableQuery, mode: TotalMode) -> Result<(Option<i32>, Option<i32>), Box<dyn std::error::Error>> {
+        // Perkiraan hanya untuk tabel utuh: view tidak punya partisi, batasan baris tidak tercermin di sys.partitions
+        if mode == TotalMode::Estimate && !query.scoped {
+            let row: Option<Row> = client.query(ESTIMATE_COUNT_SQL, &[&query.table.as_str()]).await?.into_row().await?;
+            let estimate = row.and_then(|row| row.try_get::<i64, _>(0).ok().flatten());
+            if let Some(estimate) = estimate {
+                let estimate = i32::try_from(estimate).unwrap_or(i32::MAX);
+                return Ok((Some(estimate), (!query.filtered).then_some(estimate)));
             }
-    
-            let _ = write!(
-                q_skip_row,
-                " OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
-                allparams.offset, allparams.limit
-            );
         }
-    
-        // Query utama
-        result.query = format!(
-            "SELECT * FROM {} {} {} {}",
-            tablename, q_and_where, q_order_by, q_skip_row
-        );
-    
-        result.query_total_all = query_total_all;
-        result
+
+        if mode == TotalMode::None {
+            return Ok((None, None));
+        }
+
+        let row: Option<Row> = client.query(query.count.as_str(), &query.filter_params()).await?.into_row().await?;
+        Ok(match row {
+            Some(r) => (r.try_get::<i32, _>("total")?, r.try_get::<i32, _>("totalWithFilter")?),
+            None => (Some(0), Some(0)),
+        })
     }
 
-    pub fn get_query_table_where(mut fquery: String, filter_name: HashMap<String, String>) -> String {
-        for (key, value) in filter_name {
-            if let Ok(temp_date) = NaiveDate::parse_from_str(&value, "%Y-%m-%d") {
-                if key.ends_with("Date") {
-                    let next_date = temp_date.succ_opt().unwrap_or(temp_date);
-                    let _ = write!(
-                        fquery,
-                        " AND {} BETWEEN '{}' AND '{}'",
-                        key, value, next_date
-                    );
-                } else {
-                    let _ = write!(fquery, " AND {} = '{}'", key, value);
-                }
-            } else if key.ends_with("Time") {
-                let dates: Vec<&str> = value.split("to").collect();
-                if dates.len() == 2 {
-                    let _ = write!(
-                        fquery,
-                        " AND {} BETWEEN '{} 00:00:00' AND '{} 23:59:59'",
-                        key, dates[0], dates[1]
-                    );
-                }
-            } else if key.starts_with('_') || key.ends_with("NID") || key.ends_with("ID") {
-                let _ = write!(fquery, " AND {} = '{}'", key, value);
-            } else {
-                let _ = write!(fquery, " AND {} LIKE '%{}%'", key, value);
+    async fn run_table_query(client: &mut PooledConnection<'_, ConnectionManager>, query: &TableQuery, params: &TableDataParams) -> Result<ResultList, Box<dyn std::error::Error>> {
+        let mode = TotalMode::parse(params.total.as_deref())?;
+        let (total_not_filtered, total) = Self::query_totals(client, query, mode).await?;
+
+        let mut result = ResultList {
+            total_not_filtered,
+            total,
+            rows: vec![],
+            next_cursor: None,
+            prev_cursor: None,
+        };
+
+        // FETCH NEXT 0 ROWS tidak valid di SQL Server, limit 0 = hanya total
+        if query.limit <= 0 {
+            return Ok(result);
+        }
+
+        let mut rows = client.query(query.select.as_str(), &query.select_params()).await?.into_first_result().await?;
+        // Baris ke `limit + 1` hanya penanda masih ada halaman lanjutan
+        let has_more = rows.len() > query.limit as usize;
+        rows.truncate(query.limit as usize);
+        if query.backward {
+            rows.reverse();
+        }
+
+        if !query.keyset.is_empty() {
+            let key = |row: &Row| query.keyset.iter().map(|column| Self::cursor_value(row, column)).collect::<Vec<_>>();
+            let came_from_next = params.cursor.as_deref().is_some_and(|cursor| !cursor.trim().is_empty()) && !query.backward;
+            let has_previous = if query.backward { has_more } else { came_from_next || params.offset > 0 };
+            let has_next = if query.backward { true } else { has_more };
+
+            if let (Some(first), true) = (rows.first(), has_previous) {
+                result.prev_cursor = Some(query.cursor(key(first), true).encode());
+            }
+            if let (Some(last), true) = (rows.last(), has_next) {
+                result.next_cursor = Some(query.cursor(key(last), false).encode());
             }
         }
-    
-        fquery
+
+        let decimals = match params.decimals.as_deref() {
+            Some(format) => DecimalFormat::parse(format).ok_or_else(|| QueryError::InvalidDecimals(format.to_string()))?,
+            None => DecimalFormat::String,
+        };
+        result.rows = rows.iter()
+            .map(|row| Self::row_to_json_with(row, decimals))  // 🔥 Ubah `Row` ke JSON
+            .collect();
+
+        Ok(result)
+    }
+
+    /// Tabel internal, hanya `columns` yang bisa dipilih, difilter & di-sort (kolom lain `QueryError::UnknownColumn`)
+    pub async fn get_table_data(allparams: TableDataParams, columns: &[&str], connection: web::Data<Pool<ConnectionManager>>) -> Result<ResultList, Box<dyn std::error::Error>> {
+        let mut client = connection.get().await?;
+
+        let table = Self::table_schema(&mut client, &allparams.tablename).await?
+            .ok_or_else(|| QueryError::UnknownTable(allparams.tablename.clone()))?
+            .restricted(&columns.iter().map(|column| column.to_string()).collect::<Vec<_>>());
+        let query = Self::build_table_query(&table, &allparams)?;
+
+        Self::run_table_query(&mut client, &query, &allparams).await
+    }
+
+    /// `/data/get-table`: `view` dari `find_view`, permission sudah dicek
+    pub async fn get_view_data(view: &DataView, allparams: TableDataParams, claims: &Claims, connection: web::Data<Pool<ConnectionManager>>) -> Result<ResultList, Box<dyn std::error::Error>> {
+        let mut client = connection.get().await?;
+
+        let table = Self::table_schema(&mut client, &view.view_name).await?
+            .ok_or_else(|| QueryError::UnknownTable(view.view_name.clone()))?;
+        let query = Self::build_view_query(&table, view, &allparams, claims)?;
+
+        Self::run_table_query(&mut client, &query, &allparams).await
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn table() -> TableSchema {
+        TableSchema {
+            schema: "dbo".to_string(),
+            name: "AuditLog".to_string(),
+            columns: vec![
+                TableColumn { name: "AuditNID".to_string(), kind: ColumnKind::Int, money: false },
+                TableColumn { name: "Action".to_string(), kind: ColumnKind::Text, money: false },
+            ],
+        }
+    }
+
+    fn params(filter: Option<&str>, sort: Option<&str>, cursor: Option<String>) -> TableDataParams {
+        TableDataParams {
+            tablename: "AuditLog".to_string(),
+            limit: 10,
+            offset: 0,
+            filter: filter.map(str::to_string),
+            sort: sort.map(str::to_string),
+            order: None,
+            nidkey: Some("AuditNID".to_string()),
+            cursor,
+            total: None,
+            decimals: None,
+        }
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn cursor_from_same_query_is_accepted() {
+        let table = table();
+        let first = DataService::build_table_query(&table, &params(Some(r#"{"Action":"login"}"#), None, None)).unwrap();
+        let token = first.cursor(vec![json!(42)], false).encode();
+
+        let next = DataService::build_table_query(&table, &params(Some(r#"{"Action":"login"}"#), None, Some(token))).unwrap();
+        assert_eq!(next.spec, first.spec);
+        assert!(next.select.contains("[AuditNID] < @P2"));
+    }
+
+    #[test]
+    fn cursor_from_other_query_is_rejected() {
+        let table = table();
+        let first = DataService::build_table_query(&table, &params(Some(r#"{"Action":"login"}"#), None, None)).unwrap();
+        let token = first.cursor(vec![json!(42)], false).encode();
+
+        let other_filter = DataService::build_table_query(&table, &params(Some(r#"{"Action":"logout"}"#), None, Some(token.clone())));
+        assert_eq!(other_filter.err(), Some(QueryError::InvalidCursor));
+
+        let other_sort = DataService::build_table_query(&table, &params(Some(r#"{"Action":"login"}"#), Some("Action"), Some(token)));
+        assert_eq!(other_sort.err(), Some(QueryError::InvalidCursor));
+
+        let garbage = DataService::build_table_query(&table, &params(None, None, Some("garbage".to_string())));
+        assert_eq!(garbage.err(), Some(QueryError::InvalidCursor));
+    }
+
+    #[test]
+    fn columns_outside_restriction_are_rejected() {
+        let table = table().restricted(&["AuditNID".to_string(), "Action".to_string()]);
+        let typed = DataService::build_table_query(&table, &params(Some(r#"{"field":"Action","op":"in","value":["login","logout"]}"#), None, None));
+        assert!(typed.is_ok());
+
+        let restricted = table.restricted(&["AuditNID".to_string()]);
+        let filter = DataService::build_table_query(&restricted, &params(Some(r#"{"Action":"login"}"#), None, None));
+        assert_eq!(filter.err(), Some(QueryError::UnknownColumn("Action".to_string())));
+
+        let sort = DataService::build_table_query(&restricted, &params(None, Some("Action"), None));
+        assert_eq!(sort.err(), Some(QueryError::UnknownColumn("Action".to_string())));
+
+        let invalid = DataService::build_table_query(&table, &params(Some("{not json"), None, None));
+        assert!(invalid.is_err());
+    }
+}
@@ -9,7 +9,7 @@ use futures::StreamExt;
 use tiberius::{numeric::Numeric};
 use tokio::io::AsyncWriteExt;
 use umya_spreadsheet::*;
-use crate::contexts::model::{ActionResult, ReportRow};
+use crate::contexts::{i18n::{t, t_args}, model::{ActionResult, ReportRow}};
 
 use super::data_service::DataService;
 
@@ -23,6 +23,34 @@ use super::data_service::DataService;
 pub struct ExportService;
 
 impl ExportService {
+    /// CSV dari baris hasil `DataService::row_to_json`, urutan kolom mengikuti `columns`
+    pub fn rows_to_csv(columns: &[&str], rows: &[serde_json::Value]) -> String {
+        let escape = |s: &str| {
+            if s.contains(',') || s.contains('"') || s.contains('\n') || s.contains('\r') {
+                format!("\"{}\"", s.replace('"', "\"\""))
+            } else {
+                s.to_string()
+            }
+        };
+
+        let mut csv = columns.join(",");
+        csv.push_str("\r\n");
+
+        for row in rows {
+            let line: Vec<String> = columns.iter().map(|column| match row.get(*column) {
+                // Cegah formula injection saat CSV dibuka di spreadsheet
+                Some(serde_json::Value::String(value)) if value.starts_with(['=', '+', '-', '@']) => escape(&format!("'{}", value)),
+                Some(serde_json::Value::String(value)) => escape(value),
+                Some(serde_json::Value::Null) | None => String::new(),
+                Some(value) => escape(&value.to_string()),
+            }).collect();
+            csv.push_str(&line.join(","));
+            csv.push_str("\r\n");
+        }
+
+        csv
+    }
+
     pub async fn export_to_csv_file<P: AsRef<Path>>(connection: web::Data<Pool<ConnectionManager>>, output_path: P) -> ActionResult<String, String> {
         let mut result = ActionResult::default();
 
@@ -30,7 +58,7 @@ impl ExportService {
         let mut conn: PooledConnection<ConnectionManager> = match connection.get().await {
             Ok(c) => c,
             Err(e) => {
-                result.message = "Failed to get DB connection".into();
+                result.message = t("common.connection_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
@@ -48,7 +76,7 @@ impl ExportService {
         let mut stream = match rows_result {
             Ok(r) => r.into_row_stream(),
             Err(e) => {
-                result.message = "Failed to query".into();
+                result.message = t("common.query_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
@@ -96,7 +124,7 @@ impl ExportService {
                     ));
                 }
                 Err(e) => {
-                    result.message = "Error fetching row".into();
+                    result.message = t("export.row_failed");
                     result.error = Some(e.to_string());
                     return result;
                 }
@@ -107,7 +135,7 @@ impl ExportService {
         let path = output_path.as_ref();
         if let Some(dir) = path.parent() {
             if let Err(e) = tokio::fs::create_dir_all(dir).await {
-                result.message = "Failed to create dir".into();
+                result.message = t("export.dir_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
@@ -116,12 +144,12 @@ impl ExportService {
         match tokio::fs::write(path, csv).await {
             Ok(_) => {
                 result.result = true;
-                result.message = "Export successful".into();
+                result.message = t_args("export.success", &[("format", &"CSV")]);
                 result.data = Some(path.to_string_lossy().to_string());
                 result
             }
             Err(e) => {
-                result.message = "Failed to write file".into();
+                result.message = t("export.write_failed");
                 result.error = Some(e.to_string());
                 result
             }
@@ -134,7 +162,7 @@ impl ExportService {
         let mut conn: PooledConnection<ConnectionManager> = match connection.get().await {
             Ok(c) => c,
             Err(e) => {
-                result.message = "Failed to get DB connection".into();
+                result.message = t("common.connection_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
@@ -149,7 +177,7 @@ impl ExportService {
         let mut stream = match row_result {
             Ok(r) => r.into_row_stream(),
             Err(e) => {
-                result.message = "Failed to query".into();
+                result.message = t("common.query_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
@@ -158,7 +186,7 @@ impl ExportService {
         let path = output_path.as_ref();
         if let Some(dir) = path.parent() {
             if let Err(e) = tokio::fs::create_dir_all(dir).await {
-                result.message = "Failed to create dir".into();
+                result.message = t("export.dir_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
@@ -167,7 +195,7 @@ impl ExportService {
         let mut file = match tokio::fs::File::create(path).await {
             Ok(f) => f,
             Err(e) => {
-                result.message = "Failed to create file".into();
+                result.message = t("export.write_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
@@ -177,7 +205,7 @@ impl ExportService {
             .write_all(b"Email|FullName|Age|Sex|Contact|ProductName|ProductCount|Price|IPAddress\r\n")
             .await
         {
-            result.message = "Failed to write header".into();
+            result.message = t("export.write_failed");
             result.error = Some(e.to_string());
             return result;
         }
@@ -212,13 +240,13 @@ impl ExportService {
                     );
 
                     if let Err(e) = file.write_all(line.as_bytes()).await {
-                        result.message = "Failed to write row".into();
+                        result.message = t("export.write_failed");
                         result.error = Some(e.to_string());
                         return result;
                     }
                 }
                 Err(e) => {
-                    result.message = "Error fetching row".into();
+                    result.message = t("export.row_failed");
                     result.error = Some(e.to_string());
                     return result;
                 }
@@ -226,7 +254,7 @@ impl ExportService {
         }
 
         result.result = true;
-        result.message = "Export TXT successful".into();
+        result.message = t_args("export.success", &[("format", &"TXT")]);
         result.data = Some(path.to_string_lossy().to_string());
 
         result
@@ -239,7 +267,7 @@ impl ExportService {
         let mut conn = match connection.get().await {
             Ok(c) => c,
             Err(e) => {
-                result.message = "Failed to get DB connection".into();
+                result.message = t("common.connection_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
@@ -257,7 +285,7 @@ impl ExportService {
         let mut stream = match rows_result {
             Ok(r) => r.into_row_stream(),
             Err(e) => {
-                result.message = "Failed to query".into();
+                result.message = t("common.query_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
@@ -319,7 +347,7 @@ impl ExportService {
                 }
                 Err(e) => {
                     println!("Error fetching row: {}", e);
-                    result.message = "Error fetching row".into();
+                    result.message = t("export.row_failed");
                     result.error = Some(e.to_string());
                     return result;
                 }
@@ -330,7 +358,7 @@ impl ExportService {
         let path = output_path.as_ref();
         if let Some(dir) = path.parent() {
             if let Err(e) = tokio::fs::create_dir_all(dir).await {
-                result.message = "Failed to create directory".into();
+                result.message = t("export.dir_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
@@ -338,13 +366,13 @@ impl ExportService {
 
         let _ = book.remove_sheet_by_name("Sheet1");
         if let Err(e) = writer::xlsx::write(&book, path) {
-            result.message = "Failed to write XLSX".into();
+            result.message = t("export.write_failed");
             result.error = Some(e.to_string());
             return result;
         }
 
         result.result = true;
-        result.message = "Export XLSX successful".into();
+        result.message = t_args("export.success", &[("format", &"XLSX")]);
         result.data = Some(path.to_string_lossy().to_string());
         result
     }
@@ -356,7 +384,7 @@ impl ExportService {
         let mut conn: PooledConnection<ConnectionManager> = match connection.get().await {
             Ok(c) => c,
             Err(e) => {
-                result.message = "Failed to get DB connection".into();
+                result.message = t("common.connection_failed");
                 result.error   = Some(e.to_string());
                 return result;
             }
@@ -371,7 +399,7 @@ impl ExportService {
         let stream = match conn.query(query, &[]).await {
             Ok(r) => r.into_row_stream(),
             Err(e) => {
-                result.message = "Failed to query".into();
+                result.message = t("common.query_failed");
                 result.error   = Some(e.to_string());
                 return result;
             }
@@ -381,7 +409,7 @@ impl ExportService {
         let path = output_path.as_ref();
         if let Some(dir) = path.parent() {
             if let Err(e) = tokio::fs::create_dir_all(dir).await {
-                result.message = "Failed to create dir".into();
+                result.message = t("export.dir_failed");
                 result.error   = Some(e.to_string());
                 return result;
             }
@@ -391,7 +419,7 @@ impl ExportService {
         let mut file = match tokio::fs::File::create(path).await {
             Ok(f) => f,
             Err(e) => {
-                result.message = "Failed to create file".into();
+                result.message = t("export.write_failed");
                 result.error   = Some(e.to_string());
                 return result;
             }
@@ -399,7 +427,7 @@ impl ExportService {
 
         // 5. Tulis header XML
         if let Err(e) = file.write_all(b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Records>\n").await {
-            result.message = "Failed to write XML header".into();
+            result.message = t("export.write_failed");
             result.error   = Some(e.to_string());
             return result;
         }
@@ -447,13 +475,13 @@ impl ExportService {
                     );
 
                     if let Err(e) = file.write_all(xml.as_bytes()).await {
-                        result.message = "Failed to write record".into();
+                        result.message = t("export.write_failed");
                         result.error   = Some(e.to_string());
                         return result;
                     }
                 }
                 Err(e) => {
-                    result.message = "Error fetching row".into();
+                    result.message = t("export.row_failed");
                     result.error   = Some(e.to_string());
                     return result;
                 }
@@ -462,13 +490,13 @@ impl ExportService {
 
         // 7. Tulis footer & selesai
         if let Err(e) = file.write_all(b"</Records>") .await {
-            result.message = "Failed to write XML footer".into();
+            result.message = t("export.write_failed");
             result.error   = Some(e.to_string());
             return result;
         }
 
         result.result = true;
-        result.message = "Export XML successful".into();
+        result.message = t_args("export.success", &[("format", &"XML")]);
         result.data    = Some(path.to_string_lossy().to_string());
         result
     }
@@ -483,7 +511,7 @@ impl ExportService {
         let mut conn = match pool.get().await {
             Ok(c) => c,
             Err(e) => {
-                result.message = "Failed to get DB connection".into();
+                result.message = t("common.connection_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
@@ -498,7 +526,7 @@ impl ExportService {
         let mut stream = match conn.query(query, &[]).await {
             Ok(r) => r.into_row_stream(),
             Err(e) => {
-                result.message = "Query failed".into();
+                result.message = t("common.query_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
@@ -541,7 +569,7 @@ impl ExportService {
                     });
                 }
                 Err(e) => {
-                    result.message = "Error reading row".into();
+                    result.message = t("export.row_failed");
                     result.error = Some(e.to_string());
                     return result;
                 }
@@ -562,7 +590,7 @@ impl ExportService {
         // 5️⃣ Simpan HTML sementara ke file
         let tmp_html_path = format!("templates/exports/{}.html", chrono::Utc::now().timestamp_millis());
         if let Err(e) = tokio::fs::write(&tmp_html_path, html).await {
-            result.message = "Failed to write temporary HTML file".into();
+            result.message = t("export.write_failed");
             result.error = Some(e.to_string());
             return result;
         }
@@ -589,22 +617,22 @@ impl ExportService {
                 let _ = tokio::fs::remove_file(&tmp_html_path);
 
                 result.result = true;
-                result.message = "PDF exported successfully".into();
+                result.message = t_args("export.success", &[("format", &"PDF")]);
                 result.data = Some(output_path_owned.to_string_lossy().into());
                 result
             }
             Ok(Ok(status)) => {
-                result.message = "wkhtmltopdf exited with error".into();
+                result.message = t("export.pdf_failed");
                 result.error = Some(format!("Exit code: {:?}", status.code()));
                 result
             }
             Ok(Err(e)) => {
-                result.message = "Failed to run wkhtmltopdf".into();
+                result.message = t("export.pdf_failed");
                 result.error = Some(e.to_string());
                 result
             }
             Err(e) => {
-                result.message = "Failed to spawn blocking task".into();
+                result.message = t("export.pdf_failed");
                 result.error = Some(e.to_string());
                 result
             }
@@ -617,7 +645,7 @@ impl ExportService {
         let path = output_path.as_ref();
         if let Some(dir) = path.parent() {
             if let Err(e) = tokio::fs::create_dir_all(dir).await {
-                result.message = "Failed to create dir".into();
+                result.message = t("export.dir_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
@@ -627,7 +655,7 @@ impl ExportService {
         let mut conn = match connection.get().await {
             Ok(c) => c,
             Err(e) => {
-                result.message = "Failed to get DB connection".into();
+                result.message = t("common.connection_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
@@ -645,7 +673,7 @@ impl ExportService {
         let mut stream = match rows_result {
             Ok(r) => r.into_row_stream(),
             Err(e) => {
-                result.message = "Failed to query".into();
+                result.message = t("common.query_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
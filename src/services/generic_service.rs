@@ -1,14 +1,20 @@
 use actix_web::{error, web, HttpRequest, HttpResponse, Responder};
 use bb8::Pool;
 use bb8_tiberius::ConnectionManager;
+use once_cell::sync::OnceCell;
 use serde_json::json;
+use std::net::IpAddr;
 use tiberius::QueryStream;
 use rand::{rng, Rng};
+use shuttle_runtime::SecretStore;
 
-use crate::contexts::{model::{ActionResult, Company, MyRow}, socket::send_ws_event};
+use crate::contexts::{i18n::t, model::{ActionResult, Company, MyRow}, socket::send_ws_event};
 
 pub struct GenericService;
 
+/// Reverse proxy yang boleh mengisi `X-Forwarded-For`, dari secret `TRUSTED_PROXIES` (IP dipisah koma)
+static TRUSTED_PROXIES: OnceCell<Vec<IpAddr>> = OnceCell::new();
+
 impl GenericService {
     pub async fn get_company(connection: web::Data<Pool<ConnectionManager>>) -> ActionResult<Company, String> {
         let mut result = ActionResult::default();
@@ -22,7 +28,7 @@ impl GenericService {
                     Ok(rows) => {
                         if let Ok(Some(row)) = rows.into_row().await {
                             result.result = true;
-                            result.message = "Company name".to_string();
+                            result.message = t("generic.company");
                             result.data = Some(Company {
                                 company_id: row
                                     .get::<&str, _>("CompanyID")
@@ -33,12 +39,12 @@ impl GenericService {
                             });
                             return result;
                         } else {
-                            result.message = "No company found".to_string();
+                            result.message = t("generic.company_not_found");
                             return result;
                         }
                     }
                     Err(e) => {
-                        result.message = "Internal Server Error".to_string();
+                        result.message = t("common.internal_error");
                         result.error = Some(e.to_string());
                         return result;
                     }
@@ -57,7 +63,7 @@ impl GenericService {
         let result = ActionResult::<String, _> {
             // <- Ubah dari ActionResult<()> ke ActionResult<String>
             result: false,
-            message: "Invalid Request".to_string(),
+            message: t("common.invalid_request"),
             error: Some(error_message), // <- Sekarang cocok karena `data: Option<String>`
             data: None,
         };
@@ -76,7 +82,7 @@ impl GenericService {
         HttpResponse::NotFound().json({
             json!({
                 "result": false,
-                "message": "Not Found",
+                "message": t("common.not_found"),
                 "error": format!("Url '{}' not found. Please check the URL.", req.path())
             })
         })
@@ -94,16 +100,49 @@ impl GenericService {
             .collect()
     }
 
+    /// Dipanggil sekali saat startup. Tanpa secret, `X-Forwarded-For` diabaikan dan IP diambil dari koneksi langsung
+    pub fn init_trusted_proxies(secrets: &SecretStore) -> Result<(), String> {
+        let proxies = secrets.get("TRUSTED_PROXIES").unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|ip| !ip.is_empty())
+            .map(|ip| ip.parse::<IpAddr>().map_err(|_| format!("Invalid TRUSTED_PROXIES entry: {}", ip)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        TRUSTED_PROXIES.set(proxies).map_err(|_| "Trusted proxies already initialized".to_string())
+    }
+
     pub fn get_ip_address(req: &HttpRequest) -> String {
-        req.headers()
+        let forwarded = req.headers()
             .get("X-Forwarded-For") // Jika pakai reverse proxy seperti Nginx
-            .and_then(|ip| ip.to_str().ok())
-            .map_or_else(
-                || req.peer_addr()
-                    .map(|addr| addr.ip().to_string())
-                    .unwrap_or_else(|| "Unknown IP".to_string()),
-                |ip| ip.to_string(),
-            )
+            .and_then(|ip| ip.to_str().ok());
+        let trusted = TRUSTED_PROXIES.get().map_or(&[][..], Vec::as_slice);
+
+        Self::client_ip(req.peer_addr().map(|addr| addr.ip()), forwarded, trusted)
+            .map_or_else(|| "Unknown IP".to_string(), |ip| ip.to_string())
+    }
+
+    /// IP koneksi langsung, kecuali koneksi datang dari proxy terpercaya. `X-Forwarded-For` lalu dibaca dari kanan
+    /// (entri yang ditambahkan proxy) melewati proxy terpercaya lain, karena entri paling kiri bebas diisi client
+    fn client_ip(peer: Option<IpAddr>, forwarded: Option<&str>, trusted: &[IpAddr]) -> Option<IpAddr> {
+        let mut client = peer?;
+        if !trusted.contains(&client) {
+            return Some(client);
+        }
+
+        for entry in forwarded.unwrap_or_default().rsplit(',') {
+            match entry.trim().parse::<IpAddr>() {
+                Ok(ip) => {
+                    client = ip;
+                    if !trusted.contains(&ip) {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+
+        Some(client)
     }
 
     pub fn get_device_name(req: &HttpRequest) -> String {
@@ -118,6 +157,25 @@ impl GenericService {
         return test
     }
 
+    pub fn get_user_agent(req: &HttpRequest) -> String {
+        req.headers()
+            .get("User-Agent")
+            .and_then(|ua| ua.to_str().ok())
+            .map_or_else(|| "Unknown".to_string(), |ua| ua.chars().take(512).collect())
+    }
+
+    /// Link ke halaman frontend dari secret `FRONTEND_BASE_URL`
+    pub fn frontend_url(secrets: &SecretStore, path: &str) -> String {
+        let base = secrets.get("FRONTEND_BASE_URL").unwrap_or_else(|| "https://snakesystem.github.io".to_string());
+        format!("{}/{}", base.trim_end_matches('/'), path.trim_start_matches('/'))
+    }
+
+    /// Base URL API ini (scheme + host), ikut `X-Forwarded-Proto`/`X-Forwarded-Host` jika di belakang proxy
+    pub fn api_base_url(req: &HttpRequest) -> String {
+        let info = req.connection_info();
+        format!("{}://{}", info.scheme(), info.host())
+    }
+
     pub fn is_localhost_origin(req: &HttpRequest) -> bool {
         if let Some(origin) = req.headers().get("Origin") {
             if let Ok(origin_str) = origin.to_str() {
@@ -127,19 +185,6 @@ impl GenericService {
         false
     }
 
-    pub fn get_secret_key() -> [u8; 32] {
-        let key_str = std::env::var("JWT_KEY").expect("JWT_KEY not set");
-        let key_bytes = key_str.as_bytes();
-
-        if key_bytes.len() != 32 {
-            panic!("JWT_KEY must be exactly 32 bytes");
-        }
-
-        let mut key_array = [0u8; 32];
-        key_array.copy_from_slice(&key_bytes[..32]);
-        key_array
-    }
-
     pub fn slugify(title: &str) -> String {
         title
             .to_lowercase()
@@ -200,4 +245,40 @@ impl GenericService {
         }));
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn forwarded_for_ignored_without_trusted_proxy() {
+        let client = GenericService::client_ip(Some(ip("203.0.113.7")), Some("198.51.100.1"), &[]);
+        assert_eq!(client, Some(ip("203.0.113.7")));
+    }
+
+    #[test]
+    fn spoofed_left_most_entry_is_ignored() {
+        let proxy = ip("10.0.0.2");
+        // Client mengirim "1.1.1.1", proxy menambahkan IP asli client di kanan
+        let client = GenericService::client_ip(Some(proxy), Some("1.1.1.1, 203.0.113.7"), &[proxy]);
+        assert_eq!(client, Some(ip("203.0.113.7")));
+
+        // Rantai proxy terpercaya dilewati
+        let edge = ip("10.0.0.1");
+        let client = GenericService::client_ip(Some(proxy), Some("1.1.1.1, 203.0.113.7, 10.0.0.1"), &[proxy, edge]);
+        assert_eq!(client, Some(ip("203.0.113.7")));
+    }
+
+    #[test]
+    fn invalid_forwarded_entry_falls_back_to_proxy() {
+        let proxy = ip("10.0.0.2");
+        let client = GenericService::client_ip(Some(proxy), Some("not-an-ip"), &[proxy]);
+        assert_eq!(client, Some(proxy));
+        assert_eq!(GenericService::client_ip(Some(proxy), None, &[proxy]), Some(proxy));
+    }
+}
@@ -10,7 +10,7 @@ use chrono::Utc;
 use csv_async::AsyncReaderBuilder;
 use tokio::{fs::File, io::{AsyncBufReadExt, BufReader}};
 
-use crate::contexts::{connection::Transaction, model::ActionResult, socket::send_ws_event};
+use crate::contexts::{connection::Transaction, i18n::{t, t_args}, model::ActionResult, socket::send_ws_event};
 
 pub struct ImportService;
 
@@ -22,7 +22,7 @@ impl ImportService {
         let total_count = match Self::count_csv_rows(&file_path).await {
             Ok(count) => count,
             Err(err) => {
-                result.message = "File open error".to_string();
+                result.message = t("import.file_open_error");
                 result.error = Some(format!("Failed to open file: {}", err));
                 return result;
             }
@@ -31,7 +31,7 @@ impl ImportService {
         let trans = match Transaction::begin(&connection).await {
             Ok(trans) => trans,
             Err(err) => {
-                result.message = "Internal server error".to_string();
+                result.message = t("common.internal_error");
                 result.error = Some(format!("Failed to begin transaction: {}", err));
                 return result;
             }
@@ -42,7 +42,7 @@ impl ImportService {
                 let file = match File::open(&file_path).await {
                     Ok(f) => f,
                     Err(err) => {
-                        result.message = "File open error".to_string();
+                        result.message = t("import.file_open_error");
                         result.error = Some(format!("Failed to open file: {}", err));
                         return result;
                     }
@@ -63,7 +63,7 @@ impl ImportService {
                         match record {
                             Ok(row) => row,
                             Err(e) => {
-                                result.message = "CSV parse error".to_string();
+                                result.message = t_args("import.parse_error", &[("format", &"CSV")]);
                                 result.error = Some(format!("Failed to parse row: {}", e));
                                 return result;
                             }
@@ -93,14 +93,14 @@ impl ImportService {
                             send_ws_event("import_progress", &progress);
                         },
                         Err(e) => {
-                            result.message = "Insert error".to_string();
+                            result.message = t("import.insert_error");
                             result.error = Some(format!("Query failed: {}", e));
                         }
                     }
                 }
             }
             None => {
-                result.message = "Internal server error".to_string();
+                result.message = t("common.internal_error");
                 result.error = Some("Failed to get connection from pool".to_string());
                 return result;
             }
@@ -113,21 +113,21 @@ impl ImportService {
                 "message": result.message.clone()
             }));
             if let Err(e) = trans.commit().await {
-                result.message = "Failed to commit".to_string();
+                result.message = t("import.commit_failed");
                 result.error = Some(format!("Commit error: {}", e));
                 return result;
             }
             result.result = true;
-            result.message = format!("Berhasil insert {} baris.", rowsaffected);
+            result.message = t_args("import.inserted", &[("count", &rowsaffected)]);
         } else {
             send_ws_event("import_error", serde_json::json!({
                 "result": false,
                 "imported": rowsaffected,
-                "message": "Insert error",
+                "message": t("import.insert_error"),
                 "error": result.error.clone()
             }));
             trans.rollback().await.ok();
-            result.message = "Tidak ada data yang di-insert.".to_string();
+            result.message = t("import.nothing_inserted");
         }
 
         result
@@ -141,7 +141,7 @@ impl ImportService {
         let total_count = match Self::count_txt_lines(&file_path, false).await {
             Ok(count) => count,
             Err(err) => {
-                result.message = "File open error".to_string();
+                result.message = t("import.file_open_error");
                 result.error = Some(format!("Failed to open file: {}", err));
                 return result;
             }
@@ -150,7 +150,7 @@ impl ImportService {
         let trans = match Transaction::begin(&connection).await {
             Ok(trans) => trans,
             Err(err) => {
-                result.message = "Internal server error".to_string();
+                result.message = t("common.internal_error");
                 result.error = Some(format!("Failed to begin transaction: {}", err));
                 return result;
             }
@@ -161,7 +161,7 @@ impl ImportService {
                 let file = match File::open(&file_path).await {
                     Ok(f) => f,
                     Err(err) => {
-                        result.message = "File open error".to_string();
+                        result.message = t("import.file_open_error");
                         result.error = Some(format!("Failed to open file: {}", err));
                         return result;
                     }
@@ -176,7 +176,7 @@ impl ImportService {
                     let line = match line_res {
                         Ok(l) => l.trim().to_string(),
                         Err(e) => {
-                            result.message = "TXT parse error".to_string();
+                            result.message = t_args("import.parse_error", &[("format", &"TXT")]);
                             result.error = Some(format!("Failed to read line: {}", e));
                             return result;
                         }
@@ -191,7 +191,7 @@ impl ImportService {
                     if delimiter.is_none() {
                         delimiter = Self::detect_delimiter(&line);
                         if delimiter.is_none() {
-                            result.message = "Delimiter tidak dikenali".to_string();
+                            result.message = t("import.delimiter_unknown");
                             result.error = Some("Gunakan , ; atau | sebagai pemisah.".to_string());
                             return result;
                         }
@@ -204,7 +204,7 @@ impl ImportService {
                     let fields: Vec<&str> = line.split(delimiter).collect();
 
                     if fields.len() != 9 {
-                        result.message = format!("Baris {} harus punya 9 kolom", line_number);
+                        result.message = t_args("import.column_count", &[("line", &line_number), ("count", &9)]);
                         result.error = Some(format!("Ditemukan {} kolom, seharusnya 9.", fields.len()));
                         return result;
                     }
@@ -214,7 +214,7 @@ impl ImportService {
                     let age: i32     = match fields[2].trim().parse() {
                         Ok(a) => a,
                         Err(_) => {
-                            result.message = format!("Baris {}: age bukan angka", line_number);
+                            result.message = t_args("import.not_number", &[("line", &line_number), ("column", &"age")]);
                             result.error = Some(format!("Invalid number: {}", fields[2]));
                             return result;
                         }
@@ -225,7 +225,7 @@ impl ImportService {
                     let product_count: i32 = match fields[6].trim().parse() {
                         Ok(p) => p,
                         Err(_) => {
-                            result.message = format!("Baris {}: product count bukan angka", line_number);
+                            result.message = t_args("import.not_number", &[("line", &line_number), ("column", &"product count")]);
                             result.error = Some(format!("Invalid number: {}", fields[6]));
                             return result;
                         }
@@ -233,7 +233,7 @@ impl ImportService {
                     let price: f64 = match fields[7].trim().parse() {
                         Ok(p) => p,
                         Err(_) => {
-                            result.message = format!("Baris {}: price bukan angka", line_number);
+                            result.message = t_args("import.not_number", &[("line", &line_number), ("column", &"price")]);
                             result.error = Some(format!("Invalid number: {}", fields[7]));
                             return result;
                         }
@@ -259,14 +259,14 @@ impl ImportService {
                             }));
                         },
                         Err(e) => {
-                            result.message = "Insert error".to_string();
+                            result.message = t("import.insert_error");
                             result.error = Some(format!("Query failed: {}", e));
                         }
                     }
                 }
             }
             None => {
-                result.message = "Internal server error".to_string();
+                result.message = t("common.internal_error");
                 result.error = Some("Failed to get connection from pool".to_string());
                 return result;
             }
@@ -279,21 +279,21 @@ impl ImportService {
                 "message": result.message.clone()
             }));
             if let Err(e) = trans.commit().await {
-                result.message = "Failed to commit".to_string();
+                result.message = t("import.commit_failed");
                 result.error = Some(format!("Commit error: {}", e));
                 return result;
             }
             result.result = true;
-            result.message = format!("Berhasil insert {} baris.", rowsaffected);
+            result.message = t_args("import.inserted", &[("count", &rowsaffected)]);
         } else {
             send_ws_event("import_error", serde_json::json!({
                 "result": false,
                 "imported": rowsaffected,
-                "message": "Insert error",
+                "message": t("import.insert_error"),
                 "error": result.error.clone()
             }));
             trans.rollback().await.ok();
-            result.message = "Tidak ada data yang di-insert.".to_string();
+            result.message = t("import.nothing_inserted");
         }
 
         result
@@ -306,7 +306,7 @@ impl ImportService {
         let mut workbook = match open_workbook_auto(&file_path) {
             Ok(wb) => wb,
             Err(e) => {
-                result.message = "Gagal membuka file Excel".to_string();
+                result.message = t("import.file_open_error");
                 result.error = Some(format!("Error: {}", e));
                 return result;
             }
@@ -315,7 +315,7 @@ impl ImportService {
         let range = match workbook.worksheet_range_at(0).ok_or("Sheet kosong") {
             Ok(Ok(r)) => r,
             _ => {
-                result.message = "Worksheet tidak ditemukan atau error".into();
+                result.message = t("import.worksheet_not_found");
                 result.error = Some("Sheet pertama tidak bisa diakses".into());
                 return result;
             }
@@ -326,7 +326,7 @@ impl ImportService {
         let trans = match Transaction::begin(&connection).await {
             Ok(t) => t,
             Err(e) => {
-                result.message = "Internal server error".to_string();
+                result.message = t("common.internal_error");
                 result.error = Some(format!("Failed to begin transaction: {}", e));
                 return result;
             }
@@ -338,7 +338,7 @@ impl ImportService {
             }
 
             if row.len() < 9 {
-                result.message = format!("Baris {} tidak memiliki 10 kolom", i + 1);
+                result.message = t_args("import.column_count", &[("line", &(i + 1)), ("count", &10)]);
                 result.error = Some(format!("Ditemukan hanya {} kolom", row.len()));
                 trans.rollback().await.ok();
                 return result;
@@ -389,12 +389,12 @@ impl ImportService {
                         send_ws_event("import_progress", &progress);
                     }
                     Err(e) => {
-                        result.message = "Insert error".into();
+                        result.message = t("import.insert_error");
                         result.error = Some(format!("Query failed: {}", e));
                     }
                 }
             } else {
-                result.message = "Failed to get connection".into();
+                result.message = t("common.connection_failed");
                 result.error = Some("DB connection error".into());
                 return result;
             }
@@ -404,20 +404,20 @@ impl ImportService {
             send_ws_event("import_done", &serde_json::json!({
                 "result": true,
                 "imported": rowsaffected,
-                "message": "Import selesai"
+                "message": t("import.done")
             }));
             trans.commit().await.ok();
             result.result = true;
-            result.message = format!("Berhasil import {} baris.", rowsaffected);
+            result.message = t_args("import.inserted", &[("count", &rowsaffected)]);
         } else {
             send_ws_event("import_error", serde_json::json!({
                 "result": false,
                 "imported": rowsaffected,
-                "message": "Insert error",
+                "message": t("import.insert_error"),
                 "error": result.error.clone()
             }));
             trans.rollback().await.ok();
-            result.message = "Tidak ada data yang berhasil di-insert".into();
+            result.message = t("import.nothing_inserted");
             result.error = Some("Semua baris gagal atau kosong".into());
         }
 
@@ -430,13 +430,13 @@ impl ImportService {
         let trans = match Transaction::begin(&connection).await {
             Ok(trans) => trans,
             Err(e) => {
-                result.message = "Failed to get connection".into();
+                result.message = t("common.connection_failed");
                 result.error = Some(e.to_string());
                 return result;
             }
         };
         if let Err(e) = dbase::read(&file_path) {
-            result.message = "Failed to read DBF".into();
+            result.message = t("import.file_open_error");
             result.error = Some(e.to_string());
             return result;
         } else {
@@ -505,7 +505,7 @@ impl ImportService {
                                 send_ws_event("import_progress", &progress);
                             },
                             Err(e) => {
-                                result.message = "Insert error".to_string();
+                                result.message = t("import.insert_error");
                                 result.error = Some(format!("Query failed: {}", e));
                             }
                         }
@@ -513,7 +513,7 @@ impl ImportService {
 
                 }
                 None => {
-                    result.message = "Failed to get connection".into();
+                    result.message = t("common.connection_failed");
                     result.error = Some("DB connection error".into());
                     return result;
                 }
@@ -526,21 +526,21 @@ impl ImportService {
                     "message": result.message.clone()
                 }));
                 if let Err(e) = trans.commit().await {
-                    result.message = "Failed to commit".to_string();
+                    result.message = t("import.commit_failed");
                     result.error = Some(format!("Commit error: {}", e));
                     return result;
                 }
                 result.result = true;
-                result.message = format!("Berhasil insert {} baris.", rowsaffected);
+                result.message = t_args("import.inserted", &[("count", &rowsaffected)]);
             } else {
                 send_ws_event("import_error", serde_json::json!({
                     "result": false,
                     "imported": rowsaffected,
-                    "message": "Insert error",
+                    "message": t("import.insert_error"),
                     "error": result.error.clone()
                 }));
                 trans.rollback().await.ok();
-                result.message = "Tidak ada data yang di-insert.".to_string();
+                result.message = t("import.nothing_inserted");
             }
         }
 
@@ -555,7 +555,7 @@ impl ImportService {
         let xml_content = match fs::read_to_string(&file_path) {
             Ok(s) => s,
             Err(e) => {
-                result.message = "File open error".into();
+                result.message = t("import.file_open_error");
                 result.error = Some(format!("Failed to read XML: {}", e));
                 return result;
             }
@@ -573,7 +573,7 @@ impl ImportService {
         let trans = match Transaction::begin(&connection).await {
             Ok(t) => t,
             Err(e) => {
-                result.message = "Internal server error".into();
+                result.message = t("common.internal_error");
                 result.error = Some(format!("Failed to begin transaction: {}", e));
                 return result;
             }
@@ -641,14 +641,14 @@ impl ImportService {
                         );
                     }
                     Err(e) => {
-                        result.message = format!("Baris ke-{} gagal insert", idx + 1);
+                        result.message = t_args("import.row_failed", &[("line", &(idx + 1))]);
                         result.error = Some(format!("Query failed: {}", e));
                         return result;
                     }
                 }
             }
         } else {
-            result.message = "Internal server error".into();
+            result.message = t("common.internal_error");
             result.error = Some("Failed to get connection".into());
             return result;
         }
@@ -660,21 +660,21 @@ impl ImportService {
                 "message": result.message.clone()
             }));
             if let Err(e) = trans.commit().await {
-                result.message = "Failed to commit".to_string();
+                result.message = t("import.commit_failed");
                 result.error = Some(format!("Commit error: {}", e));
                 return result;
             }
             result.result = true;
-            result.message = format!("Berhasil insert {} baris.", rowsaffected);
+            result.message = t_args("import.inserted", &[("count", &rowsaffected)]);
         } else {
             send_ws_event("import_error", serde_json::json!({
                 "result": false,
                 "imported": rowsaffected,
-                "message": "Insert error",
+                "message": t("import.insert_error"),
                 "error": result.error.clone()
             }));
             trans.rollback().await.ok();
-            result.message = "Tidak ada data yang di-insert.".to_string();
+            result.message = t("import.nothing_inserted");
         }
 
         result
@@ -0,0 +1,367 @@
+use actix_web::web;
+use bb8::{Pool, PooledConnection};
+use bb8_tiberius::ConnectionManager;
+use chrono::{Duration, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::contexts::{
+    connection::Transaction,
+    i18n::{current_locale, in_locale, t, t_args},
+    document::{process_document, KycDocumentType, REQUIRED_DOCUMENTS},
+    jwt_session::{sign_token, verify_token},
+    model::{ActionResult, KycDocumentInfo},
+    storage::{active_storage, storage}
+};
+
+use super::{generic_service::GenericService, kyc_service::KycService};
+
+/// Masa berlaku link download dokumen untuk reviewer
+pub const DOCUMENT_LINK_MINUTES: i64 = 10;
+const DOCUMENT_LINK_PURPOSE: &str = "kyc_document";
+
+/// Hasil simpan dalam transaksi: `(DocumentNID, file lama yang diganti)`,
+/// atau `(error sistem?, pesan)` jika gagal
+type SaveResult = Result<(i32, Vec<(String, String)>), (bool, String)>;
+
+/// Link download bertanda tangan, tidak perlu cookie supaya bisa dipakai langsung di `<img>`
+#[derive(Debug, Serialize, Deserialize)]
+struct DocumentLink {
+    document_nid: i32,
+    /// `original` atau `thumbnail`
+    variant: String,
+    /// Reviewer yang meminta link, untuk jejak audit
+    issued_to: i32,
+    purpose: String,
+    exp: usize,
+}
+
+pub struct DocumentFile {
+    pub content_type: String,
+    pub file_name: String,
+    pub data: Vec<u8>,
+}
+
+/// Dokumen KYC (KTP, selfie, buku tabungan), isi file lewat `DocumentStorage`, metadata di `KycDocument`
+pub struct KycDocumentService;
+
+impl KycDocumentService {
+    /// Jenis dokumen wajib yang belum diupload
+    pub async fn missing_documents(conn: &mut PooledConnection<'_, ConnectionManager>, kyc_nid: i32) -> Result<Vec<KycDocumentType>, tiberius::error::Error> {
+        let rows = conn.query(
+            "SELECT DISTINCT DocumentType FROM KycDocument WHERE KycNID = @P1 AND IsDeleted = 0",
+            &[&kyc_nid],
+        ).await?.into_first_result().await?;
+
+        let uploaded: Vec<KycDocumentType> = rows.iter()
+            .filter_map(|row| row.get::<&str, _>("DocumentType").and_then(KycDocumentType::from_slug))
+            .collect();
+
+        Ok(REQUIRED_DOCUMENTS.iter().copied().filter(|doc| !uploaded.contains(doc)).collect())
+    }
+
+    pub async fn delete_files(backend: &str, keys: &[String]) {
+        match storage(backend) {
+            Ok(storage) => {
+                for key in keys {
+                    if let Err(err) = storage.delete(key).await {
+                        eprintln!("❌ Failed to delete document file {}: {}", key, err);
+                    }
+                }
+            }
+            Err(err) => eprintln!("❌ {}", err),
+        }
+    }
+
+    /// Upload / ganti satu jenis dokumen, hanya selama KYC masih bisa diubah client
+    pub async fn upload(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, kyc_nid: i32, document_type: KycDocumentType, bytes: Vec<u8>) -> ActionResult<KycDocumentInfo, String> {
+        let mut result: ActionResult<KycDocumentInfo, String> = ActionResult::default();
+
+        // Decode & encode ulang berat di CPU, jangan tahan worker async
+        let locale = current_locale();
+        let processed = match web::block(move || in_locale(locale, || process_document(&bytes))).await {
+            Ok(Ok(processed)) => processed,
+            Ok(Err(err)) => {
+                result.message = err;
+                return result;
+            }
+            Err(err) => {
+                result.error = Some(format!("Failed to process document: {}", err));
+                return result;
+            }
+        };
+
+        let storage = match active_storage() {
+            Ok(storage) => storage,
+            Err(err) => {
+                result.error = Some(err);
+                return result;
+            }
+        };
+
+        // Nama file asli tidak dipakai, key acak tidak bisa ditebak
+        let base_key = format!("kyc/{}/{}_{}", kyc_nid, document_type.slug(), GenericService::random_string(32));
+        let file_key = format!("{}.jpg", base_key);
+        let thumbnail_key = format!("{}_thumb.jpg", base_key);
+        let new_keys = vec![file_key.clone(), thumbnail_key.clone()];
+        let sha256 = format!("{:x}", Sha256::digest(&processed.data));
+
+        for (key, data) in [(&file_key, &processed.data), (&thumbnail_key, &processed.thumbnail)] {
+            if let Err(err) = storage.put(key, data).await {
+                Self::delete_files(storage.name(), &new_keys).await;
+                result.error = Some(err);
+                return result;
+            }
+        }
+
+        let trans = match Transaction::begin(&connection).await {
+            Ok(trans) => trans,
+            Err(err) => {
+                Self::delete_files(storage.name(), &new_keys).await;
+                result.error = Some(format!("Failed to start transaction: {:?}", err));
+                return result;
+            }
+        };
+
+        let saved: SaveResult = match trans.conn.lock().await.as_mut() {
+            Some(conn) => async {
+                // 🔒 Kunci baris UserKyc supaya tidak balapan dengan submit / review
+                let state = KycService::read_state(conn, kyc_nid, true).await
+                    .map_err(|err| (true, format!("Failed to read KYC: {:?}", err)))?
+                    .ok_or_else(|| (false, t("kyc.not_found")))?;
+                if !state.is_editable() {
+                    return Err((false, t("document.locked")));
+                }
+
+                // Dokumen lama dengan jenis sama diganti
+                let replaced = conn.query(
+                    r#"UPDATE KycDocument SET IsDeleted = 1, DeletedTime = GETDATE()
+                    OUTPUT deleted.StorageBackend, deleted.StorageKey, deleted.ThumbnailKey
+                    WHERE KycNID = @P1 AND DocumentType = @P2 AND IsDeleted = 0"#,
+                    &[&kyc_nid, &document_type.slug()],
+                ).await.map_err(|err| (true, format!("Query execution failed: {:?}", err)))?
+                    .into_first_result().await
+                    .map_err(|err| (true, format!("Failed to replace document: {:?}", err)))?;
+
+                let mut old_files = Vec::new();
+                for row in replaced.iter() {
+                    let backend = row.get::<&str, _>("StorageBackend").unwrap_or("").to_string();
+                    for column in ["StorageKey", "ThumbnailKey"] {
+                        if let Some(key) = row.get::<&str, _>(column) {
+                            old_files.push((backend.clone(), key.to_string()));
+                        }
+                    }
+                }
+
+                let document_nid = conn.query(
+                    r#"INSERT INTO KycDocument
+                    (KycNID, DocumentType, StorageBackend, StorageKey, ThumbnailKey, ContentType, FileSize, Width, Height, Sha256, UploadedBy, UploadedTime, IsDeleted)
+                    OUTPUT INSERTED.DocumentNID
+                    VALUES (@P1, @P2, @P3, @P4, @P5, @P6, @P7, @P8, @P9, @P10, @P11, GETDATE(), 0)"#,
+                    &[
+                        &kyc_nid, &document_type.slug(), &storage.name(), &file_key, &thumbnail_key, &processed.content_type,
+                        &(processed.data.len() as i32), &(processed.width as i32), &(processed.height as i32), &sha256, &auth_usernid,
+                    ],
+                ).await.map_err(|err| (true, format!("Failed to insert KycDocument: {:?}", err)))?
+                    .into_row().await
+                    .map_err(|err| (true, format!("Failed to insert KycDocument: {:?}", err)))?
+                    .and_then(|row| row.get::<i32, _>("DocumentNID"))
+                    .unwrap_or(0);
+
+                Ok((document_nid, old_files))
+            }.await,
+            None => Err((true, "Failed to get database connection".to_string())),
+        };
+
+        let (document_nid, old_files) = match saved {
+            Ok(saved) => saved,
+            Err((is_error, message)) => {
+                if let Err(err) = trans.rollback().await {
+                    eprintln!("❌ Failed to rollback document upload: {:?}", err);
+                }
+                Self::delete_files(storage.name(), &new_keys).await;
+                if is_error {
+                    result.error = Some(message);
+                } else {
+                    result.message = message;
+                }
+                return result;
+            }
+        };
+
+        // 🔵 Commit transaksi
+        if let Err(err) = trans.commit().await {
+            Self::delete_files(storage.name(), &new_keys).await;
+            result.error = Some(format!("Failed to commit transaction: {:?}", err));
+            return result;
+        }
+
+        // File dokumen lama tidak disimpan lebih lama dari perlu, metadata tetap ada untuk audit
+        for (backend, key) in old_files {
+            Self::delete_files(&backend, &[key]).await;
+        }
+
+        result.result = true;
+        result.message = t_args("document.uploaded", &[("document", &document_type.slug())]);
+        result.data = Some(KycDocumentInfo {
+            document_nid,
+            document_type: document_type.slug().to_string(),
+            content_type: processed.content_type.to_string(),
+            file_size: processed.data.len() as i32,
+            width: processed.width as i32,
+            height: processed.height as i32,
+            uploaded_time: Some(Utc::now()),
+            download_url: None,
+            thumbnail_url: None,
+        });
+        result
+    }
+
+    fn sign_link(document_nid: i32, variant: &str, issued_to: i32) -> Result<String, String> {
+        sign_token(&DocumentLink {
+            document_nid,
+            variant: variant.to_string(),
+            issued_to,
+            purpose: DOCUMENT_LINK_PURPOSE.to_string(),
+            exp: (Utc::now() + Duration::minutes(DOCUMENT_LINK_MINUTES)).timestamp() as usize,
+        }).map_err(|e| format!("Failed to sign document link: {}", e))
+    }
+
+    /// Dokumen aktif satu KYC. `reviewer` = (AuthUserNID, base URL API) untuk membuat link download sementara
+    pub async fn get_documents(connection: web::Data<Pool<ConnectionManager>>, kyc_nid: i32, reviewer: Option<(i32, &str)>) -> ActionResult<Vec<KycDocumentInfo>, String> {
+        let mut result: ActionResult<Vec<KycDocumentInfo>, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query(
+                    r#"SELECT DocumentNID, DocumentType, ContentType, FileSize, Width, Height, UploadedTime
+                    FROM KycDocument WHERE KycNID = @P1 AND IsDeleted = 0
+                    ORDER BY DocumentType"#,
+                    &[&kyc_nid],
+                ).await {
+                    Ok(rows) => match rows.into_first_result().await {
+                        Ok(rows) => {
+                            let mut documents = Vec::new();
+                            for row in rows.iter() {
+                                let document_nid: i32 = row.get("DocumentNID").unwrap_or(0);
+                                let (download_url, thumbnail_url) = match reviewer {
+                                    Some((reviewer_nid, base_url)) => {
+                                        let link = |variant: &str| Self::sign_link(document_nid, variant, reviewer_nid)
+                                            .map(|token| format!("{}/api/v1/kyc/documents/file?token={}", base_url, token));
+                                        match (link("original"), link("thumbnail")) {
+                                            (Ok(original), Ok(thumbnail)) => (Some(original), Some(thumbnail)),
+                                            (Err(err), _) | (_, Err(err)) => {
+                                                result.error = Some(err);
+                                                return result;
+                                            }
+                                        }
+                                    }
+                                    None => (None, None),
+                                };
+
+                                documents.push(KycDocumentInfo {
+                                    document_nid,
+                                    document_type: row.get::<&str, _>("DocumentType").unwrap_or("").to_string(),
+                                    content_type: row.get::<&str, _>("ContentType").unwrap_or("").to_string(),
+                                    file_size: row.get("FileSize").unwrap_or(0),
+                                    width: row.get("Width").unwrap_or(0),
+                                    height: row.get("Height").unwrap_or(0),
+                                    uploaded_time: row.get::<NaiveDateTime, _>("UploadedTime").map(|dt| dt.and_utc()),
+                                    download_url,
+                                    thumbnail_url,
+                                });
+                            }
+
+                            result.result = true;
+                            result.data = Some(documents);
+                        }
+                        Err(err) => {
+                            result.error = Some(format!("Failed to read documents: {:?}", err));
+                        }
+                    },
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    /// Isi file dari link bertanda tangan
+    pub async fn download(connection: web::Data<Pool<ConnectionManager>>, token: &str) -> ActionResult<DocumentFile, String> {
+        let mut result: ActionResult<DocumentFile, String> = ActionResult::default();
+
+        let link = match verify_token::<DocumentLink>(token) {
+            Ok(link) if link.purpose == DOCUMENT_LINK_PURPOSE => link,
+            _ => {
+                result.message = t("document.link_invalid");
+                return result;
+            }
+        };
+
+        let (backend, key, document_type) = match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query(
+                    "SELECT DocumentType, StorageBackend, StorageKey, ThumbnailKey FROM KycDocument WHERE DocumentNID = @P1 AND IsDeleted = 0",
+                    &[&link.document_nid],
+                ).await {
+                    Ok(rows) => match rows.into_row().await {
+                        Ok(Some(row)) => {
+                            let column = if link.variant == "thumbnail" { "ThumbnailKey" } else { "StorageKey" };
+                            (
+                                row.get::<&str, _>("StorageBackend").unwrap_or("").to_string(),
+                                row.get::<&str, _>(column).unwrap_or("").to_string(),
+                                row.get::<&str, _>("DocumentType").unwrap_or("document").to_string(),
+                            )
+                        }
+                        Ok(None) => {
+                            result.message = t("document.not_found");
+                            return result;
+                        }
+                        Err(err) => {
+                            result.error = Some(format!("Failed to read document: {:?}", err));
+                            return result;
+                        }
+                    },
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                        return result;
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+                return result;
+            }
+        };
+
+        let data = match storage(&backend) {
+            Ok(storage) => storage.get(&key).await,
+            Err(err) => Err(err),
+        };
+
+        match data {
+            Ok(Some(data)) => {
+                result.result = true;
+                result.data = Some(DocumentFile {
+                    content_type: "image/jpeg".to_string(),
+                    file_name: format!("{}_{}{}.jpg", document_type, link.document_nid, if link.variant == "thumbnail" { "_thumb" } else { "" }),
+                    data,
+                });
+            }
+            Ok(None) => {
+                result.message = t("document.not_found");
+            }
+            Err(err) => {
+                result.error = Some(err);
+            }
+        }
+
+        result
+    }
+}
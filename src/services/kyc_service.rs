@@ -0,0 +1,452 @@
+use actix_web::web;
+use bb8::{Pool, PooledConnection};
+use bb8_tiberius::ConnectionManager;
+use chrono::NaiveDateTime;
+use serde_json::{json, Map, Value};
+
+use crate::contexts::{
+    connection::Transaction,
+    i18n::{t, t_args},
+    kyc::{KycAction, KycStage, KycState, KycStatus},
+    model::{ActionResult, KycHistory, KycOverview},
+    socket::{send_ws_event_to_permission, send_ws_event_to_user}
+};
+
+use super::kyc_document_service::KycDocumentService;
+
+/// Permission reviewer KYC, juga penerima event antrian review
+pub const KYC_REVIEW_PERMISSION: &str = "kyc:review";
+
+/// Workflow onboarding KYC di atas `UserKyc`, setiap perpindahan state dicatat di `UserKycHistory`
+pub struct KycService;
+
+/// Gagal menerapkan transisi: `Invalid` = ditolak state machine (400), `Failed` = error database (500)
+enum TransitionError {
+    Invalid(String),
+    Failed(String),
+}
+
+struct Transition {
+    owner_nid: i32,
+    from: KycState,
+    to: KycState,
+}
+
+impl KycService {
+    fn stage_name(value: i32) -> String {
+        KycStage::from_i32(value).map_or_else(|| value.to_string(), |stage| stage.slug().to_string())
+    }
+
+    fn status_name(status: KycStatus) -> String {
+        serde_json::to_value(status).ok().and_then(|v| v.as_str().map(|s| s.to_string())).unwrap_or_default()
+    }
+
+    /// `UserKyc.AutoNID` milik user (`AuthUser.WebCIFNID`)
+    pub async fn kyc_nid_for_user(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32) -> ActionResult<i32, String> {
+        let mut result: ActionResult<i32, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query("SELECT WebCIFNID FROM AuthUser WHERE AuthUserNID = @P1", &[&auth_usernid]).await {
+                    Ok(rows) => match rows.into_row().await {
+                        Ok(Some(row)) if row.get::<i32, _>("WebCIFNID").unwrap_or(0) > 0 => {
+                            result.result = true;
+                            result.data = row.get("WebCIFNID");
+                        }
+                        Ok(_) => {
+                            result.message = t("kyc.not_found");
+                        }
+                        Err(err) => {
+                            result.error = Some(format!("Failed to read user: {:?}", err));
+                        }
+                    },
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    pub async fn read_state(conn: &mut PooledConnection<'_, ConnectionManager>, kyc_nid: i32, lock: bool) -> Result<Option<KycState>, tiberius::error::Error> {
+        // UPDLOCK supaya dua transisi bersamaan pada KYC yang sama berjalan bergantian
+        let query = if lock {
+            "SELECT Stage, IsRejected, IsFinished, IsRevised FROM UserKyc WITH (UPDLOCK, ROWLOCK) WHERE AutoNID = @P1"
+        } else {
+            "SELECT Stage, IsRejected, IsFinished, IsRevised FROM UserKyc WHERE AutoNID = @P1"
+        };
+
+        let row = conn.query(query, &[&kyc_nid]).await?.into_row().await?;
+        Ok(row.map(|row| KycState {
+            stage: KycStage::from_i32(row.get::<i32, _>("Stage").unwrap_or(1)).unwrap_or(KycStage::PersonalData),
+            is_rejected: row.get("IsRejected").unwrap_or(false),
+            is_finished: row.get("IsFinished").unwrap_or(false),
+            is_revised: row.get("IsRevised").unwrap_or(false),
+        }))
+    }
+
+    async fn read_stage_data(conn: &mut PooledConnection<'_, ConnectionManager>, kyc_nid: i32) -> Result<Vec<(KycStage, Value)>, tiberius::error::Error> {
+        let rows = conn.query("SELECT Stage, Data FROM UserKycData WHERE KycNID = @P1 ORDER BY Stage", &[&kyc_nid]).await?
+            .into_first_result().await?;
+
+        Ok(rows.iter().filter_map(|row| {
+            let stage = KycStage::from_i32(row.get::<i32, _>("Stage")?)?;
+            let data = serde_json::from_str(row.get::<&str, _>("Data").unwrap_or("{}")).unwrap_or(Value::Null);
+            Some((stage, data))
+        }).collect())
+    }
+
+    /// State + data lengkap satu KYC
+    pub async fn get_overview(connection: web::Data<Pool<ConnectionManager>>, kyc_nid: i32) -> ActionResult<KycOverview, String> {
+        let mut result: ActionResult<KycOverview, String> = ActionResult::default();
+
+        let mut conn = match connection.get().await {
+            Ok(conn) => conn,
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+                return result;
+            }
+        };
+
+        let state = match Self::read_state(&mut conn, kyc_nid, false).await {
+            Ok(Some(state)) => state,
+            Ok(None) => {
+                result.message = t("kyc.not_found");
+                return result;
+            }
+            Err(err) => {
+                result.error = Some(format!("Failed to read KYC: {:?}", err));
+                return result;
+            }
+        };
+
+        let stage_data = match Self::read_stage_data(&mut conn, kyc_nid).await {
+            Ok(data) => data,
+            Err(err) => {
+                result.error = Some(format!("Failed to read KYC data: {:?}", err));
+                return result;
+            }
+        };
+
+        let row = match conn.query(
+            r#"SELECT k.Email, k.Fullname, k.LastUpdate,
+                (SELECT TOP 1 h.Reason FROM UserKycHistory h
+                 WHERE h.KycNID = k.AutoNID AND h.Action IN ('reject', 'request_revision')
+                 ORDER BY h.HistoryNID DESC) AS Reason
+            FROM UserKyc k WHERE k.AutoNID = @P1"#,
+            &[&kyc_nid],
+        ).await {
+            Ok(rows) => match rows.into_row().await {
+                Ok(Some(row)) => row,
+                Ok(None) => {
+                    result.message = t("kyc.not_found");
+                    return result;
+                }
+                Err(err) => {
+                    result.error = Some(format!("Failed to read KYC: {:?}", err));
+                    return result;
+                }
+            },
+            Err(err) => {
+                result.error = Some(format!("Query execution failed: {:?}", err));
+                return result;
+            }
+        };
+
+        let status = state.status();
+        let mut data = Map::new();
+        for (stage, value) in stage_data {
+            data.insert(stage.slug().to_string(), value);
+        }
+
+        result.result = true;
+        result.data = Some(KycOverview {
+            kyc_nid,
+            email: row.get::<&str, _>("Email").unwrap_or("").to_string(),
+            full_name: row.get::<&str, _>("Fullname").map(|s| s.to_string()),
+            stage: state.stage.slug().to_string(),
+            status: Self::status_name(status),
+            // Alasan hanya relevan selama masih ditolak / diminta revisi
+            reason: match status {
+                KycStatus::Rejected | KycStatus::RevisionRequested => row.get::<&str, _>("Reason").map(|s| s.to_string()),
+                _ => None,
+            },
+            data,
+            actions: state.client_actions(),
+            last_update: row.get::<NaiveDateTime, _>("LastUpdate").map(|dt| dt.and_utc()),
+        });
+
+        result
+    }
+
+    /// Antrian reviewer: KYC yang sedang menunggu review, terlama lebih dulu
+    pub async fn get_review_queue(connection: web::Data<Pool<ConnectionManager>>) -> ActionResult<Vec<KycOverview>, String> {
+        let mut result: ActionResult<Vec<KycOverview>, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query(
+                    r#"SELECT TOP 200 AutoNID, Email, Fullname, Stage, IsRejected, IsFinished, IsRevised, LastUpdate
+                    FROM UserKyc
+                    WHERE Stage = @P1 AND IsRejected = 0 AND IsFinished = 0
+                    ORDER BY LastUpdate ASC"#,
+                    &[&(KycStage::Review as i32)],
+                ).await {
+                    Ok(rows) => match rows.into_first_result().await {
+                        Ok(rows) => {
+                            result.result = true;
+                            result.data = Some(rows.iter().map(|row| {
+                                let state = KycState {
+                                    stage: KycStage::from_i32(row.get::<i32, _>("Stage").unwrap_or(1)).unwrap_or(KycStage::Review),
+                                    is_rejected: row.get("IsRejected").unwrap_or(false),
+                                    is_finished: row.get("IsFinished").unwrap_or(false),
+                                    is_revised: row.get("IsRevised").unwrap_or(false),
+                                };
+                                KycOverview {
+                                    kyc_nid: row.get("AutoNID").unwrap_or(0),
+                                    email: row.get::<&str, _>("Email").unwrap_or("").to_string(),
+                                    full_name: row.get::<&str, _>("Fullname").map(|s| s.to_string()),
+                                    stage: state.stage.slug().to_string(),
+                                    status: Self::status_name(state.status()),
+                                    reason: None,
+                                    data: Map::new(),
+                                    actions: vec![],
+                                    last_update: row.get::<NaiveDateTime, _>("LastUpdate").map(|dt| dt.and_utc()),
+                                }
+                            }).collect());
+                        }
+                        Err(err) => {
+                            result.error = Some(format!("Failed to read KYC: {:?}", err));
+                        }
+                    },
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    pub async fn get_history(connection: web::Data<Pool<ConnectionManager>>, kyc_nid: i32) -> ActionResult<Vec<KycHistory>, String> {
+        let mut result: ActionResult<Vec<KycHistory>, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query(
+                    r#"SELECT h.HistoryNID, h.Action, h.FromStage, h.ToStage, h.Status, h.Reason, h.ActorNID, u.Email AS ActorEmail, h.CreatedTime
+                    FROM UserKycHistory h
+                    LEFT JOIN AuthUser u ON u.AuthUserNID = h.ActorNID
+                    WHERE h.KycNID = @P1
+                    ORDER BY h.HistoryNID ASC"#,
+                    &[&kyc_nid],
+                ).await {
+                    Ok(rows) => match rows.into_first_result().await {
+                        Ok(rows) => {
+                            result.result = true;
+                            result.data = Some(rows.iter().map(|row| KycHistory {
+                                history_nid: row.get("HistoryNID").unwrap_or(0),
+                                action: row.get::<&str, _>("Action").unwrap_or("").to_string(),
+                                from_stage: Self::stage_name(row.get("FromStage").unwrap_or(0)),
+                                to_stage: Self::stage_name(row.get("ToStage").unwrap_or(0)),
+                                status: row.get::<&str, _>("Status").unwrap_or("").to_string(),
+                                reason: row.get::<&str, _>("Reason").map(|s| s.to_string()),
+                                actor_nid: row.get("ActorNID").unwrap_or(0),
+                                actor_email: row.get::<&str, _>("ActorEmail").map(|s| s.to_string()),
+                                created_time: row.get::<NaiveDateTime, _>("CreatedTime").map(|dt| dt.and_utc()),
+                            }).collect());
+                        }
+                        Err(err) => {
+                            result.error = Some(format!("Failed to read KYC history: {:?}", err));
+                        }
+                    },
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    /// Client mengisi / memperbaiki data satu tahap, `data` sudah divalidasi di handler
+    pub async fn submit_stage(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, kyc_nid: i32, stage: KycStage, data: Value) -> ActionResult<KycOverview, String> {
+        Self::transition(connection, kyc_nid, auth_usernid, KycAction::Submit(stage), None, Some(data)).await
+    }
+
+    pub async fn submit_for_review(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, kyc_nid: i32) -> ActionResult<KycOverview, String> {
+        Self::transition(connection, kyc_nid, auth_usernid, KycAction::SubmitForReview, None, None).await
+    }
+
+    /// Approve / reject / minta revisi oleh reviewer
+    pub async fn review(connection: web::Data<Pool<ConnectionManager>>, reviewer_nid: i32, kyc_nid: i32, action: KycAction, reason: Option<String>) -> ActionResult<KycOverview, String> {
+        Self::transition(connection, kyc_nid, reviewer_nid, action, reason, None).await
+    }
+
+    async fn transition(connection: web::Data<Pool<ConnectionManager>>, kyc_nid: i32, actor_nid: i32, action: KycAction, reason: Option<String>, data: Option<Value>) -> ActionResult<KycOverview, String> {
+        let mut result: ActionResult<KycOverview, String> = ActionResult::default();
+
+        let trans = match Transaction::begin(&connection).await {
+            Ok(trans) => trans,
+            Err(err) => {
+                result.error = Some(format!("Failed to start transaction: {:?}", err));
+                return result;
+            }
+        };
+
+        let applied = match trans.conn.lock().await.as_mut() {
+            Some(conn) => Self::apply_transition(conn, kyc_nid, actor_nid, action, reason.as_deref(), data.as_ref()).await,
+            None => Err(TransitionError::Failed("Failed to get database connection".to_string())),
+        };
+
+        let transition = match applied {
+            Ok(transition) => transition,
+            Err(err) => {
+                if let Err(rollback_err) = trans.rollback().await {
+                    eprintln!("❌ Failed to rollback KYC transition: {:?}", rollback_err);
+                }
+                match err {
+                    TransitionError::Invalid(message) => result.message = message,
+                    TransitionError::Failed(error) => result.error = Some(error),
+                }
+                return result;
+            }
+        };
+
+        // 🔵 Commit transaksi
+        if let Err(err) = trans.commit().await {
+            result.error = Some(format!("Failed to commit transaction: {:?}", err));
+            return result;
+        }
+
+        Self::notify(kyc_nid, action, &transition, reason.as_deref());
+
+        let mut overview = Self::get_overview(connection, kyc_nid).await;
+        if overview.result {
+            overview.message = match action {
+                KycAction::Submit(stage) => t_args("kyc.stage_saved", &[("stage", &stage.slug())]),
+                KycAction::SubmitForReview => t("kyc.submitted"),
+                KycAction::Approve => t("kyc.approved"),
+                KycAction::Reject => t("kyc.rejected"),
+                KycAction::RequestRevision(stage) => t_args("kyc.revision_requested", &[("stage", &stage.slug())]),
+            };
+        }
+        overview
+    }
+
+    async fn apply_transition(
+        conn: &mut PooledConnection<'_, ConnectionManager>,
+        kyc_nid: i32,
+        actor_nid: i32,
+        action: KycAction,
+        reason: Option<&str>,
+        data: Option<&Value>,
+    ) -> Result<Transition, TransitionError> {
+        let failed = |context: &str, err: tiberius::error::Error| TransitionError::Failed(format!("{}: {:?}", context, err));
+
+        let from = Self::read_state(conn, kyc_nid, true).await
+            .map_err(|err| failed("Failed to read KYC", err))?
+            .ok_or_else(|| TransitionError::Invalid(t("kyc.not_found")))?;
+
+        let owner_nid = match conn.query("SELECT AuthUserNID FROM AuthUser WHERE WebCIFNID = @P1", &[&kyc_nid]).await
+            .map_err(|err| failed("Query execution failed", err))?
+            .into_row().await
+            .map_err(|err| failed("Failed to read user", err))? {
+            Some(row) => row.get::<i32, _>("AuthUserNID").unwrap_or(0),
+            None => 0,
+        };
+
+        // Reviewer tidak boleh memutuskan KYC miliknya sendiri
+        if matches!(action, KycAction::Approve | KycAction::Reject | KycAction::RequestRevision(_)) && owner_nid == actor_nid {
+            return Err(TransitionError::Invalid(t("kyc.own_review")));
+        }
+
+        let mut completed: Vec<KycStage> = Self::read_stage_data(conn, kyc_nid).await
+            .map_err(|err| failed("Failed to read KYC data", err))?
+            .into_iter()
+            .map(|(stage, _)| stage)
+            .collect();
+        if let KycAction::Submit(stage) = action {
+            if !completed.contains(&stage) {
+                completed.push(stage);
+            }
+        }
+
+        let to = from.apply(action, &completed).map_err(TransitionError::Invalid)?;
+
+        // 📄 Foto KTP, selfie dan buku tabungan wajib ada sebelum masuk antrian review
+        if action == KycAction::SubmitForReview {
+            let missing = KycDocumentService::missing_documents(conn, kyc_nid).await
+                .map_err(|err| failed("Failed to read KYC documents", err))?;
+            if !missing.is_empty() {
+                let names: Vec<&str> = missing.iter().map(|doc| doc.slug()).collect();
+                return Err(TransitionError::Invalid(t_args("kyc.documents_missing", &[("documents", &names.join(", "))])));
+            }
+        }
+
+        if let (KycAction::Submit(stage), Some(data)) = (action, data) {
+            conn.execute(
+                r#"MERGE UserKycData AS t
+                USING (SELECT @P1 AS KycNID, @P2 AS Stage) AS s ON t.KycNID = s.KycNID AND t.Stage = s.Stage
+                WHEN MATCHED THEN UPDATE SET Data = @P3, UpdatedTime = GETDATE()
+                WHEN NOT MATCHED THEN INSERT (KycNID, Stage, Data, UpdatedTime) VALUES (@P1, @P2, @P3, GETDATE());"#,
+                &[&kyc_nid, &(stage as i32), &data.to_string()],
+            ).await.map_err(|err| failed("Failed to save KYC data", err))?;
+        }
+
+        conn.execute(
+            r#"UPDATE UserKyc SET Stage = @P2, IsRejected = @P3, IsFinished = @P4, IsRevised = @P5, LastUpdate = GETDATE()
+            WHERE AutoNID = @P1"#,
+            &[&kyc_nid, &(to.stage as i32), &to.is_rejected, &to.is_finished, &to.is_revised],
+        ).await.map_err(|err| failed("Failed to update KYC", err))?;
+
+        conn.execute(
+            r#"INSERT INTO UserKycHistory (KycNID, Action, FromStage, ToStage, Status, Reason, ActorNID, CreatedTime)
+            VALUES (@P1, @P2, @P3, @P4, @P5, @P6, @P7, GETDATE())"#,
+            &[
+                &kyc_nid,
+                &action.name(),
+                &(from.stage as i32),
+                &(to.stage as i32),
+                &Self::status_name(to.status()),
+                &reason,
+                &actor_nid,
+            ],
+        ).await.map_err(|err| failed("Failed to write KYC history", err))?;
+
+        Ok(Transition { owner_nid, from, to })
+    }
+
+    /// 📡 Push perubahan ke pemilik KYC, dan ke reviewer jika masuk antrian review
+    fn notify(kyc_nid: i32, action: KycAction, transition: &Transition, reason: Option<&str>) {
+        let payload = json!({
+            "kyc_nid": kyc_nid,
+            "action": action.name(),
+            "from_stage": transition.from.stage.slug(),
+            "stage": transition.to.stage.slug(),
+            "status": transition.to.status(),
+            "reason": reason,
+        });
+
+        if transition.owner_nid > 0 {
+            send_ws_event_to_user(transition.owner_nid, "kyc_status", &payload);
+        }
+
+        if transition.from.status() == KycStatus::InReview || transition.to.status() == KycStatus::InReview {
+            send_ws_event_to_permission(KYC_REVIEW_PERMISSION, "kyc_review_queue", &payload);
+        }
+    }
+}
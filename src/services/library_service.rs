@@ -3,7 +3,7 @@ use bb8::Pool;
 use bb8_tiberius::ConnectionManager;
 use chrono::{NaiveDateTime, Utc};
 
-use crate::contexts::{connection::Transaction, model::{ActionResult, NewNoteRequest, Notes}};
+use crate::contexts::{connection::Transaction, i18n::t, model::{ActionResult, NewNoteRequest, Notes}};
 
 use super::{data_service::DataService, generic_service::GenericService};
 
@@ -16,7 +16,7 @@ impl LibraryService {
         let trans = match Transaction::begin(&connection).await {
             Ok(trans) => trans,
             Err(err) => {
-                result.message = "Internal server error".to_string();
+                result.message = t("common.internal_error");
                 result.error = Some(format!("Failed to begin transaction: {}", err));
                 return result;
             }
@@ -36,15 +36,15 @@ impl LibraryService {
                     &GenericService::get_ip_address(&req),
                     &Utc::now().naive_utc(),
                 ]).await {
-                    result.message = "Internal server error".to_string();
+                    result.message = t("common.internal_error");
                     result.error = Some(format!("Query error: {}", err));
                 } else {
                     result.result = true;
-                    result.message = "Notes created successfully".to_string();
+                    result.message = t("library.note_created");
                 }
             } 
             None => {
-                result.message = "Internal server error".to_string();
+                result.message = t("common.internal_error");
                 result.error = Some("Failed to get connection from pool".to_string());
             }
         }
@@ -52,14 +52,14 @@ impl LibraryService {
         match result.result {
             true => {
                 if let Err(err) = trans.commit().await {
-                    result.message = "Internal server error".to_string();
+                    result.message = t("common.internal_error");
                     result.error = Some(format!("Failed to commit transaction: {}", err));
                     return result;
                 }
             }
             false => {
                 if let Err(err) = trans.rollback().await {
-                    result.message = "Internal server error".to_string();
+                    result.message = t("common.internal_error");
                     result.error = Some(format!("Failed to rollback transaction: {}", err));
                     return result;
                 }
@@ -80,11 +80,11 @@ impl LibraryService {
                     .filter_map(|row| Some(DataService::row_to_json(&row)))
                     .collect();
                 result.result = true;
-                result.message = "Retrieved data successfully".to_string();
+                result.message = t("common.data_retrieved");
                 result.data = Some(data);
             }
             Err(err) => {
-                result.message = "Internal server error".to_string();
+                result.message = t("common.internal_error");
                 result.error = Some(format!("Query error: {}", err));
             }
         }
@@ -100,7 +100,7 @@ impl LibraryService {
             Ok(rows) => {
                 if let Ok(Some(row)) = rows.into_row().await {
                     result.result = true;
-                    result.message = format!("Retrieve successfully");
+                    result.message = t("common.data_retrieved");
                     result.data = Some(Notes {
                         note_id: row.get::<i32, _>("NotesNID").unwrap_or(0),
                         last_update: row
@@ -115,11 +115,11 @@ impl LibraryService {
                     }); 
                 }
                 else {
-                    result.message = "Data not found".to_string();
+                    result.message = t("common.data_not_found");
                 }
             }
             Err(err) => {
-                result.message = "Internal server error".to_string();
+                result.message = t("common.internal_error");
                 result.error = Some(format!("Query error: {}", err));
             }
         }
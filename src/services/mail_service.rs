@@ -8,7 +8,7 @@ use lettre::{transport::smtp::authentication::Credentials, Message, SmtpTranspor
 use shuttle_runtime::SecretStore;
 use tiberius::QueryStream;
 
-use crate::contexts::{connection::Transaction, model::{ActionResult, EmailRequest}};
+use crate::contexts::{connection::Transaction, i18n::t, model::{ActionResult, EmailRequest, LinkEmail}};
 
 use super::generic_service::GenericService;
 
@@ -81,7 +81,7 @@ impl MailService {
             Ok(res) => {
                 println!("Email sent: {:#?}", res);
                 result.result = true;
-                result.message = "Email sent successfully!".to_string();
+                result.message = t("mail.sent");
             }
             Err(e) => {
                 eprintln!("Failed to send email: {e}");
@@ -159,7 +159,7 @@ impl MailService {
             Ok(res) => {
                 println!("Email sent: {:#?}", res);
                 result.result = true;
-                result.message = "Email sent successfully!".to_string();
+                result.message = t("mail.sent");
             }
             Err(e) => {
                 eprintln!("Failed to send email: {e}");
@@ -178,7 +178,7 @@ impl MailService {
         let trans = match Transaction::begin(&connection).await {
             Ok(trans) => trans,
             Err(err) => {
-                result.message = "Internal server error".to_string();
+                result.message = t("common.internal_error");
                 result.error = Some(format!("Failed to begin transaction: {}", err));
                 return result;
             }
@@ -191,7 +191,7 @@ impl MailService {
                     FROM EmailHistory 
                     WHERE Receiver = @P1"#, &[&request.recipient]).await;
                 if let Err(err) = query_result {
-                    result.message = "Internal server error".to_string();
+                    result.message = t("common.internal_error");
                     result.error = Some(format!("Query error: {}", err));
                     return result;
                 } else {
@@ -211,12 +211,12 @@ impl MailService {
                                     WHERE Receiver = @P1"#,
                                 &[&request.recipient, &updated_count, &request.subject, &request.message, &GenericService::get_ip_address(&req), &enabled, &last_update]
                             ).await {
-                                result.message = "Internal server error".to_string();
+                                result.message = t("common.internal_error");
                                 result.error = Some(format!("Query error: {}", err));
                                 return result;
                             }
                             result.result = true;
-                            result.message = "Email sent successfully!".to_string();
+                            result.message = t("mail.sent");
                         } else if Utc::now().naive_utc().signed_duration_since(last_update) > chrono::Duration::hours(24) {
                             if let Err(err) = conn.execute(
                                 r#"UPDATE [dbo].[EmailHistory]
@@ -231,14 +231,14 @@ impl MailService {
                                     &Utc::now().naive_utc()
                                 ],
                             ).await {
-                                result.message = "Internal server error".to_string();
+                                result.message = t("common.internal_error");
                                 result.error = Some(format!("Failed: {:?}", err));
                                 return result;
                             }
                             result.result = true;
-                            result.message = "Email sent successfully!".to_string();
+                            result.message = t("mail.sent");
                         } else {
-                            result.message = "Has exceeded the limit".to_string();
+                            result.message = t("mail.limit_exceeded");
                             result.error = Some("You can only send emails twice per day.".to_string());
                         }
                     } else {
@@ -257,18 +257,18 @@ impl MailService {
                                 &Utc::now().naive_utc(),
                             ],
                         ).await {
-                            result.message = "Internal server error".to_string();
+                            result.message = t("common.internal_error");
                             result.error = Some(format!("Failed: {}", err));
                             return result;
                         }
 
                         result.result = true;
-                        result.message = "Email sent successfully!".to_string();
+                        result.message = t("mail.sent");
                     }
                 }
             }
             None => {
-                result.message = "Internal server error".to_string();
+                result.message = t("common.internal_error");
                 result.error = Some("Failed to get connection".to_string());
                 return result;
             }
@@ -277,7 +277,7 @@ impl MailService {
         // 🔵 Commit transaksi
         if let Err(err) = trans.commit().await {
             result.result = false;
-            result.message = "Internal server error".to_string();
+            result.message = t("common.internal_error");
             result.error = Some(format!("Failed to commit transaction: {}", err));
             return result;
         }
@@ -285,4 +285,76 @@ impl MailService {
         return result;
         
     }
-}
\ No newline at end of file
+
+    /// Email transaksional dengan satu tombol link (aktivasi, reset password, notifikasi keamanan)
+    pub async fn send_link_email(request: LinkEmail, secrets: web::Data<SecretStore>) -> ActionResult<String, String> {
+        let mut result: ActionResult<String, String> = ActionResult::default();
+
+        let (smtp_username, smtp_password, smtp_server) = match (secrets.get("SMTP_USER"), secrets.get("SMTP_PASSWORD"), secrets.get("SMTP_SERVER")) {
+            (Some(user), Some(password), Some(server)) => (user, password, server),
+            _ => {
+                result.error = Some("SMTP secrets are not configured".to_string());
+                return result;
+            }
+        };
+
+        let mut handlebars = Handlebars::new();
+        if let Err(err) = handlebars.register_template_string("mail_link", include_str!("../../templates/mail_link.mustache")) {
+            result.error = Some(format!("Invalid email template: {}", err));
+            return result;
+        }
+
+        let html_body = match handlebars.render("mail_link", &request) {
+            Ok(html) => html,
+            Err(err) => {
+                result.error = Some(format!("Failed to render email: {}", err));
+                return result;
+            }
+        };
+
+        let email = match request.recipient.parse() {
+            Ok(recipient) => Message::builder()
+                .from("techsnakesystem@gmail.com".parse().unwrap())
+                .to(recipient)
+                .subject(&request.subject)
+                .header(lettre::message::header::ContentType::TEXT_HTML)
+                .body(html_body),
+            Err(err) => {
+                result.error = Some(format!("Invalid recipient: {}", err));
+                return result;
+            }
+        };
+        let email = match email {
+            Ok(email) => email,
+            Err(err) => {
+                result.error = Some(format!("Failed to build email: {}", err));
+                return result;
+            }
+        };
+
+        let mailer = match SmtpTransport::relay(&smtp_server) {
+            Ok(builder) => builder.credentials(Credentials::new(smtp_username, smtp_password)).build(),
+            Err(err) => {
+                result.error = Some(format!("Invalid SMTP server: {}", err));
+                return result;
+            }
+        };
+
+        // SMTP blocking, kirim di thread pool blocking supaya worker actix tidak tertahan selama round-trip
+        match web::block(move || mailer.send(&email)).await {
+            Ok(Ok(_)) => {
+                result.result = true;
+                result.message = t("mail.sent");
+            }
+            Ok(Err(e)) => {
+                eprintln!("Failed to send email: {e}");
+                result.error = Some(e.to_string());
+            }
+            Err(e) => {
+                result.error = Some(format!("Failed to send email: {}", e));
+            }
+        }
+
+        result
+    }
+}
@@ -0,0 +1,344 @@
+use actix_web::web;
+use base64::{engine::general_purpose, Engine as _};
+use bb8::Pool;
+use bb8_tiberius::ConnectionManager;
+use chrono::Utc;
+
+use crate::contexts::{
+    connection::Transaction, crypto::hash_token, i18n::t, jwt_session::Claims, model::{ActionResult, TotpEnrollment},
+    totp::{generate_recovery_codes, generate_secret, normalize_recovery_code, otpauth_uri, qr_code_png, verify_code}
+};
+
+use super::throttle_service::{ThrottleService, MAX_ACCOUNT_ATTEMPTS};
+
+pub struct MfaService;
+
+const TOTP_ISSUER: &str = "Snakesystem";
+const RECOVERY_CODE_COUNT: usize = 10;
+
+impl MfaService {
+    pub async fn is_enabled(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32) -> ActionResult<bool, String> {
+        let mut result: ActionResult<bool, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query("SELECT TotpEnabled FROM AuthUser WHERE AuthUserNID = @P1", &[&auth_usernid]).await {
+                    Ok(rows) => {
+                        let enabled = match rows.into_row().await {
+                            Ok(Some(row)) => row.get::<bool, _>("TotpEnabled").unwrap_or(false),
+                            _ => false,
+                        };
+                        result.result = true;
+                        result.data = Some(enabled);
+                    }
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    /// Buat secret baru (belum aktif sampai dikonfirmasi dengan kode pertama)
+    pub async fn enroll(connection: web::Data<Pool<ConnectionManager>>, user: Claims) -> ActionResult<TotpEnrollment, String> {
+        let mut result: ActionResult<TotpEnrollment, String> = ActionResult::default();
+
+        let enabled = Self::is_enabled(connection.clone(), user.auth_usernid).await;
+        if enabled.error.is_some() {
+            result.error = enabled.error;
+            return result;
+        }
+        if enabled.data.unwrap_or(false) {
+            result.message = t("mfa.already_enabled");
+            return result;
+        }
+
+        let secret = generate_secret();
+        let uri = otpauth_uri(&secret, &user.email, TOTP_ISSUER);
+        let qr_code = match qr_code_png(&uri) {
+            Ok(png) => format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(png)),
+            Err(err) => {
+                result.error = Some(format!("Failed to generate QR code: {}", err));
+                return result;
+            }
+        };
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                if let Err(err) = conn.execute(
+                    "UPDATE AuthUser SET TotpSecret = @P2, TotpEnabled = 0, TotpLastStep = NULL WHERE AuthUserNID = @P1",
+                    &[&user.auth_usernid, &secret],
+                ).await {
+                    result.error = Some(format!("Query execution failed: {:?}", err));
+                    return result;
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+                return result;
+            }
+        }
+
+        result.result = true;
+        result.message = t("mfa.enroll_started");
+        result.data = Some(TotpEnrollment { secret, otpauth_uri: uri, qr_code });
+        result
+    }
+
+    /// Aktifkan 2FA setelah kode pertama benar, recovery code hanya ditampilkan sekali di sini
+    pub async fn confirm(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, code: String) -> ActionResult<Vec<String>, String> {
+        let mut result: ActionResult<Vec<String>, String> = ActionResult::default();
+
+        // 🔒 Key sama dengan login 2FA, sesi yang dicuri tidak boleh menebak kode tanpa batas
+        let mfa_key = ThrottleService::key("mfa", "user", &auth_usernid.to_string());
+        match ThrottleService::locked_seconds(connection.clone(), std::slice::from_ref(&mfa_key)).await {
+            locked if locked.error.is_some() => {
+                result.error = locked.error;
+                return result;
+            }
+            ActionResult { data: Some(Some(seconds)), .. } => {
+                result.message = ThrottleService::locked_message(seconds);
+                return result;
+            }
+            _ => {}
+        }
+
+        let (secret, enabled) = match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query("SELECT TotpSecret, TotpEnabled FROM AuthUser WHERE AuthUserNID = @P1", &[&auth_usernid]).await {
+                    Ok(rows) => match rows.into_row().await {
+                        Ok(Some(row)) => (
+                            row.get::<&str, _>("TotpSecret").unwrap_or_default().to_string(),
+                            row.get::<bool, _>("TotpEnabled").unwrap_or(false),
+                        ),
+                        _ => (String::new(), false),
+                    },
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                        return result;
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+                return result;
+            }
+        };
+
+        if enabled {
+            result.message = t("mfa.already_enabled");
+            return result;
+        }
+        if secret.is_empty() {
+            result.message = t("mfa.enroll_not_started");
+            return result;
+        }
+
+        let step = match verify_code(&secret, &code, Utc::now().timestamp(), None) {
+            Some(step) => step,
+            None => {
+                let failed = ThrottleService::register_failure(connection.clone(), &mfa_key, MAX_ACCOUNT_ATTEMPTS).await;
+                if failed.error.is_some() {
+                    result.error = failed.error;
+                    return result;
+                }
+                result.message = t("mfa.invalid_code");
+                return result;
+            }
+        };
+        ThrottleService::reset(connection.clone(), &mfa_key).await;
+
+        let recovery_codes = generate_recovery_codes(RECOVERY_CODE_COUNT);
+
+        let trans = match Transaction::begin(&connection).await {
+            Ok(trans) => trans,
+            Err(err) => {
+                result.error = Some(format!("Failed to start transaction: {:?}", err));
+                return result;
+            }
+        };
+
+        let applied: Result<(), String> = match trans.conn.lock().await.as_mut() {
+            Some(conn) => async {
+                conn.execute(
+                    "UPDATE AuthUser SET TotpEnabled = 1, TotpLastStep = @P2 WHERE AuthUserNID = @P1",
+                    &[&auth_usernid, &step],
+                ).await.map_err(|err| format!("Failed to enable 2FA: {:?}", err))?;
+
+                conn.execute("DELETE FROM AuthUserRecoveryCode WHERE AuthUserNID = @P1", &[&auth_usernid])
+                    .await
+                    .map_err(|err| format!("Failed to reset recovery codes: {:?}", err))?;
+
+                for recovery_code in &recovery_codes {
+                    conn.execute(
+                        "INSERT INTO AuthUserRecoveryCode (AuthUserNID, CodeHash, CreatedTime) VALUES (@P1, @P2, GETDATE())",
+                        &[&auth_usernid, &hash_token(&normalize_recovery_code(recovery_code))],
+                    ).await.map_err(|err| format!("Failed to insert recovery code: {:?}", err))?;
+                }
+
+                Ok(())
+            }.await,
+            None => Err("Failed to get database connection".into()),
+        };
+
+        if let Err(err) = applied {
+            let _ = trans.rollback().await;
+            result.error = Some(err);
+            return result;
+        }
+
+        // 🔵 Commit transaksi
+        if let Err(err) = trans.commit().await {
+            result.error = Some(format!("Failed to commit transaction: {:?}", err));
+            return result;
+        }
+
+        result.result = true;
+        result.message = t("mfa.enabled");
+        result.data = Some(recovery_codes);
+        result
+    }
+
+    /// Cek kode TOTP (anti replay) atau recovery code sekali pakai
+    pub async fn verify_second_factor(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, code: String) -> ActionResult<(), String> {
+        let mut result: ActionResult<(), String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                let row = match conn.query(
+                    "SELECT TotpSecret, TotpEnabled, TotpLastStep FROM AuthUser WHERE AuthUserNID = @P1",
+                    &[&auth_usernid],
+                ).await {
+                    Ok(rows) => rows.into_row().await.ok().flatten(),
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                        return result;
+                    }
+                };
+
+                let (secret, enabled, last_step) = match row {
+                    Some(row) => (
+                        row.get::<&str, _>("TotpSecret").unwrap_or_default().to_string(),
+                        row.get::<bool, _>("TotpEnabled").unwrap_or(false),
+                        row.get::<i64, _>("TotpLastStep"),
+                    ),
+                    None => (String::new(), false, None),
+                };
+
+                if !enabled {
+                    result.message = t("mfa.not_enabled");
+                    return result;
+                }
+
+                if let Some(step) = verify_code(&secret, &code, Utc::now().timestamp(), last_step) {
+                    // Step hanya boleh maju, request paralel dengan kode yang sama kalah di sini
+                    match conn.execute(
+                        "UPDATE AuthUser SET TotpLastStep = @P2 WHERE AuthUserNID = @P1 AND (TotpLastStep IS NULL OR TotpLastStep < @P2)",
+                        &[&auth_usernid, &step],
+                    ).await {
+                        Ok(res) if res.rows_affected().iter().sum::<u64>() > 0 => {
+                            result.result = true;
+                            result.message = t("mfa.code_verified");
+                        }
+                        Ok(_) => result.message = t("mfa.invalid_code"),
+                        Err(err) => result.error = Some(format!("Query execution failed: {:?}", err)),
+                    }
+                    return result;
+                }
+
+                match conn.execute(
+                    "UPDATE AuthUserRecoveryCode SET UsedTime = GETDATE() WHERE AuthUserNID = @P1 AND CodeHash = @P2 AND UsedTime IS NULL",
+                    &[&auth_usernid, &hash_token(&normalize_recovery_code(&code))],
+                ).await {
+                    Ok(res) if res.rows_affected().iter().sum::<u64>() > 0 => {
+                        result.result = true;
+                        result.message = t("mfa.recovery_accepted");
+                    }
+                    Ok(_) => result.message = t("mfa.invalid_code"),
+                    Err(err) => result.error = Some(format!("Query execution failed: {:?}", err)),
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    pub async fn disable(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, code: String) -> ActionResult<(), String> {
+        let mut result: ActionResult<(), String> = ActionResult::default();
+
+        // 🔒 Key sama dengan login 2FA, sesi yang dicuri tidak boleh menebak kode tanpa batas
+        let mfa_key = ThrottleService::key("mfa", "user", &auth_usernid.to_string());
+        match ThrottleService::locked_seconds(connection.clone(), std::slice::from_ref(&mfa_key)).await {
+            locked if locked.error.is_some() => {
+                result.error = locked.error;
+                return result;
+            }
+            ActionResult { data: Some(Some(seconds)), .. } => {
+                result.message = ThrottleService::locked_message(seconds);
+                return result;
+            }
+            _ => {}
+        }
+
+        let verified = Self::verify_second_factor(connection.clone(), auth_usernid, code).await;
+        if !verified.result {
+            if verified.error.is_none() {
+                let failed = ThrottleService::register_failure(connection.clone(), &mfa_key, MAX_ACCOUNT_ATTEMPTS).await;
+                if failed.error.is_some() {
+                    result.error = failed.error;
+                    return result;
+                }
+            }
+            return verified;
+        }
+        ThrottleService::reset(connection.clone(), &mfa_key).await;
+
+        let trans = match Transaction::begin(&connection).await {
+            Ok(trans) => trans,
+            Err(err) => {
+                result.error = Some(format!("Failed to start transaction: {:?}", err));
+                return result;
+            }
+        };
+
+        let applied: Result<(), String> = match trans.conn.lock().await.as_mut() {
+            Some(conn) => async {
+                conn.execute(
+                    "UPDATE AuthUser SET TotpEnabled = 0, TotpSecret = NULL, TotpLastStep = NULL WHERE AuthUserNID = @P1",
+                    &[&auth_usernid],
+                ).await.map_err(|err| format!("Failed to disable 2FA: {:?}", err))?;
+
+                conn.execute("DELETE FROM AuthUserRecoveryCode WHERE AuthUserNID = @P1", &[&auth_usernid])
+                    .await
+                    .map_err(|err| format!("Failed to delete recovery codes: {:?}", err))?;
+
+                Ok(())
+            }.await,
+            None => Err("Failed to get database connection".into()),
+        };
+
+        if let Err(err) = applied {
+            let _ = trans.rollback().await;
+            result.error = Some(err);
+            return result;
+        }
+
+        // 🔵 Commit transaksi
+        if let Err(err) = trans.commit().await {
+            result.error = Some(format!("Failed to commit transaction: {:?}", err));
+            return result;
+        }
+
+        result.result = true;
+        result.message = t("mfa.disabled");
+        result
+    }
+}
@@ -0,0 +1,373 @@
+use actix_web::web;
+use bb8::Pool;
+use bb8_tiberius::ConnectionManager;
+use chrono::NaiveDateTime;
+
+use crate::{
+    contexts::{
+        avatar::{render_thumbnails, AVATAR_SIZES}, connection::Transaction, i18n::{current_locale, in_locale, t}, jwt_session::Claims,
+        model::{ActionResult, UpdatePasswordRequest, UpdateProfileRequest, UserProfile},
+        password::{hash_password_async, verify_password_async, PasswordCheck, PASSWORD_VERSION_ARGON2ID}
+    },
+    utils::validation::validator::normalize_phone
+};
+
+use super::{generic_service::GenericService, throttle_service::{ThrottleService, MAX_ACCOUNT_ATTEMPTS}};
+
+/// Profil milik user yang sedang login (`/auth/me`)
+pub struct ProfileService;
+
+impl ProfileService {
+    pub async fn get_profile(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32) -> ActionResult<UserProfile, String> {
+        let mut result: ActionResult<UserProfile, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query(
+                    r#"SELECT u.AuthUserNID, u.Email, u.Handphone, u.Picture, u.RegisterDate, u.TotpEnabled, u.PreferredLocale, k.Fullname
+                    FROM AuthUser u
+                    LEFT JOIN UserKyc k ON k.AutoNID = u.WebCIFNID
+                    WHERE u.AuthUserNID = @P1"#,
+                    &[&auth_usernid],
+                ).await {
+                    Ok(rows) => match rows.into_row().await {
+                        Ok(Some(row)) => {
+                            let picture = row.get::<&str, _>("Picture").filter(|p| !p.is_empty()).map(|p| p.to_string());
+                            result.result = true;
+                            result.data = Some(UserProfile {
+                                auth_usernid: row.get("AuthUserNID").unwrap_or(0),
+                                email: row.get::<&str, _>("Email").unwrap_or("").to_string(),
+                                full_name: row.get::<&str, _>("Fullname").map(|s| s.to_string()),
+                                mobile_phone: row.get::<&str, _>("Handphone").map(|s| s.to_string()),
+                                picture,
+                                register_date: row.get::<NaiveDateTime, _>("RegisterDate").map(|dt| dt.and_utc()),
+                                mfa_enabled: row.get::<bool, _>("TotpEnabled").unwrap_or(false),
+                                locale: row.get::<&str, _>("PreferredLocale").map(|s| s.to_string()),
+                            });
+                        }
+                        Ok(None) => {
+                            result.message = t("auth.user_not_found");
+                        }
+                        Err(err) => {
+                            result.error = Some(format!("Failed to read user: {:?}", err));
+                        }
+                    },
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    /// Nama disimpan di `UserKyc`, nomor HP di `AuthUser` dan `UserKyc`, bahasa di `AuthUser`, diubah dalam satu transaksi
+    pub async fn update_profile(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, request: UpdateProfileRequest) -> ActionResult<UserProfile, String> {
+        let mut result: ActionResult<UserProfile, String> = ActionResult::default();
+
+        let full_name = request.full_name.map(|name| name.trim().to_string());
+        // Sudah lolos `valid_phone_number`, disimpan dalam format E.164
+        let mobile_phone = request.mobile_phone.map(|phone| normalize_phone(&phone).unwrap_or_else(|_| phone.trim().to_string()));
+
+        let locale = request.locale;
+
+        if full_name.is_none() && mobile_phone.is_none() && locale.is_none() {
+            result.message = t("common.nothing_to_update");
+            return result;
+        }
+
+        let trans = match Transaction::begin(&connection).await {
+            Ok(trans) => trans,
+            Err(err) => {
+                result.error = Some(format!("Failed to start transaction: {:?}", err));
+                return result;
+            }
+        };
+
+        // Ok(false) = user tidak ditemukan, tidak ada yang perlu di-commit
+        let updated: Result<bool, String> = match trans.conn.lock().await.as_mut() {
+            Some(conn) => async {
+                let changed = conn.execute(
+                    "UPDATE AuthUser SET Handphone = COALESCE(@P2, Handphone), PreferredLocale = COALESCE(@P3, PreferredLocale) WHERE AuthUserNID = @P1",
+                    &[&auth_usernid, &mobile_phone, &locale],
+                ).await.map_err(|err| format!("Failed to update AuthUser: {:?}", err))?;
+
+                if changed.rows_affected().iter().sum::<u64>() == 0 {
+                    return Ok(false);
+                }
+
+                conn.execute(
+                    r#"UPDATE k SET
+                        k.Fullname = COALESCE(@P2, k.Fullname),
+                        k.MobilePhone = COALESCE(@P3, k.MobilePhone),
+                        k.LastUpdate = GETDATE()
+                    FROM UserKyc k
+                    JOIN AuthUser u ON u.WebCIFNID = k.AutoNID
+                    WHERE u.AuthUserNID = @P1"#,
+                    &[&auth_usernid, &full_name, &mobile_phone],
+                ).await.map_err(|err| format!("Failed to update UserKyc: {:?}", err))?;
+
+                Ok(true)
+            }.await,
+            None => Err("Failed to get database connection".into()),
+        };
+
+        match updated {
+            Ok(true) => {}
+            Ok(false) => {
+                let _ = trans.rollback().await;
+                result.message = t("auth.user_not_found");
+                return result;
+            }
+            Err(err) => {
+                let _ = trans.rollback().await;
+                result.error = Some(err);
+                return result;
+            }
+        }
+
+        // 🔵 Commit transaksi
+        if let Err(err) = trans.commit().await {
+            result.error = Some(format!("Failed to commit transaction: {:?}", err));
+            return result;
+        }
+
+        let mut profile = Self::get_profile(connection, auth_usernid).await;
+        if profile.result {
+            profile.message = t("profile.updated");
+        }
+        profile
+    }
+
+    /// Ganti password dari sesi login, wajib password lama. Sesi lain ikut dicabut, sesi ini tetap hidup
+    pub async fn change_password(connection: web::Data<Pool<ConnectionManager>>, user: &Claims, request: UpdatePasswordRequest) -> ActionResult<(), String> {
+        let mut result: ActionResult<(), String> = ActionResult::default();
+
+        // 🔒 Sesi yang dicuri tidak boleh dipakai menebak password lama
+        let throttle_key = ThrottleService::key("password", "user", &user.auth_usernid.to_string());
+        match ThrottleService::locked_seconds(connection.clone(), std::slice::from_ref(&throttle_key)).await {
+            locked if locked.error.is_some() => {
+                result.error = locked.error;
+                return result;
+            }
+            ActionResult { data: Some(Some(seconds)), .. } => {
+                result.message = ThrottleService::locked_message(seconds);
+                return result;
+            }
+            _ => {}
+        }
+
+        let (stored, version) = match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query("SELECT Password, PasswordVersion FROM AuthUser WHERE AuthUserNID = @P1", &[&user.auth_usernid]).await {
+                    Ok(rows) => match rows.into_row().await {
+                        Ok(Some(row)) => (
+                            row.get::<&str, _>("Password").unwrap_or("").to_string(),
+                            row.get::<i32, _>("PasswordVersion").unwrap_or(0),
+                        ),
+                        Ok(None) => {
+                            result.message = t("auth.user_not_found");
+                            return result;
+                        }
+                        Err(err) => {
+                            result.error = Some(format!("Failed to read user: {:?}", err));
+                            return result;
+                        }
+                    },
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                        return result;
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+                return result;
+            }
+        };
+
+        if verify_password_async(request.current_password.clone(), stored, version).await == PasswordCheck::Invalid {
+            let failed = ThrottleService::register_failure(connection.clone(), &throttle_key, MAX_ACCOUNT_ATTEMPTS).await;
+            if failed.error.is_some() {
+                result.error = failed.error;
+                return result;
+            }
+            result.message = t("profile.password_incorrect");
+            return result;
+        }
+
+        if request.current_password == request.new_password {
+            result.message = t("profile.password_same");
+            return result;
+        }
+
+        let enc_password = match hash_password_async(request.new_password.clone()).await {
+            Ok(hash) => hash,
+            Err(err) => {
+                result.error = Some(format!("Failed to hash password: {}", err));
+                return result;
+            }
+        };
+
+        let trans = match Transaction::begin(&connection).await {
+            Ok(trans) => trans,
+            Err(err) => {
+                result.error = Some(format!("Failed to start transaction: {:?}", err));
+                return result;
+            }
+        };
+
+        let applied: Result<(), String> = match trans.conn.lock().await.as_mut() {
+            Some(conn) => async {
+                conn.execute(
+                    r#"UPDATE AuthUser SET Password = @P2, PasswordVersion = @P3,
+                        ResetPasswordKey = NULL, ResetPasswordFlag = 0
+                    WHERE AuthUserNID = @P1"#,
+                    &[&user.auth_usernid, &enc_password, &PASSWORD_VERSION_ARGON2ID],
+                ).await.map_err(|err| format!("Failed to update password: {:?}", err))?;
+
+                // 🔴 Sesi & refresh token di device lain dicabut
+                conn.execute(
+                    r#"DELETE FROM WEB_Cookies WHERE AuthUserNID = @P1 AND SessionID <> @P2;
+                    UPDATE WEB_RefreshToken SET IsRevoked = 1 WHERE AuthUserNID = @P1 AND FamilyID <> @P2 AND IsRevoked = 0"#,
+                    &[&user.auth_usernid, &user.session_id],
+                ).await.map_err(|err| format!("Failed to revoke sessions: {:?}", err))?;
+
+                Ok(())
+            }.await,
+            None => Err("Failed to get database connection".into()),
+        };
+
+        if let Err(err) = applied {
+            let _ = trans.rollback().await;
+            result.error = Some(err);
+            return result;
+        }
+
+        // 🔵 Commit transaksi
+        if let Err(err) = trans.commit().await {
+            result.error = Some(format!("Failed to commit transaction: {:?}", err));
+            return result;
+        }
+
+        ThrottleService::reset(connection.clone(), &throttle_key).await;
+
+        result.result = true;
+        result.message = t("profile.password_changed");
+        result
+    }
+
+    /// Resize avatar ke thumbnail WebP/PNG, simpan di `AuthUserAvatar`, lalu arahkan `Picture` ke thumbnail terbesar.
+    /// Key acak baru per upload, jadi URL lama otomatis tidak berlaku dan aman di-cache lama.
+    pub async fn upload_avatar(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, image: String, base_url: &str) -> ActionResult<String, String> {
+        let mut result: ActionResult<String, String> = ActionResult::default();
+
+        // Decode & resize berat di CPU, jangan tahan worker async
+        let locale = current_locale();
+        let thumbnails = match web::block(move || in_locale(locale, || render_thumbnails(&image))).await {
+            Ok(Ok(thumbnails)) => thumbnails,
+            Ok(Err(err)) => {
+                result.message = err;
+                return result;
+            }
+            Err(err) => {
+                result.error = Some(format!("Failed to process image: {}", err));
+                return result;
+            }
+        };
+
+        let avatar_key = GenericService::random_string(32);
+        let picture = format!("{}/api/v1/auth/avatar/{}/{}.webp", base_url, avatar_key, AVATAR_SIZES[0]);
+
+        let trans = match Transaction::begin(&connection).await {
+            Ok(trans) => trans,
+            Err(err) => {
+                result.error = Some(format!("Failed to start transaction: {:?}", err));
+                return result;
+            }
+        };
+
+        let applied: Result<(), String> = match trans.conn.lock().await.as_mut() {
+            Some(conn) => async {
+                conn.execute("DELETE FROM AuthUserAvatar WHERE AuthUserNID = @P1", &[&auth_usernid])
+                    .await
+                    .map_err(|err| format!("Failed to delete old avatar: {:?}", err))?;
+
+                for thumbnail in &thumbnails {
+                    conn.execute(
+                        r#"INSERT INTO AuthUserAvatar (AuthUserNID, AvatarKey, Size, Format, ContentType, Data, CreatedTime)
+                        VALUES (@P1, @P2, @P3, @P4, @P5, @P6, GETDATE())"#,
+                        &[&auth_usernid, &avatar_key, &(thumbnail.size as i32), &thumbnail.format, &thumbnail.content_type, &thumbnail.data],
+                    ).await.map_err(|err| format!("Failed to save avatar: {:?}", err))?;
+                }
+
+                conn.execute(
+                    "UPDATE AuthUser SET Picture = @P2 WHERE AuthUserNID = @P1",
+                    &[&auth_usernid, &picture],
+                ).await.map_err(|err| format!("Failed to update picture: {:?}", err))?;
+
+                Ok(())
+            }.await,
+            None => Err("Failed to get database connection".into()),
+        };
+
+        if let Err(err) = applied {
+            let _ = trans.rollback().await;
+            result.error = Some(err);
+            return result;
+        }
+
+        // 🔵 Commit transaksi
+        if let Err(err) = trans.commit().await {
+            result.error = Some(format!("Failed to commit transaction: {:?}", err));
+            return result;
+        }
+
+        result.result = true;
+        result.message = t("profile.avatar_updated");
+        result.data = Some(picture);
+        result
+    }
+
+    /// Isi file thumbnail: (content type, bytes)
+    pub async fn get_avatar(connection: web::Data<Pool<ConnectionManager>>, avatar_key: &str, size: u32, format: &str) -> ActionResult<(String, Vec<u8>), String> {
+        let mut result: ActionResult<(String, Vec<u8>), String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query(
+                    "SELECT ContentType, Data FROM AuthUserAvatar WHERE AvatarKey = @P1 AND Size = @P2 AND Format = @P3",
+                    &[&avatar_key, &(size as i32), &format],
+                ).await {
+                    Ok(rows) => match rows.into_row().await {
+                        Ok(Some(row)) => {
+                            result.result = true;
+                            result.data = Some((
+                                row.get::<&str, _>("ContentType").unwrap_or("application/octet-stream").to_string(),
+                                row.get::<&[u8], _>("Data").unwrap_or_default().to_vec(),
+                            ));
+                        }
+                        Ok(None) => {
+                            result.message = t("profile.avatar_not_found");
+                        }
+                        Err(err) => {
+                            result.error = Some(format!("Failed to read avatar: {:?}", err));
+                        }
+                    },
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+}
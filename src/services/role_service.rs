@@ -0,0 +1,199 @@
+use actix_web::web;
+use bb8::Pool;
+use bb8_tiberius::ConnectionManager;
+
+use crate::contexts::{i18n::{t, t_args}, model::ActionResult};
+
+use super::data_service::DataService;
+
+pub struct RoleService;
+
+/// Role dengan akses penuh, minimal harus ada satu user yang memegangnya
+pub const ADMIN_ROLE: &str = "admin";
+
+impl RoleService {
+    pub async fn get_roles(connection: web::Data<Pool<ConnectionManager>>) -> ActionResult<Vec<serde_json::Value>, String> {
+        let mut result: ActionResult<Vec<serde_json::Value>, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query(
+                    r#"SELECT r.RoleNID, r.RoleName, r.Description,
+                        STUFF((SELECT ',' + p.PermissionCode FROM RolePermission rp
+                            JOIN Permissions p ON p.PermissionNID = rp.PermissionNID
+                            WHERE rp.RoleNID = r.RoleNID FOR XML PATH('')), 1, 1, '') AS Permissions
+                    FROM Roles r ORDER BY r.RoleNID"#, &[]).await {
+                    Ok(rows) => match rows.into_first_result().await {
+                        Ok(rows) => {
+                            result.result = true;
+                            result.message = t("common.data_retrieved");
+                            result.data = Some(rows.iter().map(DataService::row_to_json).collect());
+                        }
+                        Err(err) => {
+                            result.message = t("common.internal_error");
+                            result.error = Some(format!("Query error: {}", err));
+                        }
+                    },
+                    Err(err) => {
+                        result.message = t("common.internal_error");
+                        result.error = Some(format!("Query error: {}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.message = t("common.connection_failed");
+                result.error = Some(err.to_string());
+            }
+        }
+
+        result
+    }
+
+    pub async fn get_user_roles(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32) -> ActionResult<Vec<serde_json::Value>, String> {
+        let mut result: ActionResult<Vec<serde_json::Value>, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query(
+                    r#"SELECT r.RoleNID, r.RoleName, r.Description
+                    FROM AuthUserRole ur JOIN Roles r ON r.RoleNID = ur.RoleNID
+                    WHERE ur.AuthUserNID = @P1 ORDER BY r.RoleNID"#, &[&auth_usernid]).await {
+                    Ok(rows) => match rows.into_first_result().await {
+                        Ok(rows) => {
+                            result.result = true;
+                            result.message = t("common.data_retrieved");
+                            result.data = Some(rows.iter().map(DataService::row_to_json).collect());
+                        }
+                        Err(err) => {
+                            result.message = t("common.internal_error");
+                            result.error = Some(format!("Query error: {}", err));
+                        }
+                    },
+                    Err(err) => {
+                        result.message = t("common.internal_error");
+                        result.error = Some(format!("Query error: {}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.message = t("common.connection_failed");
+                result.error = Some(err.to_string());
+            }
+        }
+
+        result
+    }
+
+    pub async fn assign_role(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, role: String) -> ActionResult<(), String> {
+        let mut result: ActionResult<(), String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                let check = match conn.query(
+                    r#"SELECT (SELECT COUNT(*) FROM AuthUser WHERE AuthUserNID = @P1) AS UserCount,
+                        (SELECT RoleNID FROM Roles WHERE RoleName = @P2) AS RoleNID"#,
+                    &[&auth_usernid, &role],
+                ).await {
+                    Ok(rows) => rows.into_row().await.ok().flatten(),
+                    Err(err) => {
+                        result.message = t("common.internal_error");
+                        result.error = Some(format!("Query error: {}", err));
+                        return result;
+                    }
+                };
+
+                let (user_count, role_nid) = match check {
+                    Some(row) => (row.get::<i32, _>("UserCount").unwrap_or(0), row.get::<i32, _>("RoleNID")),
+                    None => (0, None),
+                };
+
+                let role_nid = match (user_count, role_nid) {
+                    (0, _) => {
+                        result.message = t_args("role.user_not_found", &[("user", &auth_usernid)]);
+                        return result;
+                    }
+                    (_, None) => {
+                        result.message = t_args("role.not_found", &[("role", &role)]);
+                        return result;
+                    }
+                    (_, Some(role_nid)) => role_nid,
+                };
+
+                if let Err(err) = conn.execute(
+                    r#"IF NOT EXISTS (SELECT 1 FROM AuthUserRole WHERE AuthUserNID = @P1 AND RoleNID = @P2)
+                        INSERT INTO AuthUserRole (AuthUserNID, RoleNID) VALUES (@P1, @P2)"#,
+                    &[&auth_usernid, &role_nid],
+                ).await {
+                    result.message = t("common.internal_error");
+                    result.error = Some(format!("Query error: {}", err));
+                    return result;
+                }
+
+                result.result = true;
+                result.message = t_args("role.assigned", &[("role", &role), ("user", &auth_usernid)]);
+            }
+            Err(err) => {
+                result.message = t("common.connection_failed");
+                result.error = Some(err.to_string());
+            }
+        }
+
+        result
+    }
+
+    pub async fn revoke_role(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, role: String) -> ActionResult<(), String> {
+        let mut result: ActionResult<(), String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                if role == ADMIN_ROLE {
+                    // Jangan sampai tidak ada admin sama sekali
+                    let admin_count = match conn.query(
+                        r#"SELECT COUNT(*) AS total FROM AuthUserRole ur JOIN Roles r ON r.RoleNID = ur.RoleNID
+                        WHERE r.RoleName = @P1 AND ur.AuthUserNID <> @P2"#,
+                        &[&ADMIN_ROLE, &auth_usernid],
+                    ).await {
+                        Ok(rows) => match rows.into_row().await {
+                            Ok(Some(row)) => row.get::<i32, _>("total").unwrap_or(0),
+                            _ => 0,
+                        },
+                        Err(err) => {
+                            result.message = t("common.internal_error");
+                            result.error = Some(format!("Query error: {}", err));
+                            return result;
+                        }
+                    };
+
+                    if admin_count == 0 {
+                        result.message = t("role.last_admin");
+                        return result;
+                    }
+                }
+
+                match conn.execute(
+                    r#"DELETE ur FROM AuthUserRole ur JOIN Roles r ON r.RoleNID = ur.RoleNID
+                    WHERE ur.AuthUserNID = @P1 AND r.RoleName = @P2"#,
+                    &[&auth_usernid, &role],
+                ).await {
+                    Ok(res) if res.rows_affected().iter().sum::<u64>() > 0 => {
+                        result.result = true;
+                        result.message = t_args("role.revoked", &[("role", &role), ("user", &auth_usernid)]);
+                    }
+                    Ok(_) => {
+                        result.message = t_args("role.not_assigned", &[("user", &auth_usernid), ("role", &role)]);
+                    }
+                    Err(err) => {
+                        result.message = t("common.internal_error");
+                        result.error = Some(format!("Query error: {}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.message = t("common.connection_failed");
+                result.error = Some(err.to_string());
+            }
+        }
+
+        result
+    }
+}
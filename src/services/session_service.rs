@@ -0,0 +1,199 @@
+use actix_web::{web, HttpRequest};
+use bb8::Pool;
+use bb8_tiberius::ConnectionManager;
+use chrono::NaiveDateTime;
+
+use crate::contexts::{i18n::{t, t_args}, jwt_session::Claims, model::{ActionResult, UserSession}};
+
+use super::generic_service::GenericService;
+
+/// Sesi login per device, satu baris `WEB_Cookies` per `SessionID` (= FamilyID refresh token)
+pub struct SessionService;
+
+impl SessionService {
+    /// Daftarkan sesi device baru setelah login, sesi di device lain tetap hidup
+    pub async fn create(connection: web::Data<Pool<ConnectionManager>>, session: &Claims, token: &str, req: &HttpRequest) -> ActionResult<(), String> {
+        let mut result: ActionResult<(), String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                if let Err(err) = conn.execute(
+                    r#"INSERT INTO WEB_Cookies (AuthUserNID, SessionID, Cookies, AppComputerName, AppIPAddress, AppName, UserAgent, CreatedTime, LastSeen, LastUpdate)
+                    VALUES (@P1, @P2, @P3, @P4, @P5, @P6, @P7, GETDATE(), GETDATE(), GETDATE())"#,
+                    &[
+                        &session.auth_usernid,
+                        &session.session_id,
+                        &token,
+                        &session.comp_name,
+                        &session.ip_address,
+                        &session.app_name,
+                        &GenericService::get_user_agent(req),
+                    ],
+                ).await {
+                    result.error = Some(format!("Failed to create session: {:?}", err));
+                    return result;
+                }
+
+                result.result = true;
+                result.message = t("session.created");
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    pub async fn get_sessions(connection: web::Data<Pool<ConnectionManager>>, user: &Claims) -> ActionResult<Vec<UserSession>, String> {
+        let mut result: ActionResult<Vec<UserSession>, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.query(
+                    r#"SELECT SessionID, DeviceName, AppComputerName, AppIPAddress, AppName, UserAgent, CreatedTime, LastSeen
+                    FROM WEB_Cookies WHERE AuthUserNID = @P1 ORDER BY LastSeen DESC"#,
+                    &[&user.auth_usernid],
+                ).await {
+                    Ok(rows) => match rows.into_first_result().await {
+                        Ok(rows) => {
+                            let text = |row: &tiberius::Row, col: &str| row.get::<&str, _>(col).map(|s| s.to_string());
+                            let sessions = rows.iter().map(|row| {
+                                let session_id = text(row, "SessionID").unwrap_or_default();
+                                UserSession {
+                                    current: session_id == user.session_id,
+                                    session_id,
+                                    device_name: text(row, "DeviceName"),
+                                    comp_name: text(row, "AppComputerName"),
+                                    ip_address: text(row, "AppIPAddress"),
+                                    app_name: text(row, "AppName"),
+                                    user_agent: text(row, "UserAgent"),
+                                    created_time: row.get::<NaiveDateTime, _>("CreatedTime").map(|dt| dt.and_utc()),
+                                    last_seen: row.get::<NaiveDateTime, _>("LastSeen").map(|dt| dt.and_utc()),
+                                }
+                            }).collect();
+
+                            result.result = true;
+                            result.message = t("session.list");
+                            result.data = Some(sessions);
+                        }
+                        Err(err) => {
+                            result.error = Some(format!("Failed to read sessions: {:?}", err));
+                        }
+                    },
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    pub async fn rename_session(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, session_id: String, device_name: String) -> ActionResult<(), String> {
+        let mut result: ActionResult<(), String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                match conn.execute(
+                    "UPDATE WEB_Cookies SET DeviceName = @P3 WHERE AuthUserNID = @P1 AND SessionID = @P2",
+                    &[&auth_usernid, &session_id, &device_name.trim()],
+                ).await {
+                    Ok(res) if res.rows_affected().iter().sum::<u64>() > 0 => {
+                        result.result = true;
+                        result.message = t("session.renamed");
+                    }
+                    Ok(_) => result.message = t("session.not_found"),
+                    Err(err) => result.error = Some(format!("Query execution failed: {:?}", err)),
+                }
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    /// Hapus sesi device dan cabut refresh token-nya, hanya untuk sesi milik user sendiri
+    pub async fn revoke_session(connection: web::Data<Pool<ConnectionManager>>, auth_usernid: i32, session_id: String) -> ActionResult<(), String> {
+        let mut result: ActionResult<(), String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                let deleted = match conn.execute(
+                    "DELETE FROM WEB_Cookies WHERE AuthUserNID = @P1 AND SessionID = @P2",
+                    &[&auth_usernid, &session_id],
+                ).await {
+                    Ok(res) => res.total(),
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                        return result;
+                    }
+                };
+
+                if let Err(err) = conn.execute(
+                    "UPDATE WEB_RefreshToken SET IsRevoked = 1 WHERE AuthUserNID = @P1 AND FamilyID = @P2",
+                    &[&auth_usernid, &session_id],
+                ).await {
+                    result.error = Some(format!("Query execution failed: {:?}", err));
+                    return result;
+                }
+
+                if deleted == 0 {
+                    result.message = t("session.not_found");
+                    return result;
+                }
+
+                result.result = true;
+                result.message = t("session.revoked");
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    /// Logout dari semua device kecuali sesi yang sedang dipakai
+    pub async fn revoke_other_sessions(connection: web::Data<Pool<ConnectionManager>>, user: &Claims) -> ActionResult<u64, String> {
+        let mut result: ActionResult<u64, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                let deleted = match conn.execute(
+                    "DELETE FROM WEB_Cookies WHERE AuthUserNID = @P1 AND SessionID <> @P2",
+                    &[&user.auth_usernid, &user.session_id],
+                ).await {
+                    Ok(res) => res.total(),
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                        return result;
+                    }
+                };
+
+                if let Err(err) = conn.execute(
+                    "UPDATE WEB_RefreshToken SET IsRevoked = 1 WHERE AuthUserNID = @P1 AND FamilyID <> @P2 AND IsRevoked = 0",
+                    &[&user.auth_usernid, &user.session_id],
+                ).await {
+                    result.error = Some(format!("Query execution failed: {:?}", err));
+                    return result;
+                }
+
+                result.result = true;
+                result.message = t_args("session.others_revoked", &[("count", &deleted)]);
+                result.data = Some(deleted);
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+}
@@ -0,0 +1,152 @@
+use actix_web::web;
+use bb8::Pool;
+use bb8_tiberius::ConnectionManager;
+
+use crate::contexts::{crypto::hash_token, i18n::t_args, model::ActionResult};
+
+pub struct ThrottleService;
+
+/// Batas gagal sebelum dikunci, dihitung dalam jendela `ATTEMPT_WINDOW_HOURS`
+pub const MAX_ACCOUNT_ATTEMPTS: i32 = 5;
+pub const MAX_IP_ATTEMPTS: i32 = 20;
+pub const MAX_RESET_REQUESTS: i32 = 3;
+
+/// Lama kunci pertama, berlipat dua setiap gagal berikutnya (maksimal `MAX_LOCK_SECONDS`)
+const BASE_LOCK_SECONDS: i64 = 60;
+const MAX_LOCK_SECONDS: i64 = 24 * 60 * 60;
+const ATTEMPT_WINDOW_HOURS: i32 = 24;
+
+/// Hasil pencatatan satu kali gagal
+#[derive(Debug, Default)]
+pub struct FailedAttempt {
+    pub failed_count: i32,
+    /// Terisi jika percobaan ini membuat key terkunci
+    pub lock_seconds: Option<i64>,
+}
+
+impl FailedAttempt {
+    /// Gagal ke-N yang pertama kali memicu kunci, dipakai untuk kirim notifikasi sekali saja
+    pub fn lockout_started(&self, max_attempts: i32) -> bool {
+        self.failed_count == max_attempts
+    }
+}
+
+impl ThrottleService {
+    /// Key dibentuk dari input user (bukan dari data AuthUser) supaya
+    /// perilakunya sama persis untuk email yang terdaftar maupun tidak.
+    /// Nilainya di-hash supaya panjang key tetap muat di `AttemptKey` berapa pun panjang input
+    pub fn key(scope: &str, kind: &str, value: &str) -> String {
+        format!("{}:{}:{}", scope, kind, hash_token(&value.trim().to_lowercase()))
+    }
+
+    fn lock_seconds(failed_count: i32, max_attempts: i32) -> Option<i64> {
+        if failed_count < max_attempts {
+            return None;
+        }
+        let exponent = (failed_count - max_attempts).min(20) as u32;
+        Some((BASE_LOCK_SECONDS * 2i64.pow(exponent)).min(MAX_LOCK_SECONDS))
+    }
+
+    /// Sisa detik kunci terlama dari key yang diberikan, `None` jika tidak ada yang terkunci
+    pub async fn locked_seconds(connection: web::Data<Pool<ConnectionManager>>, keys: &[String]) -> ActionResult<Option<i64>, String> {
+        let mut result: ActionResult<Option<i64>, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                let mut remaining: Option<i64> = None;
+                for key in keys {
+                    match conn.query(
+                        r#"SELECT DATEDIFF(SECOND, GETDATE(), LockedUntil) AS Remaining FROM WEB_LoginAttempt
+                        WHERE AttemptKey = @P1 AND LockedUntil > GETDATE()"#,
+                        &[key],
+                    ).await {
+                        Ok(rows) => {
+                            if let Ok(Some(row)) = rows.into_row().await {
+                                let seconds = row.get::<i32, _>("Remaining").unwrap_or(0) as i64;
+                                remaining = Some(remaining.map_or(seconds, |current| current.max(seconds)));
+                            }
+                        }
+                        Err(err) => {
+                            result.error = Some(format!("Query execution failed: {:?}", err));
+                            return result;
+                        }
+                    }
+                }
+
+                result.result = true;
+                result.data = Some(remaining);
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    /// Catat satu kali gagal, counter di-reset jika gagal terakhir sudah di luar jendela waktu
+    pub async fn register_failure(connection: web::Data<Pool<ConnectionManager>>, key: &str, max_attempts: i32) -> ActionResult<FailedAttempt, String> {
+        let mut result: ActionResult<FailedAttempt, String> = ActionResult::default();
+
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                let failed_count = match conn.query(
+                    r#"MERGE WEB_LoginAttempt WITH (HOLDLOCK) AS t
+                    USING (SELECT @P1 AS AttemptKey) AS s ON t.AttemptKey = s.AttemptKey
+                    WHEN MATCHED THEN UPDATE SET
+                        FailedCount = CASE WHEN t.LastFailedTime < DATEADD(HOUR, -@P2, GETDATE()) THEN 1 ELSE t.FailedCount + 1 END,
+                        LastFailedTime = GETDATE()
+                    WHEN NOT MATCHED THEN INSERT (AttemptKey, FailedCount, LastFailedTime) VALUES (@P1, 1, GETDATE())
+                    OUTPUT inserted.FailedCount;"#,
+                    &[&key, &ATTEMPT_WINDOW_HOURS],
+                ).await {
+                    Ok(rows) => match rows.into_row().await {
+                        Ok(Some(row)) => row.get::<i32, _>("FailedCount").unwrap_or(1),
+                        _ => 1,
+                    },
+                    Err(err) => {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                        return result;
+                    }
+                };
+
+                let lock_seconds = Self::lock_seconds(failed_count, max_attempts);
+                if let Some(seconds) = lock_seconds {
+                    if let Err(err) = conn.execute(
+                        "UPDATE WEB_LoginAttempt SET LockedUntil = DATEADD(SECOND, @P2, GETDATE()) WHERE AttemptKey = @P1",
+                        &[&key, &(seconds as i32)],
+                    ).await {
+                        result.error = Some(format!("Query execution failed: {:?}", err));
+                        return result;
+                    }
+                }
+
+                result.result = true;
+                result.data = Some(FailedAttempt { failed_count, lock_seconds });
+            }
+            Err(err) => {
+                result.error = Some(format!("Internal Server error: {:?}", err));
+            }
+        }
+
+        result
+    }
+
+    /// Hapus riwayat gagal setelah berhasil login
+    pub async fn reset(connection: web::Data<Pool<ConnectionManager>>, key: &str) {
+        match connection.clone().get().await {
+            Ok(mut conn) => {
+                if let Err(err) = conn.execute("DELETE FROM WEB_LoginAttempt WHERE AttemptKey = @P1", &[&key]).await {
+                    eprintln!("❌ Failed to reset login attempts: {:?}", err);
+                }
+            }
+            Err(err) => eprintln!("❌ Failed to get connection: {:?}", err),
+        }
+    }
+
+    /// Pesan untuk user yang terkunci, dibulatkan ke atas dalam menit
+    pub fn locked_message(seconds: i64) -> String {
+        let minutes = (seconds + 59) / 60;
+        t_args("common.too_many_attempts", &[("minutes", &minutes.max(1))])
+    }
+}
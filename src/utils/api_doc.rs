@@ -1,13 +1,22 @@
 use actix_web::{get, web, HttpResponse, Responder};
 use utoipa::{OpenApi, ToSchema};
 
-use crate::contexts::{jwt_session::Claims, model::{ActionResult, ChangePasswordRequest, EmailRequest, HeaderParams, LoginRequest, NewNoteRequest, RegisterRequest, ResetPasswordRequest, TableDataParams}};
+use crate::contexts::{jwt_session::Claims, model::{AccountDeletionRequest, AccountDeletionStatus, ActionResult, ApiKeyInfo, AssignRoleRequest, AuditLogParams, BankInfo, CreateApiKeyRequest, CreatedApiKey, CsrfToken, ChangePasswordRequest, EmailRequest, DocumentDownloadQuery, HeaderParams, KycApproveRequest, KycBankAccount, KycDocumentInfo, KycHistory, KycOccupation, KycOverview, KycPersonalData, KycRejectRequest, KycRevisionRequest, LoginRequest, MagicLinkLoginRequest, MagicLinkRequest, MfaLoginRequest, NewNoteRequest, OidcCallbackQuery, RegisterRequest, RenameSessionRequest, ResendActivationRequest, ResetPasswordRequest, TableDataParams, TotpCodeRequest, TotpEnrollment, UpdatePasswordRequest, UpdateProfileRequest, UploadAvatarRequest, UserProfile, UserSession, ValidateFieldRequest, ValidatedField}};
 
 #[derive(serde::Serialize, ToSchema)]
 struct HealthCheckResponse {
     message: String,
 }
 
+/// Form multipart upload dokumen KYC, hanya untuk dokumentasi
+#[derive(ToSchema)]
+#[allow(dead_code)]
+struct KycDocumentUpload {
+    /// JPEG, PNG atau WebP, maksimal 8MB
+    #[schema(value_type = String, format = Binary)]
+    file: Vec<u8>,
+}
+
 // Login Docs
 #[utoipa::path(post, path = "/api/v1/auth/login", request_body = LoginRequest,
     responses(
@@ -107,6 +116,224 @@ pub fn register_doc() {}
 #[allow(dead_code)]
 pub fn check_session_doc() {}
 
+// CSRF Token Docs
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/csrf",
+    summary = "Ambil token CSRF",
+    description = "Set cookie `snakesystem_csrf` dan kembalikan token yang sama. Semua POST/PUT/PATCH/DELETE di `/api/v1` wajib mengirim token ini di header `X-CSRF-Token`, kecuali request dengan `Authorization: ApiKey ...` atau `Bearer ...` tanpa cookie sesi",
+    responses(
+        (status = 200, description = "CSRF token", body = ActionResult<CsrfToken, String>, example = json!({
+            "result": true,
+            "message": "CSRF token issued",
+            "data": {
+                "csrf_token": "Q7X2M9K4T1B8N5V3C6Z0L2H7J4F9D1S8",
+                "header": "X-CSRF-Token"
+            }
+        }))
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn csrf_token_doc() {}
+
+// Refresh Session Docs
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/refresh",
+    summary = "Perpanjang sesi login",
+    description = "`Memerlukan refresh token dari cookies`. Refresh token lama langsung hangus dan diganti yang baru, pemakaian ulang token lama akan mencabut seluruh sesi",
+    responses(
+        (status = 200, description = "Session refreshed", body = ActionResult<Claims, String>),
+        (status = 401, description = "Unauthorized", body = ActionResult<String, String>, example = json!({
+            "result": false,
+            "message": "Refresh token has expired"
+        })),
+        (status = 500, description = "Internal Server Error", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn refresh_doc() {}
+
+// Login MFA Docs
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login/mfa",
+    summary = "Login langkah kedua (2FA)",
+    description = "Dipakai jika `/auth/login` mengembalikan `mfa_required`. Kirim `mfa_token` beserta kode 6 digit dari aplikasi authenticator atau salah satu recovery code",
+    request_body = MfaLoginRequest,
+    responses(
+        (status = 200, description = "Login success", body = ActionResult<Claims, String>),
+        (status = 401, description = "Unauthorized", body = ActionResult<String, String>, example = json!({
+            "result": false,
+            "message": "Invalid verification code"
+        })),
+        (status = 500, description = "Internal Server Error", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn login_mfa_doc() {}
+
+// Magic Link Docs
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/magic-link",
+    summary = "Kirim link login tanpa password",
+    description = "Link sekali pakai dikirim ke email dan berlaku 15 menit, link lama yang belum dipakai otomatis tidak berlaku. Respon sama untuk email terdaftar maupun tidak. Dibatasi per email & per IP, dan ditolak selama akun / IP terkunci karena gagal login",
+    request_body = MagicLinkRequest,
+    responses(
+        (status = 200, description = "Link sent", body = ActionResult<String, String>, example = json!({
+            "result": true,
+            "message": "If the email is registered, a login link has been sent"
+        })),
+        (status = 400, description = "Validation failed", body = ActionResult<String, String>),
+        (status = 429, description = "Too many attempts", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn request_magic_link_doc() {}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/magic-link/login",
+    summary = "Login dengan link dari email",
+    description = "Kirim `token` dari link email. Hasilnya sama dengan `/auth/login`: cookie sesi, atau `mfa_required` jika 2FA aktif. Link yang tidak valid dihitung sebagai gagal login dari IP tersebut",
+    request_body = MagicLinkLoginRequest,
+    responses(
+        (status = 200, description = "Login success", body = ActionResult<Claims, String>),
+        (status = 401, description = "Invalid, expired or already used login link", body = ActionResult<String, String>, example = json!({
+            "result": false,
+            "message": "Invalid, expired or already used login link"
+        })),
+        (status = 500, description = "Internal Server Error", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn login_magic_link_doc() {}
+
+// Enroll 2FA Docs
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/2fa/enroll",
+    summary = "Mulai aktivasi 2FA (TOTP)",
+    description = "`Memerlukan login`. Menghasilkan secret dan QR code untuk aplikasi authenticator, 2FA baru aktif setelah dikonfirmasi",
+    responses(
+        (status = 200, description = "Enrollment started", body = ActionResult<TotpEnrollment, String>),
+        (status = 400, description = "Bad Request", body = ActionResult<String, String>, example = json!({
+            "result": false,
+            "message": "Two-factor authentication is already enabled"
+        })),
+        (status = 500, description = "Internal Server Error", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn enroll_totp_doc() {}
+
+// Confirm 2FA Docs
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/2fa/confirm",
+    summary = "Konfirmasi aktivasi 2FA",
+    description = "`Memerlukan login`. Kode pertama dari aplikasi authenticator mengaktifkan 2FA. Recovery code hanya ditampilkan sekali di response ini",
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 200, description = "2FA enabled", body = ActionResult<Vec<String>, String>, example = json!({
+            "result": true,
+            "message": "Two-factor authentication enabled, store the recovery codes safely",
+            "data": ["AB3CD-EF4GH", "JK5LM-NP6QR"]
+        })),
+        (status = 400, description = "Bad Request", body = ActionResult<String, String>, example = json!({
+            "result": false,
+            "message": "Invalid verification code"
+        })),
+        (status = 500, description = "Internal Server Error", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn confirm_totp_doc() {}
+
+// Disable 2FA Docs
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/2fa/disable",
+    summary = "Nonaktifkan 2FA",
+    description = "`Memerlukan login`. Butuh kode TOTP yang valid atau recovery code",
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 200, description = "2FA disabled", body = ActionResult<String, String>),
+        (status = 400, description = "Bad Request", body = ActionResult<String, String>),
+        (status = 500, description = "Internal Server Error", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn disable_totp_doc() {}
+
+// OIDC Docs
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/oidc/{provider}/authorize",
+    summary = "Login dengan provider OpenID Connect",
+    description = "Redirect ke halaman login provider (authorization code + PKCE). Provider dikonfigurasi lewat secret `OIDC_PROVIDERS`",
+    params(("provider" = String, Path, description = "Nama provider, contoh: google")),
+    responses(
+        (status = 302, description = "Redirect ke provider"),
+        (status = 404, description = "Provider tidak dikenal", body = ActionResult<String, String>),
+        (status = 502, description = "Discovery provider gagal", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn oidc_authorize_doc() {}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/oidc/{provider}/callback",
+    summary = "Callback OpenID Connect",
+    description = "Dipanggil provider setelah login. ID token divalidasi lalu ditautkan ke akun dengan email terverifikasi yang sama. Respon sama seperti `/auth/login`, termasuk `mfa_required` jika 2FA aktif",
+    params(("provider" = String, Path, description = "Nama provider"), OidcCallbackQuery),
+    responses(
+        (status = 200, description = "Login success", body = ActionResult<Claims, String>),
+        (status = 400, description = "Bad Request", body = ActionResult<String, String>, example = json!({
+            "result": false,
+            "message": "No account registered for user@mail.com, please register first"
+        })),
+        (status = 401, description = "ID token tidak valid", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn oidc_callback_doc() {}
+
+// JWKS Docs
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    summary = "Public key JWT (JWKS)",
+    description = "Daftar public key (RS256/EdDSA) untuk verifikasi token tanpa berbagi secret. Key HS256 tidak pernah dipublikasikan",
+    responses(
+        (status = 200, description = "JSON Web Key Set", example = json!({
+            "keys": [{
+                "kty": "RSA",
+                "use": "sig",
+                "alg": "RS256",
+                "kid": "2025-06",
+                "n": "0vx7agoebGcQSuu...",
+                "e": "AQAB"
+            }]
+        }))
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn jwks_doc() {}
+
 // Logout Docs
 #[utoipa::path(post, path = "/api/v1/auth/logout", 
     responses(
@@ -117,12 +344,304 @@ pub fn check_session_doc() {}
 #[allow(dead_code)]
 pub fn logout_doc() {}
 
+// Sessions Docs
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/sessions",
+    summary = "Daftar sesi login",
+    description = "`Memerlukan login`. Semua device yang sedang login, `current` menandai sesi request ini",
+    responses(
+        (status = 200, description = "Active sessions", body = ActionResult<Vec<UserSession>, String>, example = json!({
+            "result": true,
+            "message": "Active sessions",
+            "data": [{
+                "session_id": "k2Jd9sQm1xZ0aP4bV7nR3tY6wE8uI5oL",
+                "device_name": "Laptop kantor",
+                "comp_name": "snakesystem.com",
+                "ip_address": "10.0.0.1",
+                "app_name": "snakesystem-web-api",
+                "user_agent": "Mozilla/5.0 ...",
+                "created_time": "2025-06-01T08:00:00Z",
+                "last_seen": "2025-06-01T09:15:00Z",
+                "current": true
+            }]
+        })),
+        (status = 500, description = "Internal Server Error", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn get_sessions_doc() {}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/auth/sessions/{session_id}",
+    summary = "Beri nama sesi",
+    description = "`Memerlukan login`. Nama device supaya mudah dikenali di daftar sesi",
+    params(("session_id" = String, Path, description = "ID sesi")),
+    request_body = RenameSessionRequest,
+    responses(
+        (status = 200, description = "Session renamed", body = ActionResult<String, String>),
+        (status = 404, description = "Session not found", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn rename_session_doc() {}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/sessions/{session_id}",
+    summary = "Cabut satu sesi",
+    description = "`Memerlukan login`. Device tersebut langsung logout dan refresh token-nya dicabut",
+    params(("session_id" = String, Path, description = "ID sesi")),
+    responses(
+        (status = 200, description = "Session revoked", body = ActionResult<String, String>),
+        (status = 404, description = "Session not found", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn revoke_session_doc() {}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/sessions",
+    summary = "Logout dari device lain",
+    description = "`Memerlukan login`. Cabut semua sesi kecuali sesi yang sedang dipakai",
+    responses(
+        (status = 200, description = "Other sessions revoked", body = ActionResult<u64, String>, example = json!({
+            "result": true,
+            "message": "2 other session(s) revoked",
+            "data": 2
+        }))
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn revoke_other_sessions_doc() {}
+
+// API Key Docs
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/api-keys",
+    summary = "Daftar API key",
+    description = "`Memerlukan login`. Key aktif milik user, isi key tidak pernah ditampilkan lagi",
+    responses(
+        (status = 200, description = "API keys", body = ActionResult<Vec<ApiKeyInfo>, String>),
+        (status = 403, description = "Dipanggil dengan API key", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn get_api_keys_doc() {}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/api-keys",
+    summary = "Buat API key",
+    description = "`Memerlukan login`. Scope harus bagian dari permission user. Pakai dengan header `Authorization: ApiKey <key>`",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 200, description = "API key created", body = ActionResult<CreatedApiKey, String>, example = json!({
+            "result": true,
+            "message": "API key created, copy it now because it will not be shown again",
+            "data": {
+                "api_key_nid": 1,
+                "name": "ETL nightly",
+                "key": "ssk_Ab12Cd34_q9W8e7R6t5Y4u3I2o1P0a9S8d7F6g5H4j3K2l1Z0",
+                "scopes": ["export:read", "import:write"],
+                "expired_time": "2025-09-01T00:00:00Z"
+            }
+        })),
+        (status = 400, description = "Bad Request", body = ActionResult<String, String>, example = json!({
+            "result": false,
+            "message": "You don't have permission 'data:clear'"
+        }))
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn create_api_key_doc() {}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/api-keys/{api_key_nid}",
+    summary = "Cabut API key",
+    description = "`Memerlukan login`. Key langsung tidak bisa dipakai lagi",
+    params(("api_key_nid" = i32, Path, description = "ID API key")),
+    responses(
+        (status = 200, description = "API key revoked", body = ActionResult<String, String>),
+        (status = 404, description = "API key not found", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn revoke_api_key_doc() {}
+
+// Profile Docs
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/me",
+    summary = "Profil user yang sedang login",
+    description = "`Memerlukan login`. Data diambil langsung dari database, bukan dari token",
+    responses(
+        (status = 200, description = "Profile", body = ActionResult<UserProfile, String>),
+        (status = 401, description = "Unauthorized", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn get_profile_doc() {}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/auth/me",
+    summary = "Ubah nama lengkap / nomor HP / bahasa",
+    description = "`Memerlukan login`. Field yang tidak dikirim tidak diubah. `locale` (`id` / `en`) menentukan bahasa pesan API dan menimpa `Accept-Language`. Claims di token ikut berubah setelah refresh berikutnya",
+    request_body = UpdateProfileRequest,
+    responses(
+        (status = 200, description = "Profile updated", body = ActionResult<UserProfile, String>),
+        (status = 400, description = "Validation failed", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn update_profile_doc() {}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/me/password",
+    summary = "Ganti password dengan password lama",
+    description = "`Memerlukan login` (bukan API key). Semua sesi di device lain ikut logout, salah password lama berulang kali akan dikunci sementara",
+    request_body = UpdatePasswordRequest,
+    responses(
+        (status = 200, description = "Password changed", body = ActionResult<String, String>, example = json!({
+            "result": true,
+            "message": "Password changed successfully, other sessions have been signed out"
+        })),
+        (status = 400, description = "Current password is incorrect", body = ActionResult<String, String>),
+        (status = 403, description = "Request memakai API key", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn update_password_doc() {}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/me/avatar",
+    summary = "Upload foto profil",
+    description = "`Memerlukan login`. Gambar JPEG/PNG/WebP base64 maksimal 5MB, di-crop persegi lalu disimpan sebagai thumbnail WebP & PNG 256px dan 64px. `data` berisi URL baru untuk `Picture`",
+    request_body = UploadAvatarRequest,
+    responses(
+        (status = 200, description = "Avatar updated", body = ActionResult<String, String>, example = json!({
+            "result": true,
+            "message": "Avatar updated successfully",
+            "data": "https://snakesystem-web-api.shuttle.app/api/v1/auth/avatar/Xy7.../256.webp"
+        })),
+        (status = 400, description = "Validation failed", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn upload_avatar_doc() {}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/avatar/{avatar_key}/{file}",
+    summary = "File thumbnail avatar",
+    params(
+        ("avatar_key" = String, Path, description = "Key dari URL `Picture`"),
+        ("file" = String, Path, description = "`256.webp`, `256.png`, `64.webp` atau `64.png`")
+    ),
+    responses(
+        (status = 200, description = "Image", content_type = "image/webp"),
+        (status = 404, description = "Avatar not found")
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn get_avatar_doc() {}
+
+// Account Deletion Docs
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/me/export",
+    summary = "Export seluruh data pribadi",
+    description = "`Memerlukan login` (bukan API key). File JSON berisi akun, role, login OIDC, sesi, API key, KYC (data, riwayat & metadata dokumen), TableRequest, riwayat email dan audit log milik user. Hash password, token & isi file dokumen tidak ikut diexport",
+    responses(
+        (status = 200, description = "File JSON", content_type = "application/json", example = json!({
+            "format": "snakesystem-personal-data/1",
+            "generated_time": "2025-01-01T00:00:00Z",
+            "account": { "AuthUserNID": 12, "Email": "user@mail.com" },
+            "roles": [{ "RoleName": "user" }],
+            "sessions": [],
+            "kyc": null,
+            "emails": [],
+            "audit_events": []
+        })),
+        (status = 403, description = "Request memakai API key", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn export_personal_data_doc() {}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/me/deletion",
+    summary = "Status permintaan hapus akun",
+    description = "`Memerlukan login`",
+    responses(
+        (status = 200, description = "Deletion scheduled", body = ActionResult<AccountDeletionStatus, String>),
+        (status = 404, description = "No pending account deletion", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn get_account_deletion_doc() {}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/me/deletion",
+    summary = "Minta akun dihapus",
+    description = "`Memerlukan login` (bukan API key). Akun dan data pribadi (AuthUser, KYC, dokumen, TableRequest, sesi, API key, riwayat email) dihapus permanen setelah masa tenggang 14 hari. Selama masa tenggang akun tetap bisa dipakai dan permintaan bisa dibatalkan. Audit log tidak dihapus, hanya email, IP & user agent yang dikosongkan",
+    request_body = AccountDeletionRequest,
+    responses(
+        (status = 200, description = "Deletion scheduled", body = ActionResult<AccountDeletionStatus, String>, example = json!({
+            "result": true,
+            "message": "Your account will be permanently deleted on 2025-01-15 10:00, cancel before then if you change your mind",
+            "data": { "deletion_nid": 3, "requested_time": "2025-01-01T10:00:00Z", "scheduled_time": "2025-01-15T10:00:00Z" }
+        })),
+        (status = 400, description = "Deletion already requested", body = ActionResult<AccountDeletionStatus, String>),
+        (status = 403, description = "Request memakai API key", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn request_account_deletion_doc() {}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/me/deletion",
+    summary = "Batalkan permintaan hapus akun",
+    description = "`Memerlukan login` (bukan API key). Hanya selama masa tenggang belum lewat",
+    responses(
+        (status = 200, description = "Account deletion cancelled", body = ActionResult<String, String>),
+        (status = 404, description = "No pending account deletion", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn cancel_account_deletion_doc() {}
+
 // Activation User Docs
 #[utoipa::path(
     get,
     path = "/api/v1/auth/activation/{otp_link}",
     params(
-        ("otp_link" = String, Path, description = "Link OTP aktivasi user")
+        ("otp_link" = String, Path, description = "Token aktivasi dari email, berlaku 24 jam dan sekali pakai")
     ),
     responses(
         (status = 200, description = "Aktivasi berhasil", body = ActionResult<String, String>, example = json!({
@@ -145,24 +664,43 @@ pub fn logout_doc() {}
 #[allow(dead_code)]
 pub fn activation_user_doc() {}
 
+// Resend activation Docs
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/activation/resend",
+    summary = "Kirim ulang link aktivasi",
+    description = "Link lama langsung tidak berlaku. Respon selalu sama untuk email terdaftar maupun tidak",
+    request_body = ResendActivationRequest,
+    responses(
+        (status = 200, description = "Permintaan diterima", body = ActionResult<String, String>, example = json!({
+            "result": true,
+            "message": "If the account is waiting for activation, a new activation link has been sent"
+        })),
+        (status = 400, description = "Terlalu banyak permintaan", body = ActionResult<String, String>)
+    ),
+    tag = "1. Authentiacation"
+)]
+#[allow(dead_code)]
+pub fn resend_activation_doc() {}
+
 // Forget password User Docs
 #[utoipa::path(
     post,
     path = "/api/v1/auth/reset-password",
+    description = "Respon selalu sama untuk email terdaftar maupun tidak. Dibatasi 3 permintaan per email per 24 jam",
     request_body = ResetPasswordRequest,
     responses(
-        (status = 200, description = "Aktivasi berhasil", body = ActionResult<String, String>, example = json!({
+        (status = 200, description = "Permintaan diterima", body = ActionResult<String, String>, example = json!({
             "result": true,
-            "message": "Akun berhasil diaktivasi",
+            "message": "If the email is registered, a reset password link has been sent",
         })),
-        (status = 400, description = "OTP tidak valid", body = ActionResult<String, String>, example = json!({
+        (status = 400, description = "Terlalu banyak permintaan", body = ActionResult<String, String>, example = json!({
             "result": false,
-            "message": "OTP invalid",
-            "error": "Bad Request"
+            "message": "Too many failed attempts, please try again in 2 minute(s)"
         })),
-        (status = 500, description = "Gagal aktivasi", body = ActionResult<String, String>, example = json!({
+        (status = 500, description = "Internal Server Error", body = ActionResult<String, String>, example = json!({
             "result": false,
-            "message": "Gagal aktivasi akun",
+            "message": "",
             "error": "Internal Server Error"
         }))
     ),
@@ -354,7 +892,7 @@ pub fn get_library_doc() {}
     get,
     path = "/api/v1/data/header",
     summary = "Get generic columns",
-    description = "`Wajib login terlebih dahulu. Memerlukan token dari cookies` untuk mengecek sesi login pengguna",
+    description = "`Wajib login terlebih dahulu. Memerlukan token dari cookies` untuk mengecek sesi login pengguna. `tablename` harus terdaftar di `WEB_DataView` dan user wajib punya permission-nya, hanya kolom yang diizinkan yang dikembalikan",
     params(
         HeaderParams
     ),
@@ -387,10 +925,15 @@ pub fn get_library_doc() {}
             "message": "Token has expired",
             "error": "Internal Server Error"
         })),
-        (status = 400, description = "Bad Request", body = ActionResult<String, String>, example = json!({
+        (status = 400, description = "Table not registered", body = ActionResult<String, String>, example = json!({
             "result": false,
-            "message": "Token not found",
-            "error": "Bad Request"
+            "message": "Table is not available",
+            "error": "Unknown table 'AuthUser'"
+        })),
+        (status = 403, description = "Missing view permission", body = ActionResult<String, String>, example = json!({
+            "result": false,
+            "message": "Forbidden",
+            "error": "Missing permission 'data:read'"
         }))
     ),
     tag = "4. Data Endpoints"
@@ -403,7 +946,7 @@ pub fn get_header_docs(_: web::Query<HeaderParams>) {}
     get,
     path = "/api/v1/data/get-table",
     summary = "Get generic columns",
-    description = "`Wajib get header terlebih dahulu.` untuk mengecek header columns",
+    description = "`Wajib get header terlebih dahulu.` untuk mengecek header columns. `tablename` harus terdaftar di `WEB_DataView`, `sort` dan kolom di `filter` harus termasuk kolom yang diizinkan, `order` hanya `ASC` / `DESC`. Tanpa `sort` urutan mengikuti default registry, view dengan pemilik baris hanya mengembalikan baris milik user login. `sort` bisa beberapa kolom (`Price:desc,Email`), primary key selalu jadi urutan terakhir. Untuk tabel besar kirim `nextCursor` / `prevCursor` sebagai `cursor` (keyset pagination) dan `total=none` / `total=estimate`. Decimal & money dikirim sebagai string persis, `decimals=number` untuk angka",
     params(
         TableDataParams
     ),
@@ -433,11 +976,18 @@ pub fn get_header_docs(_: web::Query<HeaderParams>) {}
                 "DataDescription": "Jasa Keuangan Pasar Besar",
                 "LastUpdate": "2021-01-01"
                 }
-            ]
-    
+            ],
+            "nextCursor": "eyJiIjpmYWxzZSwidiI6WzNdLCJzIjoiOWYxYzJlNGE3YjMwZDQ1NiJ9",
+            "prevCursor": null
+        })),
+        (status = 400, description = "Table not registered, column not allowed or invalid order / filter", example = json!({
+            "error": "Unknown column 'DataNID; DROP TABLE AuthUser--'"
+        })),
+        (status = 403, description = "Missing view permission", example = json!({
+            "error": "Missing permission 'data:read'"
         })),
         (status = 500, description = "Internal Server Error", example = json!({
-            "error": "Token error: 'Invalid column name 'AutoNID'.' on server S3 executing  on line 1 (code: 207, state: 1, class: 16)"
+            "error": "Token error: 'Connection reset by peer' on server S3 executing  on line 1 (code: 10054, state: 1, class: 16)"
         })),
     ),
     tag = "4. Data Endpoints"
@@ -447,6 +997,105 @@ pub fn get_table_data_docs(params: web::Query<TableDataParams>) {
     params.into_inner();
 }
 
+// Get Roles Docs
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/roles",
+    summary = "Daftar role",
+    description = "`Wajib login sebagai admin (permission admin:roles)`",
+    responses(
+        (status = 200, description = "Roles retrieved", body = ActionResult<String, String>, example = json!({
+            "result": true,
+            "message": "Retrieved data successfully",
+            "data": [
+                { "RoleNID": 1, "RoleName": "admin", "Description": "Administrator, akses penuh", "Permissions": "data:read,data:clear,import:write,export:read,admin:roles" },
+                { "RoleNID": 2, "RoleName": "client", "Description": "Client terdaftar", "Permissions": "data:read,import:write,export:read" }
+            ]
+        })),
+        (status = 401, description = "Unauthorized", body = ActionResult<String, String>),
+        (status = 403, description = "Forbidden", body = ActionResult<String, String>, example = json!({
+            "result": false,
+            "message": "Forbidden",
+            "error": "Missing permission 'admin:roles'"
+        }))
+    ),
+    tag = "6. Admin Endpoints"
+)]
+#[allow(dead_code)]
+pub fn get_roles_doc() {}
+
+// Assign Role Docs
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{auth_usernid}/roles",
+    params(
+        ("auth_usernid" = i32, Path, description = "AuthUserNID"),
+    ),
+    request_body = AssignRoleRequest,
+    summary = "Tambah role ke user",
+    description = "`Wajib login sebagai admin (permission admin:roles)`. Role baru berlaku setelah token user diperbarui",
+    responses(
+        (status = 200, description = "Role assigned", body = ActionResult<String, String>, example = json!({
+            "result": true,
+            "message": "Role 'admin' assigned to user 12"
+        })),
+        (status = 400, description = "Bad Request", body = ActionResult<String, String>, example = json!({
+            "result": false,
+            "message": "Role 'superuser' not found"
+        })),
+        (status = 403, description = "Forbidden", body = ActionResult<String, String>)
+    ),
+    tag = "6. Admin Endpoints"
+)]
+#[allow(dead_code)]
+pub fn assign_role_doc() {}
+
+// Revoke Role Docs
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/users/{auth_usernid}/roles/{role}",
+    params(
+        ("auth_usernid" = i32, Path, description = "AuthUserNID"),
+        ("role" = String, Path, description = "Nama role"),
+    ),
+    summary = "Cabut role dari user",
+    description = "`Wajib login sebagai admin (permission admin:roles)`. Admin terakhir tidak bisa dicabut",
+    responses(
+        (status = 200, description = "Role revoked", body = ActionResult<String, String>),
+        (status = 400, description = "Bad Request", body = ActionResult<String, String>, example = json!({
+            "result": false,
+            "message": "Cannot revoke the last admin"
+        })),
+        (status = 403, description = "Forbidden", body = ActionResult<String, String>)
+    ),
+    tag = "6. Admin Endpoints"
+)]
+#[allow(dead_code)]
+pub fn revoke_role_doc() {}
+
+// Delete Account Docs
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/accounts/{auth_usernid}",
+    params(
+        ("auth_usernid" = i32, Path, description = "AuthUserNID"),
+    ),
+    summary = "Hapus akun user sekarang juga",
+    description = "`Wajib login sebagai admin (permission account:delete)`. Sama dengan penghapusan setelah masa tenggang, tanpa menunggu permintaan dari user. Akun sendiri dihapus lewat `/auth/me/deletion`",
+    responses(
+        (status = 200, description = "Account deleted", body = ActionResult<String, String>, example = json!({
+            "result": true,
+            "message": "Account and all personal data have been deleted"
+        })),
+        (status = 400, description = "Admin menghapus akunnya sendiri", body = ActionResult<String, String>),
+        (status = 403, description = "Forbidden", body = ActionResult<String, String>),
+        (status = 404, description = "User not found", body = ActionResult<String, String>)
+    ),
+    tag = "6. Admin Endpoints"
+)]
+#[allow(dead_code)]
+pub fn delete_account_doc() {}
+
 // Health Check Docs
 #[utoipa::path(
     get,
@@ -480,9 +1129,16 @@ pub fn not_found_docs() {}
 
 // Not Found Docs
 #[utoipa::path(get, path = "/api/v1/generic/ws/",
+    description = "Login opsional. Event privat (`kyc_status`, `kyc_review_queue`) hanya dikirim selama sesi masih aktif, socket ditutup jika sesi dicabut",
     responses(
         (status = 200, description = "Web Socket Success", example = json!({
             "message": "on progress", 
+        })),
+        (status = 403, description = "Origin tidak terdaftar di CORS_ALLOWED_ORIGINS", body = ActionResult<String, String>, example = json!({
+            "result": false,
+            "message": "Forbidden",
+            "data": null,
+            "error": "Origin not allowed"
         }))
     ),
     tag = "5. Generic Endpoints"
@@ -490,11 +1146,312 @@ pub fn not_found_docs() {}
 #[allow(dead_code)]
 pub fn ws_route_docs() {}
 
+// KYC Docs
+#[utoipa::path(
+    get,
+    path = "/api/v1/kyc",
+    summary = "Status & data KYC milik user",
+    description = "`Memerlukan login`. `actions` berisi langkah yang boleh dilakukan sekarang",
+    responses(
+        (status = 200, description = "KYC overview", body = ActionResult<KycOverview, String>, example = json!({
+            "result": true,
+            "message": "",
+            "data": {
+                "kyc_nid": 12,
+                "email": "client@example.com",
+                "full_name": "Budi Santoso",
+                "stage": "occupation",
+                "status": "draft",
+                "reason": null,
+                "data": { "personal_data": { "nik": "3171234567890001", "birth_place": "Jakarta" } },
+                "actions": ["submit:personal_data", "submit:occupation"],
+                "last_update": "2025-05-01T10:00:00Z"
+            }
+        })),
+        (status = 404, description = "KYC data not found", body = ActionResult<String, String>)
+    ),
+    tag = "7. KYC Endpoints"
+)]
+#[allow(dead_code)]
+pub fn get_kyc_doc() {}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/kyc/history",
+    summary = "Riwayat perubahan status KYC milik user",
+    description = "`Memerlukan login`",
+    responses(
+        (status = 200, description = "KYC history", body = ActionResult<Vec<KycHistory>, String>)
+    ),
+    tag = "7. KYC Endpoints"
+)]
+#[allow(dead_code)]
+pub fn get_kyc_history_doc() {}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/kyc/stages/{stage}",
+    summary = "Isi / perbaiki data satu tahap KYC",
+    description = "`Memerlukan login`. Tahap harus diisi berurutan, tahap sebelumnya boleh diperbaiki selama belum dikirim untuk review. Body mengikuti schema `KycPersonalData`, `KycOccupation` atau `KycBankAccount`",
+    params(("stage" = String, Path, description = "`personal_data`, `occupation` atau `bank_account`")),
+    request_body(content = KycPersonalData, description = "Contoh body untuk `personal_data`"),
+    responses(
+        (status = 200, description = "Stage saved", body = ActionResult<KycOverview, String>),
+        (status = 400, description = "Validasi gagal / transisi tidak diizinkan", body = ActionResult<String, String>, example = json!({
+            "result": false,
+            "message": "Please complete stage 'personal_data' first"
+        }))
+    ),
+    tag = "7. KYC Endpoints"
+)]
+#[allow(dead_code)]
+pub fn submit_kyc_stage_doc() {}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/kyc/submit",
+    summary = "Kirim KYC untuk direview",
+    description = "`Memerlukan login`. Hanya bisa setelah semua tahap terisi (`stage` = `confirmation`) dan dokumen `id_card`, `selfie`, `bank_book` sudah diupload. Reviewer menerima event WebSocket `kyc_review_queue`",
+    responses(
+        (status = 200, description = "KYC submitted for review", body = ActionResult<KycOverview, String>),
+        (status = 400, description = "KYC data is not complete yet", body = ActionResult<String, String>)
+    ),
+    tag = "7. KYC Endpoints"
+)]
+#[allow(dead_code)]
+pub fn submit_kyc_doc() {}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/kyc/review",
+    summary = "Antrian KYC yang menunggu review",
+    description = "`Memerlukan permission kyc:review`. Terlama lebih dulu, maksimal 200",
+    responses(
+        (status = 200, description = "Review queue", body = ActionResult<Vec<KycOverview>, String>),
+        (status = 403, description = "Forbidden", body = ActionResult<String, String>)
+    ),
+    tag = "7. KYC Endpoints"
+)]
+#[allow(dead_code)]
+pub fn get_kyc_review_queue_doc() {}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/kyc/review/{kyc_nid}",
+    summary = "Detail KYC untuk reviewer",
+    description = "`Memerlukan permission kyc:review`. Riwayat ada di `/api/v1/kyc/review/{kyc_nid}/history`",
+    params(("kyc_nid" = i32, Path, description = "UserKyc.AutoNID")),
+    responses(
+        (status = 200, description = "KYC overview", body = ActionResult<KycOverview, String>),
+        (status = 404, description = "KYC data not found", body = ActionResult<String, String>)
+    ),
+    tag = "7. KYC Endpoints"
+)]
+#[allow(dead_code)]
+pub fn get_kyc_review_detail_doc() {}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/kyc/review/{kyc_nid}/approve",
+    summary = "Approve KYC",
+    description = "`Memerlukan permission kyc:review`. Tidak bisa untuk KYC milik sendiri. Client menerima event WebSocket `kyc_status`",
+    params(("kyc_nid" = i32, Path, description = "UserKyc.AutoNID")),
+    request_body = KycApproveRequest,
+    responses(
+        (status = 200, description = "KYC approved", body = ActionResult<KycOverview, String>),
+        (status = 400, description = "KYC is not waiting for review", body = ActionResult<String, String>)
+    ),
+    tag = "7. KYC Endpoints"
+)]
+#[allow(dead_code)]
+pub fn approve_kyc_doc() {}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/kyc/review/{kyc_nid}/reject",
+    summary = "Tolak KYC",
+    description = "`Memerlukan permission kyc:review`. Alasan wajib dan ditampilkan ke client",
+    params(("kyc_nid" = i32, Path, description = "UserKyc.AutoNID")),
+    request_body = KycRejectRequest,
+    responses(
+        (status = 200, description = "KYC rejected", body = ActionResult<KycOverview, String>),
+        (status = 400, description = "KYC is not waiting for review", body = ActionResult<String, String>)
+    ),
+    tag = "7. KYC Endpoints"
+)]
+#[allow(dead_code)]
+pub fn reject_kyc_doc() {}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/kyc/review/{kyc_nid}/revision",
+    summary = "Minta client memperbaiki satu tahap",
+    description = "`Memerlukan permission kyc:review`. KYC kembali ke tahap yang diminta, alasan wajib",
+    params(("kyc_nid" = i32, Path, description = "UserKyc.AutoNID")),
+    request_body = KycRevisionRequest,
+    responses(
+        (status = 200, description = "Revision requested", body = ActionResult<KycOverview, String>),
+        (status = 400, description = "KYC is not waiting for review", body = ActionResult<String, String>)
+    ),
+    tag = "7. KYC Endpoints"
+)]
+#[allow(dead_code)]
+pub fn request_kyc_revision_doc() {}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/kyc/documents",
+    summary = "Dokumen KYC milik sendiri",
+    description = "`Memerlukan login`. Hanya metadata, file tidak bisa diunduh kembali oleh client",
+    responses(
+        (status = 200, description = "KYC documents", body = ActionResult<Vec<KycDocumentInfo>, String>)
+    ),
+    tag = "7. KYC Endpoints"
+)]
+#[allow(dead_code)]
+pub fn get_kyc_documents_doc() {}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/kyc/documents/{document_type}",
+    summary = "Upload / ganti dokumen KYC",
+    description = "`Memerlukan login`. Format dideteksi dari isi file (bukan ekstensi), disimpan ulang sebagai JPEG tanpa metadata EXIF. Hanya selama KYC masih bisa diubah. Ketiga dokumen wajib ada sebelum `/api/v1/kyc/submit`",
+    params(("document_type" = String, Path, description = "`id_card`, `selfie` atau `bank_book`")),
+    request_body(content = KycDocumentUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Document uploaded", body = ActionResult<KycDocumentInfo, String>),
+        (status = 400, description = "Invalid image or KYC can no longer be changed", body = ActionResult<String, String>),
+        (status = 413, description = "File too large", body = ActionResult<String, String>)
+    ),
+    tag = "7. KYC Endpoints"
+)]
+#[allow(dead_code)]
+pub fn upload_kyc_document_doc() {}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/kyc/documents/file",
+    summary = "Unduh file dokumen KYC",
+    description = "Link dari `/api/v1/kyc/review/{kyc_nid}/documents`, berlaku 10 menit. Tidak di-cache",
+    params(DocumentDownloadQuery),
+    responses(
+        (status = 200, description = "Document file", content_type = "image/jpeg"),
+        (status = 404, description = "Link is invalid, expired or document was replaced", body = ActionResult<String, String>)
+    ),
+    tag = "7. KYC Endpoints"
+)]
+#[allow(dead_code)]
+pub fn download_kyc_document_doc() {}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/kyc/review/{kyc_nid}/documents",
+    summary = "Dokumen KYC untuk reviewer",
+    description = "`Memerlukan permission kyc:review`. Setiap dokumen berisi `download_url` dan `thumbnail_url` sementara",
+    params(("kyc_nid" = i32, Path, description = "UserKyc.AutoNID")),
+    responses(
+        (status = 200, description = "KYC documents", body = ActionResult<Vec<KycDocumentInfo>, String>),
+        (status = 403, description = "Forbidden", body = ActionResult<String, String>)
+    ),
+    tag = "7. KYC Endpoints"
+)]
+#[allow(dead_code)]
+pub fn get_kyc_review_documents_doc() {}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/validate/banks",
+    summary = "Daftar bank yang didukung",
+    description = "Kode bank kliring dan panjang nomor rekening, untuk dropdown `bank_code` di KYC",
+    responses(
+        (status = 200, description = "Supported banks", body = ActionResult<Vec<BankInfo>, String>)
+    ),
+    tag = "8. Validation Endpoints"
+)]
+#[allow(dead_code)]
+pub fn get_banks_doc() {}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/validate/{field}",
+    summary = "Validasi satu field form",
+    description = "Aturan sama dengan validasi server saat submit. `nik`: 16 digit dengan kode wilayah, tanggal lahir & jenis kelamin. `npwp`: 15/16 digit. `phone`: dinormalisasi ke E.164 (+62). `bank_account`: panjang sesuai `bank_code`",
+    params(("field" = String, Path, description = "`nik`, `npwp`, `phone` atau `bank_account`")),
+    request_body = ValidateFieldRequest,
+    responses(
+        (status = 200, description = "Valid", body = ActionResult<ValidatedField, String>, example = json!({
+            "result": true,
+            "message": "Valid",
+            "data": { "field": "phone", "normalized": "+6281234567890" },
+            "error": null
+        })),
+        (status = 400, description = "Invalid value", body = ActionResult<String, String>),
+        (status = 404, description = "Unknown field", body = ActionResult<String, String>)
+    ),
+    tag = "8. Validation Endpoints"
+)]
+#[allow(dead_code)]
+pub fn validate_field_doc() {}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit/logs",
+    summary = "Daftar audit log",
+    description = "`Memerlukan permission audit:read`. Paging, cursor & filter sama dengan `/data/get-table`, terbaru dulu. Kolom filter / sort di luar daftar berikut ditolak 400. Kolom: AuditLogNID, EventType, Success, ActorNID, ActorEmail, IPAddress, UserAgent, Target, Details, CreatedTime. EventType: login_success, login_failed, logout, register, activation, password_reset_request, password_reset, password_change, role_assign, role_revoke, data_clear, import, export",
+    params(AuditLogParams),
+    responses(
+        (status = 200, description = "Audit log", example = json!({
+            "totalNotFiltered": 1520,
+            "total": 2,
+            "rows": [
+                {
+                    "AuditLogNID": 1520,
+                    "EventType": "login_failed",
+                    "Success": false,
+                    "ActorNID": null,
+                    "ActorEmail": null,
+                    "IPAddress": "10.0.0.1",
+                    "UserAgent": "Mozilla/5.0",
+                    "Target": "user@mail.com",
+                    "Details": "{\"method\":\"password\"}",
+                    "CreatedTime": "2025-01-31T10:15:00"
+                }
+            ],
+            "nextCursor": "eyJzcGVjIjoiLi4uIn0",
+            "prevCursor": null
+        })),
+        (status = 400, description = "Filter, sort atau cursor tidak valid", example = json!({
+            "error": "Unknown column 'EvenType'"
+        })),
+        (status = 403, description = "Forbidden", body = ActionResult<String, String>)
+    ),
+    tag = "9. Audit Endpoints"
+)]
+#[allow(dead_code)]
+pub fn get_audit_logs_doc() {}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit/logs/csv",
+    summary = "Export audit log ke CSV",
+    description = "`Memerlukan permission audit:read`. Filter & sort sama dengan `/audit/logs`, maksimal 10.000 baris per export. Export ini ikut tercatat di audit log",
+    params(AuditLogParams),
+    responses(
+        (status = 200, description = "File CSV", content_type = "text/csv"),
+        (status = 400, description = "Filter atau sort tidak valid", body = ActionResult<String, String>),
+        (status = 403, description = "Forbidden", body = ActionResult<String, String>),
+        (status = 500, description = "Internal Server Error", body = ActionResult<String, String>)
+    ),
+    tag = "9. Audit Endpoints"
+)]
+#[allow(dead_code)]
+pub fn export_audit_logs_doc() {}
+
 #[derive(OpenApi)]
 #[openapi(
     info(
         title = "Snakesystem API",
-        description = "Dokumentasi untuk RESTful API SnakeSystem.\n\nSilakan gunakan token JWT untuk mengakses endpoint yang dilindungi.",
+        description = "Dokumentasi untuk RESTful API SnakeSystem.\n\nSilakan gunakan token JWT untuk mengakses endpoint yang dilindungi.\n\nBahasa pesan (`message` & error validasi) mengikuti `locale` di profil user, lalu header `Accept-Language` (`id` / `en`, default `en`). Locale yang dipakai dikirim balik lewat header `Content-Language`.\n\nRequest POST/PUT/PATCH/DELETE dengan cookie sesi wajib membawa header `X-CSRF-Token` dari `GET /api/v1/auth/csrf`.",
         version = "1.0.0"
     ),
     paths(
@@ -504,18 +1461,67 @@ pub fn ws_route_docs() {}
         reset_password_doc,
         change_password_doc,
         check_session_doc,
+        csrf_token_doc,
+        refresh_doc,
+        login_mfa_doc,
+        request_magic_link_doc,
+        login_magic_link_doc,
+        enroll_totp_doc,
+        confirm_totp_doc,
+        disable_totp_doc,
+        oidc_authorize_doc,
+        oidc_callback_doc,
+        jwks_doc,
         logout_doc,
+        get_sessions_doc,
+        rename_session_doc,
+        revoke_session_doc,
+        revoke_other_sessions_doc,
+        get_api_keys_doc,
+        create_api_key_doc,
+        revoke_api_key_doc,
         activation_user_doc,
+        resend_activation_doc,
+        get_profile_doc,
+        update_profile_doc,
+        update_password_doc,
+        upload_avatar_doc,
+        get_avatar_doc,
+        export_personal_data_doc,
+        get_account_deletion_doc,
+        request_account_deletion_doc,
+        cancel_account_deletion_doc,
         contact_form_doc,
         create_library_doc,
         get_libraries_doc,
         get_library_doc,
         not_found_docs,
         get_header_docs,
-        get_table_data_docs
+        get_table_data_docs,
+        get_roles_doc,
+        assign_role_doc,
+        revoke_role_doc,
+        delete_account_doc,
+        get_kyc_doc,
+        get_kyc_history_doc,
+        submit_kyc_stage_doc,
+        submit_kyc_doc,
+        get_kyc_review_queue_doc,
+        get_kyc_review_detail_doc,
+        approve_kyc_doc,
+        reject_kyc_doc,
+        request_kyc_revision_doc,
+        get_kyc_documents_doc,
+        upload_kyc_document_doc,
+        download_kyc_document_doc,
+        get_kyc_review_documents_doc,
+        get_banks_doc,
+        validate_field_doc,
+        get_audit_logs_doc,
+        export_audit_logs_doc
     ),
     components(
-        schemas(ActionResult<Claims, String>)
+        schemas(ActionResult<Claims, String>, KycPersonalData, KycOccupation, KycBankAccount, KycDocumentUpload)
     ),
     tags(
         (name = "0. Application Default Endpoints", description = "Default path application endpoints"),
@@ -524,6 +1530,10 @@ pub fn ws_route_docs() {}
         (name = "3. Library Endpoints", description = "Library endpoints to manage library data for Snakesystem Library"),
         (name = "4. Data Endpoints", description = "Data endpoints to manage generic data"),
         (name = "5. Generic Endpoints", description = "Generic endpoints to manage reusable url"),
+        (name = "6. Admin Endpoints", description = "Admin endpoints to manage user roles and permissions"),
+        (name = "7. KYC Endpoints", description = "KYC onboarding workflow for clients and reviewers"),
+        (name = "8. Validation Endpoints", description = "Indonesian identity, tax, phone and bank account validators"),
+        (name = "9. Audit Endpoints", description = "Append-only audit log of security-relevant events"),
     )
 )]
 
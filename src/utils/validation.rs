@@ -1,15 +1,20 @@
 pub mod validator {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, fmt::Display};
+    use actix_web::HttpResponse;
     use base64::{engine::general_purpose, Engine as _}; // Pake Engine
-    use chrono::{DateTime, Utc};
+    use chrono::{DateTime, Datelike, NaiveDate, Utc};
     use image::ImageFormat;
     use regex::Regex;
+    use serde::Serialize;
+    use serde_json::json;
     use validator::{ValidationError, ValidationErrors};
 
+    use crate::contexts::{i18n::{is_message_id, t, t_args}, model::{KycBankAccount, KycPersonalData}};
+
     pub fn required(value: &str) -> Result<(), ValidationError> {
         if value.trim().is_empty() {
             let mut error = ValidationError::new("required");
-            error.message = Some("This field is required".into());
+            error.message = Some("validation.required".into());
             return Err(error);
         }
         Ok(())
@@ -18,7 +23,7 @@ pub mod validator {
     pub fn required_int(value: i32) -> Result<(), ValidationError> {
         if value == 0 {
             let mut error = ValidationError::new("required");
-            error.message = Some("This field hand not null or 0".into());
+            error.message = Some("validation.required".into());
             return Err(error);
         }
         Ok(())
@@ -27,7 +32,7 @@ pub mod validator {
     pub fn required_datetime(value: &DateTime<Utc>) -> Result<(), ValidationError> {
         if value.to_string().trim().is_empty() {
             let mut error = ValidationError::new("required");
-            error.message = Some("This field is required".into());
+            error.message = Some("validation.required".into());
             return Err(error);
         }
         Ok(())
@@ -38,35 +43,36 @@ pub mod validator {
             .map_err(|_| ValidationError::new("invalid_regex"))?;
 
         if !email_regex.is_match(value) {
-            let mut error = ValidationError::new("invalid_email");
-            error.message = Some("Format name value has not number".into());
+            let mut error = ValidationError::new("invalid_name");
+            error.message = Some("validation.name".into());
             return Err(error);
         }
         Ok(())
     }
 
+    /// Minimal 8 karakter, hanya huruf & angka ASCII, wajib ada huruf dan angka.
+    /// Dicek manual karena crate `regex` tidak mendukung look-ahead
     pub fn valid_password(value: &str) -> Result<(), ValidationError> {
-        let password_regex = Regex::new(r"^(?=.*[A-Za-z])(?=.*\d)[A-Za-z\d]{8,}$")
-            .map_err(|_| ValidationError::new("invalid_regex"))?;
+        let valid = value.len() >= 8
+            && value.chars().all(|c| c.is_ascii_alphanumeric())
+            && value.chars().any(|c| c.is_ascii_alphabetic())
+            && value.chars().any(|c| c.is_ascii_digit());
 
-        if !password_regex.is_match(value) {
-            let mut error = ValidationError::new("invalid_password");
-            error.message = Some("Required character number and text".into());
-            return Err(error);
+        if !valid {
+            return Err(invalid("invalid_password", "validation.password"));
         }
         Ok(())
     }
 
     pub fn valid_phone_number(value: &str) -> Result<(), ValidationError> {
-        let phone_regex = Regex::new(r"^\d{10,15}$")
-            .map_err(|_| ValidationError::new("invalid_regex"))?;
+        normalize_phone(value).map(|_| ())
+    }
 
-        if !phone_regex.is_match(value) {
-            let mut error = ValidationError::new("invalid_phone");
-            error.message = Some("Valus has number from 10-15 length".into());
-            return Err(error);
+    pub fn valid_locale(value: &str) -> Result<(), ValidationError> {
+        match value {
+            "id" | "en" => Ok(()),
+            _ => Err(invalid("invalid_locale", "validation.locale")),
         }
-        Ok(())
     }
 
     pub fn valid_number_card(value: &str) -> Result<(), ValidationError> {
@@ -75,7 +81,7 @@ pub mod validator {
 
         if !phone_regex.is_match(value) {
             let mut error = ValidationError::new("invalid_number_card");
-            error.message = Some("Value has number format".into());
+            error.message = Some("validation.digits".into());
             return Err(error);
         }
         Ok(())
@@ -91,21 +97,22 @@ pub mod validator {
 
         let decoded = general_purpose::STANDARD.decode(base64_cleaned).map_err(|_| {
             let mut error = ValidationError::new("invalid_base64");
-            error.message = Some("Base64 tidak valid".into());
+            error.message = Some("validation.base64".into());
             error
         })?;
         
         // Cek ukuran maksimum (misal 5MB)
         if decoded.len() > 5 * 1024 * 1024 {
             let mut error = ValidationError::new("file_too_large");
-            error.message = Some("Ukuran file maksimal 5MB".into());
+            error.message = Some("validation.image_too_large".into());
+            error.add_param("max".into(), &5);
             return Err(error);
         }
     
         // Cek apakah Base64 ini benar-benar gambar
         let format = image::guess_format(&decoded).map_err(|_| {
             let mut error = ValidationError::new("invalid_image");
-            error.message = Some("File bukan gambar valid".into());
+            error.message = Some("validation.image_invalid".into());
             error
         })?;
     
@@ -114,28 +121,274 @@ pub mod validator {
             ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP => Ok(()),
             _ => {
                 let mut error = ValidationError::new("unsupported_format");
-                error.message = Some("Format gambar harus JPEG, PNG, atau WebP".into());
+                error.message = Some("validation.image_format".into());
                 Err(error)
             }
         }
     }
 
+    // Region Indonesia: identitas & perbankan
+    /// `message` berisi message ID katalog `i18n`, diterjemahkan saat error dikirim ke client
+    fn invalid(code: &'static str, message: &'static str) -> ValidationError {
+        let mut error = ValidationError::new(code);
+        error.message = Some(message.into());
+        error
+    }
+
+    fn digits_only(value: &str) -> String {
+        value.chars().filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')')).collect()
+    }
+
+    /// Kode provinsi Dukcapil (2 digit pertama NIK)
+    const PROVINCE_CODES: [&str; 38] = [
+        "11", "12", "13", "14", "15", "16", "17", "18", "19", "21",
+        "31", "32", "33", "34", "35", "36",
+        "51", "52", "53",
+        "61", "62", "63", "64", "65",
+        "71", "72", "73", "74", "75", "76",
+        "81", "82",
+        "91", "92", "93", "94", "95", "96",
+    ];
+
+    /// Isi NIK: `PPKKCC DDMMYY SSSS`, tanggal lahir perempuan ditambah 40
+    #[derive(Debug, Serialize)]
+    pub struct NikInfo {
+        pub province_code: String,
+        pub regency_code: String,
+        pub district_code: String,
+        pub birth_date: NaiveDate,
+        /// `male` atau `female`
+        pub gender: &'static str,
+    }
+
+    pub fn parse_nik(value: &str) -> Result<NikInfo, ValidationError> {
+        let nik = value.trim();
+        if nik.len() != 16 || !nik.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid("invalid_nik", "validation.nik_length"));
+        }
+
+        if !PROVINCE_CODES.contains(&&nik[0..2]) || &nik[2..4] == "00" || &nik[4..6] == "00" {
+            return Err(invalid("invalid_nik", "validation.nik_region"));
+        }
+        if &nik[12..16] == "0000" {
+            return Err(invalid("invalid_nik", "validation.nik_sequence"));
+        }
+
+        let mut day: u32 = nik[6..8].parse().unwrap_or(0);
+        let month: u32 = nik[8..10].parse().unwrap_or(0);
+        let year: i32 = nik[10..12].parse().unwrap_or(0);
+        let gender = if day > 40 {
+            day -= 40;
+            "female"
+        } else {
+            "male"
+        };
+
+        // Tahun 2 digit: abad ini kalau belum lewat tahun berjalan, selain itu abad lalu
+        let today = Utc::now().date_naive();
+        let century = if 2000 + year <= today.year() { 2000 } else { 1900 };
+        let birth_date = NaiveDate::from_ymd_opt(century + year, month, day)
+            .filter(|date| *date <= today)
+            .ok_or_else(|| invalid("invalid_nik", "validation.nik_birth_date"))?;
+
+        Ok(NikInfo {
+            province_code: nik[0..2].to_string(),
+            regency_code: nik[0..4].to_string(),
+            district_code: nik[0..6].to_string(),
+            birth_date,
+            gender,
+        })
+    }
+
+    pub fn valid_nik(value: &str) -> Result<(), ValidationError> {
+        parse_nik(value).map(|_| ())
+    }
+
+    pub fn valid_gender(value: &str) -> Result<(), ValidationError> {
+        match value {
+            "male" | "female" => Ok(()),
+            _ => Err(invalid("invalid_gender", "validation.gender")),
+        }
+    }
+
+    /// Tanggal lahir & jenis kelamin harus sama dengan yang ter-encode di NIK
+    pub fn valid_personal_data(data: &KycPersonalData) -> Result<(), ValidationError> {
+        let Ok(nik) = parse_nik(&data.nik) else {
+            // Error format NIK sudah dilaporkan di field `nik`
+            return Ok(());
+        };
+        if nik.birth_date != data.birth_date {
+            return Err(invalid("nik_mismatch", "validation.nik_birth_date_mismatch"));
+        }
+        if nik.gender != data.gender {
+            return Err(invalid("nik_mismatch", "validation.nik_gender_mismatch"));
+        }
+        Ok(())
+    }
+
+    /// NPWP 15 digit (`XX.XXX.XXX.X-XXX.XXX`) atau 16 digit (PMK 112/2022: NIK untuk orang pribadi,
+    /// `0` + NPWP lama untuk lainnya). Hasil normalisasi selalu 16 digit tanpa tanda baca.
+    pub fn normalize_npwp(value: &str) -> Result<String, ValidationError> {
+        let npwp = digits_only(value.trim());
+        if !npwp.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid("invalid_npwp", "validation.npwp_digits"));
+        }
+
+        let legacy = match npwp.len() {
+            15 => npwp.as_str(),
+            16 if npwp.starts_with('0') => &npwp[1..],
+            16 => {
+                parse_nik(&npwp).map_err(|_| invalid("invalid_npwp", "validation.npwp_nik"))?;
+                return Ok(npwp);
+            }
+            _ => return Err(invalid("invalid_npwp", "validation.npwp_length")),
+        };
+
+        // Digit ke-9 adalah check digit Luhn dari 8 digit pertama
+        let sum: u32 = legacy[0..9].bytes().rev().enumerate().map(|(i, b)| {
+            let digit = (b - b'0') as u32;
+            if i % 2 == 1 {
+                let doubled = digit * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                digit
+            }
+        }).sum();
+        if !sum.is_multiple_of(10) {
+            return Err(invalid("invalid_npwp", "validation.npwp_check_digit"));
+        }
+
+        Ok(format!("0{}", legacy))
+    }
+
+    pub fn valid_npwp(value: &str) -> Result<(), ValidationError> {
+        normalize_npwp(value).map(|_| ())
+    }
+
+    /// Nomor HP / telepon Indonesia ke E.164: `0812-3456-789`, `62812...`, `+62 812...` -> `+628123456789`
+    pub fn normalize_phone(value: &str) -> Result<String, ValidationError> {
+        let phone = digits_only(value.trim());
+        let national = if let Some(rest) = phone.strip_prefix("+62") {
+            rest
+        } else if let Some(rest) = phone.strip_prefix("62") {
+            rest
+        } else if let Some(rest) = phone.strip_prefix('0') {
+            rest
+        } else {
+            phone.as_str()
+        };
+
+        if !(8..=12).contains(&national.len()) || !national.bytes().all(|b| b.is_ascii_digit()) || national.starts_with('0') {
+            return Err(invalid("invalid_phone", "validation.phone"));
+        }
+
+        Ok(format!("+62{}", national))
+    }
+
+    /// Kode bank (kliring) dan panjang nomor rekening yang dipakai bank tersebut
+    pub struct BankRule {
+        pub code: &'static str,
+        pub name: &'static str,
+        pub account_lengths: &'static [usize],
+    }
+
+    pub static BANK_RULES: [BankRule; 16] = [
+        BankRule { code: "002", name: "Bank Rakyat Indonesia (BRI)", account_lengths: &[15] },
+        BankRule { code: "008", name: "Bank Mandiri", account_lengths: &[13] },
+        BankRule { code: "009", name: "Bank Negara Indonesia (BNI)", account_lengths: &[10] },
+        BankRule { code: "011", name: "Bank Danamon", account_lengths: &[10] },
+        BankRule { code: "013", name: "Bank Permata", account_lengths: &[10] },
+        BankRule { code: "014", name: "Bank Central Asia (BCA)", account_lengths: &[10] },
+        BankRule { code: "016", name: "Maybank Indonesia", account_lengths: &[10] },
+        BankRule { code: "019", name: "Bank Panin", account_lengths: &[10] },
+        BankRule { code: "022", name: "CIMB Niaga", account_lengths: &[12, 13, 14] },
+        BankRule { code: "028", name: "OCBC NISP", account_lengths: &[12] },
+        BankRule { code: "147", name: "Bank Muamalat", account_lengths: &[10] },
+        BankRule { code: "200", name: "Bank Tabungan Negara (BTN)", account_lengths: &[16] },
+        BankRule { code: "451", name: "Bank Syariah Indonesia (BSI)", account_lengths: &[10] },
+        BankRule { code: "490", name: "Bank Neo Commerce", account_lengths: &[12] },
+        BankRule { code: "535", name: "SeaBank Indonesia", account_lengths: &[12] },
+        BankRule { code: "542", name: "Bank Jago", account_lengths: &[12] },
+    ];
+
+    pub fn bank_rule(code: &str) -> Option<&'static BankRule> {
+        BANK_RULES.iter().find(|rule| rule.code == code.trim())
+    }
+
+    pub fn valid_bank_code(value: &str) -> Result<(), ValidationError> {
+        bank_rule(value).map(|_| ()).ok_or_else(|| invalid("invalid_bank_code", "validation.bank_code"))
+    }
+
+    /// Nomor rekening tanpa tanda baca, panjangnya sesuai format bank
+    pub fn normalize_bank_account(bank_code: &str, account_number: &str) -> Result<String, ValidationError> {
+        let rule = bank_rule(bank_code).ok_or_else(|| invalid("invalid_bank_code", "validation.bank_code"))?;
+        let account = digits_only(account_number.trim());
+        if !account.bytes().all(|b| b.is_ascii_digit()) || !rule.account_lengths.contains(&account.len()) {
+            let mut error = invalid("invalid_bank_account", "validation.bank_account");
+            let lengths: Vec<String> = rule.account_lengths.iter().map(|len| len.to_string()).collect();
+            error.add_param("bank".into(), &rule.name);
+            error.add_param("lengths".into(), &lengths.join("/"));
+            return Err(error);
+        }
+        Ok(account)
+    }
+
+    pub fn valid_bank_account(data: &KycBankAccount) -> Result<(), ValidationError> {
+        if bank_rule(&data.bank_code).is_none() {
+            // Sudah dilaporkan di field `bank_code`
+            return Ok(());
+        }
+        normalize_bank_account(&data.bank_code, &data.account_number).map(|_| ())
+    }
+
+    /// Pesan satu error dalam locale request. `message` boleh berupa message ID katalog,
+    /// params validator (`max`, `min`, dst) ikut jadi placeholder.
+    pub fn validation_message(field: &str, error: &ValidationError) -> String {
+        let field_name = capitalize(&field.replace('_', " "));
+        let params: Vec<(String, String)> = error.params.iter()
+            .filter(|(name, _)| name.as_ref() != "value")
+            .map(|(name, value)| (name.to_string(), value.as_str().map_or_else(|| value.to_string(), |v| v.to_string())))
+            .collect();
+
+        let key = match error.message.as_deref() {
+            Some(message) if is_message_id(message) => message,
+            Some(message) => return message.to_string(),
+            None => match error.code.as_ref() {
+                "required" => "validation.required",
+                "email" => "validation.email",
+                "length" if error.params.contains_key("max") => "validation.length_max",
+                "length" => "validation.length_min",
+                _ => "validation.invalid",
+            },
+        };
+
+        let mut args: Vec<(&str, &dyn Display)> = vec![("field", &field_name)];
+        for (name, value) in &params {
+            args.push((name.as_str(), value));
+        }
+        t_args(key, &args)
+    }
+
     pub fn format_validation_errors(errors: &ValidationErrors) -> HashMap<String, String> {
         let mut formatted_errors = HashMap::new();
     
         for (field, field_errors) in errors.field_errors() {
             if let Some(error) = field_errors.first() {
-                let error_message = match error.code.as_ref() {
-                    "required" => format!("{} is required", capitalize(&field)),
-                    _ => error.message.clone().unwrap_or_else(|| "Invalid value".into()).to_string(),
-                };
-    
-                formatted_errors.insert(field.to_string(), error_message);
+                formatted_errors.insert(field.to_string(), validation_message(&field, error));
             }
         }
     
         formatted_errors
     }
+
+    /// Response 400 standar untuk body yang gagal `validate()`
+    pub fn validation_failed(errors: &ValidationErrors) -> HttpResponse {
+        HttpResponse::BadRequest().json(json!({
+            "result": false,
+            "message": t("validation.failed"),
+            "error": format_validation_errors(errors)
+        }))
+    }
     
     // Helper untuk kapitalisasi huruf pertama
     fn capitalize(s: &str) -> String {
@@ -144,6 +397,107 @@ pub mod validator {
             None => String::new(),
             Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
         }
-    }    
-    
-}
\ No newline at end of file
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn code(result: Result<impl std::fmt::Debug, ValidationError>) -> String {
+            result.unwrap_err().code.to_string()
+        }
+
+        #[test]
+        fn password_needs_letter_and_digit() {
+            assert!(valid_password("abcdef12").is_ok());
+            assert!(valid_password("ABCDEFG1").is_ok());
+            assert_eq!(code(valid_password("abcdefgh")), "invalid_password");
+            assert_eq!(code(valid_password("12345678")), "invalid_password");
+            assert_eq!(code(valid_password("abcde12")), "invalid_password");
+            assert_eq!(code(valid_password("abcdef1!")), "invalid_password");
+            assert_eq!(code(valid_password("abcdéf12")), "invalid_password");
+        }
+
+        #[test]
+        fn nik_decodes_region_birth_date_and_gender() {
+            let nik = parse_nik("3201011201900001").unwrap();
+            assert_eq!((nik.province_code.as_str(), nik.regency_code.as_str(), nik.district_code.as_str()), ("32", "3201", "320101"));
+            assert_eq!(nik.birth_date, NaiveDate::from_ymd_opt(1990, 1, 12).unwrap());
+            assert_eq!(nik.gender, "male");
+
+            // Perempuan: tanggal lahir + 40
+            let nik = parse_nik(" 3201015201050001 ").unwrap();
+            assert_eq!(nik.birth_date, NaiveDate::from_ymd_opt(2005, 1, 12).unwrap());
+            assert_eq!(nik.gender, "female");
+        }
+
+        #[test]
+        fn nik_rejects_wrong_length_or_characters() {
+            assert_eq!(code(parse_nik("320101120190001")), "invalid_nik");
+            assert_eq!(code(parse_nik("32010112019000011")), "invalid_nik");
+            assert_eq!(code(parse_nik("32010112019000a1")), "invalid_nik");
+        }
+
+        #[test]
+        fn nik_rejects_bad_region() {
+            // Provinsi tidak terdaftar, kabupaten/kecamatan 00
+            assert!(parse_nik("9901011201900001").is_err());
+            assert!(parse_nik("3200011201900001").is_err());
+            assert!(parse_nik("3201001201900001").is_err());
+            // Nomor urut 0000
+            assert!(parse_nik("3201011201900000").is_err());
+        }
+
+        #[test]
+        fn nik_rejects_bad_birth_date() {
+            // 32 Januari, 30 Februari, bulan 13, tanggal 0
+            assert!(parse_nik("3201013201900001").is_err());
+            assert!(parse_nik("3201013002900001").is_err());
+            assert!(parse_nik("3201011213900001").is_err());
+            assert!(parse_nik("3201010001900001").is_err());
+        }
+
+        #[test]
+        fn npwp_normalizes_to_16_digits() {
+            assert_eq!(normalize_npwp("01.855.081.4-412.000").unwrap(), "0018550814412000");
+            assert_eq!(normalize_npwp("0018550814412000").unwrap(), "0018550814412000");
+            // 16 digit tanpa awalan 0 adalah NIK orang pribadi
+            assert_eq!(normalize_npwp("3201011201900001").unwrap(), "3201011201900001");
+        }
+
+        #[test]
+        fn npwp_rejects_bad_checksum_or_length() {
+            assert_eq!(code(normalize_npwp("01.855.081.5-412.000")), "invalid_npwp");
+            assert_eq!(code(normalize_npwp("01.855.081.4-412.00")), "invalid_npwp");
+            assert_eq!(code(normalize_npwp("9901011201900001")), "invalid_npwp");
+            assert_eq!(code(normalize_npwp("01.855.081.4-412.00x")), "invalid_npwp");
+        }
+
+        #[test]
+        fn bank_account_length_follows_bank() {
+            assert_eq!(normalize_bank_account("014", "123-456-7890").unwrap(), "1234567890");
+            assert_eq!(normalize_bank_account("022", "12345678901234").unwrap(), "12345678901234");
+
+            assert_eq!(code(normalize_bank_account("014", "123456789")), "invalid_bank_account");
+            assert_eq!(code(normalize_bank_account("008", "1234567890")), "invalid_bank_account");
+            assert_eq!(code(normalize_bank_account("999", "1234567890")), "invalid_bank_code");
+            assert!(valid_bank_code(" 014 ").is_ok());
+        }
+
+        #[test]
+        fn phone_normalizes_to_e164() {
+            assert_eq!(normalize_phone("0812-3456-789").unwrap(), "+628123456789");
+            assert_eq!(normalize_phone("628123456789").unwrap(), "+628123456789");
+            assert_eq!(normalize_phone("+62 812 3456 789").unwrap(), "+628123456789");
+            assert_eq!(normalize_phone("(021) 5555-1234").unwrap(), "+622155551234");
+        }
+
+        #[test]
+        fn phone_rejects_non_indonesian_prefix_or_length() {
+            assert_eq!(code(normalize_phone("+1 415 555 0100")), "invalid_phone");
+            assert_eq!(code(normalize_phone("00812345678")), "invalid_phone");
+            assert_eq!(code(normalize_phone("0812345")), "invalid_phone");
+            assert_eq!(code(normalize_phone("0812345678901234")), "invalid_phone");
+        }
+    }
+}
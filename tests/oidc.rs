@@ -0,0 +1,232 @@
+//! 🔑 Alur OIDC lengkap terhadap mock provider lokal (discovery, JWKS & token endpoint).
+//! Key RSA di `fixtures/oidc_rsa.pem` hanya untuk test, `MOCK_KEY_N` adalah modulus-nya.
+
+use std::{collections::{BTreeMap, HashMap}, net::TcpListener, sync::{Arc, Mutex, Once}};
+
+use actix_web::{web, App, HttpResponse, HttpServer};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use shuttle_runtime::SecretStore;
+use snakesystem_web_api::contexts::{
+    jwt_session::init_keys,
+    oidc::{authorization_request, discover, exchange_code, sign_state, verify_state, OidcProvider, OidcState, ProviderMetadata}
+};
+
+const MOCK_KEY_PEM: &str = include_str!("fixtures/oidc_rsa.pem");
+const MOCK_KEY_N: &str = "uH3qZ0tY7nrVG-vPFwVTXBct_QqhUoEcQMR11xwBHtosz8yJdMgnOmgeIYW5aMYAlGDu__a61bZQbkTYlrCoeCt6yVKb6CHvbbaimy2DwZjPbTkVeMyc2xmrWl793XLlb4NOqHkc0OJ_SGchdL6LPCuu_7itBtHzrddjxJ0vyIbpOY8ln9jjIwYw7r_qM71Tzrc8BaD0cUI1RgwWf2C-dUR-7rTvIZZzYJ1IMDPO8_V64yeFpL9wnOONZDLyImzsHwWT3chSN0yA8eX7IhL5-40KMuas1XfPaWwBDTM0pQlasYxbD1NHmSUMwRHVwDvdHvxpnIrnb0mGLHbtxWss7w";
+const MOCK_KID: &str = "mock-key";
+const MOCK_CODE: &str = "mock-code";
+const CLIENT_ID: &str = "snakesystem";
+const CLIENT_SECRET: &str = "mock-client-secret-with-at-least-32-bytes";
+
+static INIT_KEYS: Once = Once::new();
+
+/// Cookie state ditandatangani key JWT aplikasi
+fn init_jwt_keys() {
+    INIT_KEYS.call_once(|| {
+        let secrets = SecretStore::new(BTreeMap::from([("JWT_KEY".to_string(), "test-jwt-secret-with-at-least-32-bytes".to_string().into())]));
+        init_keys(&secrets).expect("failed to load JWT signing keys");
+    });
+}
+
+/// Isi respon mock provider, diatur per test
+#[derive(Default)]
+struct MockState {
+    /// `issuer` di dokumen discovery, default = alamat mock
+    discovery_issuer: Option<String>,
+    /// `code_challenge` dari URL authorize, dicocokkan dengan `code_verifier` di token endpoint
+    code_challenge: Option<String>,
+    id_token: Option<String>,
+}
+
+struct MockProvider {
+    issuer: String,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockProvider {
+    fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind mock provider");
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(MockState::default()));
+
+        let data = web::Data::from(state.clone());
+        let base = issuer.clone();
+        let server = HttpServer::new(move || {
+            let base = base.clone();
+            App::new()
+                .app_data(data.clone())
+                .route("/.well-known/openid-configuration", web::get().to(move |state: web::Data<Mutex<MockState>>| {
+                    let issuer = state.lock().unwrap().discovery_issuer.clone().unwrap_or_else(|| base.clone());
+                    let document = json!({
+                        "issuer": issuer,
+                        "authorization_endpoint": format!("{}/authorize", base),
+                        "token_endpoint": format!("{}/token", base),
+                        "jwks_uri": format!("{}/jwks", base),
+                    });
+                    async move { HttpResponse::Ok().json(document) }
+                }))
+                .route("/jwks", web::get().to(|| async {
+                    HttpResponse::Ok().json(json!({
+                        "keys": [{ "kty": "RSA", "kid": MOCK_KID, "alg": "RS256", "use": "sig", "n": MOCK_KEY_N, "e": "AQAB" }]
+                    }))
+                }))
+                .route("/token", web::post().to(token_endpoint))
+        })
+        .workers(1)
+        .listen(listener)
+        .expect("failed to start mock provider")
+        .run();
+        actix_web::rt::spawn(server);
+
+        Self { issuer, state }
+    }
+
+    fn provider(&self, name: &str) -> OidcProvider {
+        OidcProvider {
+            name: name.to_string(),
+            issuer: self.issuer.clone(),
+            client_id: CLIENT_ID.to_string(),
+            client_secret: Some(CLIENT_SECRET.to_string()),
+            redirect_uri: "http://localhost:8000/api/v1/auth/oidc/mock/callback".to_string(),
+            scopes: "openid email profile".to_string(),
+        }
+    }
+
+    /// Discovery, URL authorize & round trip cookie state seperti handler login
+    async fn authorize(&self, name: &str) -> (OidcProvider, ProviderMetadata, OidcState) {
+        init_jwt_keys();
+        let provider = self.provider(name);
+        let metadata = discover(&provider).await.expect("discovery failed");
+        let (url, state) = authorization_request(&provider, &metadata).expect("authorization request failed");
+
+        let url = reqwest::Url::parse(&url).unwrap();
+        assert_eq!(url.as_str().split('?').next(), Some(metadata.authorization_endpoint.as_str()));
+        let query = url.query_pairs().into_owned().collect::<HashMap<_, _>>();
+        assert_eq!(query.get("state"), Some(&state.state));
+        assert_eq!(query.get("nonce"), Some(&state.nonce));
+        assert_eq!(query.get("code_challenge_method").map(String::as_str), Some("S256"));
+        self.state.lock().unwrap().code_challenge = query.get("code_challenge").cloned();
+
+        let cookie = sign_state(&state).expect("failed to sign state");
+        let stored = verify_state(&cookie, &provider.name, &state.state).expect("state rejected");
+        (provider, metadata, stored)
+    }
+
+    fn respond_with(&self, id_token: String) {
+        self.state.lock().unwrap().id_token = Some(id_token);
+    }
+}
+
+async fn token_endpoint(state: web::Data<Mutex<MockState>>, form: web::Form<HashMap<String, String>>) -> HttpResponse {
+    let state = state.lock().unwrap();
+    let verifier = form.get("code_verifier").map(String::as_str).unwrap_or_default();
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+
+    let valid = form.get("grant_type").map(String::as_str) == Some("authorization_code")
+        && form.get("code").map(String::as_str) == Some(MOCK_CODE)
+        && form.get("client_id").map(String::as_str) == Some(CLIENT_ID)
+        && form.get("client_secret").map(String::as_str) == Some(CLIENT_SECRET)
+        && state.code_challenge.as_deref() == Some(challenge.as_str());
+    if !valid {
+        return HttpResponse::BadRequest().json(json!({ "error": "invalid_grant" }));
+    }
+
+    HttpResponse::Ok().json(json!({ "access_token": "mock-access-token", "token_type": "Bearer", "id_token": state.id_token }))
+}
+
+fn claims(issuer: &str, nonce: &str) -> Value {
+    json!({
+        "iss": issuer,
+        "aud": CLIENT_ID,
+        "sub": "mock-user-1",
+        "email": "budi@example.com",
+        "email_verified": "true",
+        "nonce": nonce,
+        "iat": Utc::now().timestamp(),
+        "exp": (Utc::now() + Duration::minutes(5)).timestamp(),
+    })
+}
+
+fn rs256_token(claims: &Value) -> String {
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some(MOCK_KID.to_string());
+    encode(&header, claims, &EncodingKey::from_rsa_pem(MOCK_KEY_PEM.as_bytes()).unwrap()).unwrap()
+}
+
+#[actix_web::test]
+async fn login_happy_path() {
+    let mock = MockProvider::start();
+    let (provider, metadata, state) = mock.authorize("mock-happy").await;
+    mock.respond_with(rs256_token(&claims(&mock.issuer, &state.nonce)));
+
+    let claims = exchange_code(&provider, &metadata, MOCK_CODE, &state).await.unwrap();
+    assert_eq!(claims.sub, "mock-user-1");
+    assert_eq!(claims.email.as_deref(), Some("budi@example.com"));
+    assert!(claims.email_verified);
+    assert_eq!(claims.nonce, Some(state.nonce));
+}
+
+#[actix_web::test]
+async fn discovery_issuer_mismatch_is_rejected() {
+    let mock = MockProvider::start();
+    mock.state.lock().unwrap().discovery_issuer = Some("https://accounts.example.com".to_string());
+
+    let error = discover(&mock.provider("mock-discovery-issuer")).await.unwrap_err();
+    assert!(error.starts_with("OIDC issuer mismatch"), "{}", error);
+}
+
+#[actix_web::test]
+async fn id_token_issuer_mismatch_is_rejected() {
+    let mock = MockProvider::start();
+    let (provider, metadata, state) = mock.authorize("mock-token-issuer").await;
+    mock.respond_with(rs256_token(&claims("https://accounts.example.com", &state.nonce)));
+
+    let error = exchange_code(&provider, &metadata, MOCK_CODE, &state).await.unwrap_err();
+    assert_eq!(error, "Invalid ID token: InvalidIssuer");
+}
+
+#[actix_web::test]
+async fn nonce_mismatch_is_rejected() {
+    let mock = MockProvider::start();
+    let (provider, metadata, state) = mock.authorize("mock-nonce").await;
+    mock.respond_with(rs256_token(&claims(&mock.issuer, "replayed-nonce")));
+
+    let error = exchange_code(&provider, &metadata, MOCK_CODE, &state).await.unwrap_err();
+    assert_eq!(error, "Invalid ID token nonce");
+}
+
+#[actix_web::test]
+async fn tampered_state_is_rejected() {
+    let mock = MockProvider::start();
+    let (provider, _, state) = mock.authorize("mock-state").await;
+    let cookie = sign_state(&state).unwrap();
+
+    // Ubah satu karakter di tengah tanda tangan (karakter terakhir bisa berisi bit padding saja)
+    let (payload, signature) = cookie.rsplit_once('.').unwrap();
+    let mut signature = signature.chars().collect::<Vec<_>>();
+    signature[4] = if signature[4] == 'A' { 'B' } else { 'A' };
+    let tampered = format!("{}.{}", payload, signature.into_iter().collect::<String>());
+    assert_eq!(verify_state(&tampered, &provider.name, &state.state).unwrap_err(), "Login session expired, please try again");
+
+    assert_eq!(verify_state(&cookie, &provider.name, "other-state").unwrap_err(), "Invalid OIDC state");
+    assert_eq!(verify_state(&cookie, "other-provider", &state.state).unwrap_err(), "Invalid OIDC state");
+}
+
+#[actix_web::test]
+async fn hmac_id_token_is_rejected() {
+    let mock = MockProvider::start();
+    let (provider, metadata, state) = mock.authorize("mock-hs256").await;
+
+    // Ditandatangani dengan client secret yang juga diketahui penyerang di sisi client
+    let mut header = Header::new(Algorithm::HS256);
+    header.kid = Some(MOCK_KID.to_string());
+    let token = encode(&header, &claims(&mock.issuer, &state.nonce), &EncodingKey::from_secret(CLIENT_SECRET.as_bytes())).unwrap();
+    mock.respond_with(token);
+
+    let error = exchange_code(&provider, &metadata, MOCK_CODE, &state).await.unwrap_err();
+    assert_eq!(error, "Unsupported ID token algorithm");
+}